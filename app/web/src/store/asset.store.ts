@@ -488,7 +488,12 @@ export const useAssetStore = (forceChangeSetId?: ChangeSetId) => {
 
         async LOAD_SCHEMA_VARIANT_LIST() {
           return new ApiRequest<
-            { installed: SchemaVariant[]; uninstalled: UninstalledVariant[] },
+            {
+              installed: SchemaVariant[];
+              uninstalled: UninstalledVariant[];
+              // the list is reloaded once the module cache is filled, see ModulesUpdated below
+              warming: boolean;
+            },
             Visibility
           >({
             url: API_PREFIX,
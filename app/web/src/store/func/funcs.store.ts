@@ -336,10 +336,13 @@ export const useFuncStore = () => {
         },
 
         async FETCH_FUNC_LIST() {
-          return new ApiRequest<FuncSummary[], Visibility>({
+          return new ApiRequest<
+            { items: FuncSummary[]; total: number },
+            Visibility
+          >({
             url: API_PREFIX,
-            onSuccess: (response) => {
-              response.forEach((func) => {
+            onSuccess: ({ items }) => {
+              items.forEach((func) => {
                 const bindings = processBindings(func);
                 this.actionBindings[func.funcId] = bindings.actionBindings;
                 this.attributeBindings[func.funcId] =
@@ -353,7 +356,7 @@ export const useFuncStore = () => {
                   bindings.managementBindings;
               });
 
-              this.funcsById = _.keyBy(response, (f) => f.funcId);
+              this.funcsById = _.keyBy(items, (f) => f.funcId);
             },
           });
         },
@@ -152,6 +152,10 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) concurrency: Option<u32>,
 
+    /// The number of functions each workspace can have executing at once [default: 256]
+    #[arg(long)]
+    pub(crate) execution_budget: Option<u32>,
+
     /// Configures the max delivery number NATS JetStream consumer(s)
     #[arg(long)]
     pub(crate) max_deliver: Option<i64>,
@@ -258,6 +262,9 @@ fn build_config_map(args: Args, config_map: &mut ConfigMap) -> &ConfigMap {
     if let Some(concurrency) = args.concurrency {
         config_map.set("concurrency_limit", i64::from(concurrency));
     }
+    if let Some(execution_budget) = args.execution_budget {
+        config_map.set("execution_budget", i64::from(execution_budget));
+    }
     if let Some(max_deliver) = args.max_deliver {
         config_map.set("max_deliver", max_deliver);
     }
@@ -94,6 +94,8 @@ type Result<T> = std::result::Result<T, AuditDatabaseError>;
 /// A row in the audit logs table of the audit database.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AuditLogRow {
+    /// The primary key of the row, which breaks ties between rows with the same timestamp when paginating.
+    pub pk: i64,
     /// Indicates the workspace that the row belongs to.
     pub workspace_id: WorkspacePk,
     /// The [kind](AuditLogKind) of the [`AuditLog`] (converted into a string because enum discriminants are not
@@ -118,6 +120,38 @@ pub struct AuditLogRow {
     pub authentication_method: AuthenticationMethod,
 }
 
+/// A position in a listing of audit logs that the next page starts after.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogCursor {
+    /// The timestamp of the last row of the previous page.
+    pub timestamp: DateTime<Utc>,
+    /// The primary key of the last row of the previous page.
+    pub pk: i64,
+}
+
+impl From<&AuditLogRow> for AuditLogCursor {
+    fn from(row: &AuditLogRow) -> Self {
+        Self {
+            timestamp: row.timestamp,
+            pk: row.pk,
+        }
+    }
+}
+
+/// Narrows down the rows returned by [`AuditLogRow::list_filtered`]. Empty or missing fields do not filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLogFilter {
+    /// Only include rows with one of these kinds.
+    pub kinds: Vec<String>,
+    /// Only include rows written by one of these users.
+    pub user_ids: Vec<UserPk>,
+    /// Only include rows at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only include rows before this time.
+    pub until: Option<DateTime<Utc>>,
+}
+
 impl AuditLogRow {
     /// Inserts a new row into the audit logs table of the audit database.
     #[allow(clippy::too_many_arguments)]
@@ -242,6 +276,86 @@ impl AuditLogRow {
         Ok((logs, can_load_more))
     }
 
+    /// Lists a page of rows of the audit logs table in the audit database matching the filter. Pages are keyed on the
+    /// timestamp and primary key of the last row of the previous page rather than an offset, so deep pages stay cheap.
+    /// Returns the rows alongside the cursor for the next page, if there is one.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(
+        name = "audit_log.database.list_filtered",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn list_filtered(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        change_set_ids: Vec<ChangeSetId>,
+        filter: &AuditLogFilter,
+        after: Option<AuditLogCursor>,
+        size: usize,
+        sort_ascending: bool,
+    ) -> Result<(Vec<Self>, Option<AuditLogCursor>)> {
+        let change_set_ids: Vec<String> = change_set_ids.iter().map(|id| id.to_string()).collect();
+        let user_ids: Vec<String> = filter.user_ids.iter().map(|id| id.to_string()).collect();
+        let after_timestamp = after.map(|cursor| cursor.timestamp);
+        let after_pk = after.map(|cursor| cursor.pk);
+        // Fetch one extra row to find out whether there is another page.
+        let limit = size as i64 + 1;
+
+        let query = if sort_ascending {
+            "SELECT * from audit_logs WHERE workspace_id = $1 AND change_set_id = ANY($2)
+                AND (cardinality($3::text[]) = 0 OR kind = ANY($3))
+                AND (cardinality($4::text[]) = 0 OR user_id = ANY($4))
+                AND ($5::timestamptz IS NULL OR timestamp >= $5)
+                AND ($6::timestamptz IS NULL OR timestamp < $6)
+                AND ($7::timestamptz IS NULL OR (timestamp, pk) > ($7, $8))
+                ORDER BY timestamp ASC, pk ASC LIMIT $9"
+        } else {
+            "SELECT * from audit_logs WHERE workspace_id = $1 AND change_set_id = ANY($2)
+                AND (cardinality($3::text[]) = 0 OR kind = ANY($3))
+                AND (cardinality($4::text[]) = 0 OR user_id = ANY($4))
+                AND ($5::timestamptz IS NULL OR timestamp >= $5)
+                AND ($6::timestamptz IS NULL OR timestamp < $6)
+                AND ($7::timestamptz IS NULL OR (timestamp, pk) < ($7, $8))
+                ORDER BY timestamp DESC, pk DESC LIMIT $9"
+        };
+        let rows = context
+            .pg_pool()
+            .get()
+            .await?
+            .query(
+                query,
+                &[
+                    &workspace_id,
+                    &change_set_ids,
+                    &filter.kinds,
+                    &user_ids,
+                    &filter.since,
+                    &filter.until,
+                    &after_timestamp,
+                    &after_pk,
+                    &limit,
+                ],
+            )
+            .await?;
+
+        let mut logs = Vec::with_capacity(rows.len());
+        for row in rows {
+            logs.push(Self::try_from(row)?);
+        }
+
+        let next = if logs.len() > size {
+            logs.truncate(size);
+            logs.last().map(AuditLogCursor::from)
+        } else {
+            None
+        };
+
+        Ok((logs, next))
+    }
+
     /// Lists rows of the audit logs table filtered by component ID in the audit database.
     #[instrument(
         name = "audit_log.database.list_for_component",
@@ -324,6 +438,7 @@ impl TryFrom<PgRow> for AuditLogRow {
         };
 
         Ok(Self {
+            pk: value.try_get("pk")?,
             workspace_id,
             kind: value.try_get("kind")?,
             timestamp: value.try_get("timestamp")?,
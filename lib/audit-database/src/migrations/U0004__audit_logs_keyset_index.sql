@@ -0,0 +1 @@
+CREATE INDEX audit_logs_workspace_timestamp_pk ON audit_logs (workspace_id, timestamp, pk);
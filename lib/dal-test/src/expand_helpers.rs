@@ -5,19 +5,24 @@
 
 #![allow(clippy::expect_used, clippy::panic)]
 
+use std::{
+    ffi::c_int,
+    sync::{
+        Mutex,
+        MutexGuard,
+        OnceLock,
+    },
+    thread,
+};
+
 use dal::{
     ChangeSet,
     ChangeSetId,
     DalContext,
-    WorkspacePk,
 };
-use jwt_simple::{
-    algorithms::RSAKeyPairLike,
-    claims::Claims,
-    prelude::Duration,
-};
-use si_db::Tenancy;
+use opentelemetry_sdk::trace::TracerProvider;
 use si_jwt_public_key::SiJwtClaims;
+use tokio::sync::oneshot;
 use tracing_subscriber::{
     EnvFilter,
     Registry,
@@ -28,10 +33,13 @@ use tracing_subscriber::{
 use crate::{
     WorkspaceSignup,
     helpers::{
+        AuthTokenClaimOverrides,
+        AuthTokenExpiry,
+        create_auth_token_with,
         create_user,
         generate_fake_name,
+        workspace_signup_with_claims,
     },
-    jwt_private_signing_key,
 };
 
 /// Creates a user for each test to run as
@@ -70,26 +78,110 @@ pub async fn create_change_set_and_update_ctx(
         .expect("could not update visibility and snapshot");
 }
 
+/// The background runtime that tracing is exported from. It is started by the first test in the
+/// binary and runs until the process exits, since tests start and finish at any time.
+#[derive(Debug)]
+struct TracingRuntime {
+    shutdown_tx: oneshot::Sender<()>,
+    thread: thread::JoinHandle<()>,
+}
+
+static TRACING_RUNTIME: Mutex<Option<TracingRuntime>> = Mutex::new(None);
+
+/// The provider spans are exported through, once exporting is enabled in [`tracing_init_inner`]
+/// (see its `otel_layer`).
+static TRACER_PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+
+unsafe extern "C" {
+    fn atexit(callback: extern "C" fn()) -> c_int;
+}
+
+/// Returned by [`tracing_init`] and held for the duration of a test. When it is dropped
+/// (including while unwinding from a failed test), the spans pending export are flushed.
+#[must_use = "spans are flushed as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct TracingGuard {
+    _private: (),
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        // Other tests may still be running, so the provider is only flushed here and is shut
+        // down once, when the process exits.
+        if let Some(tracer_provider) = TRACER_PROVIDER.get() {
+            for result in tracer_provider.force_flush() {
+                if let Err(err) = result {
+                    eprintln!("failed to flush test spans: {err}");
+                }
+            }
+        }
+    }
+}
+
+/// Shuts down the tracer provider and then the runtime it exports from, once the test binary is
+/// exiting.
+extern "C" fn shutdown_tracing() {
+    // Flushing needs the runtime the batch exporter runs on, so it has to happen before the
+    // runtime is told to stop.
+    telemetry::opentelemetry::global::shutdown_tracer_provider();
+    if let Some(runtime) = lock_tracing_runtime().take() {
+        let _ = runtime.shutdown_tx.send(());
+        let _ = runtime.thread.join();
+    }
+}
+
+fn lock_tracing_runtime() -> MutexGuard<'static, Option<TracingRuntime>> {
+    match TRACING_RUNTIME.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
 /// This function is used during macro expansion for setting up tracing in an integration test.
-pub fn tracing_init(span_events_env_var: &'static str, log_env_var: &'static str) {
-    use std::thread;
-
-    thread::spawn(move || {
-        let tokio = ::tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .expect("Failed building the Runtime for Tracing for Testing");
-
-        tokio.block_on(async move {
-            tracing_init_inner(span_events_env_var, log_env_var);
-            tokio::time::sleep(std::time::Duration::from_secs(10000000)).await
+///
+/// The subscriber and the runtime it exports from are only set up the first time this is called
+/// in a test binary, so it is safe to call for every test.
+pub fn tracing_init(span_events_env_var: &'static str, log_env_var: &'static str) -> TracingGuard {
+    let mut tracing_runtime = lock_tracing_runtime();
+
+    if tracing_runtime.is_none() {
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let thread = thread::spawn(move || {
+            let tokio = ::tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .expect("Failed building the Runtime for Tracing for Testing");
+
+            tokio.block_on(async move {
+                tracing_init_inner(span_events_env_var, log_env_var);
+                // Either a shutdown signal or the sender being dropped means we are done.
+                let _ = shutdown_rx.await;
+            });
+        });
+        *tracing_runtime = Some(TracingRuntime {
+            shutdown_tx,
+            thread,
         });
-    });
+
+        // SAFETY: `atexit` only stores the function pointer, and `shutdown_tracing` neither
+        // unwinds (panics abort at the `extern "C"` boundary) nor registers further handlers.
+        if unsafe { atexit(shutdown_tracing) } != 0 {
+            eprintln!("failed to register the test tracing shutdown; pending spans may be lost");
+        }
+    }
+
+    TracingGuard { _private: () }
 }
 
 fn tracing_init_inner(span_events_env_var: &str, log_env_var: &str) {
     use tracing_subscriber::layer::SubscriberExt;
 
+    // The subscriber outlives the runtime it was installed from, so it is only installed once per
+    // test binary.
+    if telemetry::tracing::dispatcher::has_been_set() {
+        return;
+    }
+
     telemetry::opentelemetry::global::set_text_map_propagator(
         opentelemetry_sdk::propagation::TraceContextPropagator::new(),
     );
@@ -155,6 +247,8 @@ fn tracing_init_inner(span_events_env_var: &str, log_env_var: &str) {
     //         .with_trace_config(opentelemetry_sdk::trace::config().with_resource(resource))
     //         .install_batch(opentelemetry_sdk::runtime::Tokio)
     //         .expect("Creating otel_tracer failed");
+    //     // NOTE: register the pipeline's tracer provider in `TRACER_PROVIDER` so that each test
+    //     // flushes its spans as it finishes (see `TracingGuard`).
     //
     //     tracing_opentelemetry::layer().with_tracer(otel_tracer)
     // };
@@ -171,33 +265,12 @@ fn tracing_init_inner(span_events_env_var: &str, log_env_var: &str) {
 
 /// This function is used during macro expansion for setting up the workspace for integration tests.
 pub async fn workspace_signup(ctx: &mut DalContext) -> crate::Result<(WorkspaceSignup, String)> {
-    use color_eyre::eyre::WrapErr;
-    // Tenancy must be set here or the update_visibility call will fail, since it now needs to check
-    // the workspace snapshot_kind
-    ctx.update_tenancy(Tenancy::new(WorkspacePk::NONE));
-
-    let mut ctx = ctx.clone_with_head().await?;
-
-    let workspace_name = generate_fake_name().expect("could not generate fake name");
-    let user_name = format!("frank {workspace_name}");
-    let user_email = format!("{workspace_name}@example.com");
-    let token = "workspace_name".to_string();
-
-    let nw = WorkspaceSignup::new(&mut ctx, &workspace_name, &user_name, &user_email, &token)
-        .await
-        .wrap_err("cannot signup a new workspace")?;
-    let auth_token = create_auth_token(SiJwtClaims::for_web(nw.user.pk(), *nw.workspace.pk()))
-        .await
-        .expect("could not create auth token");
+    let (nw, auth_token, _claims) =
+        workspace_signup_with_claims(ctx, AuthTokenClaimOverrides::default()).await?;
     Ok((nw, auth_token))
 }
 
-async fn create_auth_token(claim: SiJwtClaims) -> crate::Result<String> {
-    let key_pair = jwt_private_signing_key().await?;
-    let claim = Claims::with_custom_claims(claim.clone(), Duration::from_days(1))
-        .with_audience("https://app.systeminit.com")
-        .with_issuer("https://app.systeminit.com")
-        .with_subject(claim.user_id());
-
-    Ok(key_pair.sign(claim).expect("could not sign"))
+/// Signs an auth token carrying the provided claims that is valid for a day.
+pub async fn create_auth_token(claim: SiJwtClaims) -> crate::Result<String> {
+    create_auth_token_with(claim, AuthTokenExpiry::default()).await
 }
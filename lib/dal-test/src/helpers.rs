@@ -1,6 +1,13 @@
 //! This module contains helpers for use when authoring dal integration tests.
 
-use std::time::Duration;
+use std::{
+    env,
+    sync::{
+        LazyLock,
+        Mutex,
+    },
+    time::Duration,
+};
 
 use audit_database::{
     AuditDatabaseContext,
@@ -8,7 +15,10 @@ use audit_database::{
 };
 use color_eyre::{
     Result,
-    eyre::eyre,
+    eyre::{
+        WrapErr,
+        eyre,
+    },
 };
 use dal::{
     AttributeValue,
@@ -20,27 +30,56 @@ use dal::{
     KeyPair,
     OutputSocket,
     Schema,
+    SchemaError,
+    SchemaId,
     SchemaVariant,
     SchemaVariantId,
     UserPk,
+    WorkspacePk,
     audit_logging,
-    component::socket::{
-        ComponentInputSocket,
-        ComponentOutputSocket,
+    component::{
+        resource::{
+            ResourceData,
+            ResourceStatus,
+        },
+        socket::{
+            ComponentInputSocket,
+            ComponentOutputSocket,
+        },
     },
     diagram::view::View,
     key_pair::KeyPairPk,
     schema::variant::authoring::VariantAuthoringClient,
 };
 use itertools::Itertools;
+use jwt_simple::{
+    algorithms::RSAKeyPairLike,
+    claims::Claims,
+    prelude::{
+        Clock,
+        Duration as JwtDuration,
+    },
+};
 use names::{
     Generator,
     Name,
 };
 use si_data_nats::async_nats::jetstream::stream::Stream;
-use si_db::User;
+use si_db::{
+    Tenancy,
+    User,
+};
+use si_jwt_public_key::{
+    SiJwtClaimRole,
+    SiJwtClaims,
+};
 use tokio::time::Instant;
 
+use crate::{
+    WorkspaceSignup,
+    jwt_private_signing_key,
+};
+
 mod property_editor_test_view;
 
 /// Test helpers for attribute values and prototypes
@@ -51,23 +90,106 @@ pub mod change_set;
 pub mod component;
 /// Test helpers for funcs
 pub mod func;
+/// Test helpers for asserting on JSON documents
+pub mod json;
 /// Test helpers for schemas
 pub mod schema;
 /// Test helpers for secrets
 pub mod secret;
+/// Test helpers for WsEvents
+pub mod ws_event;
 
-pub use change_set::ChangeSetTestHelpers;
+pub use change_set::{
+    ChangeSetTestHarness,
+    ChangeSetTestHelpers,
+};
 use dal::diagram::view::ViewId;
 pub use property_editor_test_view::PropEditorTestView;
 use serde_json::Value;
 
-/// Generates a fake name.
+/// When set, seeds the sequence returned by [`generate_fake_name`] for the whole test process.
+const ENV_VAR_NAME_SEED: &str = "SI_TEST_NAME_SEED";
+
+/// The seeded generator shared by [`generate_fake_name`], if one has been set. It is initialized
+/// from [`ENV_VAR_NAME_SEED`] on first use and can be replaced with [`set_name_seed`].
+static SEEDED_NAME_GENERATOR: LazyLock<Mutex<Option<FakeNameGenerator>>> = LazyLock::new(|| {
+    Mutex::new(
+        env::var(ENV_VAR_NAME_SEED)
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(FakeNameGenerator::new),
+    )
+});
+
+/// A deterministic generator of names in the same "adjective-noun-1234" form as
+/// [`generate_fake_name`]. Two generators created with the same seed produce the same sequence.
+#[derive(Debug, Clone)]
+pub struct FakeNameGenerator {
+    state: u64,
+}
+
+impl FakeNameGenerator {
+    /// Creates a generator whose sequence is determined entirely by the seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next name in the sequence.
+    pub fn next_name(&mut self) -> String {
+        let adjective = names::ADJECTIVES[self.next_index(names::ADJECTIVES.len())];
+        let noun = names::NOUNS[self.next_index(names::NOUNS.len())];
+        let number = self.next_index(9999) + 1;
+        format!("{adjective}-{noun}-{number:04}")
+    }
+
+    // A splitmix64 step is plenty for picking names and keeps us from needing a seedable rng
+    // crate.
+    fn next_index(&mut self, len: usize) -> usize {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z % len as u64) as usize
+    }
+}
+
+/// Switches [`generate_fake_name`] to a deterministic sequence for the rest of the test process,
+/// restarting it if a seed was already set.
+///
+/// The sequence is shared by every test in the process, so it is only reproducible when tests
+/// run one at a time (e.g. with `--test-threads=1`). Tests that need their own reproducible
+/// sequence regardless should use a [`FakeNameGenerator`] directly.
+pub fn set_name_seed(seed: u64) {
+    let mut generator = match SEEDED_NAME_GENERATOR.lock() {
+        Ok(generator) => generator,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *generator = Some(FakeNameGenerator::new(seed));
+}
+
+/// Generates a fake name. Names are random unless a seed has been set, either via
+/// [`set_name_seed`] or the `SI_TEST_NAME_SEED` environment variable.
 pub fn generate_fake_name() -> Result<String> {
+    let mut seeded = match SEEDED_NAME_GENERATOR.lock() {
+        Ok(generator) => generator,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(generator) = seeded.as_mut() {
+        return Ok(generator.next_name());
+    }
+
     Generator::with_naming(Name::Numbered)
         .next()
         .ok_or(eyre!("could not generate fake name"))
 }
 
+/// Generates a fake name starting with the given prefix, so that fixtures created by a test can
+/// be told apart from (and cleaned up separately to) those of other tests.
+pub fn generate_fake_name_with_prefix(prefix: impl AsRef<str>) -> Result<String> {
+    Ok(format!("{}-{}", prefix.as_ref(), generate_fake_name()?))
+}
+
 /// Creates a connection annotation string.
 #[allow(clippy::expect_used)]
 #[macro_export]
@@ -96,6 +218,105 @@ pub async fn create_user(ctx: &DalContext) -> Result<User> {
     .await?)
 }
 
+/// How long a token made by [`create_auth_token_with`] is valid for, or how long ago it expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthTokenExpiry {
+    /// The token was issued now and expires after the duration.
+    ValidFor(Duration),
+    /// The token expired the duration ago. Token validation allows for some clock skew (15
+    /// minutes by default), so this must be longer than that for the token to be rejected.
+    ExpiredFor(Duration),
+}
+
+impl Default for AuthTokenExpiry {
+    fn default() -> Self {
+        Self::ValidFor(Duration::from_secs(60 * 60 * 24))
+    }
+}
+
+/// Overrides for the claims of the token made by [`workspace_signup_with_claims`]. Any field left
+/// as `None` uses the value for the newly signed up user and workspace.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AuthTokenClaimOverrides {
+    /// The user the token is for.
+    pub user_pk: Option<UserPk>,
+    /// The workspace the token is for.
+    pub workspace_pk: Option<WorkspacePk>,
+    /// The role granted by the token. Defaults to [`SiJwtClaimRole::Web`].
+    pub role: Option<SiJwtClaimRole>,
+    /// When the token expires.
+    pub expiry: AuthTokenExpiry,
+}
+
+/// Signs an auth token carrying the provided claims, the same way the auth api does.
+pub async fn create_auth_token_with(
+    claims: SiJwtClaims,
+    expiry: AuthTokenExpiry,
+) -> Result<String> {
+    let key_pair = jwt_private_signing_key().await?;
+
+    let mut jwt_claims = match expiry {
+        AuthTokenExpiry::ValidFor(valid_for) => {
+            Claims::with_custom_claims(claims.clone(), JwtDuration::from_secs(valid_for.as_secs()))
+        }
+        AuthTokenExpiry::ExpiredFor(expired_for) => {
+            let expires_at =
+                Clock::now_since_epoch() - JwtDuration::from_secs(expired_for.as_secs());
+            let issued_at = expires_at - JwtDuration::from_days(1);
+            let mut jwt_claims =
+                Claims::with_custom_claims(claims.clone(), JwtDuration::from_secs(0));
+            jwt_claims.issued_at = Some(issued_at);
+            jwt_claims.invalid_before = Some(issued_at);
+            jwt_claims.expires_at = Some(expires_at);
+            jwt_claims
+        }
+    };
+    jwt_claims = jwt_claims
+        .with_audience("https://app.systeminit.com")
+        .with_issuer("https://app.systeminit.com")
+        .with_subject(claims.user_id());
+
+    key_pair
+        .sign(jwt_claims)
+        .map_err(|err| eyre!("could not sign auth token: {err}"))
+}
+
+/// Signs up a new workspace and user, returning them along with an auth token whose claims have
+/// had the provided overrides applied, and those claims themselves.
+pub async fn workspace_signup_with_claims(
+    ctx: &mut DalContext,
+    overrides: AuthTokenClaimOverrides,
+) -> Result<(WorkspaceSignup, String, SiJwtClaims)> {
+    // Tenancy must be set here or the update_visibility call will fail, since it now needs to check
+    // the workspace snapshot_kind
+    ctx.update_tenancy(Tenancy::new(WorkspacePk::NONE));
+
+    let mut ctx = ctx.clone_with_head().await?;
+
+    let workspace_name = generate_fake_name()?;
+    let user_name = format!("frank {workspace_name}");
+    let user_email = format!("{workspace_name}@example.com");
+    let token = "workspace_name".to_string();
+
+    let nw = WorkspaceSignup::new(&mut ctx, &workspace_name, &user_name, &user_email, &token)
+        .await
+        .wrap_err("cannot signup a new workspace")?;
+
+    let mut claims = SiJwtClaims::for_web(
+        overrides.user_pk.unwrap_or_else(|| nw.user.pk()),
+        overrides.workspace_pk.unwrap_or(*nw.workspace.pk()),
+    );
+    if let (SiJwtClaims::V2(claims), Some(role)) = (&mut claims, overrides.role) {
+        claims.role = role;
+    }
+
+    let auth_token = create_auth_token_with(claims.clone(), overrides.expiry)
+        .await
+        .wrap_err("could not create auth token")?;
+
+    Ok((nw, auth_token, claims))
+}
+
 /// Creates a dummy schema.
 pub async fn create_schema(ctx: &DalContext) -> Result<Schema> {
     let name = generate_fake_name()?;
@@ -200,6 +421,84 @@ pub async fn create_named_component_for_schema_variant_on_default_view(
     Ok(Component::new(ctx, name.as_ref().to_string(), schema_variant_id, view_id).await?)
 }
 
+/// The ids of everything created by [`create_component_for_new_schema`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentBundle {
+    /// The [`Schema`] the component was created from.
+    pub schema_id: SchemaId,
+    /// The default [`SchemaVariant`] of that schema.
+    pub schema_variant_id: SchemaVariantId,
+    /// The created [`Component`].
+    pub component_id: ComponentId,
+}
+
+impl ComponentBundle {
+    /// Sets the resource id of the [`Component`] and gives it a healthy resource with the given
+    /// payload, as if a create action had run for it.
+    pub async fn attach_resource(
+        &self,
+        ctx: &DalContext,
+        resource_id: impl AsRef<str>,
+        payload: Value,
+    ) -> Result<()> {
+        let component = Component::get_by_id(ctx, self.component_id).await?;
+        component.set_resource_id(ctx, resource_id.as_ref()).await?;
+        component
+            .set_resource(ctx, ResourceData::new(ResourceStatus::Ok, Some(payload)))
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the [`Component`] in the bundle.
+    pub async fn component(&self, ctx: &DalContext) -> Result<Component> {
+        Ok(Component::get_by_id(ctx, self.component_id).await?)
+    }
+}
+
+/// Creates a [`Component`] with a fake name in the default view from the default
+/// [`SchemaVariant`] of the [`Schema`] with the given name.
+///
+/// If the schema is installed or available as a cached module (e.g. the name of a builtin), that
+/// schema is used. Otherwise a new, empty schema and variant are created with that name.
+pub async fn create_component_for_new_schema(
+    ctx: &DalContext,
+    schema_name: impl AsRef<str>,
+) -> Result<ComponentBundle> {
+    let schema_name = schema_name.as_ref();
+    let (schema_id, schema_variant_id) =
+        match Schema::get_or_install_by_name(ctx, schema_name).await {
+            Ok(schema) => (
+                schema.id(),
+                Schema::default_variant_id(ctx, schema.id()).await?,
+            ),
+            Err(SchemaError::UninstalledSchemaNotFoundByName(_)) => {
+                let variant = VariantAuthoringClient::create_schema_and_variant(
+                    ctx,
+                    schema_name,
+                    None,
+                    None,
+                    "test",
+                    "FFFFFF",
+                )
+                .await?;
+                (
+                    SchemaVariant::schema_id(ctx, variant.id()).await?,
+                    variant.id(),
+                )
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+    let component =
+        create_component_for_schema_variant_on_default_view(ctx, schema_variant_id).await?;
+
+    Ok(ComponentBundle {
+        schema_id,
+        schema_variant_id,
+        component_id: component.id(),
+    })
+}
+
 /// Gets the [`Value`] for a specific [`Component`]'s [`InputSocket`] by the [`InputSocket`] name
 pub async fn get_component_input_socket_value(
     ctx: &DalContext,
@@ -1,10 +1,16 @@
-//! This module provides [`ChangeSetTestHelpers`].
+//! This module provides [`ChangeSetTestHelpers`] and [`ChangeSetTestHarness`].
 
-use std::time::Duration;
+use std::{
+    fmt::Debug,
+    time::Duration,
+};
 
 use color_eyre::{
     Result,
-    eyre::eyre,
+    eyre::{
+        WrapErr,
+        eyre,
+    },
 };
 use dal::{
     ChangeSet,
@@ -351,3 +357,107 @@ impl ChangeSetTestHelpers {
         Ok(new_change_set)
     }
 }
+
+/// This unit struct provides helpers for tests that juggle several [`ChangeSets`](ChangeSet) at
+/// once, e.g. to check that something created in one change set is (or isn't) visible in another
+/// after it is applied or abandoned. Unlike [`ChangeSetTestHelpers`], each method takes the id
+/// of the change set it acts on and reports it in its error.
+#[derive(Debug)]
+pub struct ChangeSetTestHarness;
+
+impl ChangeSetTestHarness {
+    /// Forks a new [`ChangeSet`] from HEAD, switches to it and returns its id.
+    pub async fn fork(ctx: &mut DalContext) -> Result<ChangeSetId> {
+        let change_set = ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+            .await
+            .wrap_err("could not fork change set from head")?;
+        Ok(change_set.id)
+    }
+
+    /// Switches to the [`ChangeSet`] with the given id.
+    pub async fn switch(ctx: &mut DalContext, change_set_id: ChangeSetId) -> Result<()> {
+        ChangeSetTestHelpers::switch_to_change_set(ctx, change_set_id)
+            .await
+            .wrap_err_with(|| format!("could not switch to change set {change_set_id}"))
+    }
+
+    /// Commits and applies the [`ChangeSet`] with the given id to HEAD, leaving ctx pointed at
+    /// HEAD.
+    pub async fn apply(ctx: &mut DalContext, change_set_id: ChangeSetId) -> Result<()> {
+        Self::switch(ctx, change_set_id).await?;
+        ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+            .await
+            .wrap_err_with(|| format!("could not commit change set {change_set_id}"))?;
+        ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+            .await
+            .wrap_err_with(|| format!("could not apply change set {change_set_id}"))?;
+        Ok(())
+    }
+
+    /// Abandons the [`ChangeSet`] with the given id, leaving ctx pointed at HEAD.
+    pub async fn abandon(ctx: &mut DalContext, change_set_id: ChangeSetId) -> Result<()> {
+        Self::switch(ctx, change_set_id).await?;
+        ChangeSetTestHelpers::abandon_change_set(ctx)
+            .await
+            .wrap_err_with(|| format!("could not abandon change set {change_set_id}"))?;
+        let head_change_set_id = ctx.get_workspace_default_change_set_id().await?;
+        Self::switch(ctx, head_change_set_id).await
+    }
+
+    /// Runs the lookup in the [`ChangeSet`] with the given id and returns what it found, or an
+    /// error if it found nothing. ctx is switched back to its original change set afterwards.
+    pub async fn assert_visible_in<T, E, F, Fut>(
+        ctx: &mut DalContext,
+        change_set_id: ChangeSetId,
+        lookup: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(DalContext) -> Fut,
+        Fut: Future<Output = std::result::Result<Option<T>, E>>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self::lookup_in(ctx, change_set_id, lookup)
+            .await?
+            .ok_or_else(|| eyre!("expected lookup to find something in change set {change_set_id}"))
+    }
+
+    /// Runs the lookup in the [`ChangeSet`] with the given id and returns an error if it found
+    /// anything. ctx is switched back to its original change set afterwards.
+    pub async fn assert_not_visible_in<T, E, F, Fut>(
+        ctx: &mut DalContext,
+        change_set_id: ChangeSetId,
+        lookup: F,
+    ) -> Result<()>
+    where
+        T: Debug,
+        F: FnOnce(DalContext) -> Fut,
+        Fut: Future<Output = std::result::Result<Option<T>, E>>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        match Self::lookup_in(ctx, change_set_id, lookup).await? {
+            Some(found) => Err(eyre!(
+                "expected lookup to find nothing in change set {change_set_id}, found: {found:?}"
+            )),
+            None => Ok(()),
+        }
+    }
+
+    async fn lookup_in<T, E, F, Fut>(
+        ctx: &mut DalContext,
+        change_set_id: ChangeSetId,
+        lookup: F,
+    ) -> Result<Option<T>>
+    where
+        F: FnOnce(DalContext) -> Fut,
+        Fut: Future<Output = std::result::Result<Option<T>, E>>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let original_change_set_id = ctx.change_set_id();
+        Self::switch(ctx, change_set_id).await?;
+        let found = lookup(ctx.clone())
+            .await
+            .wrap_err_with(|| format!("lookup failed in change set {change_set_id}"));
+        Self::switch(ctx, original_change_set_id).await?;
+        found
+    }
+}
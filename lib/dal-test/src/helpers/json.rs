@@ -0,0 +1,117 @@
+//! This module contains helpers for asserting on JSON documents whose exact values (ids,
+//! timestamps, etc.) differ between test runs.
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+use dal::component::resource::ResourceView;
+use serde_json::Value;
+use ulid::Ulid;
+
+/// Matches any string that is a [`Ulid`] or a UUID.
+pub const ID_PLACEHOLDER: &str = "$id";
+/// Matches any string that is an RFC 3339 timestamp or a displayed [`DateTime<Utc>`].
+pub const TIMESTAMP_PLACEHOLDER: &str = "$timestamp";
+/// Matches any value, including `null`.
+pub const ANY_PLACEHOLDER: &str = "$any";
+
+/// Asserts that the actual document matches the expected one, where any string in the expected
+/// document may be a placeholder (see [`ID_PLACEHOLDER`], [`TIMESTAMP_PLACEHOLDER`] and
+/// [`ANY_PLACEHOLDER`]). Objects must have exactly the same keys and arrays the same length.
+///
+/// Panics with the path and reason of every mismatch.
+#[track_caller]
+pub fn assert_json_matches(actual: &Value, expected: &Value) {
+    let mismatches = json_mismatches(actual, expected);
+    assert!(
+        mismatches.is_empty(),
+        "json did not match the expected document:\n  {}\nactual: {}",
+        mismatches.join("\n  "),
+        serde_json::to_string_pretty(actual).unwrap_or_else(|_| actual.to_string()),
+    );
+}
+
+/// Asserts that the serialized [`ResourceView`] matches the expected document, as in
+/// [`assert_json_matches`].
+#[track_caller]
+pub fn assert_resource_view(view: &ResourceView, expected: &Value) {
+    #[allow(clippy::expect_used)]
+    let actual = serde_json::to_value(view).expect("could not serialize resource view");
+    assert_json_matches(&actual, expected);
+}
+
+/// Returns a description of every place the actual document does not match the expected one,
+/// prefixed with its path (e.g. `$.payload.items[2]`). See [`assert_json_matches`].
+pub fn json_mismatches(actual: &Value, expected: &Value) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    collect_mismatches("$", actual, expected, &mut mismatches);
+    mismatches
+}
+
+fn collect_mismatches(path: &str, actual: &Value, expected: &Value, mismatches: &mut Vec<String>) {
+    match (actual, expected) {
+        (_, Value::String(placeholder)) if placeholder == ANY_PLACEHOLDER => {}
+        (Value::String(actual), Value::String(placeholder)) if placeholder == ID_PLACEHOLDER => {
+            if !is_id(actual) {
+                mismatches.push(format!("{path}: expected an id, found {actual:?}"));
+            }
+        }
+        (Value::String(actual), Value::String(placeholder))
+            if placeholder == TIMESTAMP_PLACEHOLDER =>
+        {
+            if !is_timestamp(actual) {
+                mismatches.push(format!("{path}: expected a timestamp, found {actual:?}"));
+            }
+        }
+        (actual, Value::String(placeholder))
+            if placeholder == ID_PLACEHOLDER || placeholder == TIMESTAMP_PLACEHOLDER =>
+        {
+            mismatches.push(format!("{path}: expected {placeholder}, found {actual}"));
+        }
+        (Value::Object(actual), Value::Object(expected)) => {
+            for (key, expected_value) in expected {
+                let child_path = format!("{path}.{key}");
+                match actual.get(key) {
+                    Some(actual_value) => {
+                        collect_mismatches(&child_path, actual_value, expected_value, mismatches)
+                    }
+                    None => mismatches.push(format!("{child_path}: missing")),
+                }
+            }
+            for key in actual.keys().filter(|key| !expected.contains_key(*key)) {
+                mismatches.push(format!("{path}.{key}: unexpected"));
+            }
+        }
+        (Value::Array(actual), Value::Array(expected)) => {
+            if actual.len() != expected.len() {
+                mismatches.push(format!(
+                    "{path}: expected {} items, found {}",
+                    expected.len(),
+                    actual.len()
+                ));
+            }
+            for (index, (actual_value, expected_value)) in actual.iter().zip(expected).enumerate() {
+                collect_mismatches(
+                    &format!("{path}[{index}]"),
+                    actual_value,
+                    expected_value,
+                    mismatches,
+                );
+            }
+        }
+        (actual, expected) => {
+            if actual != expected {
+                mismatches.push(format!("{path}: expected {expected}, found {actual}"));
+            }
+        }
+    }
+}
+
+fn is_id(value: &str) -> bool {
+    Ulid::from_string(value).is_ok() || uuid::Uuid::parse_str(value).is_ok()
+}
+
+fn is_timestamp(value: &str) -> bool {
+    DateTime::parse_from_rfc3339(value).is_ok() || value.parse::<DateTime<Utc>>().is_ok()
+}
@@ -84,3 +84,53 @@ pub async fn create_overlay_action_func(
     FuncAuthoringClient::save_code(ctx, func.id, code.into()).await?;
     Ok(func.id)
 }
+
+/// What an action func created by [`create_test_action_func`] does when it runs
+#[derive(Debug, Clone)]
+pub enum TestActionOutcome {
+    /// Succeed, setting the resource payload to the given value
+    Succeed(serde_json::Value),
+    /// Fail with the given message
+    Fail(String),
+    /// Wait for the given number of milliseconds, then succeed with the given resource payload
+    SleepThenSucceed(u64, serde_json::Value),
+}
+
+impl TestActionOutcome {
+    /// Generate the code for an action func that produces this outcome
+    pub fn code(&self) -> Result<String> {
+        let code = match self {
+            Self::Succeed(payload) => format!(
+                "async function main() {{
+                    return {{ status: 'ok', payload: {} }};
+                }}",
+                serde_json::to_string(payload)?
+            ),
+            Self::Fail(message) => format!(
+                "async function main() {{
+                    return {{ status: 'error', message: {} }};
+                }}",
+                serde_json::to_string(message)?
+            ),
+            Self::SleepThenSucceed(millis, payload) => format!(
+                "async function main() {{
+                    await new Promise((resolve) => setTimeout(resolve, {millis}));
+                    return {{ status: 'ok', payload: {} }};
+                }}",
+                serde_json::to_string(payload)?
+            ),
+        };
+        Ok(code)
+    }
+}
+
+/// Create an action func overlay for the given schema whose code produces the given outcome
+pub async fn create_test_action_func(
+    ctx: &DalContext,
+    schema: impl SchemaKey,
+    name: impl Into<String>,
+    kind: ActionKind,
+    outcome: TestActionOutcome,
+) -> Result<FuncId> {
+    create_overlay_action_func(ctx, schema, name, outcome.code()?, kind).await
+}
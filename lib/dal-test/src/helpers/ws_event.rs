@@ -0,0 +1,133 @@
+//! This module provides [`WsEventCapture`].
+
+use std::time::Duration;
+
+use color_eyre::{
+    Result,
+    eyre::{
+        OptionExt,
+        eyre,
+    },
+};
+use dal::DalContext;
+use futures::StreamExt;
+use serde_json::Value;
+use si_data_nats::Subscriber;
+
+/// How long [`WsEventCapture::assert_no_event`] waits for stragglers before checking.
+const NO_EVENT_GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+/// Captures the [`WsEvents`](dal::WsEvent) published for a workspace, whether they were published
+/// immediately or when a transaction was committed.
+///
+/// Events are captured as JSON, since not every payload deserializes from what it serializes to.
+///
+/// Create it before doing whatever publishes the events, since only events published after
+/// [`Self::subscribe`] are captured.
+#[derive(Debug)]
+pub struct WsEventCapture {
+    subscriber: Subscriber,
+    received: Vec<Value>,
+}
+
+impl WsEventCapture {
+    /// Subscribes to the events for the workspace of the provided [`DalContext`].
+    pub async fn subscribe(ctx: &DalContext) -> Result<Self> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk_opt()
+            .ok_or_eyre("no workspace pk set on context")?;
+        let subscriber = ctx
+            .nats_conn()
+            .subscribe(format!("si.workspace_pk.{workspace_pk}.event"))
+            .await?;
+
+        Ok(Self {
+            subscriber,
+            received: Vec::new(),
+        })
+    }
+
+    /// Returns the first captured event the matcher accepts, waiting up to the timeout for it to
+    /// arrive. Events it skips over remain available to later calls.
+    pub async fn expect_event(
+        &mut self,
+        matcher: impl Fn(&Value) -> bool,
+        timeout: Duration,
+    ) -> Result<Value> {
+        if let Some(index) = self.received.iter().position(&matcher) {
+            return Ok(self.received.remove(index));
+        }
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let Some(event) = self.next_until(deadline).await? else {
+                return Err(eyre!(
+                    "no matching event received within {timeout:?}, received: [{}]",
+                    self.received_kinds().join(", ")
+                ));
+            };
+            if matcher(&event) {
+                return Ok(event);
+            }
+            self.received.push(event);
+        }
+    }
+
+    /// Returns the first captured event of the given kind (the name of its
+    /// [`WsPayload`](dal::WsPayload) variant, e.g. `"ResourceRefreshed"`), waiting up to the
+    /// timeout for it to arrive.
+    pub async fn expect_event_of_kind(
+        &mut self,
+        kind: impl AsRef<str>,
+        timeout: Duration,
+    ) -> Result<Value> {
+        let kind = kind.as_ref();
+        self.expect_event(|event| event_kind(event) == Some(kind), timeout)
+            .await
+    }
+
+    /// Returns an error if an event of the given kind has been captured, after giving any events
+    /// still in flight a moment to arrive.
+    pub async fn assert_no_event(&mut self, kind: impl AsRef<str>) -> Result<()> {
+        let kind = kind.as_ref();
+        let deadline = tokio::time::Instant::now() + NO_EVENT_GRACE_PERIOD;
+        while let Some(event) = self.next_until(deadline).await? {
+            self.received.push(event);
+        }
+
+        let count = self
+            .received
+            .iter()
+            .filter(|event| event_kind(event) == Some(kind))
+            .count();
+        if count > 0 {
+            return Err(eyre!(
+                "expected no {kind} events, but received {count} among: [{}]",
+                self.received_kinds().join(", ")
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn next_until(&mut self, deadline: tokio::time::Instant) -> Result<Option<Value>> {
+        match tokio::time::timeout_at(deadline, self.subscriber.next()).await {
+            Ok(Some(message)) => Ok(Some(serde_json::from_slice(message.payload())?)),
+            Ok(None) => Err(eyre!("ws event subscription closed")),
+            Err(_elapsed) => Ok(None),
+        }
+    }
+
+    fn received_kinds(&self) -> Vec<String> {
+        self.received
+            .iter()
+            .map(|event| event_kind(event).unwrap_or("<unknown>").to_string())
+            .collect()
+    }
+}
+
+/// Returns the name of the [`WsPayload`](dal::WsPayload) variant of a captured event.
+pub fn event_kind(event: &Value) -> Option<&str> {
+    event.get("payload")?.get("kind")?.as_str()
+}
@@ -55,6 +55,7 @@ use dal::{
         JobQueueProcessor,
         NatsProcessor,
     },
+    workspace_webhook::WebhookTargetPolicy,
 };
 use derive_builder::Builder;
 use jwt_simple::prelude::RS256KeyPair;
@@ -101,6 +102,8 @@ use uuid::Uuid;
 pub mod expand_helpers;
 pub mod expected;
 pub mod helpers;
+pub mod module_index_stub;
+pub mod pkg_fixture;
 pub mod prelude {
     //! This module provides a standard set of tools for authoring DAL integration tests.
     pub use color_eyre::{
@@ -561,6 +564,8 @@ impl TestContext {
             FeatureFlagService::default(),
             self.compute_executor.clone(),
         )
+        // Test webhook endpoints are served locally over plain http.
+        .with_webhook_target_policy(WebhookTargetPolicy::AllowLocal)
     }
 
     /// Gets a reference to the NATS configuration.
@@ -1147,7 +1152,8 @@ async fn migrate_local_builtins(
         layer_db.clone(),
         feature_flag_service,
         compute_executor,
-    );
+    )
+    .with_webhook_target_policy(WebhookTargetPolicy::AllowLocal);
     let dal_context = services_context.into_builder(true);
     let mut ctx = dal_context.build_default(None).await?;
 
@@ -0,0 +1,242 @@
+//! This module provides [`ModuleIndexStub`], an in-process stand-in for the module index.
+
+use std::{
+    net::TcpListener,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+    time::Duration,
+};
+
+use axum::{
+    Json,
+    Router,
+    extract::{
+        Path,
+        State,
+    },
+    http::StatusCode,
+    response::{
+        IntoResponse,
+        Response,
+    },
+    routing::get,
+};
+use chrono::Utc;
+use color_eyre::Result;
+use dal::{
+    DalContext,
+    SchemaId,
+};
+use module_index_types::{
+    BuiltinsDetailsResponse,
+    ModuleDetailsResponse,
+};
+use si_pkg::SiPkg;
+use telemetry::prelude::*;
+use tokio_util::sync::CancellationToken;
+use ulid::Ulid;
+
+#[derive(Debug, Default)]
+struct StubState {
+    builtins: Vec<StubBuiltin>,
+    failures_remaining: usize,
+    response_delay: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct StubBuiltin {
+    details: ModuleDetailsResponse,
+    package_data: Vec<u8>,
+}
+
+type SharedStubState = Arc<Mutex<StubState>>;
+
+/// A tiny HTTP server serving the builtin endpoints the `ModuleIndexClient` uses to populate the
+/// module cache (listing builtins and downloading them), backed by packages registered by the
+/// test, along with the system status endpoint used to check that the module index is up.
+///
+/// Use [`Self::ctx`] to get a [`DalContext`] pointed at it. The server shuts down when the stub
+/// is dropped.
+#[derive(Debug)]
+pub struct ModuleIndexStub {
+    url: String,
+    state: SharedStubState,
+    shutdown_token: CancellationToken,
+}
+
+impl ModuleIndexStub {
+    /// Starts the server on a random local port.
+    pub fn start() -> Result<Self> {
+        let state = SharedStubState::default();
+        let router = Router::new()
+            .route("/", get(system_status))
+            .route("/builtins", get(list_builtins))
+            .route("/modules/:module_id/download_builtin", get(get_builtin))
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        // NOTE: this must not be "localhost", since the client falls back to the production module
+        // index for "localhost" urls when a builtin is missing.
+        let url = format!("http://{}/", listener.local_addr()?);
+
+        let shutdown_token = CancellationToken::new();
+        let server = axum::Server::from_tcp(listener)?
+            .serve(router.into_make_service())
+            .with_graceful_shutdown(shutdown_token.clone().cancelled_owned());
+        tokio::task::spawn(async move {
+            if let Err(err) = server.await {
+                warn!(si.error.message = ?err, "module index stub server failed");
+            }
+        });
+
+        Ok(Self {
+            url,
+            state,
+            shutdown_token,
+        })
+    }
+
+    /// The base url of the server.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Clones the context, pointing it at this server instead of the configured module index.
+    pub fn ctx(&self, ctx: &DalContext) -> DalContext {
+        ctx.clone_with_module_index_url(&self.url)
+    }
+
+    /// Registers the package as the latest builtin for the schema and returns its module id.
+    pub fn add_builtin(
+        &self,
+        schema_id: SchemaId,
+        name: impl Into<String>,
+        package_data: Vec<u8>,
+    ) -> Result<Ulid> {
+        let latest_hash = SiPkg::load_from_bytes(&package_data)?.hash()?.to_string();
+        Ok(self.add_builtin_with_hash(schema_id, name, latest_hash, package_data))
+    }
+
+    /// Like [`Self::add_builtin`], but takes the hash as given rather than loading the package,
+    /// so that packages which cannot be loaded can be served too.
+    pub fn add_builtin_with_hash(
+        &self,
+        schema_id: SchemaId,
+        name: impl Into<String>,
+        latest_hash: impl Into<String>,
+        package_data: Vec<u8>,
+    ) -> Ulid {
+        let latest_hash = latest_hash.into();
+        let module_id = Ulid::new();
+        let now = Utc::now();
+
+        self.lock().builtins.push(StubBuiltin {
+            details: ModuleDetailsResponse {
+                id: module_id.to_string(),
+                name: name.into(),
+                description: None,
+                owner_user_id: "test".to_string(),
+                owner_display_name: None,
+                metadata: serde_json::Value::Null,
+                latest_hash,
+                latest_hash_created_at: now,
+                created_at: now,
+                schema_id: Some(schema_id.to_string()),
+                past_hashes: None,
+                schema_variant_id: None,
+                schema_variant_version: None,
+                structural_hash: None,
+            },
+            package_data,
+        });
+
+        module_id
+    }
+
+    /// Makes the next `count` requests fail with a 500, regardless of endpoint.
+    pub fn fail_next_requests(&self, count: usize) {
+        self.lock().failures_remaining = count;
+    }
+
+    /// Delays every response by the given duration, or stops delaying them if `None`.
+    pub fn set_response_delay(&self, delay: Option<Duration>) {
+        self.lock().response_delay = delay;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, StubState> {
+        lock(&self.state)
+    }
+}
+
+impl Drop for ModuleIndexStub {
+    fn drop(&mut self) {
+        self.shutdown_token.cancel();
+    }
+}
+
+fn lock(state: &SharedStubState) -> MutexGuard<'_, StubState> {
+    match state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Applies the configured delay, then returns an error response if the request should fail.
+async fn simulate(state: &SharedStubState) -> Option<Response> {
+    let (delay, fail) = {
+        let mut state = lock(state);
+        let fail = state.failures_remaining > 0;
+        if fail {
+            state.failures_remaining -= 1;
+        }
+        (state.response_delay, fail)
+    };
+
+    if let Some(delay) = delay {
+        tokio::time::sleep(delay).await;
+    }
+
+    fail.then(|| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+async fn system_status(State(state): State<SharedStubState>) -> Response {
+    if let Some(response) = simulate(&state).await {
+        return response;
+    }
+
+    StatusCode::OK.into_response()
+}
+
+async fn list_builtins(State(state): State<SharedStubState>) -> Response {
+    if let Some(response) = simulate(&state).await {
+        return response;
+    }
+
+    let modules = lock(&state)
+        .builtins
+        .iter()
+        .map(|builtin| builtin.details.clone())
+        .collect();
+    Json(BuiltinsDetailsResponse { modules }).into_response()
+}
+
+async fn get_builtin(
+    State(state): State<SharedStubState>,
+    Path(module_id): Path<String>,
+) -> Response {
+    if let Some(response) = simulate(&state).await {
+        return response;
+    }
+
+    match lock(&state)
+        .builtins
+        .iter()
+        .find(|builtin| builtin.details.id == module_id)
+    {
+        Some(builtin) => builtin.package_data.clone().into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
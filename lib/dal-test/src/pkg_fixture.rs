@@ -0,0 +1,239 @@
+//! This module provides [`PkgFixture`], small [`SiPkg`] packages built at test time so that tests
+//! don't need to check package binaries into the repo.
+
+use color_eyre::Result;
+use dal::{
+    ComponentType,
+    action::prototype::ActionKind,
+};
+use si_pkg::{
+    ActionFuncSpec,
+    LeafFunctionSpec,
+    LeafInputLocation,
+    LeafKind,
+    PkgSpec,
+    SchemaSpec,
+    SchemaSpecData,
+    SchemaVariantSpec,
+    SchemaVariantSpecData,
+    SiPkg,
+};
+
+use crate::test_exclusive_schemas::{
+    PKG_CREATED_BY,
+    PKG_VERSION,
+    build_action_func,
+    build_asset_func,
+    build_codegen_func,
+};
+
+/// The name of the schema in the [`simple_schema`] fixture.
+pub const SIMPLE_SCHEMA: &str = "simple-schema";
+/// The name of the schema in the [`schema_with_actions`] fixture.
+pub const SCHEMA_WITH_ACTIONS: &str = "schema-with-actions";
+
+/// A package containing a single schema with one variant and no funcs beyond its asset func.
+pub fn simple_schema() -> Result<PkgFixture> {
+    PkgFixture::builder(SIMPLE_SCHEMA).build()
+}
+
+/// A package containing a single schema whose variant has create and refresh actions and a code
+/// generation func.
+pub fn schema_with_actions() -> Result<PkgFixture> {
+    PkgFixture::builder(SCHEMA_WITH_ACTIONS)
+        .action_func(
+            ActionKind::Create,
+            "test:createSchemaWithActions",
+            "async function main() {
+                return { payload: { \"created\": true }, status: \"ok\" };
+            }",
+        )
+        .action_func(
+            ActionKind::Refresh,
+            "test:refreshSchemaWithActions",
+            "async function main(component: Input): Promise<Output> {
+                return { payload: JSON.parse(component.properties.resource?.payload), status: \"ok\" };
+            }",
+        )
+        .codegen_func(
+            "test:generateSchemaWithActionsCode",
+            "async function main(input: Input): Promise<Output> {
+                return { format: \"json\", code: JSON.stringify(input.domain || {}) };
+            }",
+        )
+        .build()
+}
+
+#[derive(Debug, Clone)]
+enum FixtureFunc {
+    Action {
+        kind: ActionKind,
+        name: String,
+        code: String,
+    },
+    CodeGeneration {
+        name: String,
+        code: String,
+    },
+}
+
+/// Builds a [`PkgFixture`] containing one schema with one variant.
+#[derive(Debug, Clone)]
+pub struct PkgFixtureBuilder {
+    schema_name: String,
+    version: String,
+    category: String,
+    funcs: Vec<FixtureFunc>,
+}
+
+impl PkgFixtureBuilder {
+    /// Sets the version of the package, which changes its hash.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Sets the category of the schema.
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = category.into();
+        self
+    }
+
+    /// Adds an action func of the given kind, with the given code, to the variant.
+    pub fn action_func(
+        mut self,
+        kind: ActionKind,
+        name: impl Into<String>,
+        code: impl Into<String>,
+    ) -> Self {
+        self.funcs.push(FixtureFunc::Action {
+            kind,
+            name: name.into(),
+            code: code.into(),
+        });
+        self
+    }
+
+    /// Adds a code generation func on the domain, with the given code, to the variant.
+    pub fn codegen_func(mut self, name: impl Into<String>, code: impl Into<String>) -> Self {
+        self.funcs.push(FixtureFunc::CodeGeneration {
+            name: name.into(),
+            code: code.into(),
+        });
+        self
+    }
+
+    /// Builds the package and serializes it.
+    pub fn build(self) -> Result<PkgFixture> {
+        let asset_func = build_asset_func(&format!("test:scaffold:{}", self.schema_name))?;
+
+        let mut variant = SchemaVariantSpec::builder();
+        variant
+            .version("v0")
+            .unique_id(format!("{}_sv", self.schema_name))
+            .data(
+                SchemaVariantSpecData::builder()
+                    .version("v0")
+                    .color("#ffffff")
+                    .func_unique_id(&asset_func.unique_id)
+                    .component_type(ComponentType::Component)
+                    .build()?,
+            );
+
+        let mut pkg = PkgSpec::builder();
+        pkg.name(&self.schema_name)
+            .version(&self.version)
+            .created_by(PKG_CREATED_BY)
+            .func(asset_func);
+
+        for func in self.funcs {
+            match func {
+                FixtureFunc::Action { kind, name, code } => {
+                    let func = build_action_func(&code, &name)?;
+                    variant.action_func(
+                        ActionFuncSpec::builder()
+                            .kind(kind)
+                            .func_unique_id(&func.unique_id)
+                            .build()?,
+                    );
+                    pkg.func(func);
+                }
+                FixtureFunc::CodeGeneration { name, code } => {
+                    let func = build_codegen_func(&code, &name)?;
+                    variant.leaf_function(
+                        LeafFunctionSpec::builder()
+                            .func_unique_id(&func.unique_id)
+                            .leaf_kind(LeafKind::CodeGeneration)
+                            .inputs(vec![LeafInputLocation::Domain])
+                            .build()?,
+                    );
+                    pkg.func(func);
+                }
+            }
+        }
+
+        let schema = SchemaSpec::builder()
+            .name(&self.schema_name)
+            .data(
+                SchemaSpecData::builder()
+                    .name(&self.schema_name)
+                    .category(&self.category)
+                    .category_name(&self.schema_name)
+                    .build()?,
+            )
+            .variant(variant.build()?)
+            .build()?;
+
+        let pkg = SiPkg::load_from_spec(pkg.schema(schema).build()?)?;
+        let bytes = pkg.write_to_bytes()?;
+
+        Ok(PkgFixture { pkg, bytes })
+    }
+}
+
+/// A package built by a [`PkgFixtureBuilder`], along with its serialized bytes.
+#[derive(Debug, Clone)]
+pub struct PkgFixture {
+    /// The package.
+    pub pkg: SiPkg,
+    /// The package, as it would be stored in the module index or the module cache.
+    pub bytes: Vec<u8>,
+}
+
+impl PkgFixture {
+    /// Starts building a package containing a schema with the given name.
+    pub fn builder(schema_name: impl Into<String>) -> PkgFixtureBuilder {
+        PkgFixtureBuilder {
+            schema_name: schema_name.into(),
+            version: PKG_VERSION.to_string(),
+            category: "test exclusive".to_string(),
+            funcs: Vec::new(),
+        }
+    }
+
+    /// The hash of the package, as reported by the module index.
+    pub fn hash(&self) -> Result<String> {
+        Ok(self.pkg.hash()?.to_string())
+    }
+
+    /// Returns the bytes with the first tar header damaged, so they can no longer be loaded as a
+    /// package at all.
+    pub fn corrupted_bytes(&self) -> Vec<u8> {
+        self.bytes_with_flipped_byte(0)
+    }
+
+    /// Returns the bytes with the byte at the offset inverted. Depending on where the offset
+    /// lands, the result may still load, but not necessarily as the same package.
+    pub fn bytes_with_flipped_byte(&self, offset: usize) -> Vec<u8> {
+        let mut bytes = self.bytes.clone();
+        if let Some(byte) = bytes.get_mut(offset) {
+            *byte = !*byte;
+        }
+        bytes
+    }
+
+    /// Returns only the first `len` bytes, as if a download had been cut short.
+    pub fn truncated_bytes(&self, len: usize) -> Vec<u8> {
+        self.bytes[..len.min(self.bytes.len())].to_vec()
+    }
+}
@@ -58,8 +58,8 @@ mod legos;
 mod starfield;
 mod swifty;
 
-const PKG_VERSION: &str = "2019-06-03";
-const PKG_CREATED_BY: &str = "System Initiative";
+pub(crate) const PKG_VERSION: &str = "2019-06-03";
+pub(crate) const PKG_CREATED_BY: &str = "System Initiative";
 
 /// Schema id for the `dummy double secret` schema variant
 pub const SCHEMA_ID_DUMMY_DOUBLE_SECRET: &str = "01JARFRNN5VV1NMM0H5M63K3QX";
@@ -288,7 +288,7 @@ fn build_management_func(code: &str, fn_name: &str) -> BuiltinsResult<FuncSpec>
         .build()?)
 }
 
-fn build_action_func(code: &str, fn_name: &str) -> BuiltinsResult<FuncSpec> {
+pub(crate) fn build_action_func(code: &str, fn_name: &str) -> BuiltinsResult<FuncSpec> {
     let func = FuncSpec::builder()
         .name(fn_name)
         .unique_id(fn_name)
@@ -306,7 +306,7 @@ fn build_action_func(code: &str, fn_name: &str) -> BuiltinsResult<FuncSpec> {
     Ok(func)
 }
 
-fn build_codegen_func(code: &str, fn_name: &str) -> BuiltinsResult<FuncSpec> {
+pub(crate) fn build_codegen_func(code: &str, fn_name: &str) -> BuiltinsResult<FuncSpec> {
     let func = FuncSpec::builder()
         .name(fn_name)
         .unique_id(fn_name)
@@ -330,7 +330,7 @@ fn build_codegen_func(code: &str, fn_name: &str) -> BuiltinsResult<FuncSpec> {
     Ok(func)
 }
 
-fn build_asset_func(fn_name: &str) -> BuiltinsResult<FuncSpec> {
+pub(crate) fn build_asset_func(fn_name: &str) -> BuiltinsResult<FuncSpec> {
     let scaffold_func = "function main() {\
                 return new AssetBuilder().build();
             }";
@@ -35,6 +35,7 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
+    ChangeSet,
     ChangeSetError,
     ChangeSetId,
     Component,
@@ -79,8 +80,10 @@ use crate::{
     },
 };
 
+pub mod batch;
 pub mod dependency_graph;
 pub mod prototype;
+pub mod report;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -490,6 +493,8 @@ impl Action {
         action_prototype_id: ActionPrototypeId,
         maybe_component_id: Option<ComponentId>,
     ) -> ActionResult<Self> {
+        ChangeSet::error_if_not_active(ctx).await?;
+
         let new_id: ActionId = ctx.workspace_snapshot()?.generate_ulid().await?.into();
         let lineage_id = ctx.workspace_snapshot()?.generate_ulid().await?;
 
@@ -0,0 +1,503 @@
+//! This module contains [`ActionBatch`], which enqueues an [`ActionPrototype`] once for every
+//! component it applies to (e.g. refreshing every EC2 instance in a view) and tracks the runs as
+//! a whole.
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_data_pg::{
+    PgError,
+    PgRow,
+};
+pub use si_id::ActionBatchId;
+use si_layer_cache::LayerDbError;
+use strum::Display;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use super::{
+    Action,
+    ActionError,
+    ActionId,
+    ActionPrototypeId,
+    ActionState,
+    prototype::{
+        ActionPrototype,
+        ActionPrototypeError,
+    },
+};
+use crate::{
+    ChangeSetId,
+    Component,
+    ComponentError,
+    ComponentId,
+    DalContext,
+    SchemaVariant,
+    SchemaVariantError,
+    TransactionsError,
+    WorkspacePk,
+    WorkspaceSnapshotError,
+    diagram::{
+        DiagramError,
+        geometry::Geometry,
+        view::ViewId,
+    },
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ActionBatchError {
+    #[error("action error: {0}")]
+    Action(#[from] Box<ActionError>),
+    #[error("action batch not found: {0}")]
+    ActionBatchNotFound(ActionBatchId),
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] Box<ActionPrototypeError>),
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] Box<DiagramError>),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] Box<SchemaVariantError>),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] Box<TransactionsError>),
+    #[error("workspace snapshot error: {0}")]
+    WorkspaceSnapshot(#[from] Box<WorkspaceSnapshotError>),
+}
+
+impl From<ActionError> for ActionBatchError {
+    fn from(value: ActionError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<ActionPrototypeError> for ActionBatchError {
+    fn from(value: ActionPrototypeError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<ComponentError> for ActionBatchError {
+    fn from(value: ComponentError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<DiagramError> for ActionBatchError {
+    fn from(value: DiagramError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<SchemaVariantError> for ActionBatchError {
+    fn from(value: SchemaVariantError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<TransactionsError> for ActionBatchError {
+    fn from(value: TransactionsError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+impl From<WorkspaceSnapshotError> for ActionBatchError {
+    fn from(value: WorkspaceSnapshotError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+pub type ActionBatchResult<T> = Result<T, ActionBatchError>;
+
+/// Narrows down the components an [`ActionBatch`] runs for. By default, it runs for every
+/// component the prototype applies to.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionBatchFilter {
+    /// Only run for the components in this view.
+    pub view_id: Option<ViewId>,
+    /// Only run for the components that have a resource.
+    #[serde(default)]
+    pub with_resource: bool,
+}
+
+/// A component an [`ActionBatch`] ran for, along with its action or, if it couldn't be enqueued,
+/// why not.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionBatchMember {
+    pub component_id: ComponentId,
+    pub action_id: Option<ActionId>,
+    pub error: Option<String>,
+}
+
+/// The state of the action of an [`ActionBatchMember`].
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ActionBatchMemberState {
+    Dispatched,
+    Failed,
+    /// The action could not be enqueued for the component.
+    NotEnqueued,
+    OnHold,
+    Queued,
+    /// The action is gone without having succeeded (e.g. it was cancelled).
+    Removed,
+    Running,
+    /// The action ran successfully, and was removed from the graph.
+    Succeeded,
+}
+
+impl From<ActionState> for ActionBatchMemberState {
+    fn from(value: ActionState) -> Self {
+        match value {
+            ActionState::Dispatched => Self::Dispatched,
+            ActionState::Failed => Self::Failed,
+            ActionState::OnHold => Self::OnHold,
+            ActionState::Queued => Self::Queued,
+            ActionState::Running => Self::Running,
+        }
+    }
+}
+
+impl ActionBatchMemberState {
+    /// Whether the action is done, one way or the other. Failed and on hold actions stay on the
+    /// graph, but won't run again unless someone retries them.
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, Self::Dispatched | Self::Queued | Self::Running)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionBatchMemberStatus {
+    pub component_id: ComponentId,
+    pub action_id: Option<ActionId>,
+    pub state: ActionBatchMemberState,
+    pub error: Option<String>,
+}
+
+/// The aggregated state of the runs of an [`ActionBatch`], as returned by [`ActionBatch::status`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionBatchStatus {
+    pub batch_id: ActionBatchId,
+    /// Whether every member is finished (see [`ActionBatchMemberState::is_finished`]).
+    pub finished: bool,
+    pub succeeded: usize,
+    /// Members whose action failed or could not be enqueued.
+    pub failed: usize,
+    pub members: Vec<ActionBatchMemberStatus>,
+}
+
+/// A run of an [`ActionPrototype`] for every component it applies to.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionBatch {
+    id: ActionBatchId,
+    workspace_pk: WorkspacePk,
+    change_set_id: ChangeSetId,
+    action_prototype_id: ActionPrototypeId,
+    members: Vec<ActionBatchMember>,
+    /// The batch whose failed members this batch re-ran, if any.
+    retries_batch_id: Option<ActionBatchId>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for ActionBatch {
+    type Error = ActionBatchError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let members: serde_json::Value = row.try_get("members")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            change_set_id: row.try_get("change_set_id")?,
+            action_prototype_id: row.try_get("action_prototype_id")?,
+            members: serde_json::from_value(members)?,
+            retries_batch_id: row.try_get("retries_batch_id")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl ActionBatch {
+    pub fn id(&self) -> ActionBatchId {
+        self.id
+    }
+
+    pub fn change_set_id(&self) -> ChangeSetId {
+        self.change_set_id
+    }
+
+    pub fn action_prototype_id(&self) -> ActionPrototypeId {
+        self.action_prototype_id
+    }
+
+    pub fn members(&self) -> &[ActionBatchMember] {
+        &self.members
+    }
+
+    /// The ids of the actions that were enqueued.
+    pub fn action_ids(&self) -> Vec<ActionId> {
+        self.members
+            .iter()
+            .filter_map(|member| member.action_id)
+            .collect()
+    }
+
+    pub fn retries_batch_id(&self) -> Option<ActionBatchId> {
+        self.retries_batch_id
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Enqueues the prototype for every component of the schema variant(s) it belongs to that
+    /// matches the filter. Components that already have the action enqueued reuse it.
+    ///
+    /// Failing to enqueue for a component is recorded on its member rather than failing the
+    /// batch. The actions are dispatched like any other, so the batch is subject to the same
+    /// concurrency limit as the rest of the workspace's actions.
+    pub async fn run_for_all_components(
+        ctx: &DalContext,
+        action_prototype_id: ActionPrototypeId,
+        filter: ActionBatchFilter,
+    ) -> ActionBatchResult<Self> {
+        let mut members = Vec::new();
+        for component_id in Self::matching_component_ids(ctx, action_prototype_id, &filter).await? {
+            members.push(Self::enqueue_member(ctx, action_prototype_id, component_id).await);
+        }
+
+        Self::insert(ctx, action_prototype_id, members, None).await
+    }
+
+    /// Starts a new batch which re-runs only the members of the given batch that failed, could
+    /// not be enqueued or were removed without succeeding. Every other member is carried over
+    /// as is, so the actions that already succeeded (or are still underway) are not run again
+    /// and the new batch's [`status`](Self::status) covers all of the original components.
+    ///
+    /// The new batch records the one it retries in [`retries_batch_id`](Self::retries_batch_id).
+    pub async fn rerun_failed(ctx: &DalContext, id: ActionBatchId) -> ActionBatchResult<Self> {
+        let batch = Self::get_by_id(ctx, id).await?;
+
+        let mut members = Vec::with_capacity(batch.members.len());
+        for member in batch.members {
+            let state = match member.action_id {
+                Some(action_id) => Self::action_state(ctx, action_id).await?,
+                None => ActionBatchMemberState::NotEnqueued,
+            };
+            let member = match state {
+                ActionBatchMemberState::Failed
+                | ActionBatchMemberState::NotEnqueued
+                | ActionBatchMemberState::Removed => {
+                    Self::enqueue_member(ctx, batch.action_prototype_id, member.component_id).await
+                }
+                ActionBatchMemberState::Dispatched
+                | ActionBatchMemberState::OnHold
+                | ActionBatchMemberState::Queued
+                | ActionBatchMemberState::Running
+                | ActionBatchMemberState::Succeeded => member,
+            };
+            members.push(member);
+        }
+
+        Self::insert(ctx, batch.action_prototype_id, members, Some(batch.id)).await
+    }
+
+    pub async fn get_by_id(ctx: &DalContext, id: ActionBatchId) -> ActionBatchResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM action_batches WHERE id = $1 AND workspace_pk = $2",
+                &[&id, &ctx.workspace_pk()?],
+            )
+            .await?
+            .ok_or(ActionBatchError::ActionBatchNotFound(id))?;
+
+        Self::try_from(row)
+    }
+
+    /// Aggregates the states of the batch's actions, as seen by the context's change set.
+    pub async fn status(
+        ctx: &DalContext,
+        id: ActionBatchId,
+    ) -> ActionBatchResult<ActionBatchStatus> {
+        let batch = Self::get_by_id(ctx, id).await?;
+
+        let mut members = Vec::with_capacity(batch.members.len());
+        for member in batch.members {
+            let state = match member.action_id {
+                Some(action_id) => Self::action_state(ctx, action_id).await?,
+                None => ActionBatchMemberState::NotEnqueued,
+            };
+            members.push(ActionBatchMemberStatus {
+                component_id: member.component_id,
+                action_id: member.action_id,
+                state,
+                error: member.error,
+            });
+        }
+
+        Ok(ActionBatchStatus {
+            batch_id: batch.id,
+            finished: members.iter().all(|member| member.state.is_finished()),
+            succeeded: members
+                .iter()
+                .filter(|member| member.state == ActionBatchMemberState::Succeeded)
+                .count(),
+            failed: members
+                .iter()
+                .filter(|member| {
+                    matches!(
+                        member.state,
+                        ActionBatchMemberState::Failed | ActionBatchMemberState::NotEnqueued
+                    )
+                })
+                .count(),
+            members,
+        })
+    }
+
+    async fn matching_component_ids(
+        ctx: &DalContext,
+        action_prototype_id: ActionPrototypeId,
+        filter: &ActionBatchFilter,
+    ) -> ActionBatchResult<Vec<ComponentId>> {
+        let schema_variant_ids =
+            ActionPrototype::schema_variant_ids(ctx, action_prototype_id).await?;
+
+        let mut component_ids = Vec::new();
+        for schema_variant_id in schema_variant_ids {
+            for component_id in SchemaVariant::list_component_ids(ctx, schema_variant_id).await? {
+                if let Some(view_id) = filter.view_id {
+                    if !Geometry::by_view_for_component_id(ctx, component_id)
+                        .await?
+                        .contains_key(&view_id)
+                    {
+                        continue;
+                    }
+                }
+                if filter.with_resource
+                    && Component::resource_by_id(ctx, component_id)
+                        .await?
+                        .is_none()
+                {
+                    continue;
+                }
+                component_ids.push(component_id);
+            }
+        }
+        component_ids.sort();
+
+        Ok(component_ids)
+    }
+
+    async fn insert(
+        ctx: &DalContext,
+        action_prototype_id: ActionPrototypeId,
+        members: Vec<ActionBatchMember>,
+        retries_batch_id: Option<ActionBatchId>,
+    ) -> ActionBatchResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO action_batches (workspace_pk, change_set_id, action_prototype_id, members, retries_batch_id) VALUES ($1, $2, $3, $4, $5) RETURNING *",
+                &[
+                    &ctx.workspace_pk()?,
+                    &ctx.change_set_id(),
+                    &action_prototype_id,
+                    &serde_json::to_value(&members)?,
+                    &retries_batch_id,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Enqueues the prototype for the component, recording why not on the member if it can't be.
+    async fn enqueue_member(
+        ctx: &DalContext,
+        action_prototype_id: ActionPrototypeId,
+        component_id: ComponentId,
+    ) -> ActionBatchMember {
+        match Self::enqueue(ctx, action_prototype_id, component_id).await {
+            Ok(action_id) => ActionBatchMember {
+                component_id,
+                action_id: Some(action_id),
+                error: None,
+            },
+            Err(err) => {
+                warn!(si.error.message = ?err, %component_id, %action_prototype_id, "unable to enqueue action for batch");
+                ActionBatchMember {
+                    component_id,
+                    action_id: None,
+                    error: Some(err.to_string()),
+                }
+            }
+        }
+    }
+
+    async fn enqueue(
+        ctx: &DalContext,
+        action_prototype_id: ActionPrototypeId,
+        component_id: ComponentId,
+    ) -> ActionBatchResult<ActionId> {
+        if let Some(action_id) =
+            Action::find_equivalent(ctx, action_prototype_id, Some(component_id)).await?
+        {
+            let action = Action::get_by_id(ctx, action_id).await?;
+            if matches!(action.state(), ActionState::Failed | ActionState::OnHold) {
+                Action::set_state(ctx, action_id, ActionState::Queued).await?;
+            }
+            return Ok(action_id);
+        }
+
+        Ok(Action::new(ctx, action_prototype_id, Some(component_id))
+            .await?
+            .id())
+    }
+
+    async fn action_state(
+        ctx: &DalContext,
+        action_id: ActionId,
+    ) -> ActionBatchResult<ActionBatchMemberState> {
+        if ctx.workspace_snapshot()?.node_exists(action_id).await {
+            return Ok(Action::get_by_id(ctx, action_id).await?.state().into());
+        }
+
+        // Actions are removed from the graph once they succeed.
+        let succeeded = ctx
+            .layer_db()
+            .func_run()
+            .get_last_run_for_action_id_opt(ctx.events_tenancy().workspace_pk, action_id)
+            .await?
+            .and_then(|func_run| func_run.action_result_state())
+            == Some(si_events::ActionResultState::Success);
+
+        Ok(if succeeded {
+            ActionBatchMemberState::Succeeded
+        } else {
+            ActionBatchMemberState::Removed
+        })
+    }
+}
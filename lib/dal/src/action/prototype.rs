@@ -1,5 +1,12 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
 
+use chrono::{
+    DateTime,
+    Utc,
+};
 use petgraph::{
     Direction::Incoming,
     Outgoing,
@@ -11,6 +18,7 @@ use serde::{
 use si_events::{
     ActionResultState,
     FuncRunId,
+    FuncRunState,
 };
 use si_frontend_types::DiagramComponentView;
 use si_id::{
@@ -29,6 +37,7 @@ use veritech_client::{
 use super::ActionError;
 use crate::{
     ActionPrototypeId,
+    ChangeSet,
     ChangeSetError,
     Component,
     ComponentError,
@@ -36,6 +45,7 @@ use crate::{
     DalContext,
     EdgeWeightKind,
     EdgeWeightKindDiscriminants,
+    Func,
     FuncError,
     HelperError,
     Schema,
@@ -57,6 +67,7 @@ use crate::{
     diagram::DiagramError,
     func::{
         FuncId,
+        FuncKind,
         binding::EventualParent,
         runner::{
             FuncRunner,
@@ -89,6 +100,8 @@ pub enum ActionPrototypeError {
     Diagram(#[from] Box<DiagramError>),
     #[error("func error: {0}")]
     Func(#[from] Box<FuncError>),
+    #[error("func {0} is not an action func (found kind: {1})")]
+    FuncNotAction(FuncId, FuncKind),
     #[error("func not found for prototype: {0}")]
     FuncNotFoundForPrototype(ActionPrototypeId),
     #[error("func runner error: {0}")]
@@ -287,6 +300,37 @@ pub struct ActionPrototype {
     pub description: Option<String>,
 }
 
+/// The most recent run of an [`ActionPrototype`] for a component, as listed by
+/// [`ActionPrototype::latest_runs_for_component`]. The run fields are empty if the prototype has
+/// never run for the component.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPrototypeLatestRun {
+    pub action_prototype_id: ActionPrototypeId,
+    pub kind: ActionKind,
+    pub name: String,
+    pub func_run_id: Option<FuncRunId>,
+    pub state: Option<FuncRunState>,
+    pub action_result_state: Option<ActionResultState>,
+    pub ran_at: Option<DateTime<Utc>>,
+}
+
+/// An [`ActionPrototype`] whose func code differs from HEAD, so that applying the change set
+/// changes what its action does, as listed by [`ActionPrototype::preview_impact`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPrototypeImpact {
+    pub action_prototype_id: ActionPrototypeId,
+    pub kind: ActionKind,
+    pub name: String,
+    pub func_id: FuncId,
+    /// Set when the func doesn't exist on HEAD.
+    pub func_added: bool,
+    pub schema_variant_ids: Vec<SchemaVariantId>,
+    /// The components in the change set that the action applies to.
+    pub component_ids: Vec<ComponentId>,
+}
+
 impl From<ActionPrototypeNodeWeight> for ActionPrototype {
     fn from(value: ActionPrototypeNodeWeight) -> Self {
         Self {
@@ -378,6 +422,93 @@ impl ActionPrototype {
         Ok(new_prototype)
     }
 
+    /// Creates an [`ActionPrototype`] of the given kind for the [`SchemaVariant`], using the
+    /// provided [action](FuncKind::Action) func. If the variant already has a prototype of that
+    /// kind (or, for [`ActionKind::Manual`], one with the same name), it is pointed at the func and
+    /// renamed instead, so that the actions already enqueued for it run the new func.
+    pub async fn upsert_for_variant(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        func_id: FuncId,
+        kind: ActionKind,
+        name: String,
+    ) -> ActionPrototypeResult<Self> {
+        let func = Func::get_by_id(ctx, func_id).await?;
+        if func.kind != FuncKind::Action {
+            return Err(ActionPrototypeError::FuncNotAction(func_id, func.kind));
+        }
+
+        let existing = Self::for_variant(ctx, schema_variant_id)
+            .await?
+            .into_iter()
+            .find(|prototype| {
+                prototype.kind == kind && (kind != ActionKind::Manual || prototype.name == name)
+            });
+
+        match existing {
+            Some(prototype) => {
+                Self::set_func(ctx, prototype.id, func_id).await?;
+                Self::set_name(ctx, prototype.id, name).await
+            }
+            None => {
+                Self::new(
+                    ctx,
+                    kind,
+                    name,
+                    func.description,
+                    schema_variant_id,
+                    func_id,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Renames the [`ActionPrototype`].
+    pub async fn set_name(
+        ctx: &DalContext,
+        id: ActionPrototypeId,
+        name: impl AsRef<str>,
+    ) -> ActionPrototypeResult<Self> {
+        let mut node_weight = ctx
+            .workspace_snapshot()?
+            .get_node_weight(id)
+            .await?
+            .get_action_prototype_node_weight()?;
+        node_weight.set_name(name);
+        ctx.workspace_snapshot()?
+            .add_or_replace_node(NodeWeight::ActionPrototype(node_weight.clone()))
+            .await?;
+
+        Ok(node_weight.into())
+    }
+
+    /// Points the [`ActionPrototype`] at another [action](FuncKind::Action) func. Since the func
+    /// is looked up whenever the prototype is run, the actions already enqueued for it will run the
+    /// new func.
+    pub async fn set_func(
+        ctx: &DalContext,
+        id: ActionPrototypeId,
+        func_id: FuncId,
+    ) -> ActionPrototypeResult<()> {
+        let func = Func::get_by_id(ctx, func_id).await?;
+        if func.kind != FuncKind::Action {
+            return Err(ActionPrototypeError::FuncNotAction(func_id, func.kind));
+        }
+
+        let current_func_id = Self::func_id(ctx, id).await?;
+        if current_func_id == func_id {
+            return Ok(());
+        }
+
+        ctx.workspace_snapshot()?
+            .remove_edge(id, current_func_id, EdgeWeightKindDiscriminants::Use)
+            .await?;
+        Self::add_edge_to_func(ctx, id, func_id, EdgeWeightKind::new_use()).await?;
+
+        Ok(())
+    }
+
     pub fn name(&self) -> &String {
         &self.name
     }
@@ -530,6 +661,98 @@ impl ActionPrototype {
         Ok(None)
     }
 
+    /// Returns the Schema Variants this Action Prototype applies to: the one it is defined on, or
+    /// every variant of the Schema(s) it is defined on.
+    pub async fn schema_variant_ids(
+        ctx: &DalContext,
+        id: ActionPrototypeId,
+    ) -> ActionPrototypeResult<Vec<SchemaVariantId>> {
+        Ok(match Self::parentage(ctx, id).await? {
+            ActionPrototypeParent::SchemaVariant(schema_variant_id) => vec![schema_variant_id],
+            ActionPrototypeParent::Schemas(schema_ids) => {
+                let mut schema_variant_ids = Vec::new();
+                for schema_id in schema_ids {
+                    schema_variant_ids.extend(
+                        Schema::list_schema_variant_ids(ctx, schema_id)
+                            .await
+                            .map_err(Box::new)?,
+                    );
+                }
+                schema_variant_ids
+            }
+        })
+    }
+
+    /// Lists the Action Prototypes whose action func runs different code in this change set than
+    /// on HEAD, along with what they apply to.
+    ///
+    /// Only funcs whose node differs from HEAD are loaded, so this stays cheap for change sets that
+    /// don't touch action funcs.
+    pub async fn preview_impact(
+        ctx: &DalContext,
+    ) -> ActionPrototypeResult<Vec<ActionPrototypeImpact>> {
+        let head_ctx = ctx.clone_with_head().await?;
+        let snapshot = ctx.workspace_snapshot()?;
+        let head_snapshot = head_ctx.workspace_snapshot()?;
+
+        let mut impacts = Vec::new();
+        for func_id in Func::list_ids(ctx).await? {
+            let Some(NodeWeight::Func(node_weight)) = snapshot.get_node_weight_opt(func_id).await
+            else {
+                continue;
+            };
+            if node_weight.func_kind() != FuncKind::Action {
+                continue;
+            }
+            let head_content_hash = match head_snapshot.get_node_weight_opt(func_id).await {
+                Some(NodeWeight::Func(head_node_weight)) => Some(head_node_weight.content_hash()),
+                _ => None,
+            };
+            if head_content_hash == Some(node_weight.content_hash()) {
+                continue;
+            }
+
+            // The content changed, but not necessarily the code (e.g. a renamed func).
+            let func_added = match head_content_hash {
+                Some(_) => {
+                    let func = Func::get_by_id(ctx, func_id).await?;
+                    let head_func = Func::get_by_id(&head_ctx, func_id).await?;
+                    if func.code_blake3 == head_func.code_blake3
+                        && func.handler == head_func.handler
+                    {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            };
+
+            for action_prototype_id in Self::list_for_func_id(ctx, func_id).await? {
+                let prototype = Self::get_by_id(ctx, action_prototype_id).await?;
+                let schema_variant_ids = Self::schema_variant_ids(ctx, action_prototype_id).await?;
+                let mut component_ids = Vec::new();
+                for schema_variant_id in &schema_variant_ids {
+                    component_ids
+                        .extend(SchemaVariant::list_component_ids(ctx, *schema_variant_id).await?);
+                }
+                component_ids.sort();
+
+                impacts.push(ActionPrototypeImpact {
+                    action_prototype_id,
+                    kind: prototype.kind,
+                    name: prototype.name,
+                    func_id,
+                    func_added,
+                    schema_variant_ids,
+                    component_ids,
+                });
+            }
+        }
+        impacts.sort_by_key(|impact| impact.action_prototype_id);
+
+        Ok(impacts)
+    }
+
     /// Find the action prototype for a given kind. If a prototype is one of the
     /// unique ones (`ActionKind::Create`, `ActionKind::Update`,
     /// `ActionKind::Refresh`, `ActionKind::Destroy`), then look first for the
@@ -605,6 +828,8 @@ impl ActionPrototype {
         id: ActionPrototypeId,
         component_id: ComponentId,
     ) -> ActionPrototypeResult<(Option<ActionRunResultSuccess>, FuncRunId)> {
+        ChangeSet::error_if_not_active(ctx).await?;
+
         let component = Component::get_by_id(ctx, component_id).await?;
         let component_view = component.view(ctx).await?;
         let func_id = Self::func_id(ctx, id).await?;
@@ -691,6 +916,50 @@ impl ActionPrototype {
         Ok((maybe_run_result, func_run_value.func_run_id()))
     }
 
+    /// Lists every [`ActionPrototype`] available to the component along with its most recent run
+    /// for the component, fetched in a single query.
+    pub async fn latest_runs_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ActionPrototypeResult<Vec<ActionPrototypeLatestRun>> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let schema_variant_id = Component::schema_variant_id(ctx, component_id).await?;
+        let prototypes = Self::list_for_schema_and_variant_id(ctx, schema_variant_id).await?;
+
+        let prototype_ids: Vec<ActionPrototypeId> =
+            prototypes.iter().map(|prototype| prototype.id).collect();
+        let mut latest_runs: HashMap<ActionPrototypeId, _> = ctx
+            .layer_db()
+            .func_run()
+            .list_latest_action_runs_for_component(workspace_pk, component_id, &prototype_ids)
+            .await?
+            .into_iter()
+            .filter_map(|func_run| {
+                func_run
+                    .action_prototype_id()
+                    .map(|prototype_id| (prototype_id, func_run))
+            })
+            .collect();
+
+        Ok(prototypes
+            .into_iter()
+            .map(|prototype| {
+                let latest_run = latest_runs.remove(&prototype.id);
+                ActionPrototypeLatestRun {
+                    action_prototype_id: prototype.id,
+                    kind: prototype.kind,
+                    name: prototype.name,
+                    func_run_id: latest_run.as_ref().map(|func_run| func_run.id()),
+                    state: latest_run.as_ref().map(|func_run| func_run.state()),
+                    action_result_state: latest_run
+                        .as_ref()
+                        .and_then(|func_run| func_run.action_result_state()),
+                    ran_at: latest_run.as_ref().map(|func_run| func_run.created_at()),
+                }
+            })
+            .collect())
+    }
+
     pub async fn for_schema(
         ctx: &DalContext,
         schema_id: SchemaId,
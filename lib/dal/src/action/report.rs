@@ -0,0 +1,411 @@
+//! This module contains [`ActionRunReport`], which summarizes the action runs that originated from
+//! a change set so that they can be attached to tickets or CI runs.
+
+use std::fmt::Write;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_events::{
+    ActionKind,
+    ActionResultState,
+    CasValue,
+    FuncRun,
+    FuncRunId,
+    FuncRunState,
+};
+use si_layer_cache::LayerDbError;
+use strum::{
+    Display,
+    EnumString,
+};
+use thiserror::Error;
+
+use crate::{
+    ChangeSetId,
+    DalContext,
+    TransactionsError,
+};
+
+/// The number of log lines kept per run. Older lines are dropped first.
+pub const MAX_REPORT_LOG_LINES: usize = 200;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ActionRunReportError {
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] Box<TransactionsError>),
+}
+
+impl From<TransactionsError> for ActionRunReportError {
+    fn from(value: TransactionsError) -> Self {
+        Box::new(value).into()
+    }
+}
+
+pub type ActionRunReportResult<T> = Result<T, ActionRunReportError>;
+
+/// The formats an [`ActionRunReport`] can be rendered in.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ActionRunReportFormat {
+    #[default]
+    Json,
+    /// A JUnit XML test suite, with a test case per run.
+    #[serde(rename = "junitXml", alias = "junit")]
+    #[strum(to_string = "junitXml", serialize = "junit")]
+    JUnitXml,
+}
+
+impl ActionRunReportFormat {
+    /// The content type of a report rendered in this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::JUnitXml => "application/xml",
+        }
+    }
+}
+
+/// How a single action run went.
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum ActionRunReportStatus {
+    /// The func failed to run, or ran and reported that the action failed.
+    Failure,
+    /// The run was killed before it finished.
+    Killed,
+    /// The run hasn't finished yet.
+    Running,
+    Success,
+}
+
+impl ActionRunReportStatus {
+    fn new(state: FuncRunState, action_result_state: Option<ActionResultState>) -> Self {
+        match (state, action_result_state) {
+            (FuncRunState::Killed, _) => Self::Killed,
+            (FuncRunState::Failure, _) | (_, Some(ActionResultState::Failure)) => Self::Failure,
+            (FuncRunState::Success, _) => Self::Success,
+            (
+                FuncRunState::Created
+                | FuncRunState::Dispatched
+                | FuncRunState::Running
+                | FuncRunState::PostProcessing,
+                _,
+            ) => Self::Running,
+        }
+    }
+}
+
+/// A single action run in an [`ActionRunReport`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionRunReportEntry {
+    pub func_run_id: FuncRunId,
+    /// The display name of the action, falling back to the name of its func.
+    pub name: String,
+    pub component_name: Option<String>,
+    pub action_kind: Option<ActionKind>,
+    pub status: ActionRunReportStatus,
+    pub duration_ms: u64,
+    /// The message the action returned, if any.
+    pub message: Option<String>,
+    /// The value the action returned, if it has been recorded.
+    pub result_value: Option<serde_json::Value>,
+    /// The last [`MAX_REPORT_LOG_LINES`] lines logged by the run. Runs whose logs weren't recorded
+    /// have none.
+    pub logs: Vec<String>,
+    /// Whether lines were dropped from [`Self::logs`].
+    pub logs_truncated: bool,
+}
+
+/// The action runs that originated from a change set, oldest first.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionRunReport {
+    pub change_set_id: ChangeSetId,
+    pub runs: Vec<ActionRunReportEntry>,
+}
+
+impl ActionRunReport {
+    /// Assembles the report for the action runs that originated from the change set, wherever
+    /// they ran (usually on HEAD, once the change set was applied).
+    pub async fn for_originating_change_set(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> ActionRunReportResult<Self> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let func_runs = ctx
+            .layer_db()
+            .func_run()
+            .list_action_history_for_originating_change_set(workspace_pk, change_set_id)
+            .await?;
+
+        let mut runs = Vec::with_capacity(func_runs.len());
+        for func_run in func_runs {
+            runs.push(ActionRunReportEntry::assemble(ctx, &func_run).await?);
+        }
+
+        Ok(Self {
+            change_set_id,
+            runs,
+        })
+    }
+
+    /// Renders the report in the given format.
+    pub fn render(&self, format: ActionRunReportFormat) -> ActionRunReportResult<String> {
+        match format {
+            ActionRunReportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            ActionRunReportFormat::JUnitXml => Ok(self.to_junit_xml()),
+        }
+    }
+
+    fn to_junit_xml(&self) -> String {
+        let count = |status| self.runs.iter().filter(|run| run.status == status).count();
+        let total_secs: f64 = self.runs.iter().map(|run| secs(run.duration_ms)).sum();
+        let suite_name = format!("change set {}", self.change_set_id);
+
+        let attributes = format!(
+            "name=\"{name}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{total_secs:.3}\"",
+            name = escape_xml(&suite_name),
+            tests = self.runs.len(),
+            failures = count(ActionRunReportStatus::Failure),
+            errors = count(ActionRunReportStatus::Killed),
+            skipped = count(ActionRunReportStatus::Running),
+        );
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(xml, "<testsuites {attributes}>");
+        let _ = writeln!(xml, "  <testsuite {attributes}>");
+
+        for run in &self.runs {
+            let class_name = run.component_name.as_deref().unwrap_or("unknown component");
+            let _ = writeln!(
+                xml,
+                "    <testcase name=\"{name}\" classname=\"{class_name}\" time=\"{time:.3}\">",
+                name = escape_xml(&run.name),
+                class_name = escape_xml(class_name),
+                time = secs(run.duration_ms),
+            );
+
+            let message = escape_xml(run.message.as_deref().unwrap_or_default());
+            match run.status {
+                ActionRunReportStatus::Failure => {
+                    let _ = writeln!(
+                        xml,
+                        "      <failure message=\"{message}\" type=\"actionFailure\"/>"
+                    );
+                }
+                ActionRunReportStatus::Killed => {
+                    let _ = writeln!(
+                        xml,
+                        "      <error message=\"{message}\" type=\"actionKilled\"/>"
+                    );
+                }
+                ActionRunReportStatus::Running => {
+                    xml.push_str("      <skipped message=\"still running\"/>\n");
+                }
+                ActionRunReportStatus::Success => {}
+            }
+
+            if !run.logs.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape_xml(&run.logs.join("\n"))
+                );
+            }
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+impl ActionRunReportEntry {
+    async fn assemble(ctx: &DalContext, func_run: &FuncRun) -> ActionRunReportResult<Self> {
+        let result_value: Option<serde_json::Value> = match func_run.result_value_cas_address() {
+            Some(address) => ctx
+                .layer_db()
+                .cas()
+                .try_read_as::<CasValue>(&address)
+                .await?
+                .map(Into::into),
+            None => None,
+        };
+        let message = result_value
+            .as_ref()
+            .and_then(|value| {
+                value
+                    .get("error")
+                    .filter(|error| !error.is_null())
+                    .or_else(|| value.get("message"))
+            })
+            .and_then(serde_json::Value::as_str)
+            .map(ToOwned::to_owned);
+
+        let mut logs: Vec<String> = ctx
+            .layer_db()
+            .func_run_log()
+            .get_for_func_run_id(func_run.id())
+            .await?
+            .map(|func_run_log| {
+                func_run_log
+                    .logs()
+                    .iter()
+                    .map(|line| line.message.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let logs_truncated = logs.len() > MAX_REPORT_LOG_LINES;
+        if logs_truncated {
+            logs.drain(..logs.len() - MAX_REPORT_LOG_LINES);
+        }
+
+        let duration_ms = (func_run.updated_at() - func_run.created_at())
+            .num_milliseconds()
+            .max(0) as u64;
+
+        Ok(Self {
+            func_run_id: func_run.id(),
+            name: func_run
+                .action_display_name()
+                .unwrap_or(func_run.function_name())
+                .to_owned(),
+            component_name: func_run.component_name().map(ToOwned::to_owned),
+            action_kind: func_run.action_kind(),
+            status: ActionRunReportStatus::new(func_run.state(), func_run.action_result_state()),
+            duration_ms,
+            message,
+            result_value,
+            logs,
+            logs_truncated,
+        })
+    }
+}
+
+fn secs(duration_ms: u64) -> f64 {
+    duration_ms as f64 / 1000.0
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace aren't allowed in XML 1.0 documents.
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, status: ActionRunReportStatus) -> ActionRunReportEntry {
+        ActionRunReportEntry {
+            func_run_id: FuncRunId::new(),
+            name: name.to_owned(),
+            component_name: Some("web<1>".to_owned()),
+            action_kind: Some(ActionKind::Create),
+            status,
+            duration_ms: 1500,
+            message: Some("it's \"broken\"".to_owned()),
+            result_value: None,
+            logs: vec!["first & second".to_owned(), "third\u{1b}".to_owned()],
+            logs_truncated: false,
+        }
+    }
+
+    #[test]
+    fn junit_xml() {
+        let change_set_id = ChangeSetId::new();
+        let report = ActionRunReport {
+            change_set_id,
+            runs: vec![
+                entry("Create Web", ActionRunReportStatus::Success),
+                entry("Update Web", ActionRunReportStatus::Failure),
+                entry("Refresh Web", ActionRunReportStatus::Running),
+            ],
+        };
+
+        let xml = report
+            .render(ActionRunReportFormat::JUnitXml)
+            .expect("could not render report");
+
+        assert_eq!(
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="change set {change_set_id}" tests="3" failures="1" errors="0" skipped="1" time="4.500">
+  <testsuite name="change set {change_set_id}" tests="3" failures="1" errors="0" skipped="1" time="4.500">
+    <testcase name="Create Web" classname="web&lt;1&gt;" time="1.500">
+      <system-out>first &amp; second
+third</system-out>
+    </testcase>
+    <testcase name="Update Web" classname="web&lt;1&gt;" time="1.500">
+      <failure message="it&apos;s &quot;broken&quot;" type="actionFailure"/>
+      <system-out>first &amp; second
+third</system-out>
+    </testcase>
+    <testcase name="Refresh Web" classname="web&lt;1&gt;" time="1.500">
+      <skipped message="still running"/>
+      <system-out>first &amp; second
+third</system-out>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+            ),
+            xml
+        );
+    }
+
+    #[test]
+    fn status() {
+        assert_eq!(
+            ActionRunReportStatus::Failure,
+            ActionRunReportStatus::new(FuncRunState::Success, Some(ActionResultState::Failure))
+        );
+        assert_eq!(
+            ActionRunReportStatus::Success,
+            ActionRunReportStatus::new(FuncRunState::Success, Some(ActionResultState::Success))
+        );
+        assert_eq!(
+            ActionRunReportStatus::Killed,
+            ActionRunReportStatus::new(FuncRunState::Killed, None)
+        );
+        assert_eq!(
+            ActionRunReportStatus::Running,
+            ActionRunReportStatus::new(FuncRunState::Dispatched, None)
+        );
+    }
+
+    #[test]
+    fn format_from_str() {
+        assert_eq!(
+            ActionRunReportFormat::JUnitXml,
+            "junit".parse().expect("could not parse format")
+        );
+        assert_eq!(
+            ActionRunReportFormat::Json,
+            "json".parse().expect("could not parse format")
+        );
+    }
+}
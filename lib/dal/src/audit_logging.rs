@@ -3,6 +3,8 @@
 use audit_database::{
     AuditDatabaseContext,
     AuditDatabaseError,
+    AuditLogCursor,
+    AuditLogFilter,
     AuditLogRow,
 };
 use audit_logs_stream::AuditLogsStreamError;
@@ -304,6 +306,33 @@ pub async fn list(
     .await?)
 }
 
+#[instrument(
+    name = "audit_logging.list_filtered",
+    level = "debug",
+    skip_all,
+    fields(size, sort_ascending)
+)]
+pub async fn list_filtered(
+    ctx: &DalContext,
+    audit_database_context: &AuditDatabaseContext,
+    filter: &AuditLogFilter,
+    after: Option<AuditLogCursor>,
+    size: usize,
+    sort_ascending: bool,
+) -> Result<(Vec<AuditLogRow>, Option<AuditLogCursor>)> {
+    let (workspace_id, change_set_ids) = prepare_accessor_query(ctx).await?;
+    Ok(AuditLogRow::list_filtered(
+        audit_database_context,
+        workspace_id,
+        change_set_ids,
+        filter,
+        after,
+        size,
+        sort_ascending,
+    )
+    .await?)
+}
+
 #[instrument(
     name = "audit_logging.list_for_component",
     level = "debug",
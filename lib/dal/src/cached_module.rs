@@ -1,9 +1,19 @@
 use std::{
     collections::{
+        BTreeSet,
         HashMap,
         HashSet,
     },
-    sync::Arc,
+    future::Future,
+    mem,
+    sync::{
+        Arc,
+        Mutex,
+        atomic::{
+            AtomicU8,
+            Ordering,
+        },
+    },
     time::Duration,
 };
 
@@ -18,7 +28,6 @@ use module_index_client::{
     ModuleIndexClient,
     ModuleIndexClientError,
 };
-use postgres_types::ToSql;
 use serde::{
     Deserialize,
     Serialize,
@@ -29,7 +38,11 @@ use si_data_pg::{
 };
 use si_db::HistoryActor;
 pub use si_id::CachedModuleId;
-use si_id::UserPk;
+use si_id::{
+    ChangeSetId,
+    UserPk,
+    WorkspacePk,
+};
 use si_pkg::{
     SiPkg,
     SiPkgError,
@@ -44,8 +57,11 @@ use ulid::Ulid;
 use crate::{
     ComponentType,
     DalContext,
+    DalContextBuilder,
     SchemaId,
     TransactionsError,
+    WsEvent,
+    WsEventError,
     slow_rt::{
         self,
         SlowRuntimeError,
@@ -61,6 +77,8 @@ pub enum CachedModuleError {
     EddaClient(#[from] edda_client::ClientError),
     #[error("join error: {0}")]
     Join(#[from] tokio::task::JoinError),
+    #[error("invalid packages in the module index for schema ids: {0:?}")]
+    InvalidPackage(Vec<SchemaId>),
     #[error("json error: {0}")]
     Json(#[from] serde_json::Error),
     #[error("module index client error: {0}")]
@@ -69,6 +87,8 @@ pub enum CachedModuleError {
     ModuleIndexUrlNotSet,
     #[error("package data None")]
     NoPackageData,
+    #[error("no modules found in the module index for schema ids: {0:?}")]
+    NotFoundInModuleIndex(Vec<SchemaId>),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("si-pkg error: {0}")]
@@ -83,6 +103,14 @@ pub enum CachedModuleError {
     UlidDecode(#[from] ulid::DecodeError),
     #[error("url parse error: {0}")]
     UrlParse(#[from] url::ParseError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] Box<WsEventError>),
+}
+
+impl From<WsEventError> for CachedModuleError {
+    fn from(value: WsEventError) -> Self {
+        Box::new(value).into()
+    }
 }
 
 pub type CachedModuleResult<T> = Result<T, CachedModuleError>;
@@ -102,7 +130,7 @@ pub struct CachedModule {
     pub package_summary: Option<PackageSummary>,
     pub latest_hash: String,
     pub created_at: DateTime<Utc>,
-    pub package_data: Option<Vec<u8>>,
+    pub package_data: Option<Arc<Vec<u8>>>,
     pub scoped_to_user_pk: Option<UserPk>,
 }
 
@@ -131,6 +159,21 @@ impl From<CachedModule> for si_frontend_types::LatestModule {
     }
 }
 
+impl From<CachedModule> for si_frontend_types::UninstalledVariant {
+    fn from(value: CachedModule) -> Self {
+        Self {
+            schema_id: value.schema_id,
+            schema_name: value.schema_name,
+            display_name: value.display_name,
+            category: value.category,
+            link: value.link,
+            color: value.color,
+            description: value.description,
+            component_type: value.component_type.into(),
+        }
+    }
+}
+
 impl TryFrom<PgRow> for CachedModule {
     type Error = CachedModuleError;
 
@@ -139,6 +182,7 @@ impl TryFrom<PgRow> for CachedModule {
         let component_type = component_type_string.parse()?;
         let package_summary: Option<serde_json::Value> = row.try_get("package_summary")?;
         let package_summary = package_summary.map(serde_json::from_value).transpose()?;
+        let package_data: Option<Vec<u8>> = row.try_get("package_data")?;
 
         Ok(Self {
             id: row.try_get("id")?,
@@ -153,7 +197,7 @@ impl TryFrom<PgRow> for CachedModule {
             package_summary,
             latest_hash: row.try_get("latest_hash")?,
             created_at: row.try_get("created_at")?,
-            package_data: row.try_get("package_data")?,
+            package_data: package_data.map(Arc::new),
             scoped_to_user_pk: row.try_get("scoped_to_user_pk")?,
         })
     }
@@ -196,60 +240,250 @@ const CACHED_MODULE_LIST_FIELDS: &str = "
 const BATCH_SIZE: usize = 10;
 const WAIT_BETWEEN_BATCHES: Duration = Duration::from_millis(100);
 
+const WARM_UP_IDLE: u8 = 0;
+const WARM_UP_RUNNING: u8 = 1;
+const WARM_UP_DONE: u8 = 2;
+
+/// The latest cached modules, as returned by [`CachedModule::latest_modules_or_warm_up`].
+#[derive(Clone)]
+pub struct LatestCachedModules {
+    pub modules: Vec<CachedModule>,
+    /// Set when the cache was empty and is being filled in the background. A
+    /// [`ModulesUpdated`](crate::WsPayload::ModulesUpdated) event is published once it is, to
+    /// the change set of every caller that found it warming.
+    pub warming: bool,
+}
+
+/// Fills an empty module cache in the background, the first time someone asks for modules
+/// rather than waiting for the next cache update. The one used by
+/// [`CachedModule::latest_modules_or_warm_up`] is held by the
+/// [`ServicesContext`](crate::ServicesContext).
+///
+/// Only one warm-up runs at a time, and once one succeeds it never runs again. If it fails, the
+/// next caller retries it.
+#[derive(Clone, Debug, Default)]
+pub struct ModuleCacheWarmUp {
+    state: Arc<AtomicU8>,
+    /// The workspaces and change sets to notify once the warm-up is done.
+    waiting: Arc<Mutex<BTreeSet<(WorkspacePk, ChangeSetId)>>>,
+}
+
+impl ModuleCacheWarmUp {
+    /// Whether a warm-up is running.
+    pub fn is_warming(&self) -> bool {
+        self.state.load(Ordering::SeqCst) == WARM_UP_RUNNING
+    }
+
+    /// Asks for the change set to be notified once the warm-up is done. Returns `false` if it is
+    /// already done, in which case the cache has been filled and nobody will be notified.
+    pub fn notify_when_done(&self, workspace_pk: WorkspacePk, change_set_id: ChangeSetId) -> bool {
+        let mut waiting = lock(&self.waiting);
+        if self.state.load(Ordering::SeqCst) == WARM_UP_DONE {
+            return false;
+        }
+        waiting.insert((workspace_pk, change_set_id));
+        true
+    }
+
+    /// Marks the warm-up as done and returns the change sets waiting to be notified. Marking it
+    /// under the same lock [`Self::notify_when_done`] takes ensures no caller is left waiting.
+    fn finish(&self) -> BTreeSet<(WorkspacePk, ChangeSetId)> {
+        let mut waiting = lock(&self.waiting);
+        self.state.store(WARM_UP_DONE, Ordering::SeqCst);
+        mem::take(&mut *waiting)
+    }
+
+    /// Spawns the update, unless one is already running or has succeeded. Returns whether it was
+    /// spawned.
+    pub fn trigger<F>(&self, update: F) -> bool
+    where
+        F: Future<Output = CachedModuleResult<()>> + Send + 'static,
+    {
+        if self
+            .state
+            .compare_exchange(
+                WARM_UP_IDLE,
+                WARM_UP_RUNNING,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_err()
+        {
+            return false;
+        }
+
+        let warm_up = self.clone();
+        tokio::spawn(async move {
+            match update.await {
+                // Marks it as done, in case the update did not already do so when taking the
+                // change sets to notify.
+                Ok(()) => drop(warm_up.finish()),
+                Err(err) => {
+                    error!(si.error.message = ?err, "unable to warm up module cache");
+                    warm_up.state.store(WARM_UP_IDLE, Ordering::SeqCst);
+                }
+            }
+        });
+
+        true
+    }
+
+    /// Returns [`CachedModule::latest_modules`], warming up the cache if it is empty and a module
+    /// index url is configured.
+    pub async fn latest_modules(
+        &self,
+        ctx: &DalContext,
+        edda_client: EddaClient,
+    ) -> CachedModuleResult<LatestCachedModules> {
+        let modules = CachedModule::latest_modules(ctx).await?;
+        if !modules.is_empty() || ctx.services_context().module_index_url().is_none() {
+            return Ok(LatestCachedModules {
+                modules,
+                warming: false,
+            });
+        }
+
+        if let Some(workspace_pk) = ctx.tenancy().workspace_pk_opt() {
+            if !self.notify_when_done(workspace_pk, ctx.change_set_id()) {
+                // The warm-up finished since the cache was read.
+                return Ok(LatestCachedModules {
+                    modules: CachedModule::latest_modules(ctx).await?,
+                    warming: false,
+                });
+            }
+        }
+
+        let builder = ctx.to_builder();
+        let warm_up = self.clone();
+        let spawned = self.trigger(async move {
+            let ctx = builder.build_default(None).await?;
+
+            let new_modules =
+                CachedModule::update_cached_modules(&ctx, edda_client.clone()).await?;
+            info!(
+                "{} builtin assets found in module index while warming up the module cache",
+                new_modules.len()
+            );
+            edda_client.rebuild_for_deployment().await?;
+            ctx.commit_no_rebase().await?;
+
+            // The cache is filled even if notifying fails, so that is not a reason to retry.
+            publish_modules_updated(&builder, warm_up.finish()).await;
+
+            Ok(())
+        });
+
+        Ok(LatestCachedModules {
+            modules,
+            warming: spawned || self.is_warming(),
+        })
+    }
+}
+
+/// Publishes a [`ModulesUpdated`](crate::WsPayload::ModulesUpdated) event to each of the
+/// change sets, each from a context with its own tenancy and visibility. Failures are logged, so
+/// that one change set failing does not keep the others from being notified.
+async fn publish_modules_updated(
+    builder: &DalContextBuilder,
+    change_sets: BTreeSet<(WorkspacePk, ChangeSetId)>,
+) {
+    for (workspace_pk, change_set_id) in change_sets {
+        if let Err(err) = publish_modules_updated_to(builder, workspace_pk, change_set_id).await {
+            error!(
+                si.error.message = ?err,
+                si.workspace.id = %workspace_pk,
+                si.change_set.id = %change_set_id,
+                "unable to notify change set of module cache warm-up",
+            );
+        }
+    }
+}
+
+async fn publish_modules_updated_to(
+    builder: &DalContextBuilder,
+    workspace_pk: WorkspacePk,
+    change_set_id: ChangeSetId,
+) -> CachedModuleResult<()> {
+    let ctx = builder
+        .build_for_change_set_as_system(workspace_pk, change_set_id, None)
+        .await?;
+    WsEvent::modules_updated(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+    ctx.commit_no_rebase().await?;
+
+    Ok(())
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
 impl CachedModule {
+    /// Parses the [`SiPkg`] for this module, loading the package data first if needed.
+    ///
+    /// The package data is dropped from the struct before parsing, so the bytes are freed as soon
+    /// as the [`SiPkg`] has been built. Use [`Self::si_pkg_keeping_package_data`] to hold on to
+    /// them instead.
     pub async fn si_pkg(&mut self, ctx: &DalContext) -> CachedModuleResult<SiPkg> {
-        self.package_data(ctx).await?;
-        if let Some(package_data) = self.package_data.take() {
-            Ok(slow_rt::spawn(async move { SiPkg::load_from_bytes(&package_data) })?.await??)
-        } else {
-            Err(CachedModuleError::NoPackageData)
+        self.load_si_pkg(ctx, true).await
+    }
+
+    /// Like [`Self::si_pkg`], but keeps the package data on the struct, for callers that need the
+    /// bytes again later. The same [`Arc`] is handed to the slow runtime for parsing, so no
+    /// copies of the bytes are made.
+    pub async fn si_pkg_keeping_package_data(
+        &mut self,
+        ctx: &DalContext,
+    ) -> CachedModuleResult<SiPkg> {
+        self.load_si_pkg(ctx, false).await
+    }
+
+    async fn load_si_pkg(
+        &mut self,
+        ctx: &DalContext,
+        release_package_data: bool,
+    ) -> CachedModuleResult<SiPkg> {
+        let package_data = self.package_data(ctx).await?;
+        if release_package_data {
+            self.package_data = None;
         }
+
+        Ok(slow_rt::spawn(async move { SiPkg::load_from_bytes(&package_data) })?.await??)
     }
 
-    async fn package_data(&mut self, ctx: &DalContext) -> CachedModuleResult<&[u8]> {
+    async fn package_data(&mut self, ctx: &DalContext) -> CachedModuleResult<Arc<Vec<u8>>> {
         if self.package_data.is_none() {
             let query = "SELECT package_data FROM cached_modules where id = $1";
             let row = ctx.txns().await?.pg().query_one(query, &[&self.id]).await?;
 
             let bytes: Option<Vec<u8>> = row.try_get("package_data")?;
-            self.package_data = bytes;
+            self.package_data = bytes.map(Arc::new);
         }
 
-        let Some(package_data) = &self.package_data else {
-            return Err(CachedModuleError::NoPackageData);
-        };
-
-        Ok(package_data.as_slice())
+        self.package_data
+            .clone()
+            .ok_or(CachedModuleError::NoPackageData)
     }
 
     pub async fn find_missing_entries(
         ctx: &DalContext,
         hashes: Vec<String>,
     ) -> CachedModuleResult<Vec<String>> {
-        // Constructs a list of parameters like '($1), ($2), ($3), ($4)' for
-        // each input value so they can be used as a table expression in the
-        // query, for the left join
-        let values_expr = hashes
-            .iter()
-            .enumerate()
-            .map(|(idx, _)| format!("(${})", idx + 1))
-            .join(",");
-
-        let params: Vec<_> = hashes
-            .iter()
-            .map(|hash| hash as &(dyn ToSql + Sync))
-            .collect();
-
-        let query = format!(
-            "
+        // The hashes are passed as a single array so that the statement text is the same no matter
+        // how many there are, letting pg prepare and cache it once.
+        let query = "
             SELECT hashes.hash
-                FROM (VALUES {values_expr}) AS hashes(hash)
+                FROM unnest($1::text[]) AS hashes(hash)
             LEFT JOIN cached_modules on cached_modules.latest_hash = hashes.hash
             WHERE cached_modules.latest_hash IS NULL
-            "
-        );
+        ";
 
-        let rows = ctx.txns().await?.pg().query(&query, &params).await?;
+        let rows = ctx.txns().await?.pg().query(query, &[&hashes]).await?;
         Ok(rows
             .into_iter()
             .map(|row| row.try_get("hash"))
@@ -274,14 +508,7 @@ impl CachedModule {
         ctx: &DalContext,
         edda_client: EddaClient,
     ) -> CachedModuleResult<Vec<CachedModule>> {
-        let module_index_client = {
-            let services_context = ctx.services_context();
-            let module_index_url = services_context
-                .module_index_url()
-                .ok_or(CachedModuleError::ModuleIndexUrlNotSet)?;
-
-            ModuleIndexClient::unauthenticated_client(module_index_url.try_into()?)?
-        };
+        let module_index_client = Self::module_index_client(ctx)?;
 
         let modules: HashMap<_, _> = module_index_client
             .list_builtins()
@@ -306,6 +533,106 @@ impl CachedModule {
         Ok(new_modules)
     }
 
+    /// Ensures the latest module for each of the given [`SchemaIds`](SchemaId) is in the cache,
+    /// fetching only the missing ones from the module index rather than running a full update.
+    ///
+    /// Returns the latest cached row (without package data) for every requested schema id. The
+    /// modules which could be fetched are inserted as part of the current transaction even if
+    /// others could not: [`CachedModuleError::InvalidPackage`] lists the schema ids whose package
+    /// could not be loaded, and failing that [`CachedModuleError::NotFoundInModuleIndex`] lists
+    /// those missing from the module index.
+    #[instrument(name = "cached_module.ensure_cached", level = "debug", skip_all)]
+    pub async fn ensure_cached(
+        ctx: &DalContext,
+        schema_ids: &[SchemaId],
+    ) -> CachedModuleResult<Vec<CachedModule>> {
+        let schema_ids: Vec<SchemaId> = schema_ids.iter().copied().unique().collect();
+        if schema_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut cached_modules = Self::latest_for_schema_ids(ctx, &schema_ids).await?;
+        let cached_schema_ids: HashSet<SchemaId> = cached_modules
+            .iter()
+            .map(|module| module.schema_id)
+            .collect();
+        let missing_schema_ids: HashSet<SchemaId> = schema_ids
+            .into_iter()
+            .filter(|schema_id| !cached_schema_ids.contains(schema_id))
+            .collect();
+        if missing_schema_ids.is_empty() {
+            return Ok(cached_modules);
+        }
+
+        let module_index_client = Self::module_index_client(ctx)?;
+        let modules: HashMap<SchemaId, ModuleDetailsResponse> = module_index_client
+            .list_builtins()
+            .await?
+            .modules
+            .into_iter()
+            .filter_map(|module| {
+                let schema_id: SchemaId = module.schema_id()?.into();
+                missing_schema_ids
+                    .contains(&schema_id)
+                    .then_some((schema_id, module))
+            })
+            .collect();
+
+        let not_found: Vec<SchemaId> = missing_schema_ids
+            .iter()
+            .filter(|schema_id| !modules.contains_key(schema_id))
+            .copied()
+            .sorted()
+            .collect();
+
+        let mut join_set = JoinSet::new();
+        for module in modules.into_values() {
+            let module_index = module_index_client.clone();
+            join_set.spawn(async move {
+                let module_id = Ulid::from_string(&module.id).unwrap_or_default();
+                let module_bytes = module_index.get_builtin(module_id).await?;
+                Ok::<(ModuleDetailsResponse, Arc<Vec<u8>>), CachedModuleError>((
+                    module,
+                    Arc::new(module_bytes),
+                ))
+            });
+        }
+
+        let mut invalid = vec![];
+        while let Some(res) = join_set.join_next().await {
+            let (module, module_bytes) = res??;
+            match Self::insert(ctx, &module, module_bytes, None).await {
+                Ok(Some(new_cached_module)) => cached_modules.push(new_cached_module),
+                Ok(None) => invalid.extend(module.schema_id().map(SchemaId::from)),
+                Err(CachedModuleError::SiPkg(err)) => {
+                    warn!(si.error.message = ?err, "builtin module {} cannot be loaded", module.id);
+                    invalid.extend(module.schema_id().map(SchemaId::from));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        if !invalid.is_empty() {
+            invalid.sort();
+            return Err(CachedModuleError::InvalidPackage(invalid));
+        }
+        if !not_found.is_empty() {
+            return Err(CachedModuleError::NotFoundInModuleIndex(not_found));
+        }
+
+        Ok(cached_modules)
+    }
+
+    fn module_index_client(ctx: &DalContext) -> CachedModuleResult<ModuleIndexClient> {
+        let services_context = ctx.services_context();
+        let module_index_url = services_context
+            .module_index_url()
+            .ok_or(CachedModuleError::ModuleIndexUrlNotSet)?;
+
+        Ok(ModuleIndexClient::unauthenticated_client(
+            module_index_url.try_into()?,
+        )?)
+    }
+
     async fn cache_modules(
         ctx: &DalContext,
         modules: &HashMap<String, ModuleDetailsResponse>,
@@ -436,6 +763,32 @@ impl CachedModule {
         row.map(TryInto::try_into).transpose()
     }
 
+    async fn latest_for_schema_ids(
+        ctx: &DalContext,
+        schema_ids: &[SchemaId],
+    ) -> CachedModuleResult<Vec<CachedModule>> {
+        let schema_ids: Vec<String> = schema_ids.iter().map(ToString::to_string).collect();
+        let user_pk = Self::history_actor_user_pk(ctx);
+        let query = format!(
+            "
+                SELECT DISTINCT ON (schema_id)
+                    {CACHED_MODULE_LIST_FIELDS}
+                FROM cached_modules
+                WHERE schema_id = ANY($1)
+                    AND (scoped_to_user_pk IS NULL OR scoped_to_user_pk = $2)
+                ORDER BY schema_id, created_at DESC
+            "
+        );
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(&query, &[&schema_ids, &user_pk])
+            .await?;
+        rows.into_iter().map(TryInto::try_into).try_collect()
+    }
+
     pub async fn list_for_schema_id(
         ctx: &DalContext,
         schema_id: SchemaId,
@@ -491,6 +844,75 @@ impl CachedModule {
         rows.into_iter().map(TryInto::try_into).try_collect()
     }
 
+    /// Returns [`Self::latest_modules`], warming up the cache in the background if it is empty
+    /// (see [`ModuleCacheWarmUp`]).
+    pub async fn latest_modules_or_warm_up(
+        ctx: &DalContext,
+        edda_client: EddaClient,
+    ) -> CachedModuleResult<LatestCachedModules> {
+        ctx.services_context()
+            .module_cache_warm_up()
+            .latest_modules(ctx, edda_client)
+            .await
+    }
+
+    /// Returns a page of [`Self::latest_modules`], ordered by schema name (and schema id to keep
+    /// the ordering stable when names collide).
+    pub async fn latest_modules_paginated(
+        ctx: &DalContext,
+        limit: u32,
+        offset: u32,
+    ) -> CachedModuleResult<Vec<CachedModule>> {
+        let user_pk = Self::history_actor_user_pk(ctx);
+
+        // The DISTINCT ON must happen before we page, otherwise older rows for a schema could
+        // land on a page in place of the latest one.
+        let query = format!(
+            "
+                SELECT *
+                FROM (
+                    SELECT DISTINCT ON (schema_id)
+                        {CACHED_MODULE_LIST_FIELDS}
+                    FROM cached_modules
+                    WHERE scoped_to_user_pk IS NULL OR scoped_to_user_pk = $1
+                    ORDER BY schema_id, created_at DESC
+                ) AS latest_modules
+                ORDER BY schema_name, schema_id
+                LIMIT $2 OFFSET $3
+            "
+        );
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(&query, &[&user_pk, &i64::from(limit), &i64::from(offset)])
+            .await?;
+        rows.into_iter().map(TryInto::try_into).try_collect()
+    }
+
+    /// Counts the number of schemas that [`Self::latest_modules`] would return.
+    pub async fn count_latest(ctx: &DalContext) -> CachedModuleResult<u64> {
+        let user_pk = Self::history_actor_user_pk(ctx);
+
+        let query = "
+            SELECT COUNT(DISTINCT schema_id) AS count
+            FROM cached_modules
+            WHERE scoped_to_user_pk IS NULL OR scoped_to_user_pk = $1
+        ";
+
+        let row = ctx.txns().await?.pg().query_one(query, &[&user_pk]).await?;
+        let count: i64 = row.try_get("count")?;
+        Ok(count as u64)
+    }
+
+    fn history_actor_user_pk(ctx: &DalContext) -> Option<UserPk> {
+        match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        }
+    }
+
     async fn insert(
         ctx: &DalContext,
         module_details: &ModuleDetailsResponse,
@@ -527,7 +949,10 @@ impl CachedModule {
         };
         let schema_id: SchemaId = schema_id.into();
 
-        let Some(package) = PackageData::load(&module_details.id, &pkg_bytes).await? else {
+        // The bytes are moved into the slow runtime for parsing and handed back afterwards so
+        // that we never hold more than one reference to them.
+        let (maybe_package, pkg_bytes) = PackageData::load(&module_details.id, pkg_bytes).await?;
+        let Some(package) = maybe_package else {
             return Ok(None);
         };
 
@@ -639,7 +1064,7 @@ impl CachedModule {
             return Ok(());
         };
         // This is more problematic because we'll end up retrying summaries all the time
-        let Some(summary) = PackageData::load(module_id, &Arc::new(pkg_bytes)).await? else {
+        let (Some(summary), _) = PackageData::load(module_id, Arc::new(pkg_bytes)).await? else {
             return Ok(());
         };
         let query = "
@@ -685,21 +1110,27 @@ struct PackageData {
 }
 
 impl PackageData {
+    /// Parses the package summary data out of the package bytes, returning the bytes back to the
+    /// caller once parsing has completed.
     async fn load(
         module_id: &str, // just for debug messages so we can find the broken rows
-        pkg_bytes: &Arc<Vec<u8>>,
-    ) -> CachedModuleResult<Option<Self>> {
-        let pkg_bytes = pkg_bytes.clone();
-        let pkg = slow_rt::spawn(async move { SiPkg::load_from_bytes(&pkg_bytes) })?.await??;
+        pkg_bytes: Arc<Vec<u8>>,
+    ) -> CachedModuleResult<(Option<Self>, Arc<Vec<u8>>)> {
+        let (pkg, pkg_bytes) = slow_rt::spawn(async move {
+            let pkg = SiPkg::load_from_bytes(&pkg_bytes);
+            (pkg, pkg_bytes)
+        })?
+        .await?;
+        let pkg = pkg?;
 
         let Some(schema) = pkg.schemas()?.into_iter().next() else {
             warn!("builtin module {} has no schema", module_id);
-            return Ok(None);
+            return Ok((None, pkg_bytes));
         };
 
         let Some(variant) = schema.variants()?.into_iter().next() else {
             warn!("builtin module {} has a schema with no variant", module_id);
-            return Ok(None);
+            return Ok((None, pkg_bytes));
         };
 
         let package_summary = PackageSummary {
@@ -707,11 +1138,14 @@ impl PackageData {
                 + variant.management_funcs()?.len().max(1) as u32,
         };
 
-        Ok(Some(Self {
-            schema: schema.data,
-            variant: variant.data,
-            package_summary: serde_json::to_value(&package_summary)?,
-        }))
+        Ok((
+            Some(Self {
+                schema: schema.data,
+                variant: variant.data,
+                package_summary: serde_json::to_value(&package_summary)?,
+            }),
+            pkg_bytes,
+        ))
     }
 
     fn schema_name(&self) -> Option<&str> {
@@ -80,6 +80,7 @@ use crate::{
 pub mod approval;
 pub mod event;
 pub mod status;
+pub mod summary;
 pub mod view;
 
 #[remain::sorted]
@@ -89,6 +90,11 @@ pub enum ChangeSetError {
     BillingPublish(#[from] Box<BillingPublishError>),
     #[error("cannot rename HEAD change set")]
     CantRenameHeadChangeSet,
+    #[error("change set {change_set_id} is no longer active (status: {status})")]
+    ChangeSetNotActive {
+        change_set_id: ChangeSetId,
+        status: ChangeSetStatus,
+    },
     #[error("change set not approved for apply. Current state: {0}")]
     ChangeSetNotApprovedForApply(ChangeSetStatus),
     #[error("change set with id {0} not found")]
@@ -434,6 +440,23 @@ impl ChangeSet {
         Ok(self.workspace(ctx).await?.default_change_set_id() == self.id)
     }
 
+    /// Returns an error if the [`ChangeSet`] of the context is no longer active (e.g. it has been
+    /// applied or abandoned), since whatever is written to it would never make it anywhere.
+    ///
+    /// The status is loaded rather than taken from the context, in case the change set was applied
+    /// or abandoned after the context was built. HEAD is always active.
+    pub async fn error_if_not_active(ctx: &DalContext) -> ChangeSetResult<()> {
+        let change_set = Self::get_by_id(ctx, ctx.change_set_id()).await?;
+        if !change_set.status.is_active() {
+            return Err(ChangeSetError::ChangeSetNotActive {
+                change_set_id: change_set.id,
+                status: change_set.status,
+            });
+        }
+
+        Ok(())
+    }
+
     #[instrument(name = "change_set.update_pointer", level = "debug", skip_all)]
     pub async fn update_pointer(
         &mut self,
@@ -1039,6 +1062,9 @@ impl ChangeSet {
         }
 
         self.update_status(ctx, ChangeSetStatus::Applied).await?;
+        Func::forget_edit_bases(ctx, self.id)
+            .await
+            .map_err(Box::new)?;
         let user = Self::extract_userid_from_context(ctx).await;
         WsEvent::change_set_applied(ctx, self.id, base_change_set_id, user)
             .await?
@@ -1078,6 +1104,9 @@ impl ChangeSet {
     /// Updates the status for a ChangeSet to be [`ChangeSetStatus::Abandoned`] and fires necessary WSEvent
     pub async fn abandon(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         self.update_status(ctx, ChangeSetStatus::Abandoned).await?;
+        Func::forget_edit_bases(ctx, self.id)
+            .await
+            .map_err(Box::new)?;
         let user_id = Self::extract_userid_from_context(ctx).await;
         WsEvent::change_set_abandoned(ctx, self.id, user_id)
             .await?
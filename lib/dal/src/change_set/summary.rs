@@ -0,0 +1,143 @@
+//! This module contains [`ChangeSetSummary`], a count of what a change set changes relative to
+//! HEAD.
+
+use std::collections::HashSet;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_events::ulid::Ulid;
+use thiserror::Error;
+
+use crate::{
+    Component,
+    ComponentError,
+    DalContext,
+    Func,
+    FuncError,
+    SchemaVariant,
+    SchemaVariantError,
+    TransactionsError,
+    WorkspaceSnapshotError,
+    action::{
+        Action,
+        ActionError,
+    },
+    workspace_snapshot::selector::WorkspaceSnapshotSelector,
+};
+
+#[allow(missing_docs)]
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ChangeSetSummaryError {
+    #[error("action error: {0}")]
+    Action(#[from] Box<ActionError>),
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("func error: {0}")]
+    Func(#[from] Box<FuncError>),
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] Box<SchemaVariantError>),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] Box<TransactionsError>),
+    #[error("workspace snapshot error: {0}")]
+    WorkspaceSnapshot(#[from] Box<WorkspaceSnapshotError>),
+}
+
+type Result<T> = std::result::Result<T, ChangeSetSummaryError>;
+
+/// Counts of the entities a change set adds, modifies or removes relative to HEAD, along with the
+/// number of actions currently running on HEAD and of funcs that applying the change set would
+/// overwrite edits to (see [`Func::detect_conflicts`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetSummary {
+    pub modified_schema_variants: usize,
+    pub modified_funcs: usize,
+    pub modified_components: usize,
+    pub running_actions: usize,
+    pub conflicting_funcs: usize,
+}
+
+impl ChangeSetSummary {
+    /// Assembles the summary for the change set in the provided [`DalContext`].
+    ///
+    /// Rather than loading each entity, this compares the merkle tree hashes of their nodes in the
+    /// two snapshots, so an entity counts as modified if anything beneath it in the graph changed.
+    pub async fn assemble(ctx: &DalContext) -> Result<Self> {
+        let head_ctx = ctx.clone_with_head().await.map_err(Box::new)?;
+        let snapshot = ctx.workspace_snapshot().map_err(Box::new)?;
+        let head_snapshot = head_ctx.workspace_snapshot().map_err(Box::new)?;
+
+        let modified_schema_variants = count_modified(
+            &snapshot,
+            &head_snapshot,
+            SchemaVariant::list_all_ids(ctx).await.map_err(Box::new)?,
+            SchemaVariant::list_all_ids(&head_ctx)
+                .await
+                .map_err(Box::new)?,
+        )
+        .await;
+        let modified_funcs = count_modified(
+            &snapshot,
+            &head_snapshot,
+            Func::list_ids(ctx).await.map_err(Box::new)?,
+            Func::list_ids(&head_ctx).await.map_err(Box::new)?,
+        )
+        .await;
+        let modified_components = count_modified(
+            &snapshot,
+            &head_snapshot,
+            Component::list_ids(ctx).await.map_err(Box::new)?,
+            Component::list_ids(&head_ctx).await.map_err(Box::new)?,
+        )
+        .await;
+
+        // Actions only run on HEAD, so that is where we look for them.
+        let running_actions = Action::dispatched_count(&head_ctx)
+            .await
+            .map_err(Box::new)?;
+
+        let conflicting_funcs = Func::list_conflicts(ctx).await.map_err(Box::new)?.len();
+
+        Ok(Self {
+            modified_schema_variants,
+            modified_funcs,
+            modified_components,
+            running_actions,
+            conflicting_funcs,
+        })
+    }
+}
+
+/// Counts the ids that were added, removed or whose subgraph changed between the two snapshots.
+async fn count_modified(
+    snapshot: &WorkspaceSnapshotSelector,
+    head_snapshot: &WorkspaceSnapshotSelector,
+    ids: impl IntoIterator<Item = impl Into<Ulid>>,
+    head_ids: impl IntoIterator<Item = impl Into<Ulid>>,
+) -> usize {
+    let ids: HashSet<Ulid> = ids.into_iter().map(Into::into).collect();
+    let head_ids: HashSet<Ulid> = head_ids.into_iter().map(Into::into).collect();
+
+    let mut count = head_ids.difference(&ids).count();
+    for id in ids {
+        let modified = match head_snapshot.get_node_weight_opt(id).await {
+            Some(head_node_weight) => {
+                snapshot
+                    .get_node_weight_opt(id)
+                    .await
+                    .is_none_or(|node_weight| {
+                        node_weight.merkle_tree_hash() != head_node_weight.merkle_tree_hash()
+                    })
+            }
+            None => true,
+        };
+        if modified {
+            count += 1;
+        }
+    }
+
+    count
+}
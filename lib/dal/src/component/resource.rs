@@ -1,9 +1,21 @@
 //! This module contains the ability to work with "resources" for [`Components`](crate::Component).
 
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+};
+
 use chrono::{
     DateTime,
     Utc,
 };
+use futures::{
+    Stream,
+    StreamExt,
+    TryStreamExt,
+    future,
+    stream,
+};
 use serde::{
     Deserialize,
     Serialize,
@@ -17,6 +29,13 @@ use crate::{
     ComponentId,
     DalContext,
     component::ComponentResult,
+    diagram::{
+        geometry::{
+            Geometry,
+            GeometryRepresents,
+        },
+        view::ViewId,
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -83,3 +102,180 @@ impl ResourceView {
         }
     }
 }
+
+/// A row of a resource inventory, for each [`Component`] which has a resource.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceInventoryEntry {
+    pub component_id: ComponentId,
+    pub component_name: String,
+    pub schema_name: String,
+    /// The value of `/si/resourceId`, if it has been set.
+    pub resource_id: Option<String>,
+    pub status: ResourceStatus,
+    pub last_synced: DateTime<Utc>,
+    /// The values found in the resource payload at each of the requested JSON pointers, with
+    /// `null` for the ones which did not point at anything.
+    pub fields: BTreeMap<String, Value>,
+}
+
+impl ResourceInventoryEntry {
+    /// Lists the resources of every [`Component`], or only those in the given view, in component
+    /// id order. Each of the `fields` is a JSON pointer into the resource payload.
+    pub async fn list(
+        ctx: &DalContext,
+        view_id: Option<ViewId>,
+        fields: &[String],
+    ) -> ComponentResult<Vec<Self>> {
+        Self::stream(ctx.clone(), view_id, fields.to_vec())
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// Like [`Self::list`], but only loads each component once the entry before it has been
+    /// consumed, so that a large inventory can be written out as it is read.
+    pub async fn stream(
+        ctx: DalContext,
+        view_id: Option<ViewId>,
+        fields: Vec<String>,
+    ) -> ComponentResult<impl Stream<Item = ComponentResult<Self>> + Send + 'static> {
+        let mut component_ids = match view_id {
+            Some(view_id) => {
+                let mut component_ids = Vec::new();
+                for geometry in Geometry::list_by_view_id(&ctx, view_id).await? {
+                    if let Some(GeometryRepresents::Component(component_id)) =
+                        Geometry::represented_id(&ctx, geometry.id()).await?
+                    {
+                        component_ids.push(component_id);
+                    }
+                }
+                component_ids
+            }
+            None => Component::list_ids(&ctx).await?,
+        };
+        component_ids.sort();
+
+        let fields = Arc::new(fields);
+        Ok(stream::iter(component_ids)
+            .then(move |component_id| {
+                let ctx = ctx.clone();
+                let fields = fields.clone();
+                async move { Self::for_component(&ctx, component_id, &fields).await }
+            })
+            .try_filter_map(future::ok))
+    }
+
+    async fn for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        fields: &[String],
+    ) -> ComponentResult<Option<Self>> {
+        let component = Component::get_by_id(ctx, component_id).await?;
+        let Some(resource) = component.resource(ctx).await? else {
+            return Ok(None);
+        };
+
+        let resource_id = component.resource_id(ctx).await?;
+        let fields = fields
+            .iter()
+            .map(|pointer| {
+                let value = resource
+                    .payload
+                    .as_ref()
+                    .and_then(|payload| payload.pointer(pointer))
+                    .cloned()
+                    .unwrap_or(Value::Null);
+                (pointer.clone(), value)
+            })
+            .collect();
+
+        Ok(Some(Self {
+            component_id,
+            component_name: component.name(ctx).await?,
+            schema_name: component.schema(ctx).await?.name,
+            resource_id: (!resource_id.is_empty()).then_some(resource_id),
+            status: resource.status,
+            last_synced: resource.last_synced,
+            fields,
+        }))
+    }
+
+    /// Renders entries as CSV, with a column for each of the `fields` after the fixed ones. Text
+    /// is written as is and any other JSON value as JSON, with `null` left empty.
+    pub fn to_csv(entries: &[Self], fields: &[String]) -> String {
+        let mut csv = Self::csv_header(fields);
+        for entry in entries {
+            csv.push_str(&entry.to_csv_record(fields));
+        }
+
+        csv
+    }
+
+    /// The first line of [`Self::to_csv`], naming its columns.
+    pub fn csv_header(fields: &[String]) -> String {
+        let mut csv = String::new();
+        let header = [
+            "componentId",
+            "componentName",
+            "schemaName",
+            "resourceId",
+            "status",
+            "lastSynced",
+        ]
+        .into_iter()
+        .chain(fields.iter().map(String::as_str));
+        push_csv_record(&mut csv, header);
+
+        csv
+    }
+
+    /// The line of [`Self::to_csv`] for this entry.
+    pub fn to_csv_record(&self, fields: &[String]) -> String {
+        let status = match self.status {
+            ResourceStatus::Error => "error",
+            ResourceStatus::Ok => "ok",
+            ResourceStatus::Warning => "warning",
+        };
+        let fixed = [
+            self.component_id.to_string(),
+            self.component_name.clone(),
+            self.schema_name.clone(),
+            self.resource_id.clone().unwrap_or_default(),
+            status.to_owned(),
+            self.last_synced.to_rfc3339(),
+        ];
+        let fields = fields
+            .iter()
+            .map(|field| match self.fields.get(field) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(text)) => text.clone(),
+                Some(value) => value.to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut csv = String::new();
+        push_csv_record(
+            &mut csv,
+            fixed.iter().chain(fields.iter()).map(String::as_str),
+        );
+
+        csv
+    }
+}
+
+fn push_csv_record<'a>(csv: &mut String, values: impl Iterator<Item = &'a str>) {
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            csv.push(',');
+        }
+        if value.contains([',', '"', '\n', '\r']) {
+            csv.push('"');
+            csv.push_str(&value.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(value);
+        }
+    }
+    csv.push_str("\r\n");
+}
@@ -1,5 +1,10 @@
 use std::{
-    collections::HashSet,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        HashMap,
+        HashSet,
+    },
     fmt,
     mem,
     path::PathBuf,
@@ -103,17 +108,24 @@ use crate::{
     EncryptedSecret,
     Workspace,
     WorkspaceError,
+    WorkspaceFeatureFlag,
     WorkspacePk,
     WorkspaceSnapshot,
     audit_logging::{
         self,
         AuditLoggingError,
     },
+    cached_module::ModuleCacheWarmUp,
     change_set::{
         ChangeSet,
         ChangeSetId,
     },
     feature_flags::FeatureFlagService,
+    func::execution_budget::ExecutionBudget,
+    health::{
+        HealthDependency,
+        HealthReport,
+    },
     jetstream_streams::JetstreamStreams,
     job::{
         consumer::DalJob,
@@ -148,6 +160,8 @@ use crate::{
             SubGraphVCurrent,
         },
     },
+    workspace_webhook::WebhookTargetPolicy,
+    ws_event_log::WsEventLog,
 };
 
 pub type DalLayerDb = LayerDb<
@@ -192,6 +206,16 @@ pub struct ServicesContext {
     feature_flag_service: FeatureFlagService,
     /// Dedicated executor for running CPU-intensive tasks
     compute_executor: DedicatedExecutor,
+    /// Limits how many functions each workspace can have executing at once
+    execution_budget: ExecutionBudget,
+    /// The dependencies that make a [`HealthReport`] unready when they are unhealthy.
+    critical_health_dependencies: BTreeSet<HealthDependency>,
+    /// The kinds of [`WsEvents`](crate::WsEvent) never recorded in the [`WsEventLog`].
+    ws_event_log_excluded_kinds: Arc<BTreeSet<String>>,
+    /// Which endpoints workspace webhooks may point at and be delivered to.
+    webhook_target_policy: WebhookTargetPolicy,
+    /// Fills the module cache in the background when it is found empty.
+    module_cache_warm_up: ModuleCacheWarmUp,
 }
 
 impl ServicesContext {
@@ -226,9 +250,54 @@ impl ServicesContext {
             layer_db,
             feature_flag_service,
             compute_executor,
+            execution_budget: ExecutionBudget::default(),
+            critical_health_dependencies: HealthDependency::default_critical(),
+            ws_event_log_excluded_kinds: Arc::new(WsEventLog::default_excluded_kinds()),
+            webhook_target_policy: WebhookTargetPolicy::default(),
+            module_cache_warm_up: ModuleCacheWarmUp::default(),
         }
     }
 
+    /// Replaces the default [`ExecutionBudget`].
+    pub fn with_execution_budget(mut self, execution_budget: ExecutionBudget) -> Self {
+        self.execution_budget = execution_budget;
+        self
+    }
+
+    /// Replaces the dependencies that make a [`HealthReport`] unready when they are unhealthy.
+    pub fn with_critical_health_dependencies(
+        mut self,
+        critical_health_dependencies: impl IntoIterator<Item = HealthDependency>,
+    ) -> Self {
+        self.critical_health_dependencies = critical_health_dependencies.into_iter().collect();
+        self
+    }
+
+    /// Replaces the kinds of [`WsEvents`](crate::WsEvent) never recorded in the [`WsEventLog`].
+    pub fn with_ws_event_log_excluded_kinds(
+        mut self,
+        ws_event_log_excluded_kinds: impl IntoIterator<Item = String>,
+    ) -> Self {
+        self.ws_event_log_excluded_kinds =
+            Arc::new(ws_event_log_excluded_kinds.into_iter().collect());
+        self
+    }
+
+    /// Replaces the default [`WebhookTargetPolicy`].
+    pub fn with_webhook_target_policy(
+        mut self,
+        webhook_target_policy: WebhookTargetPolicy,
+    ) -> Self {
+        self.webhook_target_policy = webhook_target_policy;
+        self
+    }
+
+    /// Replaces the default [`ModuleCacheWarmUp`].
+    pub fn with_module_cache_warm_up(mut self, module_cache_warm_up: ModuleCacheWarmUp) -> Self {
+        self.module_cache_warm_up = module_cache_warm_up;
+        self
+    }
+
     /// Consumes and returns [`DalContextBuilder`].
     pub fn into_builder(self, blocking: bool) -> DalContextBuilder {
         DalContextBuilder {
@@ -297,6 +366,36 @@ impl ServicesContext {
         &self.compute_executor
     }
 
+    /// Gets a reference to the per-workspace function execution budget
+    pub fn execution_budget(&self) -> &ExecutionBudget {
+        &self.execution_budget
+    }
+
+    /// Gets a reference to the dependencies that must be healthy for a [`HealthReport`] to be ready
+    pub fn critical_health_dependencies(&self) -> &BTreeSet<HealthDependency> {
+        &self.critical_health_dependencies
+    }
+
+    /// Gets the kinds of [`WsEvents`](crate::WsEvent) never recorded in the [`WsEventLog`].
+    pub fn ws_event_log_excluded_kinds(&self) -> &BTreeSet<String> {
+        &self.ws_event_log_excluded_kinds
+    }
+
+    /// Gets which endpoints workspace webhooks may point at and be delivered to.
+    pub fn webhook_target_policy(&self) -> WebhookTargetPolicy {
+        self.webhook_target_policy
+    }
+
+    /// Gets the [`ModuleCacheWarmUp`] that fills the module cache when it is found empty.
+    pub fn module_cache_warm_up(&self) -> &ModuleCacheWarmUp {
+        &self.module_cache_warm_up
+    }
+
+    /// Checks whether pg, NATS, veritech and the module index are usable.
+    pub async fn health_report(&self) -> HealthReport {
+        HealthReport::check(self).await
+    }
+
     /// Builds and returns a new [`Connections`].
     pub async fn connections(&self) -> PgPoolResult<Connections> {
         let pg_conn = self.pg_pool.get().await?;
@@ -523,6 +622,11 @@ struct WorkspaceDefaultChangeSetId {
     default_change_set_id: ChangeSetId,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+struct WorkspaceFeatureFlags {
+    feature_flags: BTreeMap<String, bool>,
+}
+
 impl DalContext {
     /// Takes a reference to a [`ServicesContext`] and returns a builder to construct a
     /// `DalContext`.
@@ -551,6 +655,28 @@ impl DalContext {
         Ok(default_change_set_id)
     }
 
+    /// Whether the feature is enabled for the workspace of this context. The workspace's flags
+    /// are only loaded the first time, so this is cheap to call for every event published.
+    pub async fn workspace_feature_enabled(
+        &self,
+        flag: WorkspaceFeatureFlag,
+    ) -> TransactionsResult<bool> {
+        if let Some(cached) = self.cache.get::<WorkspaceFeatureFlags>() {
+            return Ok(flag.enabled_in(&cached.feature_flags));
+        }
+
+        let feature_flags = self.get_workspace().await?.feature_flags().clone();
+        let enabled = flag.enabled_in(&feature_flags);
+        self.cache.insert(WorkspaceFeatureFlags { feature_flags });
+
+        Ok(enabled)
+    }
+
+    /// Drops the flags cached by [`Self::workspace_feature_enabled`], e.g. after setting one.
+    pub(crate) fn forget_workspace_feature_flags(&self) {
+        self.cache.remove::<WorkspaceFeatureFlags>();
+    }
+
     pub async fn get_workspace_token(&self) -> Result<Option<String>, TransactionsError> {
         let workspace_pk = self
             .tenancy()
@@ -832,6 +958,17 @@ impl DalContext {
         }
     }
 
+    /// Publishes the [`WsEvents`](crate::WsEvent) waiting on the commit now, without committing.
+    /// Long-lived contexts can use this so that clients aren't kept waiting until the end.
+    #[instrument(name = "context.flush_ws_events", level = "debug", skip_all)]
+    pub async fn flush_ws_events(&self) -> TransactionsResult<()> {
+        let mut txns = self.txns().await?;
+        txns.nats().flush_pending().await?;
+        // The flushed events can no longer be replaced, so neither can their log entries
+        txns.ws_event_log_coalesced.clear();
+        Ok(())
+    }
+
     /// Consumes all inner transactions and committing all changes made within them.
     #[instrument(name = "context.commit", level = "info", skip_all)]
     pub async fn commit(&self) -> TransactionsResult<()> {
@@ -987,6 +1124,14 @@ impl DalContext {
         new
     }
 
+    /// Clones a new context from this one that talks to the module index at the given url instead
+    /// of the one it was configured with.
+    pub fn clone_with_module_index_url(&self, module_index_url: impl Into<String>) -> Self {
+        let mut new = self.clone();
+        new.services_context.module_index_url = Some(module_index_url.into());
+        new
+    }
+
     /// Runs a block of code with a custom [`Visibility`] DalContext using the same transactions
     pub async fn run_with_visibility<F, Fut, R>(&self, visibility: Visibility, fun: F) -> R
     where
@@ -1038,6 +1183,7 @@ impl DalContext {
     pub fn update_tenancy(&mut self, tenancy: Tenancy) {
         // Bust cache as we're updating tenancy (i.e. workspace_pk)
         self.cache.remove::<WorkspaceDefaultChangeSetId>();
+        self.forget_workspace_feature_flags();
 
         self.tenancy = tenancy;
     }
@@ -1261,6 +1407,21 @@ impl DalContext {
         &self.services_context.compute_executor
     }
 
+    /// Gets a reference to the DAL context's function execution budget.
+    pub fn execution_budget(&self) -> &ExecutionBudget {
+        &self.services_context.execution_budget
+    }
+
+    /// Gets the kinds of [`WsEvents`](crate::WsEvent) never recorded in the [`WsEventLog`].
+    pub fn ws_event_log_excluded_kinds(&self) -> &BTreeSet<String> {
+        &self.services_context.ws_event_log_excluded_kinds
+    }
+
+    /// Gets which endpoints workspace webhooks may point at and be delivered to.
+    pub fn webhook_target_policy(&self) -> WebhookTargetPolicy {
+        self.services_context.webhook_target_policy
+    }
+
     /// Gets a reference to the DAL context's encryption key.
     pub fn encryption_key(&self) -> &VeritechEncryptionKey {
         &self.services_context.encryption_key
@@ -1319,6 +1480,13 @@ impl DalContext {
         )
     }
 
+    /// Returns the [`RequestContext`] this context was built from, so that a
+    /// [`DalContextBuilder`] (see [`Self::to_builder`]) can build an equivalent context, e.g. in a
+    /// spawned task.
+    pub fn to_request_context(&self) -> RequestContext {
+        self.access_builder().build(*self.visibility())
+    }
+
     /// Returns a new [`jetstream::Context`].
     pub fn jetstream_context(&self) -> jetstream::Context {
         jetstream::new(self.nats_conn().to_owned())
@@ -1901,6 +2069,10 @@ pub struct Transactions {
     nats_txn: NatsTxn,
     job_processor: Box<dyn JobQueueProcessor + Send + Sync>,
     job_queue: JobQueue,
+    /// The [`WsEventLog`] positions of the coalesced events still waiting on the commit, by
+    /// workspace and coalesce key, so that a later event can replace the entry along with the
+    /// event.
+    ws_event_log_coalesced: HashMap<(WorkspacePk, String), i64>,
 }
 
 impl Transactions {
@@ -1915,6 +2087,7 @@ impl Transactions {
             nats_txn,
             job_processor,
             job_queue: JobQueue::default(),
+            ws_event_log_coalesced: HashMap::new(),
         }
     }
 
@@ -1932,6 +2105,28 @@ impl Transactions {
         &self.job_queue
     }
 
+    /// Sets the [`WsEventLog`] position of the coalesced event waiting on the commit.
+    pub(crate) fn set_ws_event_log_coalesced(
+        &mut self,
+        workspace_pk: WorkspacePk,
+        coalesce_key: String,
+        position: i64,
+    ) {
+        self.ws_event_log_coalesced
+            .insert((workspace_pk, coalesce_key), position);
+    }
+
+    /// Gets the [`WsEventLog`] position of the coalesced event waiting on the commit, if any.
+    pub(crate) fn ws_event_log_coalesced(
+        &self,
+        workspace_pk: WorkspacePk,
+        coalesce_key: &str,
+    ) -> Option<i64> {
+        self.ws_event_log_coalesced
+            .get(&(workspace_pk, coalesce_key.to_string()))
+            .copied()
+    }
+
     /// Consumes all inner transactions, committing all changes made within them, and returns
     /// underlying connections.
     #[instrument(name = "transactions.commit_into_conns", level = "info", skip_all)]
@@ -29,6 +29,7 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use si_data_pg::PgError;
 use si_events::{
     CasValue,
     ContentHash,
@@ -82,7 +83,9 @@ pub mod argument;
 pub mod authoring;
 pub mod backend;
 pub mod binding;
+pub mod conflict;
 pub mod debug;
+pub mod execution_budget;
 pub mod intrinsics;
 mod kind;
 pub mod leaf;
@@ -125,6 +128,8 @@ pub enum FuncError {
     LayerDb(#[from] si_layer_cache::LayerDbError),
     #[error("node weight error: {0}")]
     NodeWeight(#[from] NodeWeightError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
     #[error("si pkg error: {0}")]
     Pkg(#[from] Box<pkg::PkgError>),
     #[error("pkg error: {0}")]
@@ -637,6 +642,10 @@ impl Func {
         let updated = FuncContent::from(func.clone());
 
         if updated != before {
+            // The node weight was read before updating the content, so this is the content the
+            // func had before this edit.
+            Self::record_edit_base(ctx, func.id, node_weight.content_hash()).await?;
+
             let (hash, _) = ctx.layer_db().cas().write(
                 Arc::new((updated.clone()).into()),
                 None,
@@ -706,6 +715,33 @@ impl Func {
         Self::list_inner(ctx, func_node_weights, func_content_hashes).await
     }
 
+    /// List the ids of all [`Funcs`](Func) in the workspace, without loading their content
+    pub async fn list_ids(ctx: &DalContext) -> FuncResult<Vec<FuncId>> {
+        let workspace_snapshot = ctx.workspace_snapshot()?;
+
+        let func_category_id = workspace_snapshot
+            .get_category_node_or_err(CategoryNodeKind::Func)
+            .await?;
+
+        let func_node_indexes = workspace_snapshot
+            .outgoing_targets_for_edge_weight_kind(
+                func_category_id,
+                EdgeWeightKindDiscriminants::Use,
+            )
+            .await?;
+
+        let mut func_ids = Vec::with_capacity(func_node_indexes.len());
+        for index in func_node_indexes {
+            let node_weight = workspace_snapshot
+                .get_node_weight(index)
+                .await?
+                .get_func_node_weight()?;
+            func_ids.push(node_weight.id().into());
+        }
+
+        Ok(func_ids)
+    }
+
     /// List all [`Funcs`](Func) in the workspace that are either unlocked, attached to a default
     /// [`SchemaVariant`] or attached to an unlocked Schema Variant
     pub async fn list_for_default_and_editing(ctx: &DalContext) -> FuncResult<Vec<Self>> {
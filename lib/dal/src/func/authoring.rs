@@ -34,6 +34,10 @@ use base64::{
     engine::general_purpose,
 };
 use chrono::Utc;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use si_events::FuncRunId;
 use si_frontend_types::FuncSummary;
 use si_id::SchemaId;
@@ -295,6 +299,53 @@ impl From<WsEventError> for FuncAuthoringError {
 
 type FuncAuthoringResult<T> = Result<T, FuncAuthoringError>;
 
+/// A problem found with a [`Func`](crate::Func)'s code when it is saved via
+/// [`FuncAuthoringClient::save_code_and_validate`]. Problems are reported to the author, but do
+/// not prevent the code from being saved.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum FuncCodeWarning {
+    /// The code is empty, so the func will fail whenever it runs.
+    EmptyCode,
+    /// The func's handler is not defined anywhere in the code, so the func will fail whenever it
+    /// runs.
+    #[serde(rename_all = "camelCase")]
+    HandlerNotFound {
+        /// The name of the handler that was not found.
+        handler: String,
+    },
+}
+
+impl FuncCodeWarning {
+    /// Finds the problems with the code for the given handler.
+    fn check(code: &str, handler: Option<&str>) -> Vec<Self> {
+        if code.trim().is_empty() {
+            return vec![Self::EmptyCode];
+        }
+
+        let mut warnings = Vec::new();
+        if let Some(handler) = handler.filter(|handler| !handler.is_empty()) {
+            if !contains_identifier(code, handler) {
+                warnings.push(Self::HandlerNotFound {
+                    handler: handler.to_string(),
+                });
+            }
+        }
+        warnings
+    }
+}
+
+/// Whether the identifier appears in the code on its own, rather than as part of a longer one.
+fn contains_identifier(code: &str, identifier: &str) -> bool {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    code.match_indices(identifier).any(|(start, _)| {
+        let before = code[..start].chars().next_back();
+        let after = code[start + identifier.len()..].chars().next();
+        !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+    })
+}
+
 /// This unit struct is the primary interface for the [`Func`](crate::Func) authoring experience.
 #[derive(Debug)]
 pub struct FuncAuthoringClient;
@@ -778,6 +829,30 @@ impl FuncAuthoringClient {
         Ok(())
     }
 
+    /// Saves the code for the [`FuncId`] as in [`Self::save_code`], then checks it for problems
+    /// that would make the func fail whenever it runs. The problems found are returned as
+    /// warnings; they do not prevent the save.
+    #[instrument(
+        level = "info",
+        name = "func.authoring.save_code_and_validate",
+        skip(ctx, code)
+    )]
+    pub async fn save_code_and_validate(
+        ctx: &DalContext,
+        func_id: FuncId,
+        code: impl Into<String>,
+    ) -> FuncAuthoringResult<Vec<FuncCodeWarning>> {
+        let code = code.into();
+        let warnings = {
+            let func = Func::get_by_id(ctx, func_id).await?;
+            FuncCodeWarning::check(&code, func.handler.as_deref())
+        };
+
+        Self::save_code(ctx, func_id, code).await?;
+
+        Ok(warnings)
+    }
+
     /// Save metadata about the [`FuncId`]
     /// Returns an error if the [`Func`] is currently locked (unless it has overlay bindings)
     #[instrument(level = "info", name = "func.authoring.update_func", skip(ctx))]
@@ -0,0 +1,243 @@
+//! This module contains [`Func::detect_conflicts`], which tells whether applying a change set
+//! would overwrite edits made to a [`Func`] on HEAD since the change set started editing it.
+
+use std::str::FromStr;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_events::ContentHash;
+use strum::Display;
+
+use super::{
+    Func,
+    FuncResult,
+};
+use crate::{
+    ChangeSetId,
+    DalContext,
+    FuncId,
+};
+
+/// How applying the change set would affect a [`Func`] on HEAD.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FuncConflictStatus {
+    /// The func was edited both in the change set and on HEAD since the change set started
+    /// editing it, so applying the change set would overwrite the edits made on HEAD.
+    Conflicting,
+    /// Only the change set edited the func, so applying it simply moves HEAD forward.
+    FastForward,
+    /// The change set never edited the func, or its edits have since been undone.
+    NoConflict,
+}
+
+/// The result of [`Func::detect_conflicts`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncConflict {
+    pub func_id: FuncId,
+    pub status: FuncConflictStatus,
+    /// The fields edited in the change set or on HEAD since the change set first edited the
+    /// func, when conflicting.
+    pub differing_fields: Vec<String>,
+}
+
+impl Func {
+    /// Compares the [`Func`] in the change set and the one on HEAD with the content it had when
+    /// the change set first edited it, to tell whether applying the change set would overwrite
+    /// edits made on HEAD in the meantime.
+    pub async fn detect_conflicts(ctx: &DalContext, func_id: FuncId) -> FuncResult<FuncConflict> {
+        let no_conflict = FuncConflict {
+            func_id,
+            status: FuncConflictStatus::NoConflict,
+            differing_fields: Vec::new(),
+        };
+        if ctx.is_head().await? {
+            return Ok(no_conflict);
+        }
+        let Some(base_content_hash) = Self::edit_base_content_hash(ctx, func_id).await? else {
+            return Ok(no_conflict);
+        };
+
+        let head_ctx = ctx.clone_with_head().await?;
+        let Some(head_func) = Self::get_by_id_opt(&head_ctx, func_id).await? else {
+            return Ok(no_conflict);
+        };
+        let node_weight = Self::node_weight(ctx, func_id).await?;
+        let func = Self::get_by_id_inner(ctx, &node_weight.content_hash(), &node_weight).await?;
+        let base_func = Self::get_by_id_inner(ctx, &base_content_hash, &node_weight).await?;
+
+        let changed_here = func.differing_fields(&base_func);
+        let changed_on_head = head_func.differing_fields(&base_func);
+        let (status, differing_fields) = match (changed_here.is_empty(), changed_on_head.is_empty())
+        {
+            (true, _) => (FuncConflictStatus::NoConflict, Vec::new()),
+            (false, true) => (FuncConflictStatus::FastForward, Vec::new()),
+            // Edits on HEAD may already have been replayed onto the change set, so list every
+            // field edited on either side rather than only those that differ right now.
+            (false, false) => {
+                let mut fields = changed_on_head;
+                for field in changed_here {
+                    if !fields.contains(&field) {
+                        fields.push(field);
+                    }
+                }
+                (FuncConflictStatus::Conflicting, fields)
+            }
+        };
+
+        Ok(FuncConflict {
+            func_id,
+            status,
+            differing_fields,
+        })
+    }
+
+    /// Runs [`Self::detect_conflicts`] for every func the change set has edited, returning the
+    /// conflicting ones.
+    pub async fn list_conflicts(ctx: &DalContext) -> FuncResult<Vec<FuncConflict>> {
+        if ctx.is_head().await? {
+            return Ok(Vec::new());
+        }
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                r#"
+            SELECT func_id FROM func_edit_bases
+            WHERE workspace_id = $1 AND change_set_id = $2
+            ORDER BY func_id
+        "#,
+                &[&ctx.workspace_pk()?, &ctx.change_set_id()],
+            )
+            .await?;
+
+        let mut conflicts = Vec::new();
+        for row in rows {
+            let func_id: FuncId = row.try_get("func_id")?;
+            let conflict = Self::detect_conflicts(ctx, func_id).await?;
+            if conflict.status == FuncConflictStatus::Conflicting {
+                conflicts.push(conflict);
+            }
+        }
+
+        Ok(conflicts)
+    }
+
+    /// Forgets the edit bases recorded for the change set, once it is applied or abandoned and
+    /// can no longer conflict with HEAD.
+    pub(crate) async fn forget_edit_bases(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+    ) -> FuncResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "DELETE FROM func_edit_bases WHERE change_set_id = $1",
+                &[&change_set_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Records the content the [`Func`] had before the change set first edited it, for
+    /// [`Self::detect_conflicts`]. Later edits keep the first recorded content.
+    pub(crate) async fn record_edit_base(
+        ctx: &DalContext,
+        func_id: FuncId,
+        base_content_hash: ContentHash,
+    ) -> FuncResult<()> {
+        let Some(workspace_pk) = ctx.tenancy().workspace_pk_opt() else {
+            return Ok(());
+        };
+        if ctx.is_head().await? {
+            return Ok(());
+        }
+
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                r#"
+            INSERT INTO func_edit_bases (
+                workspace_id,
+                change_set_id,
+                func_id,
+                base_content_hash
+            ) VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+        "#,
+                &[
+                    &workspace_pk,
+                    &ctx.change_set_id(),
+                    &func_id,
+                    &base_content_hash,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn edit_base_content_hash(
+        ctx: &DalContext,
+        func_id: FuncId,
+    ) -> FuncResult<Option<ContentHash>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                r#"
+            SELECT base_content_hash FROM func_edit_bases
+            WHERE workspace_id = $1 AND change_set_id = $2 AND func_id = $3
+        "#,
+                &[&ctx.workspace_pk()?, &ctx.change_set_id(), &func_id],
+            )
+            .await?;
+
+        Ok(match maybe_row {
+            Some(row) => {
+                let base_content_hash: String = row.try_get("base_content_hash")?;
+                ContentHash::from_str(&base_content_hash).ok()
+            }
+            None => None,
+        })
+    }
+
+    /// The fields that differ between the two funcs. Timestamps are ignored.
+    fn differing_fields(&self, other: &Self) -> Vec<String> {
+        let mut fields = Vec::new();
+        let mut compare = |field: &str, differs: bool| {
+            if differs {
+                fields.push(field.to_string());
+            }
+        };
+        compare("name", self.name != other.name);
+        compare("kind", self.kind != other.kind);
+        compare("displayName", self.display_name != other.display_name);
+        compare("description", self.description != other.description);
+        compare("link", self.link != other.link);
+        compare("hidden", self.hidden != other.hidden);
+        compare("backendKind", self.backend_kind != other.backend_kind);
+        compare(
+            "backendResponseType",
+            self.backend_response_type != other.backend_response_type,
+        );
+        compare("handler", self.handler != other.handler);
+        compare("code", self.code_base64 != other.code_base64);
+        compare("isLocked", self.is_locked != other.is_locked);
+        compare(
+            "isTransformation",
+            self.is_transformation != other.is_transformation,
+        );
+        fields
+    }
+}
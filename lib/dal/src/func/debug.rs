@@ -104,6 +104,9 @@ pub struct DebugFuncJobStateRow {
     pub change_set_id: ChangeSetId,
     pub user_id: Option<UserPk>,
     pub debug_input: Option<serde_json::Value>,
+    /// The names of the workspace secrets to pass to the func, on top of the ones the component
+    /// uses.
+    pub secret_names: Vec<String>,
     pub state: DebugFuncJobState,
     pub failure: Option<String>,
     pub result: Option<serde_json::Value>,
@@ -130,6 +133,7 @@ impl TryFrom<PgRow> for DebugFuncJobStateRow {
         let failure: Option<String> = row.try_get("failure")?;
         let result: Option<serde_json::Value> = row.try_get("result")?;
         let debug_input: Option<serde_json::Value> = row.try_get("debug_input")?;
+        let secret_names: Vec<String> = row.try_get("secret_names")?;
         let code: String = row.try_get("code")?;
         let func_name: String = row.try_get("func_name")?;
         let handler: String = row.try_get("handler")?;
@@ -145,6 +149,7 @@ impl TryFrom<PgRow> for DebugFuncJobStateRow {
             timestamp: Timestamp::new(created_at, updated_at),
             result,
             debug_input,
+            secret_names,
             failure,
             code,
             func_name,
@@ -178,6 +183,7 @@ impl DebugFuncJobStateRow {
         handler: &str,
         name: &str,
         debug_input: Option<serde_json::Value>,
+        secret_names: &[String],
     ) -> DebugFuncResult<DebugFuncJobStateId> {
         let mut ctx_clone = ctx.clone();
         ctx_clone.restart_connections().await?;
@@ -201,6 +207,7 @@ impl DebugFuncJobStateRow {
                 handler,
                 func_name,
                 debug_input,
+                secret_names,
                 state
             ) VALUES (
                 $1,
@@ -211,7 +218,8 @@ impl DebugFuncJobStateRow {
                 $6,
                 $7,
                 $8,
-                $9
+                $9,
+                $10
             ) RETURNING id;
         "#,
                 &[
@@ -223,6 +231,7 @@ impl DebugFuncJobStateRow {
                     &handler,
                     &name,
                     &debug_input,
+                    &secret_names,
                     &DebugFuncJobState::Pending.to_string(),
                 ],
             )
@@ -394,11 +403,18 @@ pub async fn dispatch_debug_func<Input: Serialize>(
     debug_component_id: ComponentId,
     debug_func: Func,
     debug_input: Option<Input>,
+    secret_names: Vec<String>,
 ) -> DebugFuncResult<DebugFuncJobStateId> {
     if debug_func.kind != FuncKind::Debug {
         return Err(DebugFuncError::NotADebugFunc(debug_func.id));
     }
 
+    // Resolve the secrets now, so that a missing one is reported to the caller rather than failing
+    // the job later on.
+    FuncRunner::resolve_named_secrets(ctx, &secret_names)
+        .await
+        .map_err(Box::new)?;
+
     let code: String = debug_func
         .code_plaintext()
         .map_err(Box::new)?
@@ -419,6 +435,7 @@ pub async fn dispatch_debug_func<Input: Serialize>(
         handler,
         name,
         debug_input,
+        &secret_names,
     )
     .await?;
 
@@ -0,0 +1,318 @@
+//! This module contains [`ExecutionBudget`], which limits how many functions a single workspace
+//! can have executing in veritech at once, and keeps track of the executions waiting on it.
+//!
+//! Every process holds a budget of its own, so the waiting executions are recorded in the
+//! database, where any server can list them for the workspace.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_data_pg::{
+    PgError,
+    PgPool,
+    PgPoolError,
+    PgRow,
+};
+use si_events::{
+    ActionId,
+    ComponentId,
+    FuncRunId,
+    WorkspacePk,
+};
+use telemetry::prelude::*;
+use telemetry_utils::metric;
+use thiserror::Error;
+use tokio::sync::{
+    OwnedSemaphorePermit,
+    Semaphore,
+};
+
+/// The default number of functions a workspace can have executing at once.
+pub const DEFAULT_EXECUTION_BUDGET_PERMITS: usize = 256;
+/// The default amount of time to wait for the budget before giving up on an execution.
+pub const DEFAULT_EXECUTION_BUDGET_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ExecutionBudgetError {
+    #[error("execution budget for workspace {0} is closed")]
+    Closed(WorkspacePk),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[from] PgPoolError),
+    #[error(
+        "timed out after {timeout:?} waiting for one of the {permits} executions allowed for workspace {workspace_pk}"
+    )]
+    Timeout {
+        workspace_pk: WorkspacePk,
+        permits: usize,
+        timeout: Duration,
+    },
+}
+
+pub type ExecutionBudgetResult<T> = Result<T, ExecutionBudgetError>;
+
+/// An execution waiting for the [`ExecutionBudget`] of its workspace, as returned by
+/// [`ExecutionBudget::queued`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedExecution {
+    pub func_run_id: FuncRunId,
+    pub function_name: String,
+    /// The action the execution is running for, if any.
+    pub action_id: Option<ActionId>,
+    pub component_id: Option<ComponentId>,
+    pub queued_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for QueuedExecution {
+    type Error = ExecutionBudgetError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            func_run_id: row.try_get("func_run_id")?,
+            function_name: row.try_get("function_name")?,
+            action_id: row.try_get("action_id")?,
+            component_id: row.try_get("component_id")?,
+            queued_at: row.try_get("queued_at")?,
+        })
+    }
+}
+
+impl QueuedExecution {
+    async fn insert(
+        &self,
+        pg_pool: &PgPool,
+        workspace_pk: WorkspacePk,
+    ) -> ExecutionBudgetResult<()> {
+        pg_pool
+            .get()
+            .await?
+            .execute(
+                "INSERT INTO queued_executions (func_run_id, workspace_pk, function_name, action_id, component_id, queued_at) VALUES ($1, $2, $3, $4, $5, $6)",
+                &[
+                    &self.func_run_id,
+                    &workspace_pk,
+                    &self.function_name,
+                    &self.action_id,
+                    &self.component_id,
+                    &self.queued_at,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete(pg_pool: &PgPool, func_run_id: FuncRunId) -> ExecutionBudgetResult<()> {
+        pg_pool
+            .get()
+            .await?
+            .execute(
+                "DELETE FROM queued_executions WHERE func_run_id = $1",
+                &[&func_run_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ExecutionBudgetState {
+    permits: usize,
+    acquire_timeout: Duration,
+    semaphores: HashMap<WorkspacePk, Arc<Semaphore>>,
+}
+
+/// Removes an execution from the queue when dropped, whether it got its permit, timed out or
+/// was cancelled while waiting.
+struct QueuedEntry {
+    pg_pool: PgPool,
+    func_run_id: FuncRunId,
+}
+
+impl Drop for QueuedEntry {
+    fn drop(&mut self) {
+        let pg_pool = self.pg_pool.clone();
+        let func_run_id = self.func_run_id;
+        // Dropping can't wait, so the row is removed in the background.
+        tokio::spawn(async move {
+            if let Err(err) = QueuedExecution::delete(&pg_pool, func_run_id).await {
+                warn!(si.error.message = ?err, %func_run_id, "unable to remove queued execution");
+            }
+        });
+    }
+}
+
+/// Limits how many functions each workspace can have executing in veritech at once, so that a
+/// single busy workspace can't starve the others on a shared install.
+///
+/// The budget is per process (each server holds one in its
+/// [`ServicesContext`](crate::ServicesContext)) and cheap to clone.
+#[derive(Clone, Debug)]
+pub struct ExecutionBudget {
+    state: Arc<Mutex<ExecutionBudgetState>>,
+}
+
+impl Default for ExecutionBudget {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_EXECUTION_BUDGET_PERMITS,
+            DEFAULT_EXECUTION_BUDGET_ACQUIRE_TIMEOUT,
+        )
+    }
+}
+
+impl ExecutionBudget {
+    /// Creates a budget allowing each workspace the given number of concurrent executions (at
+    /// least one), waiting up to the timeout for one to become available.
+    pub fn new(permits: usize, acquire_timeout: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ExecutionBudgetState {
+                permits: permits.max(1),
+                acquire_timeout,
+                semaphores: HashMap::new(),
+            })),
+        }
+    }
+
+    /// The number of concurrent executions each workspace is allowed.
+    pub fn permits(&self) -> usize {
+        self.lock().permits
+    }
+
+    /// Changes the number of concurrent executions each workspace is allowed (at least one).
+    ///
+    /// Executions already holding a permit are unaffected; they count against the budget they
+    /// acquired, so the new budget is fully in effect once they finish.
+    pub fn set_permits(&self, permits: usize) {
+        let mut state = self.lock();
+        state.permits = permits.max(1);
+        state.semaphores.clear();
+    }
+
+    /// Waits for one of the workspace's executions to become available. The execution is counted
+    /// against the budget until the returned permit is dropped.
+    pub async fn acquire(
+        &self,
+        workspace_pk: WorkspacePk,
+    ) -> ExecutionBudgetResult<OwnedSemaphorePermit> {
+        let (semaphore, permits, timeout) = self.semaphore(workspace_pk);
+
+        let start = Instant::now();
+        let permit = tokio::time::timeout(timeout, semaphore.acquire_owned())
+            .await
+            .map_err(|_elapsed| ExecutionBudgetError::Timeout {
+                workspace_pk,
+                permits,
+                timeout,
+            })?
+            .map_err(|_closed| ExecutionBudgetError::Closed(workspace_pk))?;
+
+        metric!(
+            histogram.dal.func_runner.execution_budget_wait_seconds = start.elapsed().as_secs_f64()
+        );
+
+        Ok(permit)
+    }
+
+    /// Like [`Self::acquire`], but lists the execution in [`Self::queued`] for as long as it has
+    /// to wait. Nothing is recorded when a permit is available right away.
+    pub async fn acquire_queued(
+        &self,
+        pg_pool: &PgPool,
+        workspace_pk: WorkspacePk,
+        execution: QueuedExecution,
+    ) -> ExecutionBudgetResult<OwnedSemaphorePermit> {
+        let (semaphore, _, _) = self.semaphore(workspace_pk);
+        if let Ok(permit) = semaphore.try_acquire_owned() {
+            metric!(histogram.dal.func_runner.execution_budget_wait_seconds = 0.0);
+            return Ok(permit);
+        }
+
+        // Failing to list the execution shouldn't keep it from running.
+        let func_run_id = execution.func_run_id;
+        let _entry = match execution.insert(pg_pool, workspace_pk).await {
+            Ok(()) => Some(QueuedEntry {
+                pg_pool: pg_pool.clone(),
+                func_run_id,
+            }),
+            Err(err) => {
+                warn!(si.error.message = ?err, %func_run_id, "unable to record queued execution");
+                None
+            }
+        };
+
+        self.acquire(workspace_pk).await
+    }
+
+    /// The executions of the workspace waiting for the budget on any server, oldest first.
+    ///
+    /// Executions can't wait longer than the acquire timeout, so older ones (left behind by a
+    /// server which stopped while they waited) are ignored.
+    pub async fn queued(
+        &self,
+        pg_pool: &PgPool,
+        workspace_pk: WorkspacePk,
+    ) -> ExecutionBudgetResult<Vec<QueuedExecution>> {
+        let acquire_timeout = self.lock().acquire_timeout;
+        let oldest = chrono::Duration::from_std(acquire_timeout)
+            .ok()
+            .and_then(|timeout| Utc::now().checked_sub_signed(timeout))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let rows = pg_pool
+            .get()
+            .await?
+            .query(
+                "SELECT * FROM queued_executions WHERE workspace_pk = $1 AND queued_at > $2 ORDER BY queued_at",
+                &[&workspace_pk, &oldest],
+            )
+            .await?;
+
+        rows.into_iter().map(QueuedExecution::try_from).collect()
+    }
+
+    fn semaphore(&self, workspace_pk: WorkspacePk) -> (Arc<Semaphore>, usize, Duration) {
+        let mut state = self.lock();
+        let permits = state.permits;
+        // Forget the workspaces that have nothing executing, so the map doesn't grow forever.
+        // Every permit holds a reference to its semaphore.
+        state
+            .semaphores
+            .retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+        let semaphore = state
+            .semaphores
+            .entry(workspace_pk)
+            .or_insert_with(|| Arc::new(Semaphore::new(permits)))
+            .clone();
+        (semaphore, permits, state.acquire_timeout)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ExecutionBudgetState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
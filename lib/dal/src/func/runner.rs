@@ -1,5 +1,8 @@
 use std::{
-    collections::VecDeque,
+    collections::{
+        HashMap,
+        VecDeque,
+    },
     sync::Arc,
 };
 
@@ -118,7 +121,14 @@ use crate::{
         },
         value::AttributeValueError,
     },
-    func::backend::FuncBackendError,
+    func::{
+        backend::FuncBackendError,
+        execution_budget::{
+            ExecutionBudgetError,
+            ExecutionBudgetResult,
+            QueuedExecution,
+        },
+    },
     management::prototype::ManagementPrototypeId,
     prop::PropError,
     schema::variant::root_prop::RootPropChild,
@@ -154,6 +164,8 @@ pub enum FuncRunnerError {
     DoNotHavePermissionToKillExecution,
     #[error("empty widget options for secret prop id: {0}")]
     EmptyWidgetOptionsForSecretProp(PropId),
+    #[error("execution budget error: {0}")]
+    ExecutionBudget(#[from] ExecutionBudgetError),
     #[error("func error: {0}")]
     Func(#[from] Box<FuncError>),
     #[error("function backend error: {0}")]
@@ -168,6 +180,8 @@ pub enum FuncRunnerError {
     LayerDb(#[from] LayerDbError),
     #[error("missing attribute value for component ({0}) and prop ({1})")]
     MissingAttributeValue(ComponentId, PropId),
+    #[error("named secrets not found: {0:?}")]
+    NamedSecretsNotFound(Vec<String>),
     #[error("no widget options for secret prop id: {0}")]
     NoWidgetOptionsForSecretProp(PropId),
     #[error("prop error: {0}")]
@@ -292,6 +306,17 @@ pub struct FuncRunner {
 }
 
 impl FuncRunner {
+    /// The func runs of the context's workspace which are waiting for its
+    /// [`ExecutionBudget`](crate::func::execution_budget::ExecutionBudget) on any server, oldest
+    /// first.
+    pub async fn queued_executions(
+        ctx: &DalContext,
+    ) -> ExecutionBudgetResult<Vec<QueuedExecution>> {
+        ctx.execution_budget()
+            .queued(ctx.pg_pool(), ctx.events_tenancy().workspace_pk)
+            .await
+    }
+
     #[instrument(
         name = "func_runner.run_test",
         level = "debug",
@@ -1106,11 +1131,15 @@ impl FuncRunner {
             si.workspace.id = Empty,
         )
     )]
+    /// Runs a debug [`Func`] against the component. On top of the secrets the component uses, the
+    /// workspace secrets named in `secret_names` are passed to the execution as the inputs of their
+    /// authentication funcs, so they never end up in the func run's args.
     pub async fn run_debug(
         ctx: &DalContext,
         debug_func: Func,
         debug_component_id: ComponentId,
         args: serde_json::Value,
+        secret_names: &[String],
     ) -> FuncRunnerResult<(FuncRunId, FuncRunnerValueChannel)> {
         let span = current_span_for_instrument_at!("debug");
 
@@ -1130,6 +1159,7 @@ impl FuncRunner {
             func: Func,
             debug_component_id: ComponentId,
             args: serde_json::Value,
+            secret_names: &[String],
             span: &Span,
         ) -> FuncRunnerResult<FuncRunner> {
             let function_args: CasValue = args.clone().into();
@@ -1155,7 +1185,8 @@ impl FuncRunner {
                 ContentHash::new("".as_bytes())
             };
 
-            let before = FuncRunner::before_funcs(ctx, debug_component_id, &func).await?;
+            let mut before = FuncRunner::before_funcs(ctx, debug_component_id, &func).await?;
+            before.extend(FuncRunner::before_funcs_for_named_secrets(ctx, secret_names).await?);
             let debug_component = Component::get_by_id(ctx, debug_component_id).await?;
             let component_name = debug_component.name(ctx).await?;
             let schema_name = debug_component.schema(ctx).await?.name;
@@ -1239,9 +1270,16 @@ impl FuncRunner {
             })
         }
 
-        let runner = prepare(ctx, debug_func, debug_component_id, args, &span)
-            .await
-            .map_err(|err| span.record_err(err))?;
+        let runner = prepare(
+            ctx,
+            debug_func,
+            debug_component_id,
+            args,
+            secret_names,
+            &span,
+        )
+        .await
+        .map_err(|err| span.record_err(err))?;
 
         let func_run_id = runner.func_run.id();
         let result_channel = runner.execute(ctx.clone(), span).await;
@@ -1612,9 +1650,73 @@ impl FuncRunner {
         let ordered_before_funcs_with_secret_keys =
             Self::ordered_before_funcs_with_secret_keys(ctx, component_id).await?;
 
+        Self::before_funcs_for_secret_keys(ctx, ordered_before_funcs_with_secret_keys).await
+    }
+
+    /// Collects the [`BeforeFunctions`](BeforeFunction) for the workspace [`Secrets`](Secret) with
+    /// the given names, in order. Unlike the secrets a component uses, these are named by the
+    /// caller, so a name that doesn't match any secret is an error.
+    #[instrument(
+        name = "func_runner.before_funcs_for_named_secrets",
+        level = "debug",
+        skip_all
+    )]
+    pub async fn before_funcs_for_named_secrets(
+        ctx: &DalContext,
+        secret_names: &[String],
+    ) -> FuncRunnerResult<Vec<BeforeFunction>> {
+        if secret_names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let secrets = Self::resolve_named_secrets(ctx, secret_names).await?;
+
+        let mut before_funcs_with_secret_keys = Vec::with_capacity(secrets.len());
+        for secret in secrets {
+            let auth_funcs =
+                Self::auth_funcs_for_secret_definition(ctx, secret.definition()).await?;
+            before_funcs_with_secret_keys.push((secret.encrypted_secret_key(), auth_funcs));
+        }
+
+        Self::before_funcs_for_secret_keys(ctx, before_funcs_with_secret_keys).await
+    }
+
+    /// Finds the workspace [`Secrets`](Secret) with the given names, in order, returning
+    /// [`FuncRunnerError::NamedSecretsNotFound`] with every name that doesn't match one.
+    pub async fn resolve_named_secrets(
+        ctx: &DalContext,
+        secret_names: &[String],
+    ) -> FuncRunnerResult<Vec<Secret>> {
+        let secrets_by_name: HashMap<String, Secret> = Secret::list(ctx)
+            .await?
+            .into_iter()
+            .map(|secret| (secret.name().to_owned(), secret))
+            .collect();
+
+        let mut secrets = Vec::with_capacity(secret_names.len());
+        let mut missing = Vec::new();
+        for secret_name in secret_names {
+            match secrets_by_name.get(secret_name) {
+                Some(secret) => secrets.push(secret.clone()),
+                None => missing.push(secret_name.to_owned()),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(FuncRunnerError::NamedSecretsNotFound(missing));
+        }
+
+        Ok(secrets)
+    }
+
+    /// This _private_ method decrypts the secret for each [`key`](EncryptedSecretKey) and builds
+    /// the [`BeforeFunctions`](BeforeFunction) that pass it to the corresponding [`Funcs`](Func).
+    async fn before_funcs_for_secret_keys(
+        ctx: &DalContext,
+        before_funcs_with_secret_keys: Vec<(EncryptedSecretKey, Vec<Func>)>,
+    ) -> FuncRunnerResult<Vec<BeforeFunction>> {
         let mut before_functions = Vec::new();
 
-        for (key, before_funcs) in ordered_before_funcs_with_secret_keys {
+        for (key, before_funcs) in before_funcs_with_secret_keys {
             let encrypted_secret = EncryptedSecret::get_by_key(ctx, key)
                 .await?
                 .ok_or(SecretError::EncryptedSecretNotFound(key))?;
@@ -1809,8 +1911,17 @@ impl FuncRunner {
             ))?
             .value;
 
+        Self::auth_funcs_for_secret_definition(ctx, &secret_definition_name).await
+    }
+
+    /// This _private_ method gathers the authentication functions for the secret definition with
+    /// the given name.
+    async fn auth_funcs_for_secret_definition(
+        ctx: &DalContext,
+        secret_definition_name: &str,
+    ) -> FuncRunnerResult<Vec<Func>> {
         // Iterate through all default secret defining schema variants and find the output socket that matches the
-        // provided secret child prop. This works on two assumptions. First: secret defining schema variants can have
+        // secret definition. This works on two assumptions. First: secret defining schema variants can have
         // one and only one output socket, and that socket must correspond to the secret that it defines. Second:
         // secret definition names are unique with the change set.
         let mut auth_funcs = Vec::new();
@@ -1961,6 +2072,43 @@ impl FuncRunnerExecutionTask {
     }
 
     async fn try_run(self) -> FuncRunnerResult<()> {
+        // Intrinsics never leave this process, so only funcs dispatched to veritech count against
+        // the workspace's budget. The permit is held until the execution finishes.
+        let _execution_permit = if self.func.is_intrinsic() {
+            None
+        } else {
+            let queued_execution = QueuedExecution {
+                func_run_id: self.func_run.id(),
+                function_name: self.func_run.function_name().to_owned(),
+                action_id: self.func_run.action_id(),
+                component_id: self.func_run.component_id(),
+                queued_at: Utc::now(),
+            };
+            match self
+                .ctx
+                .execution_budget()
+                .acquire_queued(
+                    self.ctx.pg_pool(),
+                    self.func_run.workspace_pk(),
+                    queued_execution,
+                )
+                .await
+            {
+                Ok(permit) => Some(permit),
+                Err(err) => {
+                    FuncRunner::update_run(&self.ctx, self.func_run.id(), |func_run| {
+                        func_run.set_state(FuncRunState::Failure);
+                    })
+                    .await?;
+
+                    // Don't stop running the function just because we can't send the result
+                    #[allow(unused_must_use)]
+                    self.result_tx.send(Err(err.into()));
+                    return Ok(());
+                }
+            }
+        };
+
         if !self.func.is_intrinsic() {
             FuncRunner::update_run(&self.ctx, self.func_run.id(), |func_run| {
                 func_run.set_state(FuncRunState::Running);
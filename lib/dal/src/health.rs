@@ -0,0 +1,211 @@
+//! This module contains [`HealthReport`], which summarizes whether the services a
+//! [`ServicesContext`] depends on are usable.
+
+use std::{
+    collections::BTreeSet,
+    fmt,
+    future::Future,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use module_index_client::ModuleIndexClient;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use strum::{
+    Display,
+    EnumIter,
+    EnumString,
+    IntoEnumIterator,
+};
+use url::Url;
+
+use crate::ServicesContext;
+
+/// How long to wait on a dependency before reporting it as unhealthy.
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The error [`check_module_index`] returns when no module index url is configured.
+pub const MODULE_INDEX_URL_NOT_SET: &str = "module index url not set";
+
+/// A service a [`ServicesContext`] depends on.
+#[remain::sorted]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum HealthDependency {
+    ModuleIndex,
+    Nats,
+    Pg,
+    Veritech,
+}
+
+impl HealthDependency {
+    /// Whether the dependency being unhealthy makes the server unready, unless configured
+    /// otherwise. Only the module index is optional, since everything but installing modules
+    /// works without it.
+    pub fn critical_by_default(&self) -> bool {
+        !matches!(self, Self::ModuleIndex)
+    }
+
+    /// The dependencies that are critical unless configured otherwise.
+    pub fn default_critical() -> BTreeSet<Self> {
+        Self::iter()
+            .filter(HealthDependency::critical_by_default)
+            .collect()
+    }
+}
+
+/// The outcome of checking a single [`HealthDependency`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyHealth {
+    pub dependency: HealthDependency,
+    pub healthy: bool,
+    /// Whether the dependency being unhealthy makes the report unready.
+    pub critical: bool,
+    /// How long the check took, if it succeeded.
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// The health of every [`HealthDependency`], as reported by [`ServicesContext::health_report`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// Whether every critical dependency is healthy.
+    pub ready: bool,
+    pub dependencies: Vec<DependencyHealth>,
+}
+
+impl HealthReport {
+    /// Checks every dependency of the [`ServicesContext`] concurrently.
+    pub async fn check(services_context: &ServicesContext) -> Self {
+        let critical = services_context.critical_health_dependencies();
+
+        let (module_index, nats, pg, veritech) = futures::join!(
+            check_dependency(
+                HealthDependency::ModuleIndex,
+                critical,
+                check_module_index(services_context.module_index_url(), None),
+            ),
+            check_dependency(
+                HealthDependency::Nats,
+                critical,
+                services_context.nats_conn().flush(),
+            ),
+            check_dependency(
+                HealthDependency::Pg,
+                critical,
+                services_context.pg_pool().test_connection(),
+            ),
+            check_dependency(
+                HealthDependency::Veritech,
+                critical,
+                check_veritech(services_context),
+            ),
+        );
+        let dependencies = vec![module_index, nats, pg, veritech];
+
+        Self {
+            ready: dependencies
+                .iter()
+                .all(|dependency| dependency.healthy || !dependency.critical),
+            dependencies,
+        }
+    }
+
+    /// Returns the health of the given dependency.
+    pub fn dependency(&self, dependency: HealthDependency) -> Option<&DependencyHealth> {
+        self.dependencies
+            .iter()
+            .find(|health| health.dependency == dependency)
+    }
+
+    /// Returns the report without the error of each dependency, for callers who should only
+    /// learn whether the dependencies are healthy.
+    pub fn without_errors(mut self) -> Self {
+        for dependency in &mut self.dependencies {
+            dependency.error = None;
+        }
+        self
+    }
+}
+
+async fn check_dependency<E: fmt::Display>(
+    dependency: HealthDependency,
+    critical: &BTreeSet<HealthDependency>,
+    check: impl Future<Output = Result<(), E>>,
+) -> DependencyHealth {
+    let started_at = Instant::now();
+    let result = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, check).await {
+        Ok(result) => result.map_err(|err| err.to_string()),
+        Err(_elapsed) => Err(format!("timed out after {HEALTH_CHECK_TIMEOUT:?}")),
+    };
+
+    let (healthy, latency_ms, error) = match result {
+        Ok(()) => (true, Some(started_at.elapsed().as_millis() as u64), None),
+        Err(error) => (false, None, Some(error)),
+    };
+
+    DependencyHealth {
+        dependency,
+        healthy,
+        critical: critical.contains(&dependency),
+        latency_ms,
+        error,
+    }
+}
+
+/// Checks that a module index url is configured and that the module index responds to a ping
+/// within [`HEALTH_CHECK_TIMEOUT`]. [`HealthReport::check`] pings without a token, while callers
+/// checking on behalf of a user can pass theirs.
+pub async fn check_module_index(
+    module_index_url: Option<&str>,
+    auth_token: Option<&str>,
+) -> Result<(), String> {
+    let module_index_url = module_index_url.ok_or(MODULE_INDEX_URL_NOT_SET)?;
+    let url = Url::parse(module_index_url).map_err(|err| err.to_string())?;
+
+    let client = match auth_token {
+        Some(auth_token) => ModuleIndexClient::new(url, auth_token),
+        None => ModuleIndexClient::unauthenticated_client(url),
+    };
+    client
+        .map_err(|err| err.to_string())?
+        .system_status(HEALTH_CHECK_TIMEOUT)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Veritech only listens on the work queue, so rather than executing something, check that at
+/// least one veritech server is pulling from it.
+async fn check_veritech(services_context: &ServicesContext) -> Result<(), String> {
+    match services_context
+        .veritech()
+        .work_queue_is_being_pulled()
+        .await
+    {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("no veritech servers are pulling from the work queue".to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
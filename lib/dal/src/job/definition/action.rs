@@ -51,6 +51,12 @@ use crate::{
         JobConsumer,
         JobConsumerResult,
     },
+    resource_metadata,
+    workspace_webhook::{
+        WorkspaceWebhook,
+        WorkspaceWebhookEvent,
+        WorkspaceWebhookEventKind,
+    },
 };
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -201,6 +207,7 @@ async fn process_execution_result(
     let prototype = ActionPrototype::get_by_id(ctx, prototype_id).await?;
     let func_id = ActionPrototype::func_id(ctx, prototype_id).await?;
     let func = Func::get_by_id(ctx, func_id).await?;
+    let func_name = func.name.clone();
 
     let component_id = Action::component_id(ctx, action_id)
         .await?
@@ -301,17 +308,85 @@ async fn process_execution_result(
             func_name: func.name.clone(),
             func_display_name: func.display_name,
             run_status: success,
+            component_id: Some(component_id),
         },
         func.name,
     )
     .await?;
 
+    if prototype.kind == ActionKind::Refresh {
+        let component_name = Component::name_by_id(ctx, component_id).await?;
+        ctx.write_audit_log(
+            AuditLogKind::RefreshResource {
+                component_id,
+                component_name: component_name.clone(),
+                resource_id: action_run_result.and_then(|result| result.resource_id.clone()),
+                status: action_run_result
+                    .map(|result| resource_metadata::events_resource_status(result.status)),
+                func_run_id,
+            },
+            component_name,
+        )
+        .await?;
+    }
+
+    if !success {
+        notify_action_failed(
+            ctx,
+            action_id,
+            prototype.kind,
+            component_id,
+            func_name,
+            func_run_id,
+        )
+        .await;
+    }
+
     // Send the rebase request with the resource updated (if applicable)
     ctx.commit().await?;
     ctx.update_snapshot_to_visibility().await?;
     Ok(())
 }
 
+/// Notifies the workspace's webhooks that the action failed. Failing to do so is logged rather
+/// than returned, since it has no bearing on the action itself.
+async fn notify_action_failed(
+    ctx: &DalContext,
+    action_id: ActionId,
+    action_kind: ActionKind,
+    component_id: ComponentId,
+    func_name: String,
+    func_run_id: FuncRunId,
+) {
+    let event = match ctx.workspace_pk() {
+        Ok(workspace_pk) => WorkspaceWebhookEvent {
+            kind: match action_kind {
+                ActionKind::Refresh => WorkspaceWebhookEventKind::ResourceRefreshFailed,
+                _ => WorkspaceWebhookEventKind::ActionFailed,
+            },
+            workspace_pk,
+            change_set_id: ctx.change_set_id(),
+            action_id,
+            action_kind,
+            component_id,
+            component_name: Component::name_by_id(ctx, component_id)
+                .await
+                .unwrap_or_default(),
+            func_name,
+            func_run_id,
+            occurred_at: chrono::Utc::now(),
+        },
+        Err(err) => {
+            error!(si.error.message = ?err, %action_id, "unable to notify webhooks of failed action");
+            return;
+        }
+    };
+
+    if let Err(err) = WorkspaceWebhook::notify(ctx, event).await {
+        error!(si.error.message = ?err, %action_id, "unable to notify webhooks of failed action");
+    }
+}
+
 #[instrument(
     name = "action_job.perform_graph_cleanups",
     level = "info",
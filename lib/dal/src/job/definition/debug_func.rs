@@ -76,10 +76,15 @@ impl DebugFuncJob {
             &job_state_row.handler,
         );
 
-        let (func_run_id, result_channel) =
-            FuncRunner::run_debug(ctx, func, job_state_row.component_id, args)
-                .await
-                .map_err(Box::new)?;
+        let (func_run_id, result_channel) = FuncRunner::run_debug(
+            ctx,
+            func,
+            job_state_row.component_id,
+            args,
+            &job_state_row.secret_names,
+        )
+        .await
+        .map_err(Box::new)?;
 
         Ok((func_run_id, result_channel))
     }
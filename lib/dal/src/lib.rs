@@ -39,6 +39,7 @@ pub mod diagram;
 pub mod entity_kind;
 pub mod feature_flags;
 pub mod func;
+pub mod health;
 pub mod input_sources;
 pub mod jetstream_streams;
 pub mod job;
@@ -66,7 +67,9 @@ pub mod validation;
 pub mod workspace;
 pub mod workspace_integrations;
 pub mod workspace_snapshot;
+pub mod workspace_webhook;
 pub mod ws_event;
+pub mod ws_event_log;
 
 pub use action::ActionPrototypeId;
 pub use attribute::{
@@ -201,6 +204,7 @@ pub use standard_connection::{
 pub use workspace::{
     Workspace,
     WorkspaceError,
+    WorkspaceFeatureFlag,
     WorkspacePk,
     WorkspaceResult,
 };
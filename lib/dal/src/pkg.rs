@@ -6,6 +6,7 @@ use chrono::{
 };
 pub use import::{
     ImportOptions,
+    find_identical_funcs,
     import_pkg,
     import_pkg_from_pkg,
 };
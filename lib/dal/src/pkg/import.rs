@@ -47,6 +47,8 @@ use crate::{
     DalContext,
     EdgeWeightKind,
     Func,
+    FuncBackendKind,
+    FuncBackendResponseType,
     FuncId,
     InputSocket,
     OutputSocket,
@@ -70,7 +72,10 @@ use crate::{
     },
     func::{
         FuncKind,
-        argument::FuncArgument,
+        argument::{
+            FuncArgument,
+            FuncArgumentKind,
+        },
         binding::attribute::AttributeBinding,
         intrinsics::IntrinsicFunc,
         leaf::{
@@ -398,6 +403,75 @@ pub async fn import_pkg(ctx: &DalContext, pkg_file_path: impl AsRef<Path>) -> Pk
     Ok(pkg)
 }
 
+/// Finds the funcs of the package which are identical to a func already in the change set, so
+/// that [`ImportOptions::skip_import_funcs`] can reuse them rather than importing a copy (e.g.
+/// the utility funcs many builtin modules bundle).
+///
+/// Funcs are identical when their name, backend, handler, code and arguments all match. Only
+/// locked funcs are reused, as an editable func could diverge from the package later, and
+/// intrinsics and transformations are left alone as the importer already dedupes them.
+pub async fn find_identical_funcs(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+) -> PkgResult<HashMap<String, Func>> {
+    let mut candidates: HashMap<String, Vec<Func>> = HashMap::new();
+    for func in Func::list_all(ctx).await? {
+        if func.is_locked && !func.is_transformation && !func.is_intrinsic() {
+            candidates.entry(func.name.clone()).or_default().push(func);
+        }
+    }
+
+    let mut identical = HashMap::new();
+    for func_spec in pkg.funcs()? {
+        let Some(candidates) = candidates.get(func_spec.name()) else {
+            continue;
+        };
+        let Some(func_spec_data) = func_spec.data() else {
+            continue;
+        };
+        if func_spec_data.is_transformation() {
+            continue;
+        }
+
+        let mut spec_args: Vec<_> = func_spec
+            .arguments()?
+            .iter()
+            .map(|arg| {
+                (
+                    arg.name().to_owned(),
+                    FuncArgumentKind::from(arg.kind()),
+                    arg.element_kind().map(|&kind| FuncArgumentKind::from(kind)),
+                )
+            })
+            .collect();
+        spec_args.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for func in candidates {
+            if func.backend_kind != FuncBackendKind::from(func_spec_data.backend_kind())
+                || func.backend_response_type
+                    != FuncBackendResponseType::from(func_spec_data.response_type())
+                || func.handler.as_deref() != Some(func_spec_data.handler())
+                || func.code_base64.as_deref() != Some(func_spec_data.code_base64())
+            {
+                continue;
+            }
+
+            let mut args: Vec<_> = FuncArgument::list_for_func(ctx, func.id)
+                .await?
+                .into_iter()
+                .map(|arg| (arg.name, arg.kind, arg.element_kind))
+                .collect();
+            args.sort_by(|a, b| a.0.cmp(&b.0));
+            if args == spec_args {
+                identical.insert(func_spec.unique_id().to_owned(), func.clone());
+                break;
+            }
+        }
+    }
+
+    Ok(identical)
+}
+
 async fn create_func(
     ctx: &DalContext,
     func_spec: &SiPkgFunc<'_>,
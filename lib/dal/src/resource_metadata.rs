@@ -64,11 +64,16 @@ pub async fn list(ctx: &DalContext) -> ResourceMetadataResult<Vec<si_events::Res
 fn assemble_metadata(component_id: ComponentId, data: ResourceData) -> si_events::ResourceMetadata {
     si_events::ResourceMetadata {
         component_id,
-        status: match data.status {
-            ResourceStatus::Error => si_events::ResourceStatus::Error,
-            ResourceStatus::Ok => si_events::ResourceStatus::Ok,
-            ResourceStatus::Warning => si_events::ResourceStatus::Warning,
-        },
+        status: events_resource_status(data.status),
         last_synced: data.last_synced,
     }
 }
+
+/// Converts the status reported by veritech into its [`si_events`] counterpart.
+pub(crate) fn events_resource_status(status: ResourceStatus) -> si_events::ResourceStatus {
+    match status {
+        ResourceStatus::Error => si_events::ResourceStatus::Error,
+        ResourceStatus::Ok => si_events::ResourceStatus::Ok,
+        ResourceStatus::Warning => si_events::ResourceStatus::Warning,
+    }
+}
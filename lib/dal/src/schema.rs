@@ -61,6 +61,7 @@ use crate::{
     pkg::{
         ImportOptions,
         PkgError,
+        find_identical_funcs,
         import_pkg_from_pkg,
     },
     workspace_snapshot::{
@@ -148,6 +149,16 @@ pub type SchemaResult<T> = Result<T, SchemaError>;
 
 pub use si_id::SchemaId;
 
+/// The outcome of [`Schema::install_from_cache`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedSchemaInstall {
+    pub schema_variant_id: SchemaVariantId,
+    /// Whether the schema had to be installed, rather than already being installed.
+    pub installed: bool,
+    /// The funcs already in the change set which the install reused instead of copying.
+    pub reused_func_ids: Vec<FuncId>,
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Schema {
     id: SchemaId,
@@ -654,46 +665,116 @@ impl Schema {
         Self::default_variant_id(ctx, schema_id).await
     }
 
-    async fn ensure_installed(ctx: &DalContext, schema_id: SchemaId) -> SchemaResult<()> {
+    /// Like [`Self::get_or_install_default_variant`], but also reports whether the schema had to
+    /// be installed from the local module cache (`true`) or was already installed (`false`).
+    pub async fn install_default_variant_from_cache(
+        ctx: &DalContext,
+        schema_id: SchemaId,
+    ) -> SchemaResult<(SchemaVariantId, bool)> {
+        let install = Self::install_from_cache(ctx, schema_id, false).await?;
+        Ok((install.schema_variant_id, install.installed))
+    }
+
+    /// Installs the schema from the local module cache if it isn't already installed.
+    ///
+    /// Funcs in the module which are identical to ones already in the change set (see
+    /// [`find_identical_funcs`]) are reused rather than copied, unless `force_copy` is set.
+    pub async fn install_from_cache(
+        ctx: &DalContext,
+        schema_id: SchemaId,
+        force_copy: bool,
+    ) -> SchemaResult<CachedSchemaInstall> {
+        let reused_func_ids = Self::ensure_installed_with(ctx, schema_id, force_copy).await?;
+        Ok(CachedSchemaInstall {
+            schema_variant_id: Self::default_variant_id(ctx, schema_id).await?,
+            installed: reused_func_ids.is_some(),
+            reused_func_ids: reused_func_ids.unwrap_or_default(),
+        })
+    }
+
+    /// Installs the schema from the local module cache if it isn't already installed, returning
+    /// whether an install took place.
+    async fn ensure_installed(ctx: &DalContext, schema_id: SchemaId) -> SchemaResult<bool> {
+        Ok(Self::ensure_installed_with(ctx, schema_id, false)
+            .await?
+            .is_some())
+    }
+
+    /// Like [`Self::ensure_installed`], but returns the funcs reused by the install, if one took
+    /// place.
+    async fn ensure_installed_with(
+        ctx: &DalContext,
+        schema_id: SchemaId,
+        force_copy: bool,
+    ) -> SchemaResult<Option<Vec<FuncId>>> {
         // Install the schema, if it isn't already
-        if !Self::exists_locally(ctx, schema_id).await? {
-            let module = CachedModule::find_latest_for_schema_id(ctx, schema_id)
-                .await?
-                .ok_or(SchemaError::UninstalledSchemaNotFound(schema_id))?;
-            Self::install_from_module(ctx, module.clone()).await?;
-
-            let variant_id = Self::default_variant_id(ctx, schema_id).await?;
-
-            ctx.write_audit_log(
-                AuditLogKind::InstallSchemaAndVariant {
-                    schema_id,
-                    schema_variant_id: variant_id,
-                    schema_variant_display_name: module.schema_name.clone(),
-                },
-                module.schema_name,
-            )
-            .await?;
+        if Self::exists_locally(ctx, schema_id).await? {
+            return Ok(None);
         }
-        Ok(())
+
+        // Schemas published since the cache was last updated are fetched on demand
+        let module = match CachedModule::ensure_cached(ctx, &[schema_id]).await {
+            Ok(modules) => modules.into_iter().next(),
+            Err(
+                CachedModuleError::ModuleIndexUrlNotSet
+                | CachedModuleError::NotFoundInModuleIndex(_),
+            ) => None,
+            Err(err) => return Err(err.into()),
+        }
+        .ok_or(SchemaError::UninstalledSchemaNotFound(schema_id))?;
+        let (_, reused_func_ids) =
+            Self::install_from_module_with(ctx, module.clone(), force_copy).await?;
+
+        let variant_id = Self::default_variant_id(ctx, schema_id).await?;
+
+        ctx.write_audit_log(
+            AuditLogKind::InstallSchemaAndVariant {
+                schema_id,
+                schema_variant_id: variant_id,
+                schema_variant_display_name: module.schema_name.clone(),
+            },
+            module.schema_name,
+        )
+        .await?;
+
+        Ok(Some(reused_func_ids))
+    }
+
+    async fn install_from_module(ctx: &DalContext, module: CachedModule) -> SchemaResult<Schema> {
+        Ok(Self::install_from_module_with(ctx, module, false).await?.0)
     }
 
     #[instrument(name = "schema.install_from_module", level = "info", skip_all)]
-    async fn install_from_module(
+    async fn install_from_module_with(
         ctx: &DalContext,
         mut module: CachedModule,
-    ) -> SchemaResult<Schema> {
+        force_copy: bool,
+    ) -> SchemaResult<(Schema, Vec<FuncId>)> {
         let si_pkg = module.si_pkg(ctx).await?;
+        let skip_import_funcs = if force_copy {
+            HashMap::new()
+        } else {
+            find_identical_funcs(ctx, &si_pkg).await?
+        };
+        let mut reused_func_ids: Vec<FuncId> =
+            skip_import_funcs.values().map(|func| func.id).collect();
+        reused_func_ids.sort();
+        reused_func_ids.dedup();
+
         import_pkg_from_pkg(
             ctx,
             &si_pkg,
             Some(ImportOptions {
                 schema_id: Some(module.schema_id.into()),
+                skip_import_funcs: Some(skip_import_funcs),
                 ..Default::default()
             }),
         )
         .await?;
-        Self::get_by_id_opt(ctx, module.schema_id)
+        let schema = Self::get_by_id_opt(ctx, module.schema_id)
             .await?
-            .ok_or(SchemaError::UninstalledSchemaNotFound(module.schema_id))
+            .ok_or(SchemaError::UninstalledSchemaNotFound(module.schema_id))?;
+
+        Ok((schema, reused_func_ids))
     }
 }
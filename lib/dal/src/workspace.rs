@@ -1,8 +1,11 @@
 use std::{
     collections::{
+        BTreeMap,
         HashMap,
+        HashSet,
         VecDeque,
     },
+    ops::RangeInclusive,
     str::FromStr,
     sync::Arc,
 };
@@ -50,8 +53,11 @@ use si_layer_cache::{
 };
 use si_pkg::{
     WorkspaceExport,
+    WorkspaceExportArtifactKindV0,
+    WorkspaceExportArtifactV0,
     WorkspaceExportChangeSetV0,
     WorkspaceExportContentV0,
+    WorkspaceExportManifestV0,
     WorkspaceExportMetadataV0,
 };
 use telemetry::prelude::*;
@@ -62,10 +68,18 @@ use crate::{
     BuiltinsError,
     DalContext,
     KeyPairError,
+    SchemaId,
     TransactionsError,
     WorkspaceSnapshot,
     WorkspaceSnapshotGraph,
+    WsEvent,
+    WsEventResult,
+    WsPayload,
     builtins::func::migrate_intrinsics_no_commit,
+    cached_module::{
+        CachedModule,
+        CachedModuleError,
+    },
     change_set::{
         ChangeSet,
         ChangeSetError,
@@ -81,7 +95,9 @@ use crate::{
     workspace_snapshot::{
         WorkspaceSnapshotError,
         WorkspaceSnapshotSelector,
+        edge_weight::EdgeWeightKindDiscriminants,
         graph::WorkspaceSnapshotGraphDiscriminants,
+        node_weight::category_node_weight::CategoryNodeKind,
         selector::WorkspaceSnapshotSelectorDiscriminants,
         split_snapshot::{
             SplitSnapshot,
@@ -97,6 +113,13 @@ const DEFAULT_BUILTIN_WORKSPACE_TOKEN: &str = "builtin";
 const DEFAULT_CHANGE_SET_NAME: &str = "HEAD";
 const DEFAULT_COMPONENT_CONCURRENCY_LIMIT: i32 = 256;
 
+/// The workspace export format version produced by [`Workspace::generate_export_data`].
+pub const WORKSPACE_EXPORT_FORMAT_VERSION: u32 = 1;
+/// The workspace export format versions [`Workspace::import`] accepts. Version 0 exports predate
+/// the manifest and are imported on a best-effort basis.
+pub const SUPPORTED_WORKSPACE_EXPORT_FORMAT_VERSIONS: RangeInclusive<u32> =
+    0..=WORKSPACE_EXPORT_FORMAT_VERSION;
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum WorkspaceError {
@@ -108,14 +131,25 @@ pub enum WorkspaceError {
     ChangeSet(#[from] ChangeSetError),
     #[error("could not find default change set {1} for workspace {0}")]
     DefaultChangeSetNotFound(WorkspacePk, ChangeSetId),
+    #[error("workspace export artifact is corrupted: {0}")]
+    CorruptedExportArtifact(String),
     #[error("Trying to export from system actor. This can only be done by a user actor")]
     ExportingFromSystemActor,
     #[error("Trying to import a changeset that does not have a valid base: {0}")]
     ImportingOrphanChangeset(ChangeSetId),
+    #[error(
+        "workspace export format version {found} is not supported, supported versions are {supported:?}"
+    )]
+    IncompatibleExportVersion {
+        found: u32,
+        supported: RangeInclusive<u32>,
+    },
     #[error("key pair error: {0}")]
     KeyPair(#[from] KeyPairError),
     #[error("LayerDb error: {0}")]
     LayerDb(#[from] LayerDbError),
+    #[error("workspace export artifact is missing: {0}")]
+    MissingExportArtifact(String),
     #[error("nats error: {0}")]
     Nats(#[from] NatsError),
     #[error("no user in context")]
@@ -157,6 +191,39 @@ impl SnapshotVersion {
     }
 }
 
+/// The outcome of checking a workspace backup against the current workspace without importing it.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceImportReport {
+    /// The version recorded in the backup's metadata.
+    pub version: String,
+    /// Names of the change sets that an import would create.
+    pub change_sets: Vec<String>,
+    /// Names of the open change sets in the current workspace that an import would abandon.
+    pub abandoned_change_sets: Vec<String>,
+    /// Change sets in the backup whose base change set isn't part of the backup, which an
+    /// import would skip.
+    pub unreachable_change_sets: Vec<String>,
+    /// Change sets in the backup whose snapshot can't be read by this version of the dal.
+    pub unreadable_change_sets: Vec<String>,
+    /// Whether the content store values in the backup can be read.
+    pub content_store_readable: bool,
+    /// The version of SI that produced the backup, if it has a manifest.
+    pub si_version: Option<String>,
+    /// Artifacts listed in the backup's manifest that are missing or don't match their hash.
+    pub corrupted_artifacts: Vec<String>,
+}
+
+impl WorkspaceImportReport {
+    /// Returns `true` if importing the backup would bring across every change set.
+    pub fn is_importable(&self) -> bool {
+        self.content_store_readable
+            && self.corrupted_artifacts.is_empty()
+            && self.unreachable_change_sets.is_empty()
+            && self.unreadable_change_sets.is_empty()
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Workspace {
     pk: WorkspacePk,
@@ -171,6 +238,53 @@ pub struct Workspace {
     snapshot_kind: WorkspaceSnapshotSelectorDiscriminants,
     subgraph_version: Option<SubGraphVersionDiscriminants>,
     approvals_enabled: bool,
+    /// Flags explicitly set for this workspace, keyed by [`WorkspaceFeatureFlag`]. Flags that
+    /// aren't set fall back to their default.
+    #[serde(default)]
+    feature_flags: BTreeMap<String, bool>,
+}
+
+/// Features that can be turned on or off for individual workspaces, e.g. to roll out new routes
+/// gradually.
+#[remain::sorted]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WorkspaceFeatureFlag {
+    /// Installing modules through the v2 module routes.
+    ModuleInstall,
+    /// Recording published WsEvents in the [`WsEventLog`](crate::ws_event_log::WsEventLog).
+    WsEventLog,
+}
+
+impl WorkspaceFeatureFlag {
+    /// Whether the feature is enabled for workspaces that haven't set the flag.
+    pub fn enabled_by_default(&self) -> bool {
+        match self {
+            Self::ModuleInstall => true,
+            Self::WsEventLog => false,
+        }
+    }
+
+    /// Whether the feature is enabled given the flags explicitly set for a workspace (see
+    /// [`Workspace::feature_flags`]).
+    pub fn enabled_in(&self, feature_flags: &BTreeMap<String, bool>) -> bool {
+        feature_flags
+            .get(&self.to_string())
+            .copied()
+            .unwrap_or_else(|| self.enabled_by_default())
+    }
 }
 
 impl TryFrom<PgRow> for Workspace {
@@ -217,6 +331,7 @@ impl TryFrom<PgRow> for Workspace {
             snapshot_kind,
             subgraph_version,
             approvals_enabled: row.try_get("approvals_enabled")?,
+            feature_flags: serde_json::from_value(row.try_get("feature_flags")?)?,
         })
     }
 }
@@ -254,6 +369,66 @@ impl Workspace {
         self.approvals_enabled
     }
 
+    /// The flags explicitly set for this workspace.
+    pub fn feature_flags(&self) -> &BTreeMap<String, bool> {
+        &self.feature_flags
+    }
+
+    /// Whether the feature is enabled for this workspace, falling back to the flag's default if
+    /// it hasn't been set.
+    pub fn feature_flag_enabled(&self, flag: WorkspaceFeatureFlag) -> bool {
+        flag.enabled_in(&self.feature_flags)
+    }
+
+    /// Whether the feature is enabled for the workspace of the provided [`DalContext`].
+    pub async fn feature_enabled(
+        ctx: &DalContext,
+        flag: WorkspaceFeatureFlag,
+    ) -> WorkspaceResult<bool> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let workspace = Self::get_by_pk(ctx, workspace_pk).await?;
+        Ok(workspace.feature_flag_enabled(flag))
+    }
+
+    /// Sets the flag for this workspace, or clears it (falling back to its default) if `None`.
+    pub async fn set_feature_flag(
+        &mut self,
+        ctx: &DalContext,
+        flag: WorkspaceFeatureFlag,
+        enabled: Option<bool>,
+    ) -> WorkspaceResult<()> {
+        let flag = flag.to_string();
+        let txns = ctx.txns().await?;
+        let row = match enabled {
+            Some(enabled) => {
+                txns.pg()
+                    .query_one(
+                        "UPDATE workspaces
+                        SET feature_flags = feature_flags || jsonb_build_object($2::text, $3::bool)
+                        WHERE pk = $1
+                        RETURNING feature_flags",
+                        &[&self.pk, &flag, &enabled],
+                    )
+                    .await?
+            }
+            None => {
+                txns.pg()
+                    .query_one(
+                        "UPDATE workspaces SET feature_flags = feature_flags - $2::text
+                        WHERE pk = $1
+                        RETURNING feature_flags",
+                        &[&self.pk, &flag],
+                    )
+                    .await?
+            }
+        };
+
+        self.feature_flags = serde_json::from_value(row.try_get("feature_flags")?)?;
+        ctx.forget_workspace_feature_flags();
+
+        Ok(())
+    }
+
     pub fn is_current_version_and_kind(&self) -> bool {
         match self.snapshot_kind() {
             WorkspaceSnapshotSelectorDiscriminants::LegacySnapshot => false,
@@ -445,6 +620,35 @@ impl Workspace {
         Ok(result)
     }
 
+    /// Lists workspaces that no member has accessed since `older_than`, including workspaces with
+    /// no recorded access at all.
+    pub async fn list_dormant(
+        ctx: &DalContext,
+        older_than: DateTime<Utc>,
+    ) -> WorkspaceResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT w.* FROM workspaces AS w
+                    LEFT JOIN user_belongs_to_workspaces bt ON bt.workspace_pk = w.pk
+                GROUP BY w.pk
+                HAVING MAX(bt.last_accessed_at) IS NULL OR MAX(bt.last_accessed_at) < $1
+                ORDER BY w.created_at ASC",
+                &[&older_than],
+            )
+            .await?;
+
+        let mut result = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            result.push(Self::try_from(row)?);
+        }
+
+        Ok(result)
+    }
+
     pub async fn search(
         ctx: &DalContext,
         query: Option<&str>,
@@ -860,13 +1064,28 @@ impl Workspace {
             workspace_name: self.name().clone(),
         };
 
+        let manifest = WorkspaceExportManifestV0 {
+            format_version: WORKSPACE_EXPORT_FORMAT_VERSION,
+            si_version: env!("CARGO_PKG_VERSION").to_string(),
+            snapshot_version: self.snapshot_version().db_string(),
+            artifacts: export_artifacts(&change_sets, &content_store_values),
+        };
+
         Ok(WorkspaceExport::new(WorkspaceExportContentV0 {
             change_sets,
             content_store_values,
             metadata,
+            manifest: Some(manifest),
         }))
     }
 
+    /// Returns an error if the export's format version can't be imported, without looking at
+    /// anything else. Lets callers refuse an export before doing any work with it.
+    pub fn check_export_format_version(workspace_data: &WorkspaceExport) -> WorkspaceResult<()> {
+        let WorkspaceExport::V0(content) = workspace_data;
+        check_export_format_version(content.manifest.as_ref())
+    }
+
     pub async fn import(
         &mut self,
         ctx: &mut DalContext,
@@ -876,8 +1095,25 @@ impl Workspace {
             change_sets,
             content_store_values,
             metadata,
+            manifest,
         } = workspace_data.into_latest();
 
+        // Check everything is there before changing anything
+        check_export_format_version(manifest.as_ref())?;
+        if let Some(manifest) = &manifest {
+            let actual = export_artifacts(&change_sets, &content_store_values);
+            if let Some((artifact, problem)) = check_export_artifacts(manifest, &actual).first() {
+                return Err(match problem {
+                    ArtifactProblem::Corrupted => {
+                        WorkspaceError::CorruptedExportArtifact(artifact.name())
+                    }
+                    ArtifactProblem::Missing => {
+                        WorkspaceError::MissingExportArtifact(artifact.name())
+                    }
+                });
+            }
+        }
+
         // ABANDON PREVIOUS CHANGESETS
         for mut change_set in ChangeSet::list_active(ctx).await? {
             change_set.abandon(ctx).await?;
@@ -894,6 +1130,7 @@ impl Workspace {
         // Go from head changeset to children, creating new changesets and updating base references
         let mut base_change_set_queue = VecDeque::from([metadata.default_change_set_base]);
         let mut change_set_id_map = HashMap::new();
+        let mut schema_ids = HashSet::new();
         while let Some(base_change_set_ulid) = base_change_set_queue.pop_front() {
             let Some(change_sets) = change_sets.get(&base_change_set_ulid) else {
                 continue;
@@ -903,6 +1140,7 @@ impl Workspace {
                 let imported_snapshot = WorkspaceSnapshot::from_bytes(
                     &change_set_data.workspace_snapshot_serialized_data,
                 )?;
+                schema_ids.extend(imported_schema_ids(&imported_snapshot).await?);
 
                 // If base_change_set is default_change_set_base, it pointed to the builtin workspace
                 // originally, so this change set needs to be the new default for the workspace - HEAD
@@ -939,6 +1177,18 @@ impl Workspace {
             }
         }
 
+        // The imported change sets may use schemas which have been published since the module
+        // cache was last updated. Schemas authored in the workspace itself are not in the module
+        // index, so not finding some is expected.
+        let schema_ids: Vec<SchemaId> = schema_ids.into_iter().collect();
+        match CachedModule::ensure_cached(ctx, &schema_ids).await {
+            Ok(_) | Err(CachedModuleError::NotFoundInModuleIndex(_)) => {}
+            Err(err) => warn!(
+                si.error.message = ?err,
+                "unable to cache the modules of the imported workspace",
+            ),
+        }
+
         let cas_values: HashMap<ContentHash, (Arc<ContentTypes>, String)> =
             serialize::from_bytes(&content_store_values)?;
 
@@ -954,6 +1204,83 @@ impl Workspace {
         Ok(())
     }
 
+    /// Walks a workspace backup the same way [`Self::import`] does, without writing anything,
+    /// and reports what an import would do.
+    pub async fn validate_import(
+        ctx: &DalContext,
+        workspace_data: WorkspaceExport,
+    ) -> WorkspaceResult<WorkspaceImportReport> {
+        let WorkspaceExportContentV0 {
+            change_sets,
+            content_store_values,
+            metadata,
+            manifest,
+        } = workspace_data.into_latest();
+
+        check_export_format_version(manifest.as_ref())?;
+        let (si_version, corrupted_artifacts) = match &manifest {
+            Some(manifest) => {
+                let actual = export_artifacts(&change_sets, &content_store_values);
+                (
+                    Some(manifest.si_version.clone()),
+                    check_export_artifacts(manifest, &actual)
+                        .into_iter()
+                        .map(|(artifact, _)| artifact.name())
+                        .collect(),
+                )
+            }
+            None => (None, Vec::new()),
+        };
+
+        let mut report = WorkspaceImportReport {
+            version: metadata.version,
+            abandoned_change_sets: ChangeSet::list_active(ctx)
+                .await?
+                .into_iter()
+                .map(|change_set| change_set.name)
+                .collect(),
+            content_store_readable: serialize::from_bytes::<
+                HashMap<ContentHash, (Arc<ContentTypes>, String)>,
+            >(&content_store_values)
+            .is_ok(),
+            si_version,
+            corrupted_artifacts,
+            ..Default::default()
+        };
+
+        let mut reached = HashSet::new();
+        let mut base_change_set_queue = VecDeque::from([metadata.default_change_set_base]);
+        while let Some(base_change_set_ulid) = base_change_set_queue.pop_front() {
+            let Some(change_sets) = change_sets.get(&base_change_set_ulid) else {
+                continue;
+            };
+
+            for change_set_data in change_sets {
+                if WorkspaceSnapshot::from_bytes(
+                    &change_set_data.workspace_snapshot_serialized_data,
+                )
+                .is_err()
+                {
+                    report
+                        .unreadable_change_sets
+                        .push(change_set_data.name.clone());
+                }
+                report.change_sets.push(change_set_data.name.clone());
+                reached.insert(change_set_data.id);
+                base_change_set_queue.push_back(change_set_data.id);
+            }
+        }
+
+        report.unreachable_change_sets = change_sets
+            .values()
+            .flatten()
+            .filter(|change_set_data| !reached.contains(&change_set_data.id))
+            .map(|change_set_data| change_set_data.name.clone())
+            .collect();
+
+        Ok(report)
+    }
+
     getter!(name, String);
 
     pub async fn has_change_set(
@@ -1052,3 +1379,131 @@ impl Workspace {
         &self.timestamp
     }
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceImportValidatedPayload {
+    id: Ulid,
+    report: WorkspaceImportReport,
+}
+
+impl WsEvent {
+    pub async fn workspace_import_validated(
+        ctx: &DalContext,
+        id: Ulid,
+        report: WorkspaceImportReport,
+    ) -> WsEventResult<Self> {
+        WsEvent::new_for_workspace(
+            ctx,
+            WsPayload::WorkspaceImportValidated(WorkspaceImportValidatedPayload { id, report }),
+        )
+        .await
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ArtifactProblem {
+    Corrupted,
+    Missing,
+}
+
+/// Returns the ids of the schemas installed in a snapshot being imported.
+async fn imported_schema_ids(snapshot: &WorkspaceSnapshot) -> WorkspaceResult<Vec<SchemaId>> {
+    let Some(schema_category_id) = snapshot.get_category_node(CategoryNodeKind::Schema).await?
+    else {
+        return Ok(vec![]);
+    };
+
+    Ok(snapshot
+        .outgoing_targets_for_edge_weight_kind(schema_category_id, EdgeWeightKindDiscriminants::Use)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+/// Returns an error if the export's format version is outside of
+/// [`SUPPORTED_WORKSPACE_EXPORT_FORMAT_VERSIONS`]. Older formats are imported as they are,
+/// relying on the content being versioned.
+fn check_export_format_version(
+    manifest: Option<&WorkspaceExportManifestV0>,
+) -> WorkspaceResult<()> {
+    let found = manifest.map_or(0, |manifest| manifest.format_version);
+    if !SUPPORTED_WORKSPACE_EXPORT_FORMAT_VERSIONS.contains(&found) {
+        return Err(WorkspaceError::IncompatibleExportVersion {
+            found,
+            supported: SUPPORTED_WORKSPACE_EXPORT_FORMAT_VERSIONS,
+        });
+    }
+    if found < WORKSPACE_EXPORT_FORMAT_VERSION {
+        info!(
+            found,
+            current = WORKSPACE_EXPORT_FORMAT_VERSION,
+            "importing workspace export made with an older format version, best effort"
+        );
+    }
+
+    Ok(())
+}
+
+fn export_artifact(
+    kind: WorkspaceExportArtifactKindV0,
+    id: Option<Ulid>,
+    bytes: &[u8],
+) -> WorkspaceExportArtifactV0 {
+    WorkspaceExportArtifactV0 {
+        kind,
+        id,
+        size: bytes.len() as u64,
+        hash: blake3::hash(bytes).to_hex().to_string(),
+    }
+}
+
+/// Lists the artifacts contained in a workspace export, as recorded in its manifest.
+fn export_artifacts(
+    change_sets: &HashMap<Ulid, Vec<WorkspaceExportChangeSetV0>>,
+    content_store_values: &[u8],
+) -> Vec<WorkspaceExportArtifactV0> {
+    let mut artifacts: Vec<_> = change_sets
+        .values()
+        .flatten()
+        .map(|change_set| {
+            export_artifact(
+                WorkspaceExportArtifactKindV0::ChangeSetSnapshot,
+                Some(change_set.id),
+                &change_set.workspace_snapshot_serialized_data,
+            )
+        })
+        .collect();
+    artifacts.sort_by_key(|artifact| artifact.id);
+    artifacts.push(export_artifact(
+        WorkspaceExportArtifactKindV0::ContentStoreValues,
+        None,
+        content_store_values,
+    ));
+    artifacts
+}
+
+/// Returns the artifacts listed in the manifest that are missing from the export or whose size
+/// or hash don't match.
+fn check_export_artifacts(
+    manifest: &WorkspaceExportManifestV0,
+    actual: &[WorkspaceExportArtifactV0],
+) -> Vec<(WorkspaceExportArtifactV0, ArtifactProblem)> {
+    manifest
+        .artifacts
+        .iter()
+        .filter_map(|expected| {
+            match actual
+                .iter()
+                .find(|artifact| artifact.kind == expected.kind && artifact.id == expected.id)
+            {
+                None => Some((expected.clone(), ArtifactProblem::Missing)),
+                Some(artifact) if artifact != expected => {
+                    Some((expected.clone(), ArtifactProblem::Corrupted))
+                }
+                Some(_) => None,
+            }
+        })
+        .collect()
+}
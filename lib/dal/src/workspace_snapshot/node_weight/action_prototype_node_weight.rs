@@ -112,6 +112,10 @@ impl ActionPrototypeNodeWeight {
         content_hasher.finalize()
     }
 
+    pub fn set_name(&mut self, name: impl AsRef<str>) {
+        self.name = name.as_ref().to_string();
+    }
+
     pub fn set_merkle_tree_hash(&mut self, new_hash: MerkleTreeHash) {
         self.merkle_tree_hash = new_hash;
     }
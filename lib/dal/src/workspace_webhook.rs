@@ -0,0 +1,574 @@
+//! This module contains [`WorkspaceWebhook`], an endpoint that is notified when something goes
+//! wrong in a workspace (e.g. an action fails), so that teams can be paged outside of SI.
+
+use std::{
+    io,
+    net::{
+        IpAddr,
+        SocketAddr,
+    },
+    time::Duration,
+};
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_data_pg::{
+    PgError,
+    PgPool,
+    PgPoolError,
+    PgRow,
+};
+pub use si_id::WorkspaceWebhookId;
+use si_id::{
+    ActionId,
+    ChangeSetId,
+    ComponentId,
+    FuncRunId,
+    WorkspacePk,
+};
+use sodiumoxide::crypto::auth::hmacsha256;
+use strum::{
+    Display,
+    EnumString,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+use url::{
+    Host,
+    Url,
+};
+
+use crate::{
+    DalContext,
+    TransactionsError,
+    action::prototype::ActionKind,
+};
+
+/// The header carrying the signature of the payload, as `sha256=<hex encoded HMAC-SHA256 of the
+/// body, keyed with the webhook's secret>`.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-SI-Signature";
+/// How many times a delivery is attempted before giving up.
+pub const WEBHOOK_DELIVERY_ATTEMPTS: i32 = 3;
+
+/// How long to wait before the first retry; each retry waits twice as long as the last.
+const WEBHOOK_DELIVERY_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const WEBHOOK_DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WorkspaceWebhookError {
+    #[error("webhook url {0} resolves to a non-public address: {1}")]
+    DisallowedAddress(String, IpAddr),
+    #[error("webhook url {0} must use https")]
+    InsecureUrl(String),
+    #[error("invalid webhook url {0}: {1}")]
+    InvalidUrl(String, #[source] url::ParseError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[from] PgPoolError),
+    #[error("reqwest error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("unable to resolve webhook url {0}: {1}")]
+    Resolve(String, #[source] io::Error),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("webhook not found: {0}")]
+    WebhookNotFound(WorkspaceWebhookId),
+}
+
+pub type WorkspaceWebhookResult<T> = Result<T, WorkspaceWebhookError>;
+
+/// Which endpoints a [`WorkspaceWebhook`] may point at and be delivered to.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WebhookTargetPolicy {
+    /// Any url, including plain `http` and loopback or private addresses. Only meant for tests,
+    /// whose endpoints are local.
+    AllowLocal,
+    /// Only `https` urls whose host resolves to public addresses, so that a webhook can't be used
+    /// to reach SI's own network (e.g. a cloud metadata endpoint).
+    #[default]
+    PublicHttps,
+}
+
+impl WebhookTargetPolicy {
+    /// Parses the url, rejecting anything but `https` unless local targets are allowed.
+    pub fn validate_url(self, url: &str) -> WorkspaceWebhookResult<Url> {
+        let parsed = Url::parse(url)
+            .map_err(|err| WorkspaceWebhookError::InvalidUrl(url.to_owned(), err))?;
+        if self == Self::PublicHttps && parsed.scheme() != "https" {
+            return Err(WorkspaceWebhookError::InsecureUrl(url.to_owned()));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Builds the client to deliver to the url with, after checking every address its host
+    /// resolves to. The client is pinned to a checked address and doesn't follow redirects, so
+    /// neither a changed DNS answer nor the endpoint itself can send the request elsewhere.
+    pub async fn client_for(self, url: &str) -> WorkspaceWebhookResult<reqwest::Client> {
+        let parsed = self.validate_url(url)?;
+        if self == Self::AllowLocal {
+            return Ok(reqwest::Client::new());
+        }
+
+        let builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+        let builder = match parsed.host() {
+            Some(Host::Ipv4(ip)) => {
+                check_public_address(url, ip.into())?;
+                builder
+            }
+            Some(Host::Ipv6(ip)) => {
+                check_public_address(url, ip.into())?;
+                builder
+            }
+            Some(Host::Domain(domain)) => {
+                let port = parsed.port_or_known_default().unwrap_or(443);
+                let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, port))
+                    .await
+                    .map_err(|err| WorkspaceWebhookError::Resolve(url.to_owned(), err))?
+                    .collect();
+                for addr in &addrs {
+                    check_public_address(url, addr.ip())?;
+                }
+                let addr = addrs.first().ok_or_else(|| {
+                    WorkspaceWebhookError::Resolve(
+                        url.to_owned(),
+                        io::Error::new(io::ErrorKind::NotFound, "no addresses found"),
+                    )
+                })?;
+                builder.resolve(domain, *addr)
+            }
+            None => {
+                return Err(WorkspaceWebhookError::InvalidUrl(
+                    url.to_owned(),
+                    url::ParseError::EmptyHost,
+                ));
+            }
+        };
+
+        Ok(builder.build()?)
+    }
+}
+
+/// The kinds of events a [`WorkspaceWebhook`] can subscribe to.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WorkspaceWebhookEventKind {
+    /// An action other than a refresh failed.
+    ActionFailed,
+    /// A refresh action failed.
+    ResourceRefreshFailed,
+}
+
+/// The JSON payload POSTed to each matching [`WorkspaceWebhook`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceWebhookEvent {
+    pub kind: WorkspaceWebhookEventKind,
+    pub workspace_pk: WorkspacePk,
+    pub change_set_id: ChangeSetId,
+    pub action_id: ActionId,
+    pub action_kind: ActionKind,
+    pub component_id: ComponentId,
+    pub component_name: String,
+    pub func_name: String,
+    pub func_run_id: FuncRunId,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceWebhook {
+    id: WorkspaceWebhookId,
+    workspace_pk: WorkspacePk,
+    url: String,
+    secret: String,
+    event_kinds: Vec<WorkspaceWebhookEventKind>,
+    enabled: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for WorkspaceWebhook {
+    type Error = WorkspaceWebhookError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let event_kinds: Vec<String> = row.try_get("event_kinds")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            url: row.try_get("url")?,
+            secret: row.try_get("secret")?,
+            event_kinds: event_kinds
+                .iter()
+                .map(|kind| kind.parse())
+                .collect::<Result<_, _>>()?,
+            enabled: row.try_get("enabled")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl WorkspaceWebhook {
+    pub fn id(&self) -> WorkspaceWebhookId {
+        self.id
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn event_kinds(&self) -> &[WorkspaceWebhookEventKind] {
+        &self.event_kinds
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    pub async fn new(
+        ctx: &DalContext,
+        url: impl Into<String>,
+        secret: impl Into<String>,
+        event_kinds: Vec<WorkspaceWebhookEventKind>,
+    ) -> WorkspaceWebhookResult<Self> {
+        let url = url.into();
+        ctx.webhook_target_policy().validate_url(&url)?;
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO workspace_webhooks (workspace_pk, url, secret, event_kinds) VALUES ($1, $2, $3, $4) RETURNING *",
+                &[
+                    &workspace_pk,
+                    &url,
+                    &secret.into(),
+                    &event_kind_strings(&event_kinds),
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    pub async fn get_by_id(
+        ctx: &DalContext,
+        id: WorkspaceWebhookId,
+    ) -> WorkspaceWebhookResult<Self> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM workspace_webhooks WHERE id = $1 AND workspace_pk = $2",
+                &[&id, &workspace_pk],
+            )
+            .await?
+            .ok_or(WorkspaceWebhookError::WebhookNotFound(id))?;
+
+        Self::try_from(row)
+    }
+
+    /// Lists the webhooks of the workspace, oldest first.
+    pub async fn list(ctx: &DalContext) -> WorkspaceWebhookResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM workspace_webhooks WHERE workspace_pk = $1 ORDER BY created_at",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Updates the fields that are provided, leaving the others as they are.
+    pub async fn update(
+        &mut self,
+        ctx: &DalContext,
+        url: Option<String>,
+        secret: Option<String>,
+        event_kinds: Option<Vec<WorkspaceWebhookEventKind>>,
+        enabled: Option<bool>,
+    ) -> WorkspaceWebhookResult<()> {
+        if let Some(url) = &url {
+            ctx.webhook_target_policy().validate_url(url)?;
+        }
+        let event_kinds = event_kinds.as_deref().map(event_kind_strings);
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                r#"
+            UPDATE workspace_webhooks SET
+                url = COALESCE($2, url),
+                secret = COALESCE($3, secret),
+                event_kinds = COALESCE($4, event_kinds),
+                enabled = COALESCE($5, enabled),
+                updated_at = now()
+            WHERE id = $1
+            RETURNING *
+        "#,
+                &[&self.id, &url, &secret, &event_kinds, &enabled],
+            )
+            .await?;
+        *self = Self::try_from(row)?;
+
+        Ok(())
+    }
+
+    pub async fn delete(ctx: &DalContext, id: WorkspaceWebhookId) -> WorkspaceWebhookResult<()> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let deleted = ctx
+            .txns()
+            .await?
+            .pg()
+            .execute(
+                "DELETE FROM workspace_webhooks WHERE id = $1 AND workspace_pk = $2",
+                &[&id, &workspace_pk],
+            )
+            .await?;
+        if deleted == 0 {
+            return Err(WorkspaceWebhookError::WebhookNotFound(id));
+        }
+
+        Ok(())
+    }
+
+    /// Delivers the event to every enabled webhook of the workspace subscribed to its kind.
+    ///
+    /// Deliveries happen on spawned tasks, so this returns as soon as the webhooks are found and
+    /// never waits on (or fails because of) the endpoints themselves.
+    pub async fn notify(
+        ctx: &DalContext,
+        event: WorkspaceWebhookEvent,
+    ) -> WorkspaceWebhookResult<()> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM workspace_webhooks WHERE workspace_pk = $1 AND enabled AND $2 = ANY(event_kinds)",
+                &[&event.workspace_pk, &event.kind.to_string()],
+            )
+            .await?;
+
+        for row in rows {
+            let webhook = Self::try_from(row)?;
+            let pg_pool = ctx.pg_pool().clone();
+            let policy = ctx.webhook_target_policy();
+            let event = event.clone();
+            tokio::spawn(async move {
+                if let Err(err) = webhook.deliver(&pg_pool, policy, &event).await {
+                    error!(si.error.message = ?err, webhook.id = %webhook.id, "unable to deliver webhook");
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// POSTs the signed event to the webhook, retrying with backoff until it answers with a
+    /// success status or [`WEBHOOK_DELIVERY_ATTEMPTS`] is reached. Every attempt is recorded as
+    /// a [`WorkspaceWebhookDelivery`]. A url the [`WebhookTargetPolicy`] refuses is recorded as a
+    /// single failed attempt and never retried. Returns whether the event was delivered.
+    pub async fn deliver(
+        &self,
+        pg_pool: &PgPool,
+        policy: WebhookTargetPolicy,
+        event: &WorkspaceWebhookEvent,
+    ) -> WorkspaceWebhookResult<bool> {
+        let body = serde_json::to_vec(event)?;
+        let signature = sign(&self.secret, &body);
+
+        let mut backoff = WEBHOOK_DELIVERY_INITIAL_BACKOFF;
+        for attempt in 1..=WEBHOOK_DELIVERY_ATTEMPTS {
+            // Checked on every attempt, since what the host resolves to can change between them.
+            let (status_code, error, retryable) = match policy.client_for(&self.url).await {
+                Ok(client) => match client
+                    .post(&self.url)
+                    .timeout(WEBHOOK_DELIVERY_TIMEOUT)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .header(WEBHOOK_SIGNATURE_HEADER, &signature)
+                    .body(body.clone())
+                    .send()
+                    .await
+                {
+                    Ok(response) if response.status().is_success() => {
+                        (Some(response.status().as_u16() as i32), None, false)
+                    }
+                    Ok(response) => (
+                        Some(response.status().as_u16() as i32),
+                        Some(format!("unsuccessful status: {}", response.status())),
+                        true,
+                    ),
+                    Err(err) => (None, Some(err.to_string()), true),
+                },
+                Err(err @ WorkspaceWebhookError::Resolve(..)) => {
+                    (None, Some(err.to_string()), true)
+                }
+                Err(err) => (None, Some(err.to_string()), false),
+            };
+
+            pg_pool
+                .get()
+                .await?
+                .execute(
+                    "INSERT INTO workspace_webhook_deliveries (webhook_id, event_kind, attempt, status_code, error) VALUES ($1, $2, $3, $4, $5)",
+                    &[
+                        &self.id,
+                        &event.kind.to_string(),
+                        &attempt,
+                        &status_code,
+                        &error,
+                    ],
+                )
+                .await?;
+
+            let Some(error) = error else {
+                return Ok(true);
+            };
+            warn!(webhook.id = %self.id, attempt, error, "webhook delivery attempt failed");
+
+            if !retryable {
+                break;
+            }
+            if attempt < WEBHOOK_DELIVERY_ATTEMPTS {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// A single attempt at delivering an event to a [`WorkspaceWebhook`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceWebhookDelivery {
+    pub event_kind: WorkspaceWebhookEventKind,
+    pub attempt: i32,
+    /// The status the endpoint answered with, if it answered at all.
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WorkspaceWebhookDelivery {
+    /// Lists the delivery attempts of the webhook, oldest first.
+    pub async fn list_for_webhook(
+        ctx: &DalContext,
+        webhook_id: WorkspaceWebhookId,
+    ) -> WorkspaceWebhookResult<Vec<Self>> {
+        // Scope to the workspace through the webhook.
+        WorkspaceWebhook::get_by_id(ctx, webhook_id).await?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM workspace_webhook_deliveries WHERE webhook_id = $1 ORDER BY created_at, attempt",
+                &[&webhook_id],
+            )
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let event_kind: String = row.try_get("event_kind")?;
+                Ok(Self {
+                    event_kind: event_kind.parse()?,
+                    attempt: row.try_get("attempt")?,
+                    status_code: row.try_get("status_code")?,
+                    error: row.try_get("error")?,
+                    created_at: row.try_get("created_at")?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Signs the body with the secret, as sent in the [`WEBHOOK_SIGNATURE_HEADER`].
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut state = hmacsha256::State::init(secret.as_bytes());
+    state.update(body);
+    format!("sha256={}", hex::encode(state.finalize().0))
+}
+
+fn check_public_address(url: &str, ip: IpAddr) -> WorkspaceWebhookResult<()> {
+    if is_public_address(ip) {
+        Ok(())
+    } else {
+        Err(WorkspaceWebhookError::DisallowedAddress(url.to_owned(), ip))
+    }
+}
+
+fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            // 100.64.0.0/10 is shared address space (carrier-grade NAT).
+            let shared = first == 100 && (second & 0b1100_0000) == 64;
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || shared)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_address(ip.into()),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+fn event_kind_strings(event_kinds: &[WorkspaceWebhookEventKind]) -> Vec<String> {
+    event_kinds.iter().map(ToString::to_string).collect()
+}
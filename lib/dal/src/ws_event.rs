@@ -1,4 +1,14 @@
-use std::num::ParseIntError;
+use std::{
+    num::ParseIntError,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
 
 use serde::{
     Deserialize,
@@ -85,6 +95,8 @@ use crate::{
         OnlinePayload,
         UserWorkspaceFlagsPayload,
     },
+    workspace::WorkspaceImportValidatedPayload,
+    ws_event_log::WsEventLog,
 };
 
 #[remain::sorted]
@@ -121,7 +133,7 @@ impl From<FuncError> for WsEventError {
 }
 
 #[remain::sorted]
-#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq, strum::AsRefStr)]
 #[serde(tag = "kind", content = "data")]
 #[allow(clippy::large_enum_variant)]
 pub enum WsPayload {
@@ -188,6 +200,32 @@ pub enum WsPayload {
     ViewUpdated(ViewWsPayload),
     WorkspaceImportBeginApprovalProcess(WorkspaceImportApprovalActorPayload),
     WorkspaceImportCancelApprovalProcess(WorkspaceActorPayload),
+    WorkspaceImportValidated(WorkspaceImportValidatedPayload),
+}
+
+impl WsPayload {
+    /// Returns a key identifying the entity this payload is about, for payloads that only convey
+    /// its latest state. Only the last of several payloads with the same key needs to reach
+    /// clients.
+    pub fn coalesce_key(&self) -> Option<String> {
+        match self {
+            Self::ActionsListUpdated(change_set_id) => {
+                Some(format!("ActionsListUpdated.{change_set_id}"))
+            }
+            Self::ChangeSetWritten(change_set_id) => {
+                Some(format!("ChangeSetWritten.{change_set_id}"))
+            }
+            Self::ComponentUpdated(payload) => Some(format!(
+                "ComponentUpdated.{}.{}",
+                payload.change_set_id, payload.component.id
+            )),
+            Self::ResourceRefreshed(payload) => Some(format!(
+                "ResourceRefreshed.{}.{}",
+                payload.change_set_id, payload.component.id
+            )),
+            _ => None,
+        }
+    }
 }
 
 #[remain::sorted]
@@ -200,9 +238,34 @@ pub enum StatusValueKind {
     Qualification,
 }
 
+/// The last sequence number handed out by [`next_sequence`] in this process.
+static LAST_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a sequence number greater than any returned before by this process.
+///
+/// Sequence numbers follow the wall clock (in microseconds since the epoch), so they keep
+/// increasing across restarts. They are only ordered within a process: the clocks of different
+/// publishers drift apart, so consumers that need a single order across publishers (e.g. the sdf
+/// event relay) must assign their own.
+pub fn next_sequence() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_micros() as u64)
+        .unwrap_or_default();
+    let previous = LAST_SEQUENCE
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or_else(|last| last);
+    now.max(previous + 1)
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 pub struct WsEvent {
     version: i64,
+    /// Orders the events created by this process. See [`next_sequence`].
+    #[serde(default)]
+    sequence: u64,
     workspace_pk: WorkspacePk,
     change_set_id: Option<ChangeSetId>,
     actor: Option<Actor>,
@@ -220,6 +283,7 @@ impl WsEvent {
     ) -> WsEventResult<Self> {
         Ok(WsEvent {
             version: 1,
+            sequence: next_sequence(),
             workspace_pk,
             change_set_id,
             actor,
@@ -262,6 +326,10 @@ impl WsEvent {
         .await
     }
 
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
     pub fn workspace_pk(&self) -> WorkspacePk {
         self.workspace_pk
     }
@@ -278,18 +346,35 @@ impl WsEvent {
         self.change_set_id
     }
 
+    pub fn actor(&self) -> Option<&Actor> {
+        self.actor.as_ref()
+    }
+
+    pub fn payload(&self) -> &WsPayload {
+        &self.payload
+    }
+
     fn workspace_subject(&self) -> String {
         format!("si.workspace_pk.{}.event", self.workspace_pk)
     }
 
     /// Publishes the [`event`](Self) to the [`NatsTxn`](si_data_nats::NatsTxn). When the
     /// transaction is committed, the [`event`](Self) will be published for external use.
+    ///
+    /// Events that only convey the latest state of an entity (see [`WsPayload::coalesce_key`])
+    /// replace any earlier event of the same kind for that entity still waiting on the commit.
     pub async fn publish_on_commit(&self, ctx: &DalContext) -> WsEventResult<()> {
-        ctx.txns()
-            .await?
-            .nats()
-            .publish(self.workspace_subject(), &self)
-            .await?;
+        let coalesce_key = self.payload.coalesce_key();
+        WsEventLog::record(ctx, self, coalesce_key.clone()).await;
+        let txns = ctx.txns().await?;
+        match coalesce_key {
+            Some(coalesce_key) => {
+                txns.nats()
+                    .publish_coalesced(self.workspace_subject(), coalesce_key, &self)
+                    .await?
+            }
+            None => txns.nats().publish(self.workspace_subject(), &self).await?,
+        }
         Ok(())
     }
 
@@ -298,6 +383,7 @@ impl WsEvent {
     /// sending data to the frontend, such as object ids, that will only be
     /// valid if the transaction commits successfully.
     pub async fn publish_immediately(&self, ctx: &DalContext) -> WsEventResult<()> {
+        WsEventLog::record(ctx, self, None).await;
         ctx.txns()
             .await?
             .nats()
@@ -312,6 +398,8 @@ impl WsEvent {
 pub struct ErrorPayload {
     id: Ulid,
     error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
@@ -321,8 +409,13 @@ pub struct FinishPayload {
 }
 
 impl WsEvent {
-    pub async fn async_error(ctx: &DalContext, id: Ulid, error: String) -> WsEventResult<Self> {
-        WsEvent::new(ctx, WsPayload::AsyncError(ErrorPayload { id, error })).await
+    pub async fn async_error(
+        ctx: &DalContext,
+        id: Ulid,
+        error: String,
+        code: Option<String>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::AsyncError(ErrorPayload { id, error, code })).await
     }
     pub async fn async_finish(ctx: &DalContext, id: Ulid) -> WsEventResult<Self> {
         WsEvent::new(ctx, WsPayload::AsyncFinish(FinishPayload { id })).await
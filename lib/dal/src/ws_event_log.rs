@@ -0,0 +1,281 @@
+//! This module contains [`WsEventLog`], an opt-in record of the [`WsEvents`](WsEvent) published
+//! for a workspace, kept so that what was sent to the frontend can be looked at after the fact.
+//!
+//! Events are only recorded for workspaces with [`WorkspaceFeatureFlag::WsEventLog`] turned on,
+//! and never for the kinds excluded in the [`ServicesContext`](crate::ServicesContext) (by
+//! default, those in [`WsEventLog::DEFAULT_EXCLUDED_KINDS`]).
+//!
+//! Entries are ordered by their position in the log, which postgres assigns as they are written,
+//! rather than by the [sequence](WsEvent::sequence) of the event itself, as the latter is handed
+//! out by each publisher on its own.
+
+use std::collections::BTreeSet;
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+use si_data_pg::{
+    PgError,
+    PgRow,
+    PgTxn,
+};
+use si_events::Actor;
+use si_id::ChangeSetId;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    DalContext,
+    TransactionsError,
+    Workspace,
+    WorkspaceError,
+    WsEvent,
+    workspace::WorkspaceFeatureFlag,
+};
+
+/// The most entries [`WsEventLog::list`] returns at once.
+const WS_EVENT_LOG_MAX_LIMIT: i64 = 1000;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum WsEventLogError {
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace error: {0}")]
+    Workspace(#[from] Box<WorkspaceError>),
+}
+
+pub type WsEventLogResult<T> = Result<T, WsEventLogError>;
+
+/// A recorded [`WsEvent`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsEventLogEntry {
+    /// Where the entry is in the log. Later entries have greater positions.
+    pub position: i64,
+    /// The [sequence](WsEvent::sequence) of the recorded event.
+    pub sequence: i64,
+    pub kind: String,
+    pub change_set_id: Option<ChangeSetId>,
+    pub actor: Option<Actor>,
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for WsEventLogEntry {
+    type Error = WsEventLogError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let actor: Option<Value> = row.try_get("actor")?;
+        Ok(Self {
+            position: row.try_get("position")?,
+            sequence: row.try_get("sequence")?,
+            kind: row.try_get("kind")?,
+            change_set_id: row.try_get("change_set_id")?,
+            actor: actor.map(serde_json::from_value).transpose()?,
+            payload: row.try_get("payload")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+/// Narrows down the entries returned by [`WsEventLog::list`]. Every field that is set must match.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsEventLogFilter {
+    /// Only entries of these kinds (e.g. `ComponentUpdated`).
+    pub kinds: Option<Vec<String>>,
+    pub change_set_id: Option<ChangeSetId>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only entries after the one at this position, to page through the log.
+    pub after_position: Option<i64>,
+    /// Defaults to, and is capped at, 1000 entries.
+    pub limit: Option<i64>,
+}
+
+pub struct WsEventLog;
+
+impl WsEventLog {
+    /// The kinds of events which are not recorded unless configured otherwise, as they are
+    /// published too often to be worth keeping (e.g. every line of func output or every cursor
+    /// movement).
+    pub const DEFAULT_EXCLUDED_KINDS: &[&str] = &["Cursor", "FuncRunLogUpdated", "Online"];
+
+    /// The [`DEFAULT_EXCLUDED_KINDS`](Self::DEFAULT_EXCLUDED_KINDS), as a set.
+    pub fn default_excluded_kinds() -> BTreeSet<String> {
+        Self::DEFAULT_EXCLUDED_KINDS
+            .iter()
+            .map(|kind| kind.to_string())
+            .collect()
+    }
+
+    /// Records the event, if the workspace it was published for has opted in. The entry is
+    /// written as part of the current transaction, so it only persists if that commits.
+    ///
+    /// The entry of an event published with a coalesce key (see
+    /// [`WsPayload::coalesce_key`](crate::WsPayload::coalesce_key)) replaces that of the earlier
+    /// event it replaces on the commit, so that the log matches what was sent.
+    ///
+    /// Failing to record an event is logged rather than returned, so that it never stops the
+    /// event from being published.
+    pub async fn record(ctx: &DalContext, event: &WsEvent, coalesce_key: Option<String>) {
+        if let Err(err) = Self::try_record(ctx, event, coalesce_key).await {
+            warn!(
+                si.error.message = ?err,
+                si.workspace.id = %event.workspace_pk(),
+                "unable to record ws event",
+            );
+        }
+    }
+
+    async fn try_record(
+        ctx: &DalContext,
+        event: &WsEvent,
+        coalesce_key: Option<String>,
+    ) -> WsEventLogResult<()> {
+        let kind = event.payload().as_ref();
+        if ctx.ws_event_log_excluded_kinds().contains(kind) {
+            return Ok(());
+        }
+
+        // The flags of the workspace of the context are cached, so workspaces which have not
+        // opted in only pay for this once per context.
+        let flag = WorkspaceFeatureFlag::WsEventLog;
+        let enabled = if ctx.tenancy().workspace_pk_opt() == Some(event.workspace_pk()) {
+            ctx.workspace_feature_enabled(flag).await?
+        } else {
+            Workspace::get_by_pk(ctx, event.workspace_pk())
+                .await
+                .map_err(Box::new)?
+                .feature_flag_enabled(flag)
+        };
+        if !enabled {
+            return Ok(());
+        }
+
+        let mut payload = serde_json::to_value(event.payload())?;
+        let data = payload
+            .get_mut("data")
+            .map(Value::take)
+            .unwrap_or(Value::Null);
+        let actor = event.actor().map(serde_json::to_value).transpose()?;
+
+        // The insert happens within a savepoint so that, should it fail, the rest of the
+        // transaction can still be committed.
+        let mut txns = ctx.txns().await?;
+        let replaced = coalesce_key
+            .as_deref()
+            .and_then(|key| txns.ws_event_log_coalesced(event.workspace_pk(), key));
+        let pg = txns.pg();
+        pg.execute("SAVEPOINT ws_event_log", &[]).await?;
+        let inserted = Self::insert(pg, event, kind, &actor, &data, replaced).await;
+        match inserted {
+            Ok(position) => {
+                pg.execute("RELEASE SAVEPOINT ws_event_log", &[]).await?;
+                if let Some(coalesce_key) = coalesce_key {
+                    txns.set_ws_event_log_coalesced(event.workspace_pk(), coalesce_key, position);
+                }
+                Ok(())
+            }
+            Err(err) => {
+                pg.execute("ROLLBACK TO SAVEPOINT ws_event_log", &[])
+                    .await?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Writes the entry for the event, first deleting the entry at the `replaced` position, if
+    /// any, and returns the position of the new entry.
+    async fn insert(
+        pg: &PgTxn,
+        event: &WsEvent,
+        kind: &str,
+        actor: &Option<Value>,
+        data: &Value,
+        replaced: Option<i64>,
+    ) -> WsEventLogResult<i64> {
+        if let Some(replaced) = replaced {
+            pg.execute("DELETE FROM ws_event_log WHERE position = $1", &[&replaced])
+                .await?;
+        }
+        let row = pg
+            .query_one(
+                "INSERT INTO ws_event_log (workspace_pk, change_set_id, sequence, kind, actor, payload)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING position",
+                &[
+                    &event.workspace_pk(),
+                    &event.change_set_id(),
+                    &(event.sequence() as i64),
+                    &kind,
+                    actor,
+                    data,
+                ],
+            )
+            .await?;
+        Ok(row.try_get("position")?)
+    }
+
+    /// Lists the recorded events of the workspace matching the filter, oldest first.
+    pub async fn list(
+        ctx: &DalContext,
+        filter: WsEventLogFilter,
+    ) -> WsEventLogResult<Vec<WsEventLogEntry>> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let limit = filter
+            .limit
+            .unwrap_or(WS_EVENT_LOG_MAX_LIMIT)
+            .clamp(0, WS_EVENT_LOG_MAX_LIMIT);
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM ws_event_log
+                WHERE workspace_pk = $1
+                    AND ($2::text[] IS NULL OR kind = ANY($2))
+                    AND ($3::ident IS NULL OR change_set_id = $3)
+                    AND ($4::timestamptz IS NULL OR created_at >= $4)
+                    AND ($5::timestamptz IS NULL OR created_at < $5)
+                    AND ($6::bigint IS NULL OR position > $6)
+                ORDER BY position
+                LIMIT $7",
+                &[
+                    &workspace_pk,
+                    &filter.kinds,
+                    &filter.change_set_id,
+                    &filter.since,
+                    &filter.until,
+                    &filter.after_position,
+                    &limit,
+                ],
+            )
+            .await?;
+
+        rows.into_iter().map(WsEventLogEntry::try_from).collect()
+    }
+
+    /// Deletes the entries recorded before the given time, across all workspaces, returning how
+    /// many were deleted.
+    pub async fn prune(ctx: &DalContext, before: DateTime<Utc>) -> WsEventLogResult<u64> {
+        Ok(ctx
+            .txns()
+            .await?
+            .pg()
+            .execute("DELETE FROM ws_event_log WHERE created_at < $1", &[&before])
+            .await?)
+    }
+}
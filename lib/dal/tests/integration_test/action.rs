@@ -3,16 +3,26 @@ use std::time::Duration;
 use dal::{
     AttributeValue,
     ChangeSet,
+    ChangeSetError,
+    ChangeSetStatus,
     Component,
     DalContext,
+    Func,
     SchemaVariant,
     action::{
         Action,
+        ActionError,
         ActionState,
         dependency_graph::ActionDependencyGraph,
         prototype::{
             ActionKind,
             ActionPrototype,
+            ActionPrototypeError,
+        },
+        report::{
+            ActionRunReport,
+            ActionRunReportFormat,
+            ActionRunReportStatus,
         },
     },
     func::authoring::FuncAuthoringClient,
@@ -34,8 +44,10 @@ use pretty_assertions_sorted::{
     assert_ne,
 };
 use serde_json::json;
+use si_events::FuncRunState;
 use si_id::ActionId;
 
+mod batch;
 mod schema_level;
 
 #[test]
@@ -154,6 +166,325 @@ async fn run(ctx: &mut DalContext) -> Result<()> {
     Ok(())
 }
 
+#[test]
+async fn upsert_prototype_and_set_func(ctx: &mut DalContext) -> Result<()> {
+    let variant = ExpectSchemaVariant(
+        VariantAuthoringClient::create_schema_and_variant_from_code(
+            ctx,
+            "upserted",
+            None,
+            None,
+            "Category",
+            "#0077cc",
+            "function main() { return new AssetBuilder().build(); }",
+        )
+        .await?
+        .id,
+    );
+
+    // Two action funcs with different payloads, each of which comes with its own prototype.
+    let mut func_ids = Vec::new();
+    for (name, kind) in [
+        ("first", ActionKind::Create),
+        ("second", ActionKind::Manual),
+    ] {
+        let func = FuncAuthoringClient::create_new_action_func(
+            ctx,
+            Some(name.to_string()),
+            kind,
+            variant.id(),
+        )
+        .await?;
+        FuncAuthoringClient::save_code(
+            ctx,
+            func.id,
+            format!(
+                "async function main(component: Input): Promise<Output> {{ return {{ status: 'ok', payload: {{ name: '{name}' }} }}; }}"
+            ),
+        )
+        .await?;
+        func_ids.push(func.id);
+    }
+    let (first_func_id, second_func_id) = (func_ids[0], func_ids[1]);
+    let existing_prototype =
+        ActionPrototype::find_by_kind_for_schema_or_variant(ctx, ActionKind::Create, variant.id())
+            .await?
+            .pop()
+            .expect("no create prototype");
+
+    // Upserting the create prototype updates the existing one rather than adding another.
+    let prototype = ActionPrototype::upsert_for_variant(
+        ctx,
+        variant.id(),
+        first_func_id,
+        ActionKind::Create,
+        "Create Upserted".to_string(),
+    )
+    .await?;
+    assert_eq!(
+        existing_prototype.id(), // expected
+        prototype.id()           // actual
+    );
+    assert_eq!(
+        "Create Upserted",         // expected
+        prototype.name().as_str()  // actual
+    );
+
+    // Upserting a manual prototype with a new name adds one.
+    let manual_prototype = ActionPrototype::upsert_for_variant(
+        ctx,
+        variant.id(),
+        second_func_id,
+        ActionKind::Manual,
+        "Another Manual".to_string(),
+    )
+    .await?;
+    assert_eq!(
+        3,                                                            // expected
+        ActionPrototype::for_variant(ctx, variant.id()).await?.len()  // actual
+    );
+
+    let component_id = component::create(ctx, "upserted", "upserted").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    let (result, _func_run_id) = ActionPrototype::run(ctx, prototype.id(), component_id).await?;
+    assert_eq!(
+        Some(json!({ "name": "first" })),                     // expected
+        result.expect("action should have a result").payload  // actual
+    );
+
+    // Once the prototype points at the other func, running it runs that func.
+    ActionPrototype::set_func(ctx, prototype.id(), second_func_id).await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    assert_eq!(
+        second_func_id,                                       // expected
+        ActionPrototype::func_id(ctx, prototype.id()).await?  // actual
+    );
+
+    let (result, _func_run_id) = ActionPrototype::run(ctx, prototype.id(), component_id).await?;
+    assert_eq!(
+        Some(json!({ "name": "second" })),                    // expected
+        result.expect("action should have a result").payload  // actual
+    );
+
+    // Only action funcs can be used.
+    let identity_func_id = Func::find_id_by_name(ctx, "si:identity")
+        .await?
+        .expect("no identity func");
+    assert!(matches!(
+        ActionPrototype::set_func(ctx, prototype.id(), identity_func_id).await,
+        Err(ActionPrototypeError::FuncNotAction(func_id, _)) if func_id == identity_func_id
+    ));
+
+    ActionPrototype::remove(ctx, manual_prototype.id()).await?;
+    let prototype_ids: Vec<_> = ActionPrototype::for_variant(ctx, variant.id())
+        .await?
+        .iter()
+        .map(ActionPrototype::id)
+        .collect();
+    assert_eq!(
+        2,                   // expected
+        prototype_ids.len()  // actual
+    );
+    assert!(!prototype_ids.contains(&manual_prototype.id()));
+
+    Ok(())
+}
+
+#[test]
+async fn run_report(ctx: &mut DalContext) -> Result<()> {
+    let change_set_id = ctx.change_set_id();
+    create_component_for_default_schema_name_in_default_view(ctx, "swifty", "shake it off").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    // Apply the change set so that its create action runs on HEAD.
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx).await?;
+
+    let report = ActionRunReport::for_originating_change_set(ctx, change_set_id).await?;
+    assert_eq!(
+        1,                 // expected
+        report.runs.len()  // actual
+    );
+    let run = &report.runs[0];
+    assert_eq!(
+        Some("shake it off"),          // expected
+        run.component_name.as_deref()  // actual
+    );
+    assert_eq!(
+        ActionRunReportStatus::Success, // expected
+        run.status                      // actual
+    );
+    assert!(run.result_value.is_some());
+
+    let xml = report.render(ActionRunReportFormat::JUnitXml)?;
+    assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    assert!(xml.contains(r#"tests="1" failures="0" errors="0" skipped="0""#));
+    assert!(xml.contains(&format!(
+        r#"<testcase name="{}" classname="shake it off""#,
+        run.name
+    )));
+    assert!(!xml.contains("<failure"));
+
+    let json: ActionRunReport = serde_json::from_str(&report.render(ActionRunReportFormat::Json)?)?;
+    assert_eq!(
+        report, // expected
+        json    // actual
+    );
+
+    // Change sets without action runs have an empty report.
+    let report = ActionRunReport::for_originating_change_set(ctx, ctx.change_set_id()).await?;
+    assert!(report.runs.is_empty());
+
+    Ok(())
+}
+
+#[test]
+async fn actions_require_an_active_change_set(ctx: &mut DalContext) -> Result<()> {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "shake it off")
+            .await?;
+    let variant_id = Component::schema_variant_id(ctx, component.id()).await?;
+    let prototype = ActionPrototype::for_variant(ctx, variant_id)
+        .await?
+        .pop()
+        .expect("unable to find prototype for variant");
+
+    // Enqueueing and running actions works in an open change set.
+    Action::new(ctx, prototype.id(), Some(component.id())).await?;
+    ActionPrototype::run(ctx, prototype.id(), component.id()).await?;
+
+    let applied_change_set_id = ctx.change_set_id();
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx).await?;
+
+    // HEAD is always active, so actions can still run there (e.g. refreshes).
+    ActionPrototype::run(ctx, prototype.id(), component.id()).await?;
+
+    // Once applied, the change set no longer accepts actions.
+    ctx.update_visibility_and_snapshot_to_visibility(applied_change_set_id)
+        .await?;
+    let is_not_active = |err: &ChangeSetError| {
+        matches!(
+            err,
+            ChangeSetError::ChangeSetNotActive {
+                change_set_id,
+                status: ChangeSetStatus::Applied,
+            } if *change_set_id == applied_change_set_id
+        )
+    };
+    match Action::new(ctx, prototype.id(), Some(component.id())).await {
+        Err(ActionError::ChangeSet(err)) if is_not_active(&err) => {}
+        other => panic!("unexpected result enqueueing an action: {other:?}"),
+    }
+    match ActionPrototype::run(ctx, prototype.id(), component.id()).await {
+        Err(ActionPrototypeError::ChangeSet(err)) if is_not_active(&err) => {}
+        other => panic!("unexpected result running an action: {other:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+async fn preview_impact(ctx: &mut DalContext) -> Result<()> {
+    // Nothing differs from HEAD yet.
+    assert!(ActionPrototype::preview_impact(ctx).await?.is_empty());
+
+    let create_func_id = Func::find_id_by_name(ctx, "test:createActionStarfield")
+        .await?
+        .expect("no func found");
+    let func_id = FuncAuthoringClient::create_unlocked_func_copy(ctx, create_func_id, None)
+        .await?
+        .id;
+    let handler = Func::get_by_id(ctx, func_id)
+        .await?
+        .handler
+        .expect("func has no handler");
+    FuncAuthoringClient::save_code(
+        ctx,
+        func_id,
+        format!(
+            "async function {handler}(component: Input): Promise<Output> {{ return {{ status: 'error' }}; }}"
+        ),
+    )
+    .await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    // Only the create action runs different code. The refresh action's func is untouched.
+    let impacts = ActionPrototype::preview_impact(ctx).await?;
+    assert_eq!(
+        vec![(func_id, ActionKind::Create, true)], // expected
+        impacts
+            .iter()
+            .map(|impact| (impact.func_id, impact.kind, impact.func_added))
+            .collect::<Vec<_>>(), // actual
+    );
+    let impact = impacts.first().expect("no impact found");
+    assert_eq!(
+        ActionPrototype::schema_variant_ids(ctx, impact.action_prototype_id).await?, // expected
+        impact.schema_variant_ids,                                                   // actual
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn latest_runs_for_component(ctx: &mut DalContext) -> Result<()> {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "fearless").await?;
+    let variant_id = Component::schema_variant_id(ctx, component.id()).await?;
+    let prototypes = ActionPrototype::for_variant(ctx, variant_id).await?;
+    assert!(prototypes.len() >= 2);
+    let (ran_prototype, idle_prototype) = (&prototypes[0], &prototypes[1]);
+
+    // Nothing has run yet, but every prototype is listed.
+    let latest_runs = ActionPrototype::latest_runs_for_component(ctx, component.id()).await?;
+    assert_eq!(
+        prototypes.len(),  // expected
+        latest_runs.len()  // actual
+    );
+    assert!(latest_runs.iter().all(|run| run.func_run_id.is_none()));
+
+    // Run one of the prototypes twice; only the second run is listed for it.
+    let (_, first_func_run_id) =
+        ActionPrototype::run(ctx, ran_prototype.id(), component.id()).await?;
+    let (_, second_func_run_id) =
+        ActionPrototype::run(ctx, ran_prototype.id(), component.id()).await?;
+    assert_ne!(first_func_run_id, second_func_run_id);
+
+    let latest_runs = ActionPrototype::latest_runs_for_component(ctx, component.id()).await?;
+    let latest_run_for = |prototype: &ActionPrototype| {
+        latest_runs
+            .iter()
+            .find(|run| run.action_prototype_id == prototype.id())
+            .expect("prototype not listed")
+    };
+
+    let ran = latest_run_for(ran_prototype);
+    assert_eq!(
+        Some(second_func_run_id), // expected
+        ran.func_run_id           // actual
+    );
+    assert_eq!(
+        Some(FuncRunState::Success), // expected
+        ran.state                    // actual
+    );
+    assert!(ran.ran_at.is_some());
+
+    let idle = latest_run_for(idle_prototype);
+    assert_eq!(
+        None,             // expected
+        idle.func_run_id  // actual
+    );
+    assert_eq!(
+        None,       // expected
+        idle.state  // actual
+    );
+
+    Ok(())
+}
+
 #[test]
 async fn auto_queue_creation(ctx: &mut DalContext) -> Result<()> {
     // ======================================================
@@ -0,0 +1,178 @@
+use dal::{
+    Component,
+    DalContext,
+    action::{
+        Action,
+        ActionState,
+        batch::{
+            ActionBatch,
+            ActionBatchFilter,
+            ActionBatchMemberState,
+        },
+        prototype::{
+            ActionKind,
+            ActionPrototype,
+        },
+    },
+};
+use dal_test::{
+    Result,
+    helpers::{
+        ChangeSetTestHelpers,
+        create_component_for_default_schema_name_in_default_view,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn run_for_all_components(ctx: &mut DalContext) -> Result<()> {
+    let mut component_ids = Vec::new();
+    for name in ["fearless", "red", "lover"] {
+        let component =
+            create_component_for_default_schema_name_in_default_view(ctx, "swifty", name).await?;
+        component_ids.push(component.id());
+    }
+    component_ids.sort();
+
+    // Drop the create actions enqueued alongside the components, so that only the batch runs
+    for action_id in Action::list_topologically(ctx).await? {
+        Action::remove_by_id(ctx, action_id).await?;
+    }
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+
+    let schema_variant_id = Component::schema_variant_id(ctx, component_ids[0]).await?;
+    let prototype = ActionPrototype::find_by_kind_for_schema_or_variant(
+        ctx,
+        ActionKind::Create,
+        schema_variant_id,
+    )
+    .await?
+    .pop()
+    .expect("swifty has a create action");
+
+    let batch =
+        ActionBatch::run_for_all_components(ctx, prototype.id(), ActionBatchFilter::default())
+            .await?;
+    assert_eq!(
+        component_ids, // expected
+        batch
+            .members()
+            .iter()
+            .map(|member| member.component_id)
+            .collect::<Vec<_>>(), // actual
+    );
+    let action_ids = batch.action_ids();
+    assert_eq!(3, action_ids.len());
+
+    // Fail one of the runs, which should not get in the way of the others
+    let failed_action_id = action_ids[1];
+    Action::set_state(ctx, failed_action_id, ActionState::Failed).await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx).await?;
+
+    let status = ActionBatch::status(ctx, batch.id()).await?;
+    assert!(status.finished);
+    assert_eq!(2, status.succeeded);
+    assert_eq!(1, status.failed);
+    assert_eq!(
+        vec![
+            ActionBatchMemberState::Succeeded,
+            ActionBatchMemberState::Failed,
+            ActionBatchMemberState::Succeeded,
+        ], // expected
+        status
+            .members
+            .iter()
+            .map(|member| member.state)
+            .collect::<Vec<_>>(), // actual
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn rerun_failed(ctx: &mut DalContext) -> Result<()> {
+    let mut component_ids = Vec::new();
+    for name in ["midnights", "reputation", "folklore"] {
+        let component =
+            create_component_for_default_schema_name_in_default_view(ctx, "swifty", name).await?;
+        component_ids.push(component.id());
+    }
+    component_ids.sort();
+
+    for action_id in Action::list_topologically(ctx).await? {
+        Action::remove_by_id(ctx, action_id).await?;
+    }
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+
+    let schema_variant_id = Component::schema_variant_id(ctx, component_ids[0]).await?;
+    let prototype = ActionPrototype::find_by_kind_for_schema_or_variant(
+        ctx,
+        ActionKind::Create,
+        schema_variant_id,
+    )
+    .await?
+    .pop()
+    .expect("swifty has a create action");
+
+    let batch =
+        ActionBatch::run_for_all_components(ctx, prototype.id(), ActionBatchFilter::default())
+            .await?;
+    let action_ids = batch.action_ids();
+    let failed_action_id = action_ids[1];
+    Action::set_state(ctx, failed_action_id, ActionState::Failed).await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx).await?;
+
+    let mut succeeded_func_run_ids = Vec::new();
+    for action_id in [action_ids[0], action_ids[2]] {
+        succeeded_func_run_ids.push(
+            ctx.layer_db()
+                .func_run()
+                .get_last_run_for_action_id_opt(ctx.events_tenancy().workspace_pk, action_id)
+                .await?
+                .expect("the action ran")
+                .id(),
+        );
+    }
+
+    let rerun = ActionBatch::rerun_failed(ctx, batch.id()).await?;
+    assert_eq!(Some(batch.id()), rerun.retries_batch_id());
+    // The failed action is re-queued rather than replaced, and the others are carried over
+    assert_eq!(
+        action_ids,         // expected
+        rerun.action_ids(), // actual
+    );
+    assert_eq!(
+        ActionState::Queued,                                     // expected
+        Action::get_by_id(ctx, failed_action_id).await?.state(), // actual
+    );
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx).await?;
+
+    let status = ActionBatch::status(ctx, rerun.id()).await?;
+    assert!(status.finished);
+    assert_eq!(3, status.succeeded);
+    assert_eq!(0, status.failed);
+
+    // The actions which had already succeeded did not run again
+    for (action_id, func_run_id) in [action_ids[0], action_ids[2]]
+        .into_iter()
+        .zip(succeeded_func_run_ids)
+    {
+        assert_eq!(
+            func_run_id, // expected
+            ctx.layer_db()
+                .func_run()
+                .get_last_run_for_action_id_opt(ctx.events_tenancy().workspace_pk, action_id)
+                .await?
+                .expect("the action ran")
+                .id(), // actual
+        );
+    }
+
+    Ok(())
+}
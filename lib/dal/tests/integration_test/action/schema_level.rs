@@ -7,6 +7,7 @@ use dal::{
     Schema,
     action::{
         Action,
+        ActionState,
         dependency_graph::ActionDependencyGraph,
         prototype::{
             ActionKind,
@@ -16,7 +17,13 @@ use dal::{
 };
 use dal_test::{
     Result,
-    helpers::create_component_for_default_schema_name_in_default_view,
+    helpers::{
+        create_component_for_default_schema_name_in_default_view,
+        schema::{
+            TestActionOutcome,
+            create_test_action_func,
+        },
+    },
     prelude::ChangeSetTestHelpers,
     test,
 };
@@ -154,3 +161,74 @@ async fn schema_level_action_prototype(ctx: &mut DalContext) -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+async fn test_action_func_outcomes(ctx: &mut DalContext) -> Result<()> {
+    let created_func_id = create_test_action_func(
+        ctx,
+        "swifty",
+        "test:succeedingCreate",
+        ActionKind::Create,
+        TestActionOutcome::SleepThenSucceed(10, serde_json::json!({ "era": "folklore" })),
+    )
+    .await?;
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "cardigan").await?;
+
+    let action_id = Action::find_for_component_id(ctx, component.id())
+        .await?
+        .pop()
+        .expect("should have an action");
+    let prototype_id = Action::prototype_id(ctx, action_id).await?;
+    assert_eq!(
+        created_func_id,                                    // expected
+        ActionPrototype::func_id(ctx, prototype_id).await?  // actual
+    );
+
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx).await?;
+
+    let payload_id = Component::get_by_id(ctx, component.id())
+        .await?
+        .attribute_values_for_prop(ctx, &["root", "resource", "payload"])
+        .await?
+        .pop()
+        .expect("should have a payload");
+    let payload = AttributeValue::view(ctx, payload_id)
+        .await?
+        .expect("a value should exist");
+    assert_eq!(
+        serde_json::json!({ "era": "folklore" }), // expected
+        payload,                                  // actual
+    );
+
+    // A failing create leaves its action on the queue in the failed state. We use another schema,
+    // since swifty now has a create overlay.
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx).await?;
+    create_test_action_func(
+        ctx,
+        "small even lego",
+        "test:failingCreate",
+        ActionKind::Create,
+        TestActionOutcome::Fail("out of the woods".to_string()),
+    )
+    .await?;
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "small even lego", "august")
+            .await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx).await?;
+
+    let action_id = Action::find_for_component_id(ctx, component.id())
+        .await?
+        .pop()
+        .expect("failed action should remain");
+    assert_eq!(
+        ActionState::Failed,                              // expected
+        Action::get_by_id(ctx, action_id).await?.state()  // actual
+    );
+
+    Ok(())
+}
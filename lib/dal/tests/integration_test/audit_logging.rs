@@ -1,11 +1,23 @@
-use audit_database::AuditDatabaseContext;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use audit_database::{
+    AuditDatabaseContext,
+    AuditLogFilter,
+    AuditLogRow,
+};
 use audit_logs_stream::AuditLogsStream;
+use chrono::Utc;
 use dal::{
     AttributeValue,
+    Component,
     DalContext,
     Prop,
     Schema,
     SchemaVariant,
+    action::Action,
     audit_logging,
     prop::PropPath,
 };
@@ -13,6 +25,7 @@ use dal_test::{
     helpers::{
         ChangeSetTestHelpers,
         confirm_jetstream_stream_has_no_messages,
+        create_component_for_default_schema_name_in_default_view,
         create_named_component_for_schema_variant_on_default_view,
         list_audit_logs_until_expected_number_of_rows,
     },
@@ -20,7 +33,13 @@ use dal_test::{
 };
 use pending_events::PendingEventsStream;
 use pretty_assertions_sorted::assert_eq;
-use si_events::audit_log::AuditLogKind;
+use si_events::{
+    ActionKind,
+    Actor,
+    AuthenticationMethod,
+    audit_log::AuditLogKind,
+};
+use si_id::ViewId;
 
 const DATABASE_RETRY_TIMEOUT_SECONDS: u64 = 2;
 const DATABASE_RETRY_INTERVAL_MILLISECONDS: u64 = 100;
@@ -267,3 +286,202 @@ async fn round_trip(ctx: &mut DalContext, audit_database_context: AuditDatabaseC
             .expect("could not list component-specific audit logs");
     }
 }
+
+#[test]
+async fn list_filtered(ctx: &DalContext, audit_database_context: AuditDatabaseContext) {
+    let context = audit_database_context;
+    let workspace_id = ctx.workspace_pk().expect("could not get workspace pk");
+
+    // Seed rows of two kinds directly, bypassing the stream.
+    let mut created_view_ids = Vec::new();
+    for _ in 0..3 {
+        let view_id = ViewId::new();
+        created_view_ids.push(view_id);
+        AuditLogRow::insert(
+            &context,
+            workspace_id,
+            AuditLogKind::CreateView { view_id },
+            Utc::now().to_rfc3339(),
+            Some(ctx.change_set_id()),
+            Actor::System,
+            None,
+            AuthenticationMethod::System,
+        )
+        .await
+        .expect("could not insert audit log");
+    }
+    for _ in 0..2 {
+        AuditLogRow::insert(
+            &context,
+            workspace_id,
+            AuditLogKind::DeleteView {
+                view_id: ViewId::new(),
+            },
+            Utc::now().to_rfc3339(),
+            Some(ctx.change_set_id()),
+            Actor::System,
+            None,
+            AuthenticationMethod::System,
+        )
+        .await
+        .expect("could not insert audit log");
+    }
+
+    let filter = AuditLogFilter {
+        kinds: vec!["CreateView".to_string()],
+        ..Default::default()
+    };
+
+    // Everything fits on one page.
+    let (logs, next) = audit_logging::list_filtered(ctx, &context, &filter, None, SIZE, true)
+        .await
+        .expect("could not list filtered audit logs");
+    assert_eq!(
+        3,          // expected
+        logs.len()  // actual
+    );
+    assert!(logs.iter().all(|log| log.kind == "CreateView"));
+    assert!(next.is_none());
+
+    // Page through two at a time, oldest first.
+    let (first_page, next) = audit_logging::list_filtered(ctx, &context, &filter, None, 2, true)
+        .await
+        .expect("could not list first page");
+    let next = next.expect("first page should have a cursor");
+    let (second_page, last) =
+        audit_logging::list_filtered(ctx, &context, &filter, Some(next), 2, true)
+            .await
+            .expect("could not list second page");
+    assert!(last.is_none());
+
+    let paged_view_ids: Vec<_> = first_page
+        .iter()
+        .chain(second_page.iter())
+        .map(|log| {
+            log.metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("viewId"))
+                .and_then(|view_id| view_id.as_str())
+                .expect("could not get view id from metadata")
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        created_view_ids
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(), // expected
+        paged_view_ids // actual
+    );
+}
+
+#[test]
+async fn action_runs_and_resource_refreshes(
+    ctx: &mut DalContext,
+    audit_database_context: AuditDatabaseContext,
+) {
+    let context = audit_database_context;
+
+    // Create a component with a resource id so that it can be refreshed, then run its create
+    // action on HEAD.
+    let component = create_component_for_default_schema_name_in_default_view(
+        ctx,
+        "small even lego",
+        "audited lego",
+    )
+    .await
+    .expect("could not create component");
+    let av_id =
+        Component::attribute_value_for_prop(ctx, component.id(), &["root", "si", "resourceId"])
+            .await
+            .expect("could not get resource id attribute value");
+    AttributeValue::update(ctx, av_id, Some(serde_json::json!("audited-resource")))
+        .await
+        .expect("could not set resource id");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set");
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx)
+        .await
+        .expect("create action did not run");
+
+    // Refresh it.
+    Action::enqueue_refresh_in_correct_change_set_and_commit(ctx, component.id())
+        .await
+        .expect("could not enqueue refresh");
+    ChangeSetTestHelpers::wait_for_actions_to_run(ctx)
+        .await
+        .expect("refresh action did not run");
+
+    let filter = AuditLogFilter {
+        kinds: vec!["RunAction".to_string(), "RefreshResource".to_string()],
+        ..Default::default()
+    };
+    let component_id = serde_json::json!(component.id());
+    let timeout = Duration::from_secs(STREAM_RETRY_TIMEOUT_SECONDS);
+    let start = Instant::now();
+    let (run_action_logs, refresh_logs) = loop {
+        let (logs, _) = audit_logging::list_filtered(ctx, &context, &filter, None, SIZE, true)
+            .await
+            .expect("could not list filtered audit logs");
+        let (refresh_logs, run_action_logs): (Vec<_>, Vec<_>) = logs
+            .into_iter()
+            .filter(|log| {
+                log.metadata
+                    .as_ref()
+                    .is_some_and(|metadata| metadata["componentId"] == component_id)
+            })
+            .partition(|log| log.kind == "RefreshResource");
+        if run_action_logs.len() >= 2 && !refresh_logs.is_empty() {
+            break (run_action_logs, refresh_logs);
+        }
+        assert!(
+            start.elapsed() < timeout,
+            "hit timeout before the action audit logs arrived (run actions: {}, refreshes: {})",
+            run_action_logs.len(),
+            refresh_logs.len()
+        );
+        tokio::time::sleep(Duration::from_millis(DATABASE_RETRY_INTERVAL_MILLISECONDS)).await;
+    };
+
+    // Both the create and the refresh actions are recorded as runs against the component.
+    let action_kinds: Vec<_> = run_action_logs
+        .iter()
+        .filter_map(|log| {
+            log.metadata
+                .as_ref()
+                .map(|metadata| metadata["actionKind"].clone())
+        })
+        .collect();
+    for kind in [ActionKind::Create, ActionKind::Refresh] {
+        let kind = serde_json::json!(kind);
+        assert!(
+            action_kinds.contains(&kind),
+            "no run recorded for {kind} among {action_kinds:?}"
+        );
+    }
+
+    // The refresh is also recorded as a refresh of the resource.
+    let refresh_log = refresh_logs.first().expect("no refresh audit log");
+    assert_eq!(
+        "Refreshed",       // expected
+        refresh_log.title  // actual
+    );
+    assert_eq!(
+        Some("Resource"),                   // expected
+        refresh_log.entity_type.as_deref()  // actual
+    );
+    assert_eq!(
+        Some("audited lego"),               // expected
+        refresh_log.entity_name.as_deref()  // actual
+    );
+    let metadata = refresh_log.metadata.as_ref().expect("no metadata");
+    assert_eq!(
+        serde_json::json!("audited lego"), // expected
+        metadata["componentName"]          // actual
+    );
+    assert!(metadata["funcRunId"].is_string());
+}
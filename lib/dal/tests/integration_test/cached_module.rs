@@ -0,0 +1,799 @@
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::Utc;
+use dal::{
+    DalContext,
+    Func,
+    FuncId,
+    Schema,
+    SchemaId,
+    SchemaVariant,
+    SchemaVariantId,
+    action::prototype::{
+        ActionKind,
+        ActionPrototype,
+    },
+    cached_module::{
+        CachedModule,
+        CachedModuleError,
+        ModuleCacheWarmUp,
+    },
+    pkg::export::PkgExporter,
+};
+use dal_test::{
+    helpers::{
+        ChangeSetTestHarness,
+        ws_event::WsEventCapture,
+    },
+    module_index_stub::ModuleIndexStub,
+    pkg_fixture::{
+        self,
+        PkgFixture,
+        SCHEMA_WITH_ACTIONS,
+        SIMPLE_SCHEMA,
+    },
+    test,
+};
+use edda_client::EddaClient;
+use pretty_assertions_sorted::assert_eq;
+use tokio::sync::Notify;
+
+async fn insert_cached_module(
+    ctx: &DalContext,
+    schema_id: SchemaId,
+    schema_name: &str,
+    latest_hash: &str,
+    package_data: Option<&[u8]>,
+) {
+    ctx.txns()
+        .await
+        .expect("could not get txns")
+        .pg()
+        .execute(
+            "INSERT INTO cached_modules (
+                schema_id,
+                schema_name,
+                component_type,
+                latest_hash,
+                created_at,
+                package_data
+            ) VALUES ($1, $2, 'component', $3, $4, $5)",
+            &[
+                &schema_id,
+                &schema_name,
+                &latest_hash,
+                &Utc::now(),
+                &package_data,
+            ],
+        )
+        .await
+        .expect("could not insert cached module");
+}
+
+/// Empties the module cache, committing so that a warm-up running on its own connection sees it.
+async fn clear_cached_modules(ctx: &DalContext) {
+    ctx.txns()
+        .await
+        .expect("could not get txns")
+        .pg()
+        .execute("DELETE FROM cached_modules", &[])
+        .await
+        .expect("could not clear cached modules");
+    ctx.commit_no_rebase().await.expect("could not commit");
+}
+
+async fn wait_for_warm_up(warm_up: &ModuleCacheWarmUp) {
+    tokio::time::timeout(Duration::from_secs(60), async {
+        while warm_up.is_warming() {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for the module cache warm-up");
+}
+
+async fn export_schema_bytes(ctx: &DalContext, schema_name: &str) -> Vec<u8> {
+    let schema = Schema::get_by_name(ctx, schema_name)
+        .await
+        .expect("schema not found");
+
+    PkgExporter::new_for_module_contribution(
+        schema_name,
+        "2019-06-03",
+        "System Initiative",
+        schema.id(),
+        false,
+    )
+    .export_as_bytes(ctx)
+    .await
+    .expect("could not export schema")
+}
+
+#[test]
+async fn si_pkg_shares_package_data(ctx: &DalContext) {
+    let bytes = export_schema_bytes(ctx, "swifty").await;
+    let schema_id = SchemaId::generate();
+    insert_cached_module(ctx, schema_id, "swifty", "swifty-hash", Some(&bytes)).await;
+
+    let mut module = CachedModule::find_latest_for_schema_id(ctx, schema_id)
+        .await
+        .expect("could not find cached module")
+        .expect("cached module not found");
+
+    // The row buffer has been moved into the struct, not copied.
+    let package_data = module.package_data.clone().expect("package data missing");
+    assert_eq!(2, Arc::strong_count(&package_data));
+    drop(package_data);
+
+    let pkg = module
+        .si_pkg_keeping_package_data(ctx)
+        .await
+        .expect("could not load si pkg");
+    assert_eq!("swifty", pkg.metadata().expect("no metadata").name());
+
+    // Parsing hands the same Arc to the slow runtime and releases it afterwards.
+    let package_data = module.package_data.as_ref().expect("package data missing");
+    assert_eq!(1, Arc::strong_count(package_data));
+
+    // By default, the bytes are released once parsed.
+    module.si_pkg(ctx).await.expect("could not load si pkg");
+    assert!(module.package_data.is_none());
+}
+
+#[test]
+async fn si_pkg_loads_package_data_lazily(ctx: &DalContext) {
+    let bytes = export_schema_bytes(ctx, "swifty").await;
+    let schema_id = SchemaId::generate();
+    insert_cached_module(ctx, schema_id, "swifty", "swifty-hash", Some(&bytes)).await;
+
+    let mut module = CachedModule::list_for_schema_id(ctx, schema_id)
+        .await
+        .expect("could not list cached modules")
+        .pop()
+        .expect("cached module not found");
+    assert!(module.package_data.is_none());
+
+    module
+        .si_pkg_keeping_package_data(ctx)
+        .await
+        .expect("could not load si pkg");
+    let package_data = module.package_data.as_ref().expect("package data missing");
+    assert_eq!(1, Arc::strong_count(package_data));
+    assert_eq!(bytes.as_slice(), package_data.as_slice());
+}
+
+#[test]
+async fn latest_modules_paginated(ctx: &DalContext) {
+    let count_before = CachedModule::count_latest(ctx)
+        .await
+        .expect("could not count latest modules");
+
+    let mut schema_ids = Vec::new();
+    for name in ["paged-e", "paged-a", "paged-d", "paged-b", "paged-c"] {
+        let schema_id = SchemaId::generate();
+        insert_cached_module(ctx, schema_id, name, &format!("{name}-1"), None).await;
+        schema_ids.push(schema_id);
+    }
+    // A second, newer version of one schema must only show up once.
+    insert_cached_module(ctx, schema_ids[0], "paged-e", "paged-e-2", None).await;
+
+    let count = CachedModule::count_latest(ctx)
+        .await
+        .expect("could not count latest modules");
+    assert_eq!(count_before + 5, count);
+
+    let mut paged = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = CachedModule::latest_modules_paginated(ctx, 2, offset)
+            .await
+            .expect("could not list page");
+        assert!(page.len() <= 2);
+        if page.is_empty() {
+            break;
+        }
+        offset += page.len() as u32;
+        paged.extend(page);
+    }
+    assert_eq!(count as usize, paged.len());
+
+    // Paging through everything yields exactly the unpaged result, with no duplicates.
+    let mut expected: Vec<_> = CachedModule::latest_modules(ctx)
+        .await
+        .expect("could not list latest modules")
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    expected.sort();
+    let mut actual: Vec<_> = paged.iter().map(|m| m.id).collect();
+    actual.sort();
+    actual.dedup();
+    assert_eq!(expected, actual);
+
+    let ours: Vec<(&str, &str)> = paged
+        .iter()
+        .filter(|m| m.schema_name.starts_with("paged-"))
+        .map(|m| (m.schema_name.as_str(), m.latest_hash.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("paged-a", "paged-a-1"),
+            ("paged-b", "paged-b-1"),
+            ("paged-c", "paged-c-1"),
+            ("paged-d", "paged-d-1"),
+            ("paged-e", "paged-e-2"),
+        ],
+        ours
+    );
+}
+
+#[test]
+async fn find_missing_entries(ctx: &DalContext) {
+    let missing = CachedModule::find_missing_entries(ctx, vec![])
+        .await
+        .expect("could not find missing entries");
+    assert!(missing.is_empty());
+
+    for count in [1, 100, 1000] {
+        // Quotes and other sql punctuation must come back untouched.
+        let hashes: Vec<String> = (0..count)
+            .map(|idx| format!("it's a \"hash\"; ({count}-{idx})"))
+            .collect();
+        let cached_hashes: Vec<&String> = hashes.iter().step_by(2).collect();
+        for hash in &cached_hashes {
+            insert_cached_module(ctx, SchemaId::generate(), "hashy", hash, None).await;
+        }
+
+        let missing = CachedModule::find_missing_entries(ctx, hashes.clone())
+            .await
+            .expect("could not find missing entries");
+
+        let expected: HashSet<&String> = hashes
+            .iter()
+            .filter(|hash| !cached_hashes.contains(hash))
+            .collect();
+        assert_eq!(count / 2, expected.len());
+        assert_eq!(
+            expected,                               // expected
+            missing.iter().collect::<HashSet<_>>(), // actual
+        );
+        assert_eq!(
+            expected.len(), // expected
+            missing.len(),  // actual
+        );
+    }
+}
+
+#[test]
+async fn update_cached_modules_from_module_index(ctx: &DalContext) {
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    let swifty_schema_id = SchemaId::generate();
+    let starfield_schema_id = SchemaId::generate();
+    stub.add_builtin(
+        swifty_schema_id,
+        "swifty",
+        export_schema_bytes(ctx, "swifty").await,
+    )
+    .expect("could not add swifty builtin");
+    stub.add_builtin(
+        starfield_schema_id,
+        "starfield",
+        export_schema_bytes(ctx, "starfield").await,
+    )
+    .expect("could not add starfield builtin");
+
+    let ctx = stub.ctx(ctx);
+    let edda_client = EddaClient::new(ctx.nats_conn().clone())
+        .await
+        .expect("could not create edda client");
+    let new_modules = CachedModule::update_cached_modules(&ctx, edda_client)
+        .await
+        .expect("could not update cached modules");
+
+    assert_eq!(
+        HashSet::from([swifty_schema_id, starfield_schema_id]), // expected
+        new_modules
+            .iter()
+            .map(|module| module.schema_id)
+            .collect::<HashSet<_>>(), // actual
+    );
+
+    // Everything not in the module index has been removed from the cache.
+    let latest_schema_ids: HashSet<SchemaId> = CachedModule::latest_user_independent_modules(&ctx)
+        .await
+        .expect("could not list latest modules")
+        .into_iter()
+        .map(|module| module.schema_id)
+        .collect();
+    assert_eq!(
+        HashSet::from([swifty_schema_id, starfield_schema_id]), // expected
+        latest_schema_ids,                                      // actual
+    );
+
+    let swifty = CachedModule::find_latest_for_schema_id(&ctx, swifty_schema_id)
+        .await
+        .expect("could not find cached module")
+        .expect("cached module not found");
+    assert_eq!(
+        "swifty",           // expected
+        swifty.schema_name, // actual
+    );
+}
+
+#[test]
+async fn si_pkg_loads_fixture_package_data(ctx: &DalContext) {
+    let fixture = pkg_fixture::simple_schema().expect("could not build fixture");
+    let hash = fixture.hash().expect("could not hash fixture");
+    let schema_id = SchemaId::generate();
+    insert_cached_module(ctx, schema_id, SIMPLE_SCHEMA, &hash, Some(&fixture.bytes)).await;
+
+    let mut module = CachedModule::find_latest_for_schema_id(ctx, schema_id)
+        .await
+        .expect("could not find cached module")
+        .expect("cached module not found");
+    let pkg = module.si_pkg(ctx).await.expect("could not load si pkg");
+
+    let metadata = pkg.metadata().expect("no metadata");
+    assert_eq!(SIMPLE_SCHEMA, metadata.name());
+    assert_eq!(hash, metadata.hash().to_string());
+}
+
+#[test]
+async fn si_pkg_fails_for_corrupted_package_data(ctx: &DalContext) {
+    let fixture = pkg_fixture::simple_schema().expect("could not build fixture");
+    let hash = fixture.hash().expect("could not hash fixture");
+    let schema_id = SchemaId::generate();
+    insert_cached_module(
+        ctx,
+        schema_id,
+        SIMPLE_SCHEMA,
+        &hash,
+        Some(&fixture.corrupted_bytes()),
+    )
+    .await;
+
+    let mut module = CachedModule::find_latest_for_schema_id(ctx, schema_id)
+        .await
+        .expect("could not find cached module")
+        .expect("cached module not found");
+    assert!(module.si_pkg(ctx).await.is_err());
+}
+
+#[test]
+async fn update_cached_modules_stores_fixture_package(ctx: &DalContext) {
+    let fixture = pkg_fixture::schema_with_actions().expect("could not build fixture");
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    let schema_id = SchemaId::generate();
+    stub.add_builtin(schema_id, SCHEMA_WITH_ACTIONS, fixture.bytes.clone())
+        .expect("could not add builtin");
+
+    let ctx = stub.ctx(ctx);
+    let edda_client = EddaClient::new(ctx.nats_conn().clone())
+        .await
+        .expect("could not create edda client");
+    CachedModule::update_cached_modules(&ctx, edda_client)
+        .await
+        .expect("could not update cached modules");
+
+    let mut module = CachedModule::find_latest_for_schema_id(&ctx, schema_id)
+        .await
+        .expect("could not find cached module")
+        .expect("cached module not found");
+    assert_eq!(
+        fixture.hash().expect("could not hash fixture"), // expected
+        module.latest_hash,                              // actual
+    );
+
+    let pkg = module.si_pkg(&ctx).await.expect("could not load si pkg");
+    let func_names: HashSet<String> = pkg
+        .funcs()
+        .expect("could not list funcs")
+        .iter()
+        .map(|func| func.name().to_owned())
+        .collect();
+    assert!(func_names.contains("test:createSchemaWithActions"));
+    assert!(func_names.contains("test:refreshSchemaWithActions"));
+    assert!(func_names.contains("test:generateSchemaWithActionsCode"));
+}
+
+async fn action_prototypes(
+    ctx: &DalContext,
+    schema_variant_id: SchemaVariantId,
+) -> Vec<(String, ActionKind)> {
+    let mut prototypes: Vec<_> =
+        ActionPrototype::list_for_schema_and_variant_id(ctx, schema_variant_id)
+            .await
+            .expect("could not list action prototypes")
+            .into_iter()
+            .map(|prototype| (prototype.name, prototype.kind))
+            .collect();
+    prototypes.sort_by(|(a, _), (b, _)| a.cmp(b));
+    prototypes
+}
+
+#[test]
+async fn install_from_cache_binds_action_prototypes(ctx: &DalContext) {
+    let fixture = pkg_fixture::schema_with_actions().expect("could not build fixture");
+    let hash = fixture.hash().expect("could not hash fixture");
+    let schema_id = SchemaId::generate();
+    insert_cached_module(
+        ctx,
+        schema_id,
+        SCHEMA_WITH_ACTIONS,
+        &hash,
+        Some(&fixture.bytes),
+    )
+    .await;
+
+    let (schema_variant_id, installed) = Schema::install_default_variant_from_cache(ctx, schema_id)
+        .await
+        .expect("could not install schema");
+    assert!(installed);
+
+    let prototypes = action_prototypes(ctx, schema_variant_id).await;
+    assert_eq!(
+        HashSet::from([ActionKind::Create, ActionKind::Refresh]), // expected
+        prototypes
+            .iter()
+            .map(|(_, kind)| *kind)
+            .collect::<HashSet<_>>(), // actual
+    );
+    assert_eq!(2, prototypes.len());
+
+    // Installing again is a no-op, and doesn't duplicate the prototypes
+    let (reinstalled_variant_id, installed) =
+        Schema::install_default_variant_from_cache(ctx, schema_id)
+            .await
+            .expect("could not install schema");
+    assert!(!installed);
+    assert_eq!(schema_variant_id, reinstalled_variant_id);
+    assert_eq!(
+        prototypes,                                      // expected
+        action_prototypes(ctx, schema_variant_id).await, // actual
+    );
+}
+
+/// Caches a module for a schema whose variant has the same code generation func as every other
+/// schema cached by this, returning the schema's id.
+async fn cache_module_with_shared_func(ctx: &DalContext, schema_name: &str) -> SchemaId {
+    let fixture = PkgFixture::builder(schema_name)
+        .codegen_func(
+            "test:sharedCodegen",
+            "async function main(input: Input): Promise<Output> {
+                return { format: \"json\", code: JSON.stringify(input.domain || {}) };
+            }",
+        )
+        .build()
+        .expect("could not build fixture");
+    let hash = fixture.hash().expect("could not hash fixture");
+    let schema_id = SchemaId::generate();
+    insert_cached_module(ctx, schema_id, schema_name, &hash, Some(&fixture.bytes)).await;
+    schema_id
+}
+
+async fn shared_func_ids(ctx: &DalContext) -> Vec<FuncId> {
+    Func::list_all(ctx)
+        .await
+        .expect("could not list funcs")
+        .into_iter()
+        .filter(|func| func.name == "test:sharedCodegen")
+        .map(|func| func.id)
+        .collect()
+}
+
+#[test]
+async fn install_from_cache_reuses_identical_funcs(ctx: &DalContext) {
+    let first_schema_id = cache_module_with_shared_func(ctx, "first-sharing-schema").await;
+    let second_schema_id = cache_module_with_shared_func(ctx, "second-sharing-schema").await;
+    let copying_schema_id = cache_module_with_shared_func(ctx, "copying-schema").await;
+
+    let first = Schema::install_from_cache(ctx, first_schema_id, false)
+        .await
+        .expect("could not install first schema");
+    assert!(first.installed);
+    assert!(first.reused_func_ids.is_empty());
+    let shared_ids = shared_func_ids(ctx).await;
+    assert_eq!(1, shared_ids.len());
+
+    // The second module's copy of the func is identical, so the installed one is reused
+    let second = Schema::install_from_cache(ctx, second_schema_id, false)
+        .await
+        .expect("could not install second schema");
+    assert!(second.installed);
+    assert_eq!(
+        shared_ids,             // expected
+        second.reused_func_ids, // actual
+    );
+    assert_eq!(
+        shared_ids,                 // expected
+        shared_func_ids(ctx).await, // actual
+    );
+    assert!(
+        SchemaVariant::all_func_ids(ctx, second.schema_variant_id)
+            .await
+            .expect("could not list variant funcs")
+            .contains(&shared_ids[0])
+    );
+
+    // Forcing a copy imports the func again
+    let copying = Schema::install_from_cache(ctx, copying_schema_id, true)
+        .await
+        .expect("could not install copying schema");
+    assert!(copying.reused_func_ids.is_empty());
+    assert_eq!(2, shared_func_ids(ctx).await.len());
+}
+
+#[test]
+async fn warm_up_fills_empty_cache(ctx: &DalContext) {
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    let swifty_schema_id = SchemaId::generate();
+    stub.add_builtin(
+        swifty_schema_id,
+        "swifty",
+        export_schema_bytes(ctx, "swifty").await,
+    )
+    .expect("could not add swifty builtin");
+
+    let ctx = stub.ctx(ctx);
+    clear_cached_modules(&ctx).await;
+    let edda_client = EddaClient::new(ctx.nats_conn().clone())
+        .await
+        .expect("could not create edda client");
+
+    let warm_up = ModuleCacheWarmUp::default();
+    let latest = warm_up
+        .latest_modules(&ctx, edda_client.clone())
+        .await
+        .expect("could not list latest modules");
+    assert!(latest.modules.is_empty());
+    assert!(latest.warming);
+
+    wait_for_warm_up(&warm_up).await;
+
+    let latest = warm_up
+        .latest_modules(&ctx, edda_client)
+        .await
+        .expect("could not list latest modules");
+    assert!(!latest.warming);
+    assert_eq!(
+        vec![swifty_schema_id], // expected
+        latest
+            .modules
+            .iter()
+            .map(|module| module.schema_id)
+            .collect::<Vec<_>>(), // actual
+    );
+}
+
+#[test]
+async fn warm_up_notifies_every_waiting_change_set(ctx: &DalContext) {
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    stub.add_builtin(
+        SchemaId::generate(),
+        "swifty",
+        export_schema_bytes(ctx, "swifty").await,
+    )
+    .expect("could not add swifty builtin");
+    // Keeps the warm-up running while the second caller asks for modules.
+    stub.set_response_delay(Some(Duration::from_millis(500)));
+
+    let first = stub.ctx(ctx);
+    clear_cached_modules(&first).await;
+    let second = stub.ctx(
+        &ChangeSetTestHarness::fork(ctx)
+            .await
+            .expect("could not fork change set"),
+    );
+    let edda_client = EddaClient::new(ctx.nats_conn().clone())
+        .await
+        .expect("could not create edda client");
+    let mut capture = WsEventCapture::subscribe(ctx)
+        .await
+        .expect("could not subscribe to ws events");
+
+    let warm_up = ModuleCacheWarmUp::default();
+    for caller in [&first, &second] {
+        assert!(
+            warm_up
+                .latest_modules(caller, edda_client.clone())
+                .await
+                .expect("could not list latest modules")
+                .warming
+        );
+    }
+    wait_for_warm_up(&warm_up).await;
+
+    // Both callers are notified, not only the one which triggered the warm-up.
+    for caller in [&first, &second] {
+        let change_set_id = caller.change_set_id().to_string();
+        capture
+            .expect_event(
+                |event| {
+                    event["payload"]["kind"] == "ModulesUpdated"
+                        && event["change_set_id"].as_str() == Some(change_set_id.as_str())
+                },
+                Duration::from_secs(10),
+            )
+            .await
+            .expect("change set was not notified");
+    }
+}
+
+#[test]
+async fn warm_up_is_deduplicated() {
+    let warm_up = ModuleCacheWarmUp::default();
+    let release = Arc::new(Notify::new());
+
+    let first_release = release.clone();
+    assert!(warm_up.trigger(async move {
+        first_release.notified().await;
+        Ok(())
+    }));
+    assert!(warm_up.is_warming());
+
+    // Concurrent triggers while the first is running do not spawn another update
+    let mut tasks = tokio::task::JoinSet::new();
+    for _ in 0..5 {
+        let warm_up = warm_up.clone();
+        tasks.spawn(async move { warm_up.trigger(async { Ok(()) }) });
+    }
+    while let Some(spawned) = tasks.join_next().await {
+        assert!(!spawned.expect("could not join trigger"));
+    }
+
+    release.notify_one();
+    wait_for_warm_up(&warm_up).await;
+
+    // Nor do triggers once it has succeeded
+    assert!(!warm_up.trigger(async { Ok(()) }));
+    assert!(!warm_up.is_warming());
+}
+
+#[test]
+async fn warm_up_retries_after_failure(ctx: &DalContext) {
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    let swifty_schema_id = SchemaId::generate();
+    stub.add_builtin(
+        swifty_schema_id,
+        "swifty",
+        export_schema_bytes(ctx, "swifty").await,
+    )
+    .expect("could not add swifty builtin");
+    stub.fail_next_requests(1);
+
+    let ctx = stub.ctx(ctx);
+    clear_cached_modules(&ctx).await;
+    let edda_client = EddaClient::new(ctx.nats_conn().clone())
+        .await
+        .expect("could not create edda client");
+
+    let warm_up = ModuleCacheWarmUp::default();
+    assert!(
+        warm_up
+            .latest_modules(&ctx, edda_client.clone())
+            .await
+            .expect("could not list latest modules")
+            .warming
+    );
+    wait_for_warm_up(&warm_up).await;
+
+    // The failed warm-up released the guard, so the next request retries it
+    let latest = warm_up
+        .latest_modules(&ctx, edda_client.clone())
+        .await
+        .expect("could not list latest modules");
+    assert!(latest.modules.is_empty());
+    assert!(latest.warming);
+    wait_for_warm_up(&warm_up).await;
+
+    let latest = warm_up
+        .latest_modules(&ctx, edda_client)
+        .await
+        .expect("could not list latest modules");
+    assert!(!latest.warming);
+    assert_eq!(1, latest.modules.len());
+
+    // A failing update also releases the guard when triggered directly
+    let warm_up = ModuleCacheWarmUp::default();
+    assert!(warm_up.trigger(async { Err(CachedModuleError::ModuleIndexUrlNotSet) }));
+    wait_for_warm_up(&warm_up).await;
+    assert!(warm_up.trigger(async { Ok(()) }));
+}
+
+#[test]
+async fn ensure_cached_uses_the_cache_when_it_can(ctx: &DalContext) {
+    let schema_id = SchemaId::generate();
+    insert_cached_module(ctx, schema_id, "swifty", "swifty-hash", None).await;
+
+    // Nothing is missing, so the module index is never asked
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    stub.fail_next_requests(usize::MAX);
+    let ctx = stub.ctx(ctx);
+
+    let modules = CachedModule::ensure_cached(&ctx, &[schema_id, schema_id])
+        .await
+        .expect("could not ensure modules are cached");
+    assert_eq!(
+        vec![(schema_id, "swifty-hash".to_string())], // expected
+        modules
+            .into_iter()
+            .map(|module| (module.schema_id, module.latest_hash))
+            .collect::<Vec<_>>(), // actual
+    );
+}
+
+#[test]
+async fn ensure_cached_fetches_missing_modules(ctx: &DalContext) {
+    let fixture = pkg_fixture::simple_schema().expect("could not build fixture");
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    let cached_schema_id = SchemaId::generate();
+    insert_cached_module(ctx, cached_schema_id, "swifty", "swifty-hash", None).await;
+    let missing_schema_id = SchemaId::generate();
+    stub.add_builtin(missing_schema_id, SIMPLE_SCHEMA, fixture.bytes.clone())
+        .expect("could not add builtin");
+    let ctx = stub.ctx(ctx);
+
+    let modules = CachedModule::ensure_cached(&ctx, &[cached_schema_id, missing_schema_id])
+        .await
+        .expect("could not ensure modules are cached");
+    assert_eq!(
+        HashSet::from([cached_schema_id, missing_schema_id]), // expected
+        modules
+            .iter()
+            .map(|module| module.schema_id)
+            .collect::<HashSet<_>>(), // actual
+    );
+
+    let mut module = CachedModule::find_latest_for_schema_id(&ctx, missing_schema_id)
+        .await
+        .expect("could not find cached module")
+        .expect("cached module not found");
+    assert_eq!(
+        fixture.hash().expect("could not hash fixture"), // expected
+        module.latest_hash,                              // actual
+    );
+    module.si_pkg(&ctx).await.expect("could not load si pkg");
+
+    // Schemas the module index doesn't have are reported
+    let unknown_schema_id = SchemaId::generate();
+    match CachedModule::ensure_cached(&ctx, &[missing_schema_id, unknown_schema_id]).await {
+        Err(CachedModuleError::NotFoundInModuleIndex(schema_ids)) => {
+            assert_eq!(vec![unknown_schema_id], schema_ids)
+        }
+        other => panic!(
+            "expected the schema to not be found, got {:?}",
+            other.map(|_| ())
+        ),
+    }
+}
+
+#[test]
+async fn ensure_cached_reports_invalid_packages(ctx: &DalContext) {
+    let fixture = pkg_fixture::simple_schema().expect("could not build fixture");
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    let schema_id = SchemaId::generate();
+    stub.add_builtin_with_hash(
+        schema_id,
+        SIMPLE_SCHEMA,
+        fixture.hash().expect("could not hash fixture"),
+        fixture.corrupted_bytes(),
+    );
+    let ctx = stub.ctx(ctx);
+
+    match CachedModule::ensure_cached(&ctx, &[schema_id]).await {
+        Err(CachedModuleError::InvalidPackage(schema_ids)) => {
+            assert_eq!(vec![schema_id], schema_ids)
+        }
+        other => panic!("expected an invalid package, got {:?}", other.map(|_| ())),
+    }
+    assert!(
+        CachedModule::find_latest_for_schema_id(&ctx, schema_id)
+            .await
+            .expect("could not find cached module")
+            .is_none()
+    );
+}
@@ -10,11 +10,16 @@ use dal::{
     RequestContext,
     Workspace,
     WorkspacePk,
-    change_set::view::OpenChangeSetsView,
+    change_set::{
+        summary::ChangeSetSummary,
+        view::OpenChangeSetsView,
+    },
     context::TransactionsErrorDiscriminants,
 };
 use dal_test::{
+    Result,
     helpers::{
+        ChangeSetTestHarness,
         ChangeSetTestHelpers,
         create_component_for_default_schema_name_in_default_view,
         create_user,
@@ -172,6 +177,65 @@ async fn abandon_change_set_and_check_open_change_sets(ctx: &mut DalContext) {
     assert!(!change_set_names.contains(&change_set_name))
 }
 
+#[test]
+async fn component_visible_on_head_after_apply(ctx: &mut DalContext) -> Result<()> {
+    let head_change_set_id = ctx.get_workspace_default_change_set_id().await?;
+    let change_set_id = ChangeSetTestHarness::fork(ctx).await?;
+    let component_id =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "applied")
+            .await?
+            .id();
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    ChangeSetTestHarness::assert_not_visible_in(ctx, head_change_set_id, |ctx| async move {
+        Component::try_get_by_id(&ctx, component_id).await
+    })
+    .await?;
+
+    ChangeSetTestHarness::apply(ctx, change_set_id).await?;
+
+    let component =
+        ChangeSetTestHarness::assert_visible_in(ctx, head_change_set_id, |ctx| async move {
+            Component::try_get_by_id(&ctx, component_id).await
+        })
+        .await?;
+    assert_eq!(
+        "applied",                  // expected
+        component.name(ctx).await?  // actual
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn component_not_visible_on_head_after_abandon(ctx: &mut DalContext) -> Result<()> {
+    let head_change_set_id = ctx.get_workspace_default_change_set_id().await?;
+    let change_set_id = ChangeSetTestHarness::fork(ctx).await?;
+    let component_id =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "abandoned")
+            .await?
+            .id();
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    ChangeSetTestHarness::assert_visible_in(ctx, change_set_id, |ctx| async move {
+        Component::try_get_by_id(&ctx, component_id).await
+    })
+    .await?;
+
+    ChangeSetTestHarness::abandon(ctx, change_set_id).await?;
+
+    assert_eq!(
+        head_change_set_id,  // expected
+        ctx.change_set_id()  // actual
+    );
+    ChangeSetTestHarness::assert_not_visible_in(ctx, head_change_set_id, |ctx| async move {
+        Component::try_get_by_id(&ctx, component_id).await
+    })
+    .await?;
+
+    Ok(())
+}
+
 #[test]
 async fn build_from_request_context_limits_to_workspaces_user_has_access_to(
     ctx: &mut DalContext,
@@ -632,3 +696,86 @@ async fn update_pointer_tracks_last_used(ctx: &mut DalContext) {
         .expect("could not get snapshot_id");
     assert_eq!(snapshot_id, old_snapshot.to_string());
 }
+
+#[test]
+async fn summary(ctx: &mut DalContext) {
+    let summary = ChangeSetSummary::assemble(ctx)
+        .await
+        .expect("could not assemble summary");
+    assert_eq!(
+        ChangeSetSummary::default(), // expected
+        summary                      // actual
+    );
+
+    create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "small")
+        .await
+        .expect("could not create component");
+    create_component_for_default_schema_name_in_default_view(ctx, "small even lego", "large")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    let summary = ChangeSetSummary::assemble(ctx)
+        .await
+        .expect("could not assemble summary");
+    assert_eq!(
+        2,                           // expected
+        summary.modified_components  // actual
+    );
+
+    // Once applied, a fresh change set has nothing left to summarize.
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set");
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+
+    let summary = ChangeSetSummary::assemble(ctx)
+        .await
+        .expect("could not assemble summary");
+    assert_eq!(
+        0,                           // expected
+        summary.modified_components  // actual
+    );
+}
+
+#[test]
+async fn spawned_task_commits_with_context_from_request_context(ctx: &mut DalContext) {
+    let builder = ctx.to_builder();
+    let request_context = ctx.to_request_context();
+    assert_eq!(
+        *ctx.visibility(),          // expected
+        request_context.visibility  // actual
+    );
+
+    let component_id = tokio::spawn(async move {
+        let mut task_ctx = builder
+            .build(request_context)
+            .await
+            .expect("could not build context in task");
+        let component = create_component_for_default_schema_name_in_default_view(
+            &task_ctx,
+            "small odd lego",
+            "spawned",
+        )
+        .await
+        .expect("could not create component");
+        ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(&mut task_ctx)
+            .await
+            .expect("could not commit and update");
+        component.id()
+    })
+    .await
+    .expect("task panicked");
+
+    ctx.update_snapshot_to_visibility()
+        .await
+        .expect("could not update snapshot");
+    let component_ids = Component::list_ids(ctx)
+        .await
+        .expect("could not list components");
+    assert!(component_ids.contains(&component_id));
+}
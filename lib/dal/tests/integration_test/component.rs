@@ -43,6 +43,7 @@ mod get_code;
 mod get_diff;
 mod paste;
 mod property_order;
+mod resource;
 mod set_type;
 mod upgrade;
 
@@ -32,6 +32,7 @@ use dal_test::{
         attribute::value,
         component,
         create_component_for_default_schema_name_in_default_view,
+        create_component_for_new_schema,
     },
     test,
 };
@@ -60,21 +61,17 @@ async fn delete(ctx: &mut DalContext) {
 
 #[test]
 async fn delete_enqueues_destroy_action(ctx: &mut DalContext) {
-    let component =
-        create_component_for_default_schema_name_in_default_view(ctx, "dummy-secret", "component")
-            .await
-            .expect("could not create component");
-    let resource_data = ResourceData::new(
-        ResourceStatus::Ok,
-        Some(serde_json::json![{"resource": "something"}]),
-    );
-    component
-        .set_resource(ctx, resource_data)
+    let bundle = create_component_for_new_schema(ctx, "dummy-secret")
         .await
-        .expect("Unable to set resource");
-    let schema_variant_id = Component::schema_variant_id(ctx, component.id())
+        .expect("could not create component");
+    bundle
+        .attach_resource(
+            ctx,
+            "something",
+            serde_json::json![{"resource": "something"}],
+        )
         .await
-        .expect("Unable to get schema variant id");
+        .expect("Unable to attach resource");
     ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
         .await
         .expect("could not commit and update snapshot to visibility");
@@ -84,7 +81,7 @@ async fn delete_enqueues_destroy_action(ctx: &mut DalContext) {
         ActionKind::Destroy,
         "Destroy action".to_string(),
         None,
-        schema_variant_id,
+        bundle.schema_variant_id,
         Func::find_intrinsic(ctx, IntrinsicFunc::Identity)
             .await
             .expect("Unable to find identity func"),
@@ -99,6 +96,10 @@ async fn delete_enqueues_destroy_action(ctx: &mut DalContext) {
             .is_empty()
     );
 
+    let component = bundle
+        .component(ctx)
+        .await
+        .expect("could not get component");
     component
         .delete(ctx)
         .await
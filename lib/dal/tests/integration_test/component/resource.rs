@@ -0,0 +1,223 @@
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+
+use dal::{
+    Component,
+    DalContext,
+    WsEvent,
+    change_status::ChangeStatus,
+    component::resource::{
+        ResourceInventoryEntry,
+        ResourceView,
+    },
+};
+use dal_test::{
+    Result,
+    helpers::{
+        ChangeSetTestHelpers,
+        create_component_for_new_schema,
+        json::{
+            assert_resource_view,
+            json_mismatches,
+        },
+        ws_event::WsEventCapture,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+#[test]
+async fn resource_view(ctx: &mut DalContext) -> Result<()> {
+    let bundle = create_component_for_new_schema(ctx, "swifty").await?;
+
+    let view = ResourceView::get_by_component_id(ctx, bundle.component_id).await?;
+    assert_resource_view(
+        &view,
+        &json!({
+            "status": null,
+            "message": null,
+            "payload": null,
+            "lastSynced": null,
+        }),
+    );
+
+    bundle
+        .attach_resource(
+            ctx,
+            "i-1234",
+            json!({ "id": "i-1234", "tags": ["one", "two"] }),
+        )
+        .await?;
+
+    let view = ResourceView::get_by_component_id(ctx, bundle.component_id).await?;
+    assert_resource_view(
+        &view,
+        &json!({
+            "status": "ok",
+            "message": "$any",
+            "payload": { "id": "i-1234", "tags": ["one", "two"] },
+            "lastSynced": "$timestamp",
+        }),
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn resource_view_mismatches(ctx: &mut DalContext) -> Result<()> {
+    let bundle = create_component_for_new_schema(ctx, "swifty").await?;
+    bundle
+        .attach_resource(
+            ctx,
+            "i-1234",
+            json!({ "id": "i-1234", "tags": ["one", "two"] }),
+        )
+        .await?;
+    let view = ResourceView::get_by_component_id(ctx, bundle.component_id).await?;
+
+    let mut mismatches = json_mismatches(
+        &serde_json::to_value(view)?,
+        &json!({
+            "status": "error",
+            "message": "$any",
+            "payload": { "id": "$id", "tags": ["one"] },
+            "lastSynced": "$timestamp",
+        }),
+    );
+    mismatches.sort();
+    assert_eq!(
+        vec![
+            "$.payload.id: expected an id, found \"i-1234\"".to_string(),
+            "$.payload.tags: expected 1 items, found 2".to_string(),
+            "$.status: expected \"error\", found \"ok\"".to_string(),
+        ], // expected
+        mismatches, // actual
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn resource_refreshed_ws_event(ctx: &mut DalContext) -> Result<()> {
+    let bundle = create_component_for_new_schema(ctx, "swifty").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    let mut capture = WsEventCapture::subscribe(ctx).await?;
+
+    let component = Component::get_by_id(ctx, bundle.component_id).await?;
+    let summary = component
+        .into_frontend_type(ctx, None, ChangeStatus::Unmodified, &mut HashMap::new())
+        .await?;
+
+    // Published immediately
+    WsEvent::resource_refreshed(ctx, summary.clone())
+        .await?
+        .publish_immediately(ctx)
+        .await?;
+    let event = capture
+        .expect_event_of_kind("ResourceRefreshed", Duration::from_secs(10))
+        .await?;
+    assert_eq!(
+        json!(ctx.change_set_id()), // expected
+        event["change_set_id"],     // actual
+    );
+
+    // Published when the transaction commits
+    WsEvent::resource_refreshed(ctx, summary)
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+    capture.assert_no_event("ResourceRefreshed").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    let event = capture
+        .expect_event(
+            |event| event["payload"]["data"]["component"]["id"] == json!(bundle.component_id),
+            Duration::from_secs(10),
+        )
+        .await?;
+    assert_eq!(
+        json!(ctx.change_set_id()), // expected
+        event["change_set_id"],     // actual
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn resource_inventory(ctx: &mut DalContext) -> Result<()> {
+    let beta = create_component_for_new_schema(ctx, "swifty").await?;
+    beta.component(ctx).await?.set_name(ctx, "beta").await?;
+    beta.attach_resource(
+        ctx,
+        "i-5678",
+        json!({ "id": "i-5678", "tags": ["one"], "note": "says \"hi\", twice\nthen leaves" }),
+    )
+    .await?;
+    let alpha = create_component_for_new_schema(ctx, "swifty").await?;
+    alpha.component(ctx).await?.set_name(ctx, "alpha").await?;
+    alpha
+        .attach_resource(ctx, "i-1234", json!({ "id": "i-1234", "size": 2 }))
+        .await?;
+    // Components without a resource are left out
+    create_component_for_new_schema(ctx, "swifty").await?;
+
+    let fields = vec![
+        "/id".to_string(),
+        "/tags/0".to_string(),
+        "/note".to_string(),
+    ];
+    // Entries come in component id order, so in the order they were created.
+    let entries = ResourceInventoryEntry::list(ctx, None, &fields).await?;
+    assert_eq!(
+        vec![
+            (
+                "beta".to_string(),
+                Some("i-5678".to_string()),
+                vec![
+                    json!("i-5678"),
+                    json!("one"),
+                    json!("says \"hi\", twice\nthen leaves"),
+                ],
+            ),
+            (
+                "alpha".to_string(),
+                Some("i-1234".to_string()),
+                vec![json!("i-1234"), json!(null), json!(null)],
+            ),
+        ], // expected
+        entries
+            .iter()
+            .map(|entry| (
+                entry.component_name.clone(),
+                entry.resource_id.clone(),
+                fields
+                    .iter()
+                    .map(|field| entry.fields[field].clone())
+                    .collect::<Vec<_>>(),
+            ))
+            .collect::<Vec<_>>(), // actual
+    );
+
+    let csv = ResourceInventoryEntry::to_csv(&entries, &fields);
+    assert_eq!(
+        vec![
+            "componentId,componentName,schemaName,resourceId,status,lastSynced,/id,/tags/0,/note"
+                .to_string(),
+            format!(
+                "{},beta,swifty,i-5678,ok,{},i-5678,one,\"says \"\"hi\"\", twice\nthen leaves\"",
+                beta.component_id,
+                entries[0].last_synced.to_rfc3339(),
+            ),
+            format!(
+                "{},alpha,swifty,i-1234,ok,{},i-1234,,",
+                alpha.component_id,
+                entries[1].last_synced.to_rfc3339(),
+            ),
+        ], // expected
+        csv.split_terminator("\r\n").collect::<Vec<_>>(), // actual
+    );
+
+    Ok(())
+}
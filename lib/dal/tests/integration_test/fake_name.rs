@@ -0,0 +1,35 @@
+use dal_test::{
+    helpers::{
+        FakeNameGenerator,
+        generate_fake_name_with_prefix,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn same_seed_produces_same_sequence() {
+    let first_run: Vec<String> = {
+        let mut generator = FakeNameGenerator::new(42);
+        (0..10).map(|_| generator.next_name()).collect()
+    };
+    let second_run: Vec<String> = {
+        let mut generator = FakeNameGenerator::new(42);
+        (0..10).map(|_| generator.next_name()).collect()
+    };
+    assert_eq!(
+        first_run,  // expected
+        second_run, // actual
+    );
+
+    let mut other_generator = FakeNameGenerator::new(43);
+    let other_run: Vec<String> = (0..10).map(|_| other_generator.next_name()).collect();
+    assert_ne!(first_run, other_run);
+}
+
+#[test]
+async fn prefixed_names() {
+    let name = generate_fake_name_with_prefix("fixture").expect("could not generate fake name");
+    assert!(name.starts_with("fixture-"));
+    assert!(name.len() > "fixture-".len());
+}
@@ -14,7 +14,9 @@ use pretty_assertions_sorted::assert_eq;
 
 mod argument;
 mod authoring;
+mod conflict;
 mod debug;
+mod execution_budget;
 
 #[test]
 async fn summary(ctx: &mut DalContext) {
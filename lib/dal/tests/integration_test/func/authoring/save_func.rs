@@ -2,9 +2,13 @@ use dal::{
     DalContext,
     Func,
     FuncId,
-    func::authoring::FuncAuthoringClient,
+    func::authoring::{
+        FuncAuthoringClient,
+        FuncCodeWarning,
+    },
 };
 use dal_test::{
+    Result,
     helpers::ChangeSetTestHelpers,
     test,
 };
@@ -35,6 +39,59 @@ async fn qualification(ctx: &mut DalContext) {
         save_func_setup(ctx, "test:qualificationDummySecretStringIsTodd").await;
 }
 
+#[test]
+async fn save_code_and_validate(ctx: &mut DalContext) -> Result<()> {
+    let old_func_id = Func::find_id_by_name(ctx, "test:createActionStarfield")
+        .await?
+        .expect("no func found");
+    let func_id = FuncAuthoringClient::create_unlocked_func_copy(ctx, old_func_id, None)
+        .await?
+        .id;
+    let handler = Func::get_by_id(ctx, func_id)
+        .await?
+        .handler
+        .expect("func has no handler");
+
+    // Code that defines the handler saves without warnings.
+    let valid_code = format!(
+        "async function {handler}(component: Input): Promise<Output> {{ return {{ status: 'ok' }}; }}"
+    );
+    let warnings = FuncAuthoringClient::save_code_and_validate(ctx, func_id, &valid_code).await?;
+    assert_eq!(
+        Vec::<FuncCodeWarning>::new(), // expected
+        warnings                       // actual
+    );
+
+    // Code that doesn't define the handler still saves, but is flagged. A longer identifier
+    // containing the handler's name doesn't count.
+    let invalid_code = format!(
+        "async function {handler}Renamed(component: Input): Promise<Output> {{ return {{ status: 'ok' }}; }}"
+    );
+    let warnings = FuncAuthoringClient::save_code_and_validate(ctx, func_id, &invalid_code).await?;
+    assert_eq!(
+        vec![FuncCodeWarning::HandlerNotFound {
+            handler: handler.clone()
+        }], // expected
+        warnings // actual
+    );
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    assert_eq!(
+        Some(invalid_code.as_str()), // expected
+        Func::get_by_id(ctx, func_id)
+            .await?
+            .code_plaintext()?
+            .as_deref()  // actual
+    );
+
+    let warnings = FuncAuthoringClient::save_code_and_validate(ctx, func_id, "  ").await?;
+    assert_eq!(
+        vec![FuncCodeWarning::EmptyCode], // expected
+        warnings                          // actual
+    );
+
+    Ok(())
+}
+
 // Sets up the tests within the module. Find the func to be saved by name and then save it
 // immediately when found. This is the basic "does it work in place" check.
 pub async fn save_func_setup(
@@ -0,0 +1,100 @@
+use dal::{
+    DalContext,
+    Func,
+    FuncBackendKind,
+    FuncBackendResponseType,
+    change_set::summary::ChangeSetSummary,
+    func::{
+        authoring::FuncAuthoringClient,
+        conflict::{
+            FuncConflict,
+            FuncConflictStatus,
+        },
+    },
+};
+use dal_test::{
+    Result,
+    helpers::{
+        ChangeSetTestHarness,
+        ChangeSetTestHelpers,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn detect_conflicts(ctx: &mut DalContext) -> Result<()> {
+    // Funcs without bindings can be edited in any change set, even once locked on HEAD.
+    let func_id = Func::new(
+        ctx,
+        "conflicted",
+        None::<String>,
+        None::<String>,
+        None::<String>,
+        false,
+        false,
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::Boolean,
+        Some("main"),
+        None::<String>,
+        false,
+    )
+    .await?
+    .id;
+    FuncAuthoringClient::save_code(ctx, func_id, "function main() { return true; }").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx).await?;
+
+    // A change set that hasn't edited the func has nothing to conflict with.
+    let first_change_set_id = ChangeSetTestHarness::fork(ctx).await?;
+    assert_eq!(
+        FuncConflict {
+            func_id,
+            status: FuncConflictStatus::NoConflict,
+            differing_fields: Vec::new(),
+        }, // expected
+        Func::detect_conflicts(ctx, func_id).await? // actual
+    );
+
+    // Once it edits the func, applying it would move HEAD forward.
+    FuncAuthoringClient::save_code(ctx, func_id, "function main() { return false; }").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    assert_eq!(
+        FuncConflict {
+            func_id,
+            status: FuncConflictStatus::FastForward,
+            differing_fields: Vec::new(),
+        }, // expected
+        Func::detect_conflicts(ctx, func_id).await? // actual
+    );
+    assert_eq!(0, ChangeSetSummary::assemble(ctx).await?.conflicting_funcs);
+
+    // Another change set edits the func too and is applied first.
+    let second_change_set_id = ChangeSetTestHarness::fork(ctx).await?;
+    FuncAuthoringClient::save_code(ctx, func_id, "function main() { return 1 > 0; }").await?;
+    ChangeSetTestHarness::apply(ctx, second_change_set_id).await?;
+
+    // Now applying the first change set would overwrite the second one's edits.
+    ChangeSetTestHarness::switch(ctx, first_change_set_id).await?;
+    assert_eq!(
+        FuncConflict {
+            func_id,
+            status: FuncConflictStatus::Conflicting,
+            differing_fields: vec!["code".to_string()],
+        }, // expected
+        Func::detect_conflicts(ctx, func_id).await? // actual
+    );
+
+    // The conflict shows up in the change set's summary too.
+    assert_eq!(
+        vec![func_id], // expected
+        Func::list_conflicts(ctx)
+            .await?
+            .into_iter()
+            .map(|conflict| conflict.func_id)
+            .collect::<Vec<_>>(), // actual
+    );
+    assert_eq!(1, ChangeSetSummary::assemble(ctx).await?.conflicting_funcs);
+
+    Ok(())
+}
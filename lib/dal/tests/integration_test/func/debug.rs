@@ -3,22 +3,30 @@ use std::time::Duration;
 use dal::{
     DalContext,
     Func,
+    Secret,
     func::{
         debug::{
+            DebugFuncError,
             DebugFuncJobState,
             DebugFuncJobStateRow,
             dispatch_debug_func,
         },
-        runner::FuncRunner,
+        runner::{
+            FuncRunner,
+            FuncRunnerError,
+        },
     },
 };
 use dal_test::{
+    WorkspaceSignup,
     helpers::{
         ChangeSetTestHelpers,
         create_component_for_default_schema_name_in_default_view,
+        encrypt_message,
     },
     test,
 };
+use si_events::CasValue;
 use veritech_client::ComponentKind;
 
 #[test]
@@ -46,9 +54,10 @@ async fn test_execute_debug_func(ctx: &mut DalContext) {
         "id": component.id(),
     }  });
 
-    let (_func_run_id, func_run) = FuncRunner::run_debug(ctx, debug_func, component.id(), args)
-        .await
-        .expect("run debug func");
+    let (_func_run_id, func_run) =
+        FuncRunner::run_debug(ctx, debug_func, component.id(), args, &[])
+            .await
+            .expect("run debug func");
 
     let mut run_value = func_run
         .await
@@ -79,9 +88,15 @@ async fn test_debug_func_job(ctx: &mut DalContext) {
        "ged": "sparrowhawk",
     });
 
-    let job_state_id = dispatch_debug_func(ctx, component.id(), debug_func, Some(debug_input))
-        .await
-        .expect("dispatch debug func");
+    let job_state_id = dispatch_debug_func(
+        ctx,
+        component.id(),
+        debug_func,
+        Some(debug_input),
+        Vec::new(),
+    )
+    .await
+    .expect("dispatch debug func");
 
     ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
         .await
@@ -117,3 +132,112 @@ async fn test_debug_func_job(ctx: &mut DalContext) {
         job_state.result.expect("should have a value")["output"]
     );
 }
+
+#[test]
+async fn test_execute_debug_func_with_named_secret(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let code = r#"function debug({ component, debugInput }) {
+        return { secret: requestStorage.getItem('dummySecretString') };
+    }"#;
+
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "debug it")
+            .await
+            .expect("make debug component");
+    Secret::new(
+        ctx,
+        "aws-prod",
+        "dummy".to_string(),
+        None,
+        &encrypt_message(ctx, nw.key_pair.pk(), &serde_json::json![{"value": "todd"}])
+            .await
+            .expect("could not encrypt message"),
+        nw.key_pair.pk(),
+        Default::default(),
+        Default::default(),
+    )
+    .await
+    .expect("cannot create secret");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("commit and update snapshot");
+
+    let properties = component.view(ctx).await.expect("get component view");
+    let args = serde_json::json!({ "debug_input": null, "component": {
+        "kind": ComponentKind::Standard,
+        "properties": properties,
+        "id": component.id(),
+    }});
+
+    let (func_run_id, func_run) = FuncRunner::run_debug(
+        ctx,
+        Func::new_debug("debug_test", code, "debug"),
+        component.id(),
+        args.clone(),
+        &["aws-prod".to_string()],
+    )
+    .await
+    .expect("run debug func");
+    let mut run_value = func_run
+        .await
+        .expect("get run value")
+        .expect("is sucessful");
+    let value = run_value.take_value().expect("should have a value");
+    assert_eq!(
+        serde_json::json!({ "secret": "todd" }), // expected
+        value["output"]                          // actual
+    );
+
+    // The secret is only available through the before funcs, never in the persisted args.
+    let persisted_func_run = ctx
+        .layer_db()
+        .func_run()
+        .try_read(func_run_id)
+        .await
+        .expect("read func run");
+    let persisted_args: serde_json::Value = ctx
+        .layer_db()
+        .cas()
+        .try_read_as::<CasValue>(&persisted_func_run.function_args_cas_address())
+        .await
+        .expect("read func run args")
+        .expect("func run args should be stored")
+        .into();
+    assert_eq!(
+        args["component"]["id"],           // expected
+        persisted_args["component"]["id"], // actual
+    );
+    assert!(!persisted_args.to_string().contains("todd"));
+
+    // Missing secrets are reported by name when resolving them, before anything is run.
+    let err = FuncRunner::run_debug(
+        ctx,
+        Func::new_debug("debug_test", code, "debug"),
+        component.id(),
+        args,
+        &["aws-prod".to_string(), "aws-staging".to_string()],
+    )
+    .await
+    .expect_err("should not run with a missing secret");
+    match err {
+        FuncRunnerError::NamedSecretsNotFound(names) => assert_eq!(
+            vec!["aws-staging".to_string()], // expected
+            names                            // actual
+        ),
+        err => panic!("unexpected error: {err}"),
+    }
+
+    let err = dispatch_debug_func(
+        ctx,
+        component.id(),
+        Func::new_debug("debug_test", code, "debug"),
+        None::<serde_json::Value>,
+        vec!["aws-staging".to_string()],
+    )
+    .await
+    .expect_err("should not dispatch with a missing secret");
+    assert!(matches!(
+        err,
+        DebugFuncError::FuncRunner(err)
+            if matches!(*err, FuncRunnerError::NamedSecretsNotFound(_))
+    ));
+}
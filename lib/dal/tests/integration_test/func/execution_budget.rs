@@ -0,0 +1,287 @@
+use std::time::Duration;
+
+use chrono::{
+    SubsecRound,
+    Utc,
+};
+use dal::{
+    Component,
+    ComponentId,
+    DalContext,
+    Func,
+    func::{
+        execution_budget::{
+            ExecutionBudget,
+            ExecutionBudgetError,
+            QueuedExecution,
+        },
+        runner::{
+            FuncRunner,
+            FuncRunnerError,
+            FuncRunnerValueChannel,
+        },
+    },
+};
+use dal_test::{
+    helpers::{
+        ChangeSetTestHelpers,
+        create_component_for_default_schema_name_in_default_view,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use si_data_pg::PgPool;
+use si_events::{
+    FuncRunId,
+    WorkspacePk,
+};
+use veritech_client::ComponentKind;
+
+#[test]
+async fn budget_serializes_executions_per_workspace() {
+    let budget = ExecutionBudget::new(1, Duration::from_secs(10));
+    let workspace_pk = WorkspacePk::new();
+
+    let first = budget
+        .acquire(workspace_pk)
+        .await
+        .expect("could not acquire first permit");
+    let second = tokio::spawn({
+        let budget = budget.clone();
+        async move { budget.acquire(workspace_pk).await }
+    });
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!second.is_finished());
+
+    // Other workspaces have budgets of their own.
+    budget
+        .acquire(WorkspacePk::new())
+        .await
+        .expect("could not acquire permit for another workspace");
+
+    drop(first);
+    tokio::time::timeout(Duration::from_secs(5), second)
+        .await
+        .expect("second permit was never acquired")
+        .expect("task panicked")
+        .expect("could not acquire second permit");
+}
+
+#[test]
+async fn budget_times_out_and_can_be_raised() {
+    let budget = ExecutionBudget::new(1, Duration::from_millis(100));
+    let workspace_pk = WorkspacePk::new();
+
+    let _first = budget
+        .acquire(workspace_pk)
+        .await
+        .expect("could not acquire first permit");
+    let err = budget
+        .acquire(workspace_pk)
+        .await
+        .expect_err("second permit should not be available");
+    assert!(matches!(
+        err,
+        ExecutionBudgetError::Timeout { permits: 1, .. }
+    ));
+
+    budget.set_permits(2);
+    assert_eq!(
+        2,                // expected
+        budget.permits()  // actual
+    );
+    budget
+        .acquire(workspace_pk)
+        .await
+        .expect("could not acquire permit after raising the budget");
+}
+
+#[test]
+async fn budget_lists_queued_executions(ctx: &DalContext) {
+    let pg_pool = ctx.pg_pool();
+    let budget = ExecutionBudget::new(1, Duration::from_secs(10));
+    let workspace_pk = WorkspacePk::new();
+
+    let first = budget
+        .acquire_queued(pg_pool, workspace_pk, queued_execution("first"))
+        .await
+        .expect("could not acquire first permit");
+    // Executions are only listed while they wait.
+    assert_queued_len(&budget, pg_pool, workspace_pk, 0).await;
+
+    let second_execution = queued_execution("second");
+    let second = tokio::spawn({
+        let budget = budget.clone();
+        let pg_pool = pg_pool.clone();
+        let second_execution = second_execution.clone();
+        async move {
+            budget
+                .acquire_queued(&pg_pool, workspace_pk, second_execution)
+                .await
+        }
+    });
+    assert_queued_len(&budget, pg_pool, workspace_pk, 1).await;
+    assert_eq!(
+        vec![second_execution], // expected
+        budget
+            .queued(pg_pool, workspace_pk)
+            .await
+            .expect("could not list queued executions"), // actual
+    );
+    assert_queued_len(&budget, pg_pool, WorkspacePk::new(), 0).await;
+
+    // Another server's budget sees the same queue.
+    assert_queued_len(&ExecutionBudget::default(), pg_pool, workspace_pk, 1).await;
+
+    // Cancelled executions leave the queue as well.
+    let third = tokio::spawn({
+        let budget = budget.clone();
+        let pg_pool = pg_pool.clone();
+        async move {
+            budget
+                .acquire_queued(&pg_pool, workspace_pk, queued_execution("third"))
+                .await
+        }
+    });
+    assert_queued_len(&budget, pg_pool, workspace_pk, 2).await;
+    third.abort();
+    assert!(
+        third
+            .await
+            .expect_err("third acquire should be cancelled")
+            .is_cancelled()
+    );
+    assert_queued_len(&budget, pg_pool, workspace_pk, 1).await;
+
+    drop(first);
+    let _second = tokio::time::timeout(Duration::from_secs(5), second)
+        .await
+        .expect("second permit was never acquired")
+        .expect("task panicked")
+        .expect("could not acquire second permit");
+    assert_queued_len(&budget, pg_pool, workspace_pk, 0).await;
+}
+
+#[test]
+async fn func_runs_wait_for_the_workspace_budget(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "budgeted")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot");
+
+    let budget = ExecutionBudget::new(1, Duration::from_secs(2));
+    let budget_ctx = ctx
+        .services_context()
+        .clone()
+        .with_execution_budget(budget.clone())
+        .into_builder(false)
+        .build(ctx.to_request_context())
+        .await
+        .expect("could not build context");
+
+    // While the workspace's only execution is taken, funcs wait for it...
+    let held = budget
+        .acquire(ctx.events_tenancy().workspace_pk)
+        .await
+        .expect("could not acquire permit");
+    let mut waiting = run_debug_func(&budget_ctx, component.id()).await;
+    assert_queued_len(&budget, ctx.pg_pool(), ctx.events_tenancy().workspace_pk, 1).await;
+    assert!(waiting.try_recv().is_err());
+    assert_eq!(
+        vec!["budgeted_debug".to_string()], // expected
+        FuncRunner::queued_executions(&budget_ctx)
+            .await
+            .expect("could not list queued executions")
+            .into_iter()
+            .map(|execution| execution.function_name)
+            .collect::<Vec<_>>(), // actual
+    );
+
+    // ...and run once it is released.
+    drop(held);
+    let value = waiting
+        .await
+        .expect("could not receive result")
+        .expect("func run failed")
+        .take_value()
+        .expect("no value");
+    assert_eq!(
+        "budgeted",              // expected
+        value["output"]["name"]  // actual
+    );
+    assert_queued_len(&budget, ctx.pg_pool(), ctx.events_tenancy().workspace_pk, 0).await;
+
+    // Funcs give up if they wait past the deadline.
+    let _held = budget
+        .acquire(ctx.events_tenancy().workspace_pk)
+        .await
+        .expect("could not acquire permit");
+    let result = run_debug_func(&budget_ctx, component.id())
+        .await
+        .await
+        .expect("could not receive result");
+    assert!(matches!(
+        result,
+        Err(FuncRunnerError::ExecutionBudget(
+            ExecutionBudgetError::Timeout { .. }
+        ))
+    ));
+}
+
+async fn run_debug_func(ctx: &DalContext, component_id: ComponentId) -> FuncRunnerValueChannel {
+    let code = r#"function debug({ component }) {
+        return { name: component.properties?.domain?.name };
+    }"#;
+    let debug_func = Func::new_debug("budgeted_debug", code, "debug");
+    let properties = Component::view_by_id(ctx, component_id)
+        .await
+        .expect("could not get component view");
+    let args = serde_json::json!({ "debug_input": null, "component": {
+        "kind": ComponentKind::Standard,
+        "properties": properties,
+        "id": component_id,
+    }});
+
+    let (_func_run_id, result) = FuncRunner::run_debug(ctx, debug_func, component_id, args, &[])
+        .await
+        .expect("could not run debug func");
+    result
+}
+
+fn queued_execution(function_name: &str) -> QueuedExecution {
+    QueuedExecution {
+        func_run_id: FuncRunId::new(),
+        function_name: function_name.to_owned(),
+        action_id: None,
+        component_id: None,
+        // Postgres keeps microseconds
+        queued_at: Utc::now().trunc_subsecs(6),
+    }
+}
+
+/// Waits for the workspace to have the given number of queued executions, as they are added and
+/// removed in the background.
+async fn assert_queued_len(
+    budget: &ExecutionBudget,
+    pg_pool: &PgPool,
+    workspace_pk: WorkspacePk,
+    expected: usize,
+) {
+    let mut actual = 0;
+    for _ in 0..50 {
+        actual = budget
+            .queued(pg_pool, workspace_pk)
+            .await
+            .expect("could not list queued executions")
+            .len();
+        if actual == expected {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(expected, actual, "queued executions");
+}
@@ -0,0 +1,106 @@
+use dal::{
+    DalContext,
+    health::{
+        HealthDependency,
+        HealthReport,
+    },
+};
+use dal_test::{
+    module_index_stub::ModuleIndexStub,
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+
+fn healthy(report: &HealthReport, dependency: HealthDependency) -> bool {
+    report
+        .dependency(dependency)
+        .expect("dependency missing from report")
+        .healthy
+}
+
+#[test]
+async fn health_report_for_healthy_services(ctx: &DalContext) {
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    let ctx = stub.ctx(ctx);
+
+    let report = ctx.services_context().health_report().await;
+
+    assert!(report.ready);
+    assert_eq!(
+        vec![
+            HealthDependency::ModuleIndex,
+            HealthDependency::Nats,
+            HealthDependency::Pg,
+            HealthDependency::Veritech,
+        ], // expected
+        report
+            .dependencies
+            .iter()
+            .map(|dependency| dependency.dependency)
+            .collect::<Vec<_>>(), // actual
+    );
+    for dependency in &report.dependencies {
+        assert!(dependency.healthy, "{dependency:?}");
+        assert!(dependency.latency_ms.is_some(), "{dependency:?}");
+        assert_eq!(
+            None,             // expected
+            dependency.error, // actual
+        );
+        assert_eq!(
+            dependency.dependency.critical_by_default(), // expected
+            dependency.critical,                         // actual
+        );
+    }
+}
+
+#[test]
+async fn health_report_with_module_index_down(ctx: &DalContext) {
+    let stub = ModuleIndexStub::start().expect("could not start module index stub");
+    let ctx = stub.ctx(ctx);
+    stub.fail_next_requests(1);
+
+    let report = ctx.services_context().health_report().await;
+
+    // The module index isn't critical by default, so the report is still ready.
+    assert!(report.ready);
+    let module_index = report
+        .dependency(HealthDependency::ModuleIndex)
+        .expect("module index missing from report");
+    assert!(!module_index.healthy);
+    assert!(!module_index.critical);
+    assert_eq!(
+        None,                    // expected
+        module_index.latency_ms, // actual
+    );
+    assert!(
+        module_index
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("500")),
+        "{module_index:?}"
+    );
+    assert!(healthy(&report, HealthDependency::Nats));
+    assert!(healthy(&report, HealthDependency::Pg));
+    assert!(healthy(&report, HealthDependency::Veritech));
+
+    // Once it is critical, the same failure makes the report unready.
+    let services_context = ctx
+        .services_context()
+        .with_critical_health_dependencies([HealthDependency::ModuleIndex, HealthDependency::Pg]);
+    stub.fail_next_requests(1);
+
+    let report = services_context.health_report().await;
+
+    assert!(!report.ready);
+    let module_index = report
+        .dependency(HealthDependency::ModuleIndex)
+        .expect("module index missing from report");
+    assert!(!module_index.healthy);
+    assert!(module_index.critical);
+    assert!(
+        !report
+            .dependency(HealthDependency::Veritech)
+            .expect("veritech missing from report")
+            .critical
+    );
+}
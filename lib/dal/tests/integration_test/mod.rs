@@ -4,13 +4,16 @@ mod attribute_prototype;
 mod attribute_value;
 mod attributes;
 mod audit_logging;
+mod cached_module;
 mod change_set;
 mod component;
 mod cycle_check_guard;
 mod dependent_values_update;
 mod deserialize;
 mod diagram;
+mod fake_name;
 mod func;
+mod health;
 mod input_sources;
 mod management;
 mod materialized_views;
@@ -30,3 +33,6 @@ mod split_snapshot;
 mod validations;
 mod view;
 mod workspace;
+mod workspace_webhook;
+mod ws_event;
+mod ws_event_log;
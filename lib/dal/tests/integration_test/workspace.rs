@@ -1,10 +1,20 @@
+use chrono::{
+    Duration,
+    Utc,
+};
 use dal::{
     DalContext,
     Workspace,
+    WorkspaceError,
     change_set::view::OpenChangeSetsView,
     diagram::Diagram,
+    workspace::{
+        SUPPORTED_WORKSPACE_EXPORT_FORMAT_VERSIONS,
+        WORKSPACE_EXPORT_FORMAT_VERSION,
+    },
 };
 use dal_test::{
+    WorkspaceSignup,
     helpers::{
         ChangeSetTestHelpers,
         PropEditorTestView,
@@ -13,6 +23,8 @@ use dal_test::{
     test,
 };
 use pretty_assertions_sorted::assert_eq;
+use si_db::User;
+use si_pkg::WorkspaceExport;
 
 #[test]
 async fn export_import_loop(ctx: &mut DalContext) {
@@ -50,6 +62,18 @@ async fn export_import_loop(ctx: &mut DalContext) {
         .await
         .expect("commit and update snapshot to visibility");
 
+    // The manifest lists every change set snapshot plus the content store values.
+    let export_content = workspace_export.clone().into_latest();
+    let manifest = export_content.manifest.expect("export has a manifest");
+    assert_eq!(
+        env!("CARGO_PKG_VERSION"), // expected
+        manifest.si_version,       // actual
+    );
+    assert_eq!(
+        export_content.change_sets.values().flatten().count() + 1, // expected
+        manifest.artifacts.len()                                   // actual
+    );
+
     ChangeSetTestHelpers::abandon_change_set(ctx)
         .await
         .expect("abandon change set");
@@ -116,3 +140,249 @@ async fn export_import_loop(ctx: &mut DalContext) {
             .expect("get value for domain/name")
     );
 }
+
+#[test]
+async fn validate_import_does_not_import(ctx: &mut DalContext) {
+    let change_set_name = "exported".to_string();
+    ChangeSetTestHelpers::fork_from_head_change_set_with_name(ctx, &change_set_name)
+        .await
+        .expect("fork change set");
+    create_component_for_default_schema_name_in_default_view(ctx, "pirate", "Long John Silver")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("commit and update snapshot to visibility");
+
+    let workspace_pk = ctx.tenancy().workspace_pk_opt().expect("find workspace pk");
+    let workspace = Workspace::get_by_pk(ctx, workspace_pk)
+        .await
+        .expect("execute find workspace");
+    let workspace_export = workspace
+        .generate_export_data(ctx, "0.0")
+        .await
+        .expect("export workspace");
+
+    let open_change_sets_before = OpenChangeSetsView::assemble(ctx)
+        .await
+        .expect("assemble view")
+        .change_sets
+        .len();
+
+    let report = Workspace::validate_import(ctx, workspace_export)
+        .await
+        .expect("validate import");
+
+    assert!(report.is_importable());
+    assert_eq!(
+        "0.0",          // expected
+        report.version, // actual
+    );
+    assert!(report.change_sets.contains(&change_set_name));
+    assert_eq!(
+        report.change_sets.len(),           // expected
+        report.abandoned_change_sets.len()  // actual
+    );
+
+    // Nothing was abandoned or created
+    assert_eq!(
+        open_change_sets_before, // expected
+        OpenChangeSetsView::assemble(ctx) // actual
+            .await
+            .expect("assemble view")
+            .change_sets
+            .len()
+    );
+}
+
+#[test]
+async fn import_rejects_corrupted_export(ctx: &mut DalContext) {
+    ChangeSetTestHelpers::fork_from_head_change_set_with_name(ctx, "corrupted")
+        .await
+        .expect("fork change set");
+    create_component_for_default_schema_name_in_default_view(ctx, "pirate", "Davy Jones")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("commit and update snapshot to visibility");
+
+    let workspace_pk = ctx.tenancy().workspace_pk_opt().expect("find workspace pk");
+    let mut workspace = Workspace::get_by_pk(ctx, workspace_pk)
+        .await
+        .expect("execute find workspace");
+    let mut export = workspace
+        .generate_export_data(ctx, "0.0")
+        .await
+        .expect("export workspace")
+        .into_latest();
+
+    // Flip a byte in the content store values, as a partially corrupted upload would.
+    let last = export.content_store_values.len() - 1;
+    export.content_store_values[last] ^= 0xff;
+    let workspace_export = WorkspaceExport::new(export);
+
+    let report = Workspace::validate_import(ctx, workspace_export.clone())
+        .await
+        .expect("validate import");
+    assert!(!report.is_importable());
+    assert_eq!(
+        vec!["content store values".to_string()], // expected
+        report.corrupted_artifacts                // actual
+    );
+
+    let open_change_sets_before = OpenChangeSetsView::assemble(ctx)
+        .await
+        .expect("assemble view")
+        .change_sets
+        .len();
+    match workspace.import(ctx, workspace_export).await {
+        Err(WorkspaceError::CorruptedExportArtifact(artifact)) => {
+            assert_eq!(
+                "content store values", // expected
+                artifact                // actual
+            );
+        }
+        other => panic!("unexpected import result: {other:?}"),
+    }
+
+    // Nothing was abandoned before the corruption was found
+    assert_eq!(
+        open_change_sets_before, // expected
+        OpenChangeSetsView::assemble(ctx) // actual
+            .await
+            .expect("assemble view")
+            .change_sets
+            .len()
+    );
+}
+
+#[test]
+async fn import_checks_export_format_version(ctx: &mut DalContext) {
+    let change_set_name = "versioned".to_string();
+    ChangeSetTestHelpers::fork_from_head_change_set_with_name(ctx, &change_set_name)
+        .await
+        .expect("fork change set");
+    create_component_for_default_schema_name_in_default_view(ctx, "pirate", "Anne Bonny")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("commit and update snapshot to visibility");
+
+    let workspace_pk = ctx.tenancy().workspace_pk_opt().expect("find workspace pk");
+    let mut workspace = Workspace::get_by_pk(ctx, workspace_pk)
+        .await
+        .expect("execute find workspace");
+    let export = workspace
+        .generate_export_data(ctx, "0.0")
+        .await
+        .expect("export workspace")
+        .into_latest();
+
+    // Exports from this version are importable.
+    assert_eq!(
+        Some(WORKSPACE_EXPORT_FORMAT_VERSION), // expected
+        export
+            .manifest
+            .as_ref()
+            .map(|manifest| manifest.format_version)  // actual
+    );
+    assert!(
+        Workspace::validate_import(ctx, WorkspaceExport::new(export.clone()))
+            .await
+            .expect("validate import")
+            .is_importable()
+    );
+
+    // Exports from a future version are refused before anything is done.
+    let mut future_export = export.clone();
+    if let Some(manifest) = future_export.manifest.as_mut() {
+        manifest.format_version = WORKSPACE_EXPORT_FORMAT_VERSION + 1;
+    }
+    let is_incompatible = |result: Result<_, WorkspaceError>| {
+        matches!(
+            result,
+            Err(WorkspaceError::IncompatibleExportVersion { found, supported })
+                if found == WORKSPACE_EXPORT_FORMAT_VERSION + 1
+                    && supported == SUPPORTED_WORKSPACE_EXPORT_FORMAT_VERSIONS
+        )
+    };
+    assert!(is_incompatible(
+        Workspace::validate_import(ctx, WorkspaceExport::new(future_export.clone()))
+            .await
+            .map(|_| ())
+    ));
+    assert!(is_incompatible(Workspace::check_export_format_version(
+        &WorkspaceExport::new(future_export.clone())
+    )));
+    let open_change_sets_before = OpenChangeSetsView::assemble(ctx)
+        .await
+        .expect("assemble view")
+        .change_sets
+        .len();
+    assert!(is_incompatible(
+        workspace
+            .import(ctx, WorkspaceExport::new(future_export))
+            .await
+    ));
+    assert_eq!(
+        open_change_sets_before, // expected
+        OpenChangeSetsView::assemble(ctx) // actual
+            .await
+            .expect("assemble view")
+            .change_sets
+            .len()
+    );
+
+    // Legacy exports without a manifest are still imported, on a best-effort basis.
+    let mut legacy_export = export;
+    legacy_export.manifest = None;
+    Workspace::check_export_format_version(&WorkspaceExport::new(legacy_export.clone()))
+        .expect("legacy exports are a supported format version");
+    workspace
+        .import(ctx, WorkspaceExport::new(legacy_export))
+        .await
+        .expect("import legacy export");
+    assert!(
+        OpenChangeSetsView::assemble(ctx)
+            .await
+            .expect("assemble view")
+            .change_sets
+            .iter()
+            .any(|change_set| change_set.name == change_set_name)
+    );
+}
+
+#[test]
+async fn list_dormant(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let workspace_pk = *nw.workspace.pk();
+    nw.user
+        .associate_workspace(ctx, workspace_pk)
+        .await
+        .expect("associate user with workspace");
+
+    let is_dormant = |workspaces: Vec<Workspace>| {
+        workspaces
+            .iter()
+            .any(|workspace| *workspace.pk() == workspace_pk)
+    };
+
+    // A workspace that has never been accessed is dormant
+    assert!(is_dormant(
+        Workspace::list_dormant(ctx, Utc::now())
+            .await
+            .expect("list dormant workspaces")
+    ));
+
+    let before_access = Utc::now() - Duration::minutes(1);
+    User::record_workspace_access(ctx, nw.user.pk(), workspace_pk)
+        .await
+        .expect("record workspace access");
+
+    assert!(!is_dormant(
+        Workspace::list_dormant(ctx, before_access)
+            .await
+            .expect("list dormant workspaces")
+    ));
+}
@@ -0,0 +1,269 @@
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use dal::{
+    ComponentId,
+    DalContext,
+    action::{
+        ActionId,
+        prototype::ActionKind,
+    },
+    workspace_webhook::{
+        self,
+        WEBHOOK_SIGNATURE_HEADER,
+        WebhookTargetPolicy,
+        WorkspaceWebhook,
+        WorkspaceWebhookDelivery,
+        WorkspaceWebhookError,
+        WorkspaceWebhookEvent,
+        WorkspaceWebhookEventKind,
+    },
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use si_events::FuncRunId;
+use tokio::{
+    io::{
+        AsyncReadExt,
+        AsyncWriteExt,
+    },
+    net::TcpListener,
+};
+
+/// A request received by [`start_endpoint`], as its signature header and body.
+type ReceivedRequest = (Option<String>, Vec<u8>);
+
+/// Serves one request per status code, in order, recording each request it receives.
+async fn start_endpoint(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<ReceivedRequest>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("could not bind endpoint");
+    let url = format!(
+        "http://{}/hook",
+        listener
+            .local_addr()
+            .expect("could not get endpoint address")
+    );
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let recorded = received.clone();
+    tokio::spawn(async move {
+        for status in statuses {
+            let (mut stream, _) = listener.accept().await.expect("could not accept request");
+
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            let (head_len, content_length) = loop {
+                let read = stream.read(&mut buf).await.expect("could not read request");
+                request.extend_from_slice(&buf[..read]);
+                if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                    let head = String::from_utf8_lossy(&request[..end]).to_lowercase();
+                    let content_length = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .and_then(|value| value.trim().parse::<usize>().ok())
+                        .unwrap_or_default();
+                    break (end + 4, content_length);
+                }
+            };
+            while request.len() < head_len + content_length {
+                let read = stream.read(&mut buf).await.expect("could not read request");
+                request.extend_from_slice(&buf[..read]);
+            }
+
+            let signature = String::from_utf8_lossy(&request[..head_len])
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case(WEBHOOK_SIGNATURE_HEADER)
+                        .then(|| value.trim().to_owned())
+                });
+            recorded
+                .lock()
+                .expect("could not lock received requests")
+                .push((signature, request[head_len..].to_vec()));
+
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 {status} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .expect("could not write response");
+        }
+    });
+
+    (url, received)
+}
+
+fn event(ctx: &DalContext) -> WorkspaceWebhookEvent {
+    WorkspaceWebhookEvent {
+        kind: WorkspaceWebhookEventKind::ActionFailed,
+        workspace_pk: ctx.workspace_pk().expect("could not get workspace pk"),
+        change_set_id: ctx.change_set_id(),
+        action_id: ActionId::new(),
+        action_kind: ActionKind::Create,
+        component_id: ComponentId::new(),
+        component_name: "swifty".to_string(),
+        func_name: "test:createActionSwifty".to_string(),
+        func_run_id: FuncRunId::new(),
+        occurred_at: chrono::Utc::now(),
+    }
+}
+
+#[test]
+async fn deliver_retries_and_signs(ctx: &DalContext) -> dal_test::Result<()> {
+    let (url, received) = start_endpoint(vec![500, 200]).await;
+    let webhook = WorkspaceWebhook::new(
+        ctx,
+        url,
+        "shh",
+        vec![WorkspaceWebhookEventKind::ActionFailed],
+    )
+    .await?;
+
+    let event = event(ctx);
+    assert!(
+        webhook
+            .deliver(ctx.pg_pool(), ctx.webhook_target_policy(), &event)
+            .await?
+    );
+
+    let received = received
+        .lock()
+        .expect("could not lock received requests")
+        .clone();
+    assert_eq!(2, received.len());
+    for (signature, body) in received {
+        assert_eq!(
+            Some(workspace_webhook::sign("shh", &body)), // expected
+            signature,                                   // actual
+        );
+        assert_eq!(
+            event,                                                   // expected
+            serde_json::from_slice::<WorkspaceWebhookEvent>(&body)?, // actual
+        );
+    }
+
+    let deliveries = WorkspaceWebhookDelivery::list_for_webhook(ctx, webhook.id()).await?;
+    assert_eq!(
+        vec![(1, Some(500)), (2, Some(200))], // expected
+        deliveries
+            .iter()
+            .map(|delivery| (delivery.attempt, delivery.status_code))
+            .collect::<Vec<_>>(), // actual
+    );
+    assert!(deliveries[0].error.is_some());
+    assert!(deliveries[1].error.is_none());
+
+    Ok(())
+}
+
+#[test]
+async fn create_update_and_delete(ctx: &DalContext) -> dal_test::Result<()> {
+    assert!(
+        WorkspaceWebhook::new(ctx, "not a url", "shh", vec![])
+            .await
+            .is_err()
+    );
+
+    let mut webhook = WorkspaceWebhook::new(
+        ctx,
+        "https://example.com/hook",
+        "shh",
+        vec![WorkspaceWebhookEventKind::ActionFailed],
+    )
+    .await?;
+    webhook
+        .update(
+            ctx,
+            None,
+            None,
+            Some(vec![WorkspaceWebhookEventKind::ResourceRefreshFailed]),
+            Some(false),
+        )
+        .await?;
+    assert_eq!("https://example.com/hook", webhook.url());
+    assert_eq!(
+        &[WorkspaceWebhookEventKind::ResourceRefreshFailed], // expected
+        webhook.event_kinds(),                               // actual
+    );
+    assert!(!webhook.enabled());
+    assert_eq!(vec![webhook.clone()], WorkspaceWebhook::list(ctx).await?);
+
+    WorkspaceWebhook::delete(ctx, webhook.id()).await?;
+    assert!(WorkspaceWebhook::list(ctx).await?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+async fn public_https_policy_refuses_insecure_and_private_targets(
+    ctx: &DalContext,
+) -> dal_test::Result<()> {
+    let policy = WebhookTargetPolicy::PublicHttps;
+    assert!(matches!(
+        policy.validate_url("http://example.com/hook"),
+        Err(WorkspaceWebhookError::InsecureUrl(_))
+    ));
+    assert!(policy.validate_url("https://example.com/hook").is_ok());
+
+    for url in [
+        "https://127.0.0.1/hook",
+        "https://localhost/hook",
+        "https://10.0.0.1/hook",
+        "https://192.168.1.1/hook",
+        "https://169.254.169.254/latest/meta-data",
+        "https://[::1]/hook",
+        "https://[fd00::1]/hook",
+        "https://[fe80::1]/hook",
+        "https://[::ffff:127.0.0.1]/hook",
+    ] {
+        assert!(
+            matches!(
+                policy.client_for(url).await,
+                Err(WorkspaceWebhookError::DisallowedAddress(..))
+            ),
+            "{url} should be refused"
+        );
+    }
+    assert!(
+        policy
+            .client_for("https://93.184.215.14/hook")
+            .await
+            .is_ok()
+    );
+
+    // A webhook pointed at a local endpoint is recorded as one failed attempt, and never reached.
+    let (url, received) = start_endpoint(vec![200]).await;
+    let webhook = WorkspaceWebhook::new(
+        ctx,
+        url,
+        "shh",
+        vec![WorkspaceWebhookEventKind::ActionFailed],
+    )
+    .await?;
+    assert!(!webhook.deliver(ctx.pg_pool(), policy, &event(ctx)).await?);
+    assert!(
+        received
+            .lock()
+            .expect("could not lock received requests")
+            .is_empty()
+    );
+
+    let deliveries = WorkspaceWebhookDelivery::list_for_webhook(ctx, webhook.id()).await?;
+    assert_eq!(
+        vec![(1, None)], // expected
+        deliveries
+            .iter()
+            .map(|delivery| (delivery.attempt, delivery.status_code))
+            .collect::<Vec<_>>(), // actual
+    );
+    assert!(deliveries[0].error.is_some());
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    time::Duration,
+};
+
+use dal::{
+    Component,
+    ComponentId,
+    DalContext,
+    WsEvent,
+    change_status::ChangeStatus,
+};
+use dal_test::{
+    Result,
+    helpers::{
+        ChangeSetTestHelpers,
+        create_component_for_default_schema_name_in_default_view,
+        ws_event::WsEventCapture,
+    },
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+use si_frontend_types::DiagramComponentView;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn diagram_view(ctx: &DalContext, component_id: ComponentId) -> Result<DiagramComponentView> {
+    Ok(Component::get_by_id(ctx, component_id)
+        .await?
+        .into_frontend_type(ctx, None, ChangeStatus::Unmodified, &mut HashMap::new())
+        .await?)
+}
+
+#[test]
+async fn identical_events_coalesced_on_commit(ctx: &mut DalContext) -> Result<()> {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "swifty").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    let view = diagram_view(ctx, component.id()).await?;
+    let mut capture = WsEventCapture::subscribe(ctx).await?;
+
+    for _ in 0..10 {
+        WsEvent::resource_refreshed(ctx, view.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+    }
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    capture
+        .expect_event_of_kind("ResourceRefreshed", TIMEOUT)
+        .await?;
+    capture.assert_no_event("ResourceRefreshed").await?;
+
+    Ok(())
+}
+
+#[test]
+async fn distinct_events_survive_coalescing(ctx: &mut DalContext) -> Result<()> {
+    let first =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "first").await?;
+    let second =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "second").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    let first_view = diagram_view(ctx, first.id()).await?;
+    let second_view = diagram_view(ctx, second.id()).await?;
+    let mut capture = WsEventCapture::subscribe(ctx).await?;
+
+    for view in [&first_view, &second_view] {
+        WsEvent::resource_refreshed(ctx, view.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        WsEvent::component_updated(ctx, view.clone())
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+    }
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+
+    let mut received = HashSet::new();
+    for kind in [
+        "ResourceRefreshed",
+        "ResourceRefreshed",
+        "ComponentUpdated",
+        "ComponentUpdated",
+    ] {
+        let event = capture.expect_event_of_kind(kind, TIMEOUT).await?;
+        received.insert((
+            kind,
+            event["payload"]["data"]["component"]["id"]
+                .as_str()
+                .map(ToOwned::to_owned),
+        ));
+    }
+
+    assert_eq!(
+        HashSet::from([
+            ("ResourceRefreshed", Some(first.id().to_string())),
+            ("ResourceRefreshed", Some(second.id().to_string())),
+            ("ComponentUpdated", Some(first.id().to_string())),
+            ("ComponentUpdated", Some(second.id().to_string())),
+        ]), // expected
+        received, // actual
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn flush_ws_events_publishes_before_commit(ctx: &mut DalContext) -> Result<()> {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "swifty").await?;
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    let view = diagram_view(ctx, component.id()).await?;
+    let mut capture = WsEventCapture::subscribe(ctx).await?;
+
+    WsEvent::resource_refreshed(ctx, view)
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+    capture.assert_no_event("ResourceRefreshed").await?;
+
+    ctx.flush_ws_events().await?;
+    let event = capture
+        .expect_event_of_kind("ResourceRefreshed", TIMEOUT)
+        .await?;
+    assert_eq!(
+        json!(component.id()),                       // expected
+        event["payload"]["data"]["component"]["id"], // actual
+    );
+
+    // Flushed events are not published again when the transaction commits.
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx).await?;
+    capture.assert_no_event("ResourceRefreshed").await?;
+
+    Ok(())
+}
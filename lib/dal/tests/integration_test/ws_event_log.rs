@@ -0,0 +1,176 @@
+use dal::{
+    DalContext,
+    Workspace,
+    WsEvent,
+    workspace::WorkspaceFeatureFlag,
+    ws_event_log::{
+        WsEventLog,
+        WsEventLogFilter,
+    },
+};
+use dal_test::{
+    Result,
+    test,
+};
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+use si_events::{
+    FuncRunId,
+    FuncRunLogId,
+};
+use ulid::Ulid;
+
+async fn set_ws_event_log(ctx: &DalContext, enabled: bool) -> Result<()> {
+    let mut workspace = Workspace::get_by_pk(ctx, ctx.workspace_pk()?).await?;
+    workspace
+        .set_feature_flag(ctx, WorkspaceFeatureFlag::WsEventLog, Some(enabled))
+        .await?;
+    Ok(())
+}
+
+#[test]
+async fn records_only_when_opted_in(ctx: &DalContext) -> Result<()> {
+    WsEvent::async_finish(ctx, Ulid::new())
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+    assert!(
+        WsEventLog::list(ctx, WsEventLogFilter::default())
+            .await?
+            .is_empty()
+    );
+
+    set_ws_event_log(ctx, true).await?;
+    let id = Ulid::new();
+    WsEvent::async_finish(ctx, id)
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+    WsEvent::async_finish_workspace(ctx, Ulid::new())
+        .await?
+        .publish_immediately(ctx)
+        .await?;
+
+    let entries = WsEventLog::list(ctx, WsEventLogFilter::default()).await?;
+    assert_eq!(
+        vec![
+            ("AsyncFinish".to_string(), Some(ctx.change_set_id())),
+            ("AsyncFinish".to_string(), None),
+        ], // expected
+        entries
+            .iter()
+            .map(|entry| (entry.kind.clone(), entry.change_set_id))
+            .collect::<Vec<_>>(), // actual
+    );
+    assert_eq!(
+        json!({ "id": id }), // expected
+        entries[0].payload,  // actual
+    );
+    assert_eq!(Some(ctx.events_actor()), entries[0].actor);
+
+    // Paging picks up after the last entry seen
+    let after_first = WsEventLog::list(
+        ctx,
+        WsEventLogFilter {
+            after_position: Some(entries[0].position),
+            ..Default::default()
+        },
+    )
+    .await?;
+    assert_eq!(vec![entries[1].clone()], after_first);
+
+    // Turning it back off stops recording
+    set_ws_event_log(ctx, false).await?;
+    WsEvent::async_finish(ctx, Ulid::new())
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+    assert_eq!(
+        entries,                                                   // expected
+        WsEventLog::list(ctx, WsEventLogFilter::default()).await?, // actual
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn excluded_kinds_are_not_recorded(ctx: &DalContext) -> Result<()> {
+    set_ws_event_log(ctx, true).await?;
+
+    WsEvent::func_run_log_updated(ctx, FuncRunId::new(), FuncRunLogId::new(), None)
+        .await?
+        .publish_immediately(ctx)
+        .await?;
+    WsEvent::async_finish(ctx, Ulid::new())
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+
+    let entries = WsEventLog::list(ctx, WsEventLogFilter::default()).await?;
+    assert_eq!(
+        vec!["AsyncFinish".to_string()], // expected
+        entries
+            .iter()
+            .map(|entry| entry.kind.clone())
+            .collect::<Vec<_>>(), // actual
+    );
+
+    // Filtering by kind
+    assert!(
+        WsEventLog::list(
+            ctx,
+            WsEventLogFilter {
+                kinds: Some(vec!["FuncRunLogUpdated".to_string()]),
+                ..Default::default()
+            },
+        )
+        .await?
+        .is_empty()
+    );
+
+    // Pruning everything recorded so far
+    assert_eq!(1, WsEventLog::prune(ctx, chrono::Utc::now()).await?);
+    assert!(
+        WsEventLog::list(ctx, WsEventLogFilter::default())
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[test]
+async fn coalesced_events_are_recorded_once(ctx: &DalContext) -> Result<()> {
+    set_ws_event_log(ctx, true).await?;
+
+    for _ in 0..2 {
+        WsEvent::action_list_updated(ctx)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+    }
+
+    let entries = WsEventLog::list(ctx, WsEventLogFilter::default()).await?;
+    assert_eq!(
+        vec!["ActionsListUpdated".to_string()], // expected
+        entries
+            .iter()
+            .map(|entry| entry.kind.clone())
+            .collect::<Vec<_>>(), // actual
+    );
+
+    // Once flushed, the event can no longer be replaced, so the next one gets its own entry
+    ctx.flush_ws_events().await?;
+    WsEvent::action_list_updated(ctx)
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+    assert_eq!(
+        2, // expected
+        WsEventLog::list(ctx, WsEventLogFilter::default())
+            .await?
+            .len(), // actual
+    );
+
+    Ok(())
+}
@@ -4,7 +4,13 @@ use dal::{
     ComponentError,
     ComponentId,
     Func,
-    func::debug::dispatch_debug_func,
+    func::{
+        debug::{
+            DebugFuncError,
+            dispatch_debug_func,
+        },
+        runner::FuncRunnerError,
+    },
 };
 use sdf_extract::{
     PosthogEventTracker,
@@ -39,7 +45,7 @@ use super::{
         (status = 200, description = "Debug function execution started", body = ExecDebugFuncV1Response),
         (status = 400, description = "Bad request - Invalid input"),
         (status = 401, description = "Unauthorized - Invalid or missing token"),
-        (status = 404, description = "Component not found"),
+        (status = 404, description = "Component or secret not found"),
         (status = 500, description = "Internal server error", body = crate::service::v1::common::ApiError)
     )
 )]
@@ -53,9 +59,23 @@ pub async fn exec_debug_func(
     let debug_func = Func::new_debug(&payload.name, &payload.code, &payload.handler);
 
     let debug_func_job_state_id = match Component::get_by_id(ctx, payload.component_id).await {
-        Ok(_) => dispatch_debug_func(ctx, payload.component_id, debug_func, payload.debug_input)
-            .await
-            .map_err(|e| DebugFuncsError::InternalError(e.to_string()))?,
+        Ok(_) => dispatch_debug_func(
+            ctx,
+            payload.component_id,
+            debug_func,
+            payload.debug_input,
+            payload.secret_names,
+        )
+        .await
+        .map_err(|err| match err {
+            DebugFuncError::FuncRunner(err) => match *err {
+                FuncRunnerError::NamedSecretsNotFound(names) => {
+                    DebugFuncsError::SecretsNotFound(names)
+                }
+                err => DebugFuncsError::InternalError(err.to_string()),
+            },
+            err => DebugFuncsError::InternalError(err.to_string()),
+        })?,
         Err(ComponentError::NotFound(_)) => {
             return Err(DebugFuncsError::ComponentNotFound(payload.component_id));
         }
@@ -96,6 +116,12 @@ pub struct ExecDebugFuncV1Request {
     pub component_id: ComponentId,
     #[schema(value_type = Option<serde_json::Value>, example = "{ \"ami\": \"ami-0abcdef1234567890\" }")]
     pub debug_input: Option<serde_json::Value>,
+    /// The names of workspace secrets to pass to the function, on top of the ones the component
+    /// uses. Like the component's secrets, they are only available through the request storage
+    /// their authentication functions populate.
+    #[serde(default)]
+    #[schema(value_type = Vec<String>, example = json!(["aws-prod"]))]
+    pub secret_names: Vec<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, ToSchema)]
@@ -34,6 +34,8 @@ pub enum DebugFuncsError {
     DebugFuncsJobStateNotFound(DebugFuncJobStateId),
     #[error("internal error: {0}")]
     InternalError(String),
+    #[error("secrets not found with names: {0:?}")]
+    SecretsNotFound(Vec<String>),
     #[error("validation error: {0}")]
     Validation(String),
 }
@@ -73,9 +75,9 @@ impl From<JsonRejection> for DebugFuncsError {
 impl crate::service::v1::common::ErrorIntoResponse for DebugFuncsError {
     fn status_and_message(&self) -> (StatusCode, String) {
         match self {
-            Self::DebugFuncsJobStateNotFound(_) | Self::ComponentNotFound(_) => {
-                (StatusCode::NOT_FOUND, self.to_string())
-            }
+            Self::DebugFuncsJobStateNotFound(_)
+            | Self::ComponentNotFound(_)
+            | Self::SecretsNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
             Self::Validation(_) => (StatusCode::BAD_REQUEST, self.to_string()),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         }
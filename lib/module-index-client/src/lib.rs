@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 // Re-export all module index types so that client users do not have to import two crates.
 pub use module_index_types::*;
 use reqwest::{
@@ -397,6 +399,34 @@ impl ModuleIndexClient {
             .await?)
     }
 
+    /// Lists the workspace backups the caller has uploaded (route: GET /modules?kind=workspaceBackup).
+    pub async fn list_workspace_backups(&self) -> ModuleIndexClientResult<ListModulesResponse> {
+        let mut url = self.base_url.join("modules")?;
+        url.query_pairs_mut().append_pair("kind", "workspaceBackup");
+
+        Ok(self
+            .inner
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+
+    /// Pings the module index's system status route (route: GET /), failing if it doesn't respond
+    /// successfully within the given timeout.
+    pub async fn system_status(&self, timeout: Duration) -> ModuleIndexClientResult<()> {
+        self.inner
+            .get(self.base_url.clone())
+            .timeout(timeout)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
     // Will skip builtins
     pub async fn list_module_details(&self) -> ModuleIndexClientResult<ListModulesResponse> {
         let url = self.base_url.join("modules")?;
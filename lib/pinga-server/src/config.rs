@@ -1,9 +1,14 @@
 use std::{
+    collections::BTreeSet,
     env,
     path::Path,
 };
 
 use buck2_resources::Buck2Resources;
+use dal::{
+    func::execution_budget::DEFAULT_EXECUTION_BUDGET_PERMITS,
+    ws_event_log::WsEventLog,
+};
 use derive_builder::Builder;
 use serde::{
     Deserialize,
@@ -72,6 +77,9 @@ pub struct Config {
     #[builder(default = "default_max_deliver()")]
     max_deliver: i64,
 
+    #[builder(default = "default_execution_budget()")]
+    execution_budget: usize,
+
     #[builder(default = "random_instance_id()")]
     instance_id: String,
 
@@ -80,6 +88,9 @@ pub struct Config {
 
     #[builder(default = "default_layer_db_config()")]
     layer_db_config: LayerDbConfig,
+
+    #[builder(default = "WsEventLog::default_excluded_kinds()")]
+    ws_event_log_excluded_kinds: BTreeSet<String>,
 }
 
 impl StandardConfig for Config {
@@ -124,6 +135,11 @@ impl Config {
         self.max_deliver
     }
 
+    /// Gets the number of functions each workspace can have executing at once.
+    pub fn execution_budget(&self) -> usize {
+        self.execution_budget
+    }
+
     /// Gets the config's instance ID.
     pub fn instance_id(&self) -> &str {
         self.instance_id.as_ref()
@@ -133,6 +149,11 @@ impl Config {
     pub fn layer_db_config(&self) -> &LayerDbConfig {
         &self.layer_db_config
     }
+
+    /// Gets the kinds of WsEvents which are never recorded in the ws event log.
+    pub fn ws_event_log_excluded_kinds(&self) -> &BTreeSet<String> {
+        &self.ws_event_log_excluded_kinds
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -147,12 +168,16 @@ pub struct ConfigFile {
     concurrency_limit: usize,
     #[serde(default = "default_max_deliver")]
     max_deliver: i64,
+    #[serde(default = "default_execution_budget")]
+    execution_budget: usize,
     #[serde(default = "random_instance_id")]
     instance_id: String,
     #[serde(default = "default_layer_db_config")]
     layer_db_config: LayerDbConfig,
     #[serde(default = "default_symmetric_crypto_config")]
     symmetric_crypto_service: SymmetricCryptoServiceConfigFile,
+    #[serde(default = "default_ws_event_log_excluded_kinds")]
+    ws_event_log_excluded_kinds: Vec<String>,
 }
 
 impl Default for ConfigFile {
@@ -162,10 +187,12 @@ impl Default for ConfigFile {
             nats: Default::default(),
             concurrency_limit: default_concurrency_limit(),
             max_deliver: default_max_deliver(),
+            execution_budget: default_execution_budget(),
             crypto: Default::default(),
             instance_id: random_instance_id(),
             layer_db_config: default_layer_db_config(),
             symmetric_crypto_service: default_symmetric_crypto_config(),
+            ws_event_log_excluded_kinds: default_ws_event_log_excluded_kinds(),
         }
     }
 }
@@ -186,9 +213,11 @@ impl TryFrom<ConfigFile> for Config {
         config.crypto(value.crypto);
         config.concurrency_limit(value.concurrency_limit);
         config.max_deliver(value.max_deliver);
+        config.execution_budget(value.execution_budget);
         config.instance_id(value.instance_id);
         config.symmetric_crypto_service(value.symmetric_crypto_service.try_into()?);
         config.layer_db_config(value.layer_db_config);
+        config.ws_event_log_excluded_kinds(value.ws_event_log_excluded_kinds.into_iter().collect());
         config.build().map_err(Into::into)
     }
 }
@@ -213,10 +242,18 @@ fn default_max_deliver() -> i64 {
     1
 }
 
+fn default_execution_budget() -> usize {
+    DEFAULT_EXECUTION_BUDGET_PERMITS
+}
+
 fn default_layer_db_config() -> LayerDbConfig {
     LayerDbConfig::default()
 }
 
+fn default_ws_event_log_excluded_kinds() -> Vec<String> {
+    WsEventLog::default_excluded_kinds().into_iter().collect()
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 pub fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
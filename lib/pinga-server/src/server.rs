@@ -16,6 +16,10 @@ use dal::{
     NatsProcessor,
     ServicesContext,
     feature_flags::FeatureFlagService,
+    func::execution_budget::{
+        DEFAULT_EXECUTION_BUDGET_ACQUIRE_TIMEOUT,
+        ExecutionBudget,
+    },
 };
 use naxum::{
     MessageHead,
@@ -154,7 +158,12 @@ impl Server {
             layer_db,
             FeatureFlagService::default(),
             compute_executor,
-        );
+        )
+        .with_execution_budget(ExecutionBudget::new(
+            config.execution_budget(),
+            DEFAULT_EXECUTION_BUDGET_ACQUIRE_TIMEOUT,
+        ))
+        .with_ws_event_log_excluded_kinds(config.ws_event_log_excluded_kinds().iter().cloned());
 
         Self::from_services(
             config.instance_id().to_string(),
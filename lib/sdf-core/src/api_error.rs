@@ -21,6 +21,8 @@ struct ApiErrorError {
     message: String,
     #[serde(serialize_with = "status_code_to_u16")]
     status_code: StatusCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<String>,
 }
 
 fn status_code_to_u16<S>(status_code: &StatusCode, serializer: S) -> Result<S::Ok, S::Error>
@@ -45,11 +47,19 @@ impl ApiError {
             error: ApiErrorError {
                 message: err.to_string(),
                 status_code,
+                code: None,
             },
             level: None,
         }
     }
 
+    /// Attaches a stable, machine-readable code to the error so that clients can act on it
+    /// without parsing the message.
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.error.code = Some(code.into());
+        self
+    }
+
     // keeping this here to allow for future use
     #[allow(dead_code)]
     fn with_level(mut self, level: TracingLevel) -> Self {
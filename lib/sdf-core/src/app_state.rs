@@ -19,10 +19,13 @@ use tokio_util::sync::CancellationToken;
 
 use crate::{
     BroadcastGroups,
+    event_relays::EventRelays,
+    long_tasks::LongTasks,
     nats_multiplexer::{
         EddaUpdatesMultiplexerClient,
         NatsMultiplexerClients,
     },
+    workspace_access::WorkspaceAccessThrottle,
     workspace_permissions::{
         WorkspacePermissions,
         WorkspacePermissionsMode,
@@ -53,6 +56,9 @@ pub struct AppState {
     frigg: FriggStore,
     audit_database_context: AuditDatabaseContext,
     edda_client: EddaClient,
+    long_tasks: LongTasks,
+    workspace_access_throttle: WorkspaceAccessThrottle,
+    event_relays: EventRelays,
 }
 
 impl AppState {
@@ -74,6 +80,7 @@ impl AppState {
         frigg: FriggStore,
         audit_database_context: AuditDatabaseContext,
         edda_client: EddaClient,
+        long_tasks: LongTasks,
     ) -> Self {
         let nats_multiplexer_clients = NatsMultiplexerClients {
             ws: Arc::new(Mutex::new(ws_multiplexer_client)),
@@ -97,6 +104,9 @@ impl AppState {
             frigg,
             audit_database_context,
             edda_client,
+            long_tasks,
+            workspace_access_throttle: Default::default(),
+            event_relays: Default::default(),
         }
     }
 
@@ -151,6 +161,18 @@ impl AppState {
     pub fn edda_client(&self) -> &EddaClient {
         &self.edda_client
     }
+
+    pub fn long_tasks(&self) -> &LongTasks {
+        &self.long_tasks
+    }
+
+    pub fn workspace_access_throttle(&self) -> &WorkspaceAccessThrottle {
+        &self.workspace_access_throttle
+    }
+
+    pub fn event_relays(&self) -> &EventRelays {
+        &self.event_relays
+    }
 }
 
 #[derive(Clone, Debug, FromRef)]
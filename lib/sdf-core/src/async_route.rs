@@ -1,23 +1,220 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
 use dal::{
     DalContext,
+    WorkspacePk,
     WsEvent,
 };
 use hyper::Uri;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use telemetry::prelude::*;
 
 pub type TaskId = ulid::Ulid;
 
+/// How long the outcome of an "async" route is kept around for clients that missed its WsEvent.
+const TASK_STATUS_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The error code sent with the async error WsEvent of a task cancelled by [`handle_cancelled`].
+pub const TASK_CANCELLED_ERROR_CODE: &str = "task_cancelled";
+
+/// The state of the work behind an "async" SDF route, as reported by [`AsyncTaskStatuses::get`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum AsyncTaskStatus {
+    Pending,
+    Finished,
+    Errored {
+        error: String,
+    },
+    /// The task was stopped before finishing because the server shut down.
+    Cancelled,
+}
+
+#[derive(Debug)]
+struct TaskStatusEntry {
+    workspace_pk: Option<WorkspacePk>,
+    status: AsyncTaskStatus,
+    updated_at: Instant,
+}
+
+/// The last known statuses of the "async" route tasks spawned by a server, kept for
+/// [`TASK_STATUS_TTL`] so that clients that missed a task's WsEvent can ask for its outcome.
+///
+/// Cheap to clone; clones share the same statuses.
+#[derive(Clone, Debug, Default)]
+pub struct AsyncTaskStatuses {
+    entries: Arc<Mutex<HashMap<TaskId, TaskStatusEntry>>>,
+}
+
+impl AsyncTaskStatuses {
+    /// Records that the work for an "async" route has been spawned, so that [`Self::get`] reports
+    /// it as pending until [`handle_finish`] or [`handle_error`] is called.
+    pub fn register(&self, ctx: &DalContext, task_id: TaskId) {
+        self.record(ctx, task_id, AsyncTaskStatus::Pending);
+    }
+
+    /// Returns the last known status of an "async" route's task, if it belongs to the given
+    /// workspace and hasn't expired.
+    pub fn get(
+        &self,
+        workspace_pk: Option<WorkspacePk>,
+        task_id: TaskId,
+    ) -> Option<AsyncTaskStatus> {
+        self.lock()
+            .get(&task_id)
+            .filter(|entry| {
+                entry.workspace_pk == workspace_pk && entry.updated_at.elapsed() < TASK_STATUS_TTL
+            })
+            .map(|entry| entry.status.clone())
+    }
+
+    /// Returns how many "async" route tasks for the given workspace are still pending.
+    pub fn pending_count(&self, workspace_pk: Option<WorkspacePk>) -> usize {
+        self.lock()
+            .values()
+            .filter(|entry| {
+                entry.workspace_pk == workspace_pk
+                    && entry.status == AsyncTaskStatus::Pending
+                    && entry.updated_at.elapsed() < TASK_STATUS_TTL
+            })
+            .count()
+    }
+
+    fn record(&self, ctx: &DalContext, task_id: TaskId, status: AsyncTaskStatus) {
+        let mut entries = self.lock();
+
+        let now = Instant::now();
+        entries.retain(|_, entry| now.duration_since(entry.updated_at) < TASK_STATUS_TTL);
+        entries.insert(
+            task_id,
+            TaskStatusEntry {
+                workspace_pk: ctx.tenancy().workspace_pk_opt(),
+                status,
+                updated_at: now,
+            },
+        );
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<TaskId, TaskStatusEntry>> {
+        match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Handler for the successful completion of an "async" SDF route: records the task as finished and
+/// publishes the workspace-level async finish WsEvent.
+pub async fn handle_finish(
+    ctx: &DalContext,
+    statuses: &AsyncTaskStatuses,
+    uri: Uri,
+    task_id: TaskId,
+) {
+    let event = match WsEvent::async_finish_workspace(ctx, task_id).await {
+        Ok(event) => event,
+        Err(err) => return handle_error(ctx, statuses, uri, task_id, err).await,
+    };
+
+    statuses.record(ctx, task_id, AsyncTaskStatus::Finished);
+
+    if let Err(err) = event.publish_immediately(ctx).await {
+        handle_error(ctx, statuses, uri, task_id, err).await;
+    }
+}
+
+/// Like [`handle_error`], but first passes the error's kind to `track` so that failures of the
+/// route can be tracked alongside its successes. The kind is also sent as the code of the async
+/// error WsEvent, so it should be a stable name for the error that never contains request data.
+pub async fn handle_error_and_track(
+    ctx: &DalContext,
+    statuses: &AsyncTaskStatuses,
+    uri: Uri,
+    task_id: TaskId,
+    err: impl std::error::Error,
+    error_kind: &str,
+    track: impl FnOnce(&str),
+) {
+    track(error_kind);
+    handle_error_with_code(ctx, statuses, uri, task_id, err, Some(error_kind)).await;
+}
+
+/// Handler for an "async" SDF route whose work was stopped before finishing because the server is
+/// shutting down: records the task as cancelled and publishes an async error WsEvent, so that
+/// clients don't wait on it forever.
+pub async fn handle_cancelled(
+    ctx: &DalContext,
+    statuses: &AsyncTaskStatuses,
+    uri: Uri,
+    task_id: TaskId,
+) {
+    warn!("async route '{}' cancelled for shutdown", uri.to_string());
+    statuses.record(ctx, task_id, AsyncTaskStatus::Cancelled);
+    match WsEvent::async_error(
+        ctx,
+        task_id,
+        "cancelled because the server is shutting down; please retry".to_string(),
+        Some(TASK_CANCELLED_ERROR_CODE.to_owned()),
+    )
+    .await
+    {
+        Ok(event) => {
+            if let Err(commit_err) = event.publish_immediately(ctx).await {
+                error!(si.error.message = ?commit_err.to_string(), "Unable to publish ws event for async cancellation");
+            }
+        }
+        Err(creation_err) => {
+            error!(si.error.message = ?creation_err.to_string(), "Unable to create ws event for async cancellation");
+        }
+    }
+}
+
 /// Handler for any fatal error condition in an "async" SDF route (one that does
 /// work on a background thread and returns the result via a WsEvent)
 pub async fn handle_error(
     ctx: &DalContext,
+    statuses: &AsyncTaskStatuses,
+    uri: Uri,
+    task_id: TaskId,
+    err: impl std::error::Error,
+) {
+    handle_error_with_code(ctx, statuses, uri, task_id, err, None).await;
+}
+
+/// Like [`handle_error`], but also sends the same machine-readable error code the route would
+/// have put in its [`ApiError`](crate::api_error::ApiError) response along in the WsEvent.
+pub async fn handle_error_with_code(
+    ctx: &DalContext,
+    statuses: &AsyncTaskStatuses,
     uri: Uri,
     task_id: TaskId,
     err: impl std::error::Error,
+    code: Option<&str>,
 ) {
     let err_string = err.to_string();
     error!("async route '{}' error: {}", uri.to_string(), err_string);
-    match WsEvent::async_error(ctx, task_id, err_string).await {
+    statuses.record(
+        ctx,
+        task_id,
+        AsyncTaskStatus::Errored {
+            error: err_string.clone(),
+        },
+    );
+    match WsEvent::async_error(ctx, task_id, err_string, code.map(ToOwned::to_owned)).await {
         Ok(event) => {
             if let Err(commit_err) = event.publish_immediately(ctx).await {
                 error!(si.error.message = ?commit_err.to_string(), "Unable to publish ws event for async error");
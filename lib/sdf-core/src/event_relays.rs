@@ -0,0 +1,337 @@
+//! The per-workspace relays behind the server-sent events fallback and the event replay, which
+//! relay WsEvents from the same NATS subjects that `/ws/workspace_updates` subscribes to.
+//!
+//! Every relayed event is given a sequence by the workspace's relay, replacing the one its
+//! publisher gave it. Publishers hand out sequences on their own, so only the relay sees every
+//! event in the order they arrive. Each relay counts up from the time it started, so a relay
+//! started after another went idle carries on above the sequences the earlier one handed out.
+
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+    },
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use dal::{
+    ChangeSetId,
+    WorkspacePk,
+};
+use nats_multiplexer_client::MultiplexerClient;
+use serde::Deserialize;
+use serde_json::Value;
+use si_data_nats::Subject;
+use telemetry::prelude::*;
+use tokio::sync::{
+    Mutex as TokioMutex,
+    broadcast::{
+        self,
+        error::RecvError,
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+/// How many recent events per workspace are kept for clients resuming with `Last-Event-ID` or
+/// asking for a replay.
+const REPLAY_BUFFER_SIZE: usize = 256;
+
+/// How long a workspace relay outlives its last stream or replay, so that reconnecting clients can
+/// resume.
+const RELAY_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+type Relays = HashMap<WorkspacePk, Arc<WorkspaceRelay>>;
+
+/// The running [`WorkspaceRelay`] of each workspace, for a server.
+///
+/// Cheap to clone; clones share the same relays.
+#[derive(Clone, Debug, Default)]
+pub struct EventRelays {
+    relays: Arc<Mutex<Relays>>,
+}
+
+impl EventRelays {
+    /// Returns the relay for the workspace, starting one if there isn't one running.
+    pub fn relay_for(
+        &self,
+        workspace_pk: WorkspacePk,
+        ws_multiplexer_client: Arc<TokioMutex<MultiplexerClient>>,
+        shutdown_token: &CancellationToken,
+    ) -> Arc<WorkspaceRelay> {
+        lock(&self.relays)
+            .entry(workspace_pk)
+            .or_insert_with(|| {
+                self.start_relay(workspace_pk, ws_multiplexer_client, shutdown_token.clone())
+            })
+            .clone()
+    }
+
+    fn start_relay(
+        &self,
+        workspace_pk: WorkspacePk,
+        ws_multiplexer_client: Arc<TokioMutex<MultiplexerClient>>,
+        shutdown_token: CancellationToken,
+    ) -> Arc<WorkspaceRelay> {
+        let (sender, _) = broadcast::channel(REPLAY_BUFFER_SIZE);
+        let relay = Arc::new(WorkspaceRelay {
+            buffer: Mutex::new(VecDeque::with_capacity(REPLAY_BUFFER_SIZE)),
+            sender,
+            last_replayed_at: Mutex::new(None),
+            last_sequence: AtomicU64::new(relay_start_sequence()),
+        });
+
+        let relays = self.clone();
+        let task_relay = relay.clone();
+        tokio::task::spawn(async move {
+            relays
+                .run_relay(
+                    workspace_pk,
+                    &task_relay,
+                    ws_multiplexer_client,
+                    shutdown_token,
+                )
+                .await;
+
+            // Streams still holding a receiver will see the channel close once the last
+            // reference to the relay is dropped, and can reconnect to a fresh one.
+            remove_relay(&mut lock(&relays.relays), workspace_pk, &task_relay);
+        });
+
+        relay
+    }
+
+    async fn run_relay(
+        &self,
+        workspace_pk: WorkspacePk,
+        relay: &Arc<WorkspaceRelay>,
+        ws_multiplexer_client: Arc<TokioMutex<MultiplexerClient>>,
+        shutdown_token: CancellationToken,
+    ) {
+        let subject = Subject::from(format!("si.workspace_pk.{workspace_pk}.>"));
+        let mut receiver = match ws_multiplexer_client.lock().await.receiver(subject).await {
+            Ok(receiver) => receiver,
+            Err(err) => {
+                warn!(si.error.message = ?err, %workspace_pk, "event stream relay failed to subscribe");
+                return;
+            }
+        };
+        let mut idle_check = tokio::time::interval(RELAY_IDLE_TIMEOUT);
+        let mut idle_since: Option<Instant> = None;
+
+        loop {
+            tokio::select! {
+                _ = shutdown_token.cancelled() => return,
+                _ = idle_check.tick() => {
+                    if relay.sender.receiver_count() > 0 || relay.replayed_within(RELAY_IDLE_TIMEOUT) {
+                        idle_since = None;
+                        continue;
+                    }
+                    match idle_since {
+                        Some(since) if since.elapsed() >= RELAY_IDLE_TIMEOUT => {
+                            // Check again under the relays lock, since new streams subscribe under it.
+                            let removed = {
+                                let mut relays = lock(&self.relays);
+                                let is_idle = relay.sender.receiver_count() == 0
+                                    && !relay.replayed_within(RELAY_IDLE_TIMEOUT);
+                                if is_idle {
+                                    remove_relay(&mut relays, workspace_pk, relay);
+                                }
+                                is_idle
+                            };
+                            if removed {
+                                return;
+                            }
+                        }
+                        Some(_) => {}
+                        None => idle_since = Some(Instant::now()),
+                    }
+                }
+                recv_result = receiver.recv() => match recv_result {
+                    Ok(nats_msg) => {
+                        let mut event: Value = match serde_json::from_slice(nats_msg.payload()) {
+                            Ok(event) => event,
+                            Err(err) => {
+                                debug!(si.error.message = ?err, "skipping non-json message in event stream relay");
+                                continue;
+                            }
+                        };
+                        let envelope = match WsEventEnvelope::deserialize(&event) {
+                            Ok(envelope) => envelope,
+                            Err(err) => {
+                                debug!(si.error.message = ?err, "skipping non-WsEvent message in event stream relay");
+                                continue;
+                            }
+                        };
+                        let id = relay.next_sequence();
+                        if let Some(event) = event.as_object_mut() {
+                            event.insert("sequence".to_owned(), id.into());
+                        }
+                        relay.push(RelayedEvent {
+                            id,
+                            kind: envelope.payload.kind,
+                            change_set_id: envelope.change_set_id,
+                            data: event.to_string(),
+                        });
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(%workspace_pk, skipped, "event stream relay lagged behind nats");
+                    }
+                    Err(RecvError::Closed) => return,
+                },
+            }
+        }
+    }
+}
+
+/// A WsEvent as relayed to the streams and replays of its workspace.
+#[derive(Debug)]
+pub struct RelayedEvent {
+    id: u64,
+    kind: String,
+    change_set_id: Option<ChangeSetId>,
+    data: String,
+}
+
+impl RelayedEvent {
+    /// The sequence the relay gave the event, which is also its SSE event id.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The name of the event's [`WsPayload`](dal::WsPayload) variant.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// The serialized event, with its `sequence` replaced by [`Self::id`].
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// Events without a change set are workspace-wide and are sent to every stream.
+    pub fn is_visible_in(&self, change_set_id: ChangeSetId) -> bool {
+        self.change_set_id.is_none_or(|id| id == change_set_id)
+    }
+}
+
+/// The subset of a serialized [`WsEvent`](dal::WsEvent) needed to name and route it.
+#[derive(Deserialize)]
+struct WsEventEnvelope {
+    change_set_id: Option<ChangeSetId>,
+    payload: WsEventEnvelopePayload,
+}
+
+#[derive(Deserialize)]
+struct WsEventEnvelopePayload {
+    kind: String,
+}
+
+/// Relays the WsEvents of a workspace, keeping the most recent ones for replays.
+#[derive(Debug)]
+pub struct WorkspaceRelay {
+    buffer: Mutex<VecDeque<Arc<RelayedEvent>>>,
+    sender: broadcast::Sender<Arc<RelayedEvent>>,
+    last_replayed_at: Mutex<Option<Instant>>,
+    last_sequence: AtomicU64,
+}
+
+impl WorkspaceRelay {
+    /// Returns a sequence greater than any handed out before by this relay.
+    fn next_sequence(&self) -> u64 {
+        self.last_sequence.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    fn push(&self, event: RelayedEvent) {
+        let event = Arc::new(event);
+        // Sending while holding the buffer lock ensures a stream subscribing during a replay
+        // neither misses nor duplicates this event.
+        let mut buffer = lock(&self.buffer);
+        if buffer.len() == REPLAY_BUFFER_SIZE {
+            buffer.pop_front();
+        }
+        buffer.push_back(event.clone());
+        // An error only means there are currently no streams listening.
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to the events relayed from now on, returning along with the receiver the
+    /// buffered events visible in the change set after `last_event_id`, if given.
+    pub fn subscribe(
+        &self,
+        change_set_id: ChangeSetId,
+        last_event_id: Option<u64>,
+    ) -> (
+        VecDeque<Arc<RelayedEvent>>,
+        broadcast::Receiver<Arc<RelayedEvent>>,
+    ) {
+        let buffer = lock(&self.buffer);
+        let replay = match last_event_id {
+            Some(last_event_id) => events_since(&buffer, change_set_id, last_event_id).collect(),
+            None => VecDeque::new(),
+        };
+
+        (replay, self.sender.subscribe())
+    }
+
+    /// Returns the buffered events visible in the change set after `since_seq`, oldest first,
+    /// keeping the relay running for another [`RELAY_IDLE_TIMEOUT`].
+    pub fn replay(&self, change_set_id: ChangeSetId, since_seq: u64) -> Vec<Arc<RelayedEvent>> {
+        *lock(&self.last_replayed_at) = Some(Instant::now());
+        events_since(&lock(&self.buffer), change_set_id, since_seq).collect()
+    }
+
+    fn replayed_within(&self, timeout: Duration) -> bool {
+        lock(&self.last_replayed_at).is_some_and(|replayed_at| replayed_at.elapsed() < timeout)
+    }
+}
+
+fn events_since(
+    buffer: &VecDeque<Arc<RelayedEvent>>,
+    change_set_id: ChangeSetId,
+    since_seq: u64,
+) -> impl Iterator<Item = Arc<RelayedEvent>> + '_ {
+    buffer
+        .iter()
+        .filter(move |event| event.id > since_seq && event.is_visible_in(change_set_id))
+        .cloned()
+}
+
+/// The wall clock in microseconds since the epoch, which a relay counts up from so that its
+/// sequences are greater than those of any relay started before it.
+fn relay_start_sequence() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_micros() as u64)
+        .unwrap_or_default()
+}
+
+/// Removes the relay for the workspace, unless it has already been replaced by another.
+fn remove_relay(relays: &mut Relays, workspace_pk: WorkspacePk, relay: &Arc<WorkspaceRelay>) {
+    if relays
+        .get(&workspace_pk)
+        .is_some_and(|current| Arc::ptr_eq(current, relay))
+    {
+        relays.remove(&workspace_pk);
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
@@ -12,10 +12,13 @@ pub mod app_state;
 pub mod async_route;
 pub mod change_set_mvs;
 pub mod dal_wrapper;
+pub mod event_relays;
 pub mod force_change_set_response;
 pub mod index;
+pub mod long_tasks;
 pub mod nats_multiplexer;
 pub mod tracking;
+pub mod workspace_access;
 pub mod workspace_permissions;
 
 pub use edda_client::{
@@ -0,0 +1,212 @@
+//! This module contains [`LongTasks`], which tracks the work the "async" routes spawn so that the
+//! server can drain it when shutting down instead of killing it mid-flight.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+    time::Duration,
+};
+
+use dal::DalContext;
+use hyper::Uri;
+use strum::Display;
+use telemetry::prelude::*;
+use tokio_util::{
+    sync::CancellationToken,
+    task::TaskTracker,
+};
+
+use crate::async_route::{
+    AsyncTaskStatuses,
+    TaskId,
+    handle_cancelled,
+};
+
+/// How long clients are asked to wait before retrying a task that was refused because the server
+/// is draining. By then, the request should land on a server that isn't shutting down.
+pub const DRAINING_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// How long cancelled tasks get to record that they were cancelled, once the grace period is over.
+const CANCELLATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The kinds of work tracked by [`LongTasks`].
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Display, Eq, Hash, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum LongTaskKind {
+    ModuleCacheUpdate,
+    ModuleUpgrade,
+    ResourceRefresh,
+    WorkspaceInstall,
+}
+
+#[derive(Debug, Default)]
+struct LongTasksState {
+    draining: bool,
+    in_flight: HashMap<TaskId, LongTaskKind>,
+}
+
+#[derive(Debug, Default)]
+struct LongTasksInner {
+    tracker: TaskTracker,
+    cancellation_token: CancellationToken,
+    state: Mutex<LongTasksState>,
+    task_statuses: AsyncTaskStatuses,
+}
+
+/// What happened to the tracked tasks when [`LongTasks::drain`] was called.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DrainOutcome {
+    /// The tasks that were still running at the end of the grace period, and were cancelled.
+    pub cancelled: Vec<(TaskId, LongTaskKind)>,
+}
+
+/// Tracks the long-running tasks spawned by the "async" routes (workspace installs, module cache
+/// updates, etc.).
+///
+/// Once [`Self::drain`] is called, [`Self::is_draining`] is true so that routes can refuse to start
+/// new tasks, the tasks in flight get a grace period to finish, and whatever is left is cancelled
+/// so that it is reported as such rather than left pending forever.
+///
+/// The statuses of the "async" route tasks are kept in its [`AsyncTaskStatuses`], for clients that
+/// missed a task's WsEvent.
+///
+/// Cheap to clone; clones track the same tasks.
+#[derive(Clone, Debug, Default)]
+pub struct LongTasks {
+    inner: Arc<LongTasksInner>,
+}
+
+impl LongTasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether [`Self::drain`] has been called, after which no new tasks should be started.
+    pub fn is_draining(&self) -> bool {
+        self.lock().draining
+    }
+
+    /// The statuses of the "async" route tasks spawned with [`Self::spawn_async_route`].
+    pub fn task_statuses(&self) -> &AsyncTaskStatuses {
+        &self.inner.task_statuses
+    }
+
+    /// The tasks that are currently running.
+    pub fn in_flight(&self) -> Vec<(TaskId, LongTaskKind)> {
+        let mut in_flight: Vec<_> = self
+            .lock()
+            .in_flight
+            .iter()
+            .map(|(task_id, kind)| (*task_id, *kind))
+            .collect();
+        in_flight.sort_by_key(|(task_id, _)| *task_id);
+        in_flight
+    }
+
+    /// Spawns the work, tracking it until it finishes. If it is still running when the drain grace
+    /// period is over, it is dropped and `on_cancelled` is run in its place.
+    pub fn spawn<W, C>(&self, task_id: TaskId, kind: LongTaskKind, work: W, on_cancelled: C)
+    where
+        W: Future<Output = ()> + Send + 'static,
+        C: Future<Output = ()> + Send + 'static,
+    {
+        self.lock().in_flight.insert(task_id, kind);
+
+        let inner = self.inner.clone();
+        self.inner.tracker.spawn(async move {
+            tokio::select! {
+                biased;
+                _ = inner.cancellation_token.cancelled() => {
+                    warn!(%task_id, %kind, "cancelling long task for shutdown");
+                    on_cancelled.await;
+                }
+                _ = work => {}
+            }
+
+            lock(&inner.state).in_flight.remove(&task_id);
+        });
+    }
+
+    /// Registers the "async" route task and spawns the work built from the context, uri and task
+    /// statuses, as in [`Self::spawn`]. If the work is cancelled, the task is reported as cancelled
+    /// via [`handle_cancelled`].
+    pub fn spawn_async_route<F, W>(
+        &self,
+        ctx: DalContext,
+        uri: Uri,
+        task_id: TaskId,
+        kind: LongTaskKind,
+        work: F,
+    ) where
+        F: FnOnce(DalContext, Uri, AsyncTaskStatuses) -> W,
+        W: Future<Output = ()> + Send + 'static,
+    {
+        let statuses = self.task_statuses().clone();
+        statuses.register(&ctx, task_id);
+
+        let on_cancelled = {
+            let ctx = ctx.clone();
+            let uri = uri.clone();
+            let statuses = statuses.clone();
+            async move { handle_cancelled(&ctx, &statuses, uri, task_id).await }
+        };
+        self.spawn(task_id, kind, work(ctx, uri, statuses), on_cancelled);
+    }
+
+    /// Stops new tasks from being started and waits up to the grace period for the tasks in flight
+    /// to finish, then cancels the rest.
+    pub async fn drain(&self, grace_period: Duration) -> DrainOutcome {
+        self.lock().draining = true;
+        self.inner.tracker.close();
+
+        let in_flight = self.in_flight();
+        if !in_flight.is_empty() {
+            info!(
+                count = in_flight.len(),
+                ?grace_period,
+                "waiting for long tasks to finish before shutting down"
+            );
+        }
+
+        if tokio::time::timeout(grace_period, self.inner.tracker.wait())
+            .await
+            .is_ok()
+        {
+            return DrainOutcome::default();
+        }
+
+        let cancelled = self.in_flight();
+        warn!(
+            count = cancelled.len(),
+            ?grace_period,
+            "long tasks did not finish within the shutdown grace period, cancelling them"
+        );
+        self.inner.cancellation_token.cancel();
+
+        if tokio::time::timeout(CANCELLATION_TIMEOUT, self.inner.tracker.wait())
+            .await
+            .is_err()
+        {
+            error!("cancelled long tasks did not finish recording their cancellation in time");
+        }
+
+        DrainOutcome { cancelled }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LongTasksState> {
+        lock(&self.inner.state)
+    }
+}
+
+fn lock(state: &Mutex<LongTasksState>) -> MutexGuard<'_, LongTasksState> {
+    match state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use dal::{
+    UserPk,
+    WorkspacePk,
+};
+
+/// How often a user's access to a workspace is written to their membership, at most.
+pub const WORKSPACE_ACCESS_RECORD_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Throttles writes of users' workspace access, so that the hot path of building a context for a
+/// request only writes to the database once per [`WORKSPACE_ACCESS_RECORD_INTERVAL`] for each user
+/// and workspace.
+#[derive(Clone, Debug, Default)]
+pub struct WorkspaceAccessThrottle {
+    recorded_at: Arc<Mutex<HashMap<(UserPk, WorkspacePk), Instant>>>,
+}
+
+impl WorkspaceAccessThrottle {
+    /// Returns `true` if the user's access to the workspace hasn't been recorded within
+    /// [`WORKSPACE_ACCESS_RECORD_INTERVAL`], and marks it as recorded now.
+    pub fn should_record(&self, user_pk: UserPk, workspace_pk: WorkspacePk) -> bool {
+        self.should_record_at(user_pk, workspace_pk, Instant::now())
+    }
+
+    fn should_record_at(&self, user_pk: UserPk, workspace_pk: WorkspacePk, now: Instant) -> bool {
+        let mut recorded_at = match self.recorded_at.lock() {
+            Ok(recorded_at) => recorded_at,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if recorded_at
+            .get(&(user_pk, workspace_pk))
+            .is_some_and(|at| now.duration_since(*at) < WORKSPACE_ACCESS_RECORD_INTERVAL)
+        {
+            return false;
+        }
+
+        recorded_at.retain(|_, at| now.duration_since(*at) < WORKSPACE_ACCESS_RECORD_INTERVAL);
+        recorded_at.insert((user_pk, workspace_pk), now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rapid_accesses_are_recorded_once() {
+        let throttle = WorkspaceAccessThrottle::default();
+        let user_pk = UserPk::new();
+        let workspace_pk = WorkspacePk::new();
+        let now = Instant::now();
+
+        let recorded = (0..10)
+            .filter(|i| {
+                throttle.should_record_at(user_pk, workspace_pk, now + Duration::from_secs(*i))
+            })
+            .count();
+        assert_eq!(1, recorded);
+
+        // Other users and workspaces are throttled separately
+        assert!(throttle.should_record_at(UserPk::new(), workspace_pk, now));
+        assert!(throttle.should_record_at(user_pk, WorkspacePk::new(), now));
+
+        let later = now + WORKSPACE_ACCESS_RECORD_INTERVAL;
+        assert!(throttle.should_record_at(user_pk, workspace_pk, later));
+        assert!(!throttle.should_record_at(user_pk, workspace_pk, later));
+    }
+
+    #[test]
+    fn clones_share_the_throttle() {
+        let throttle = WorkspaceAccessThrottle::default();
+        let user_pk = UserPk::new();
+        let workspace_pk = WorkspacePk::new();
+
+        assert!(throttle.clone().should_record(user_pk, workspace_pk));
+        assert!(!throttle.should_record(user_pk, workspace_pk));
+    }
+}
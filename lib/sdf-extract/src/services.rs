@@ -7,9 +7,15 @@ use axum::{
         OriginalUri,
     },
     http::{
+        StatusCode,
         Uri,
+        header,
         request::Parts,
     },
+    response::{
+        IntoResponse,
+        Response,
+    },
 };
 use dal::{
     ChangeSetId,
@@ -20,7 +26,14 @@ use derive_more::{
     Deref,
     Into,
 };
-use sdf_core::app_state::AppState;
+use sdf_core::{
+    api_error::ApiError,
+    app_state::AppState,
+    long_tasks::{
+        DRAINING_RETRY_AFTER,
+        LongTasks,
+    },
+};
 
 use super::{
     ErrorResponse,
@@ -63,6 +76,39 @@ impl FromRequestParts<AppState> for PosthogClient {
     }
 }
 
+/// Gives access to the [`LongTasks`] for spawning the work of an "async" route, refusing the
+/// request with a 503 (and a `Retry-After` header) once the server has started draining them for
+/// shutdown.
+#[derive(Clone, Debug, Deref, Into)]
+pub struct LongTaskSpawner(pub LongTasks);
+
+#[async_trait]
+impl FromRequestParts<AppState> for LongTaskSpawner {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        _parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let long_tasks = state.long_tasks();
+        if long_tasks.is_draining() {
+            return Err((
+                [(
+                    header::RETRY_AFTER,
+                    DRAINING_RETRY_AFTER.as_secs().to_string(),
+                )],
+                ApiError::new(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "server is shutting down, please retry",
+                ),
+            )
+                .into_response());
+        }
+
+        Ok(Self(long_tasks.clone()))
+    }
+}
+
 #[derive(Clone, Debug, Deref, Into)]
 pub struct Nats(pub si_data_nats::NatsClient);
 
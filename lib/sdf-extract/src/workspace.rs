@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use std::{
+    marker::PhantomData,
+    str::FromStr,
+};
 
 use axum::{
     RequestPartsExt as _,
@@ -15,6 +18,8 @@ use axum::{
 use dal::{
     DalContext,
     UserPk,
+    Workspace,
+    WorkspaceFeatureFlag,
     WorkspacePk,
 };
 use derive_more::{
@@ -26,11 +31,13 @@ use serde::Deserialize;
 use si_db::User;
 use si_events::AuthenticationMethod;
 use si_jwt_public_key::SiJwtClaimRole;
+use telemetry::prelude::*;
 
 use super::{
     ErrorResponse,
     bad_request,
     internal_error,
+    not_found_error,
     request::{
         RequestUlidFromHeader,
         ValidatedToken,
@@ -136,6 +143,25 @@ impl FromRequestParts<AppState> for WorkspaceAuthorization {
             .find(|m| m.pk() == user_id)
             .ok_or_else(|| unauthorized_error("User not a member of the workspace"))?;
 
+        if state
+            .workspace_access_throttle()
+            .should_record(user_id, workspace_id)
+        {
+            let access_builder = ctx_without_snapshot.access_builder();
+            tokio::spawn(async move {
+                let result = async {
+                    let ctx = builder.build_head_without_snapshot(access_builder).await?;
+                    User::record_workspace_access(&ctx, user_id, workspace_id).await?;
+                    ctx.commit_no_rebase().await?;
+                    Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+                }
+                .await;
+                if let Err(err) = result {
+                    warn!(si.error.message = ?err, %user_id, %workspace_id, "unable to record workspace access");
+                }
+            });
+        }
+
         Ok(Self {
             ctx_without_snapshot,
             user,
@@ -321,6 +347,53 @@ impl FromRequestParts<AppState> for AuthorizedForAutomationRole {
     }
 }
 
+/// A [`WorkspaceFeatureFlag`] that routes can be gated on with [`WorkspaceFeatureEnabled`].
+pub trait GatedWorkspaceFeature: Send + Sync + 'static {
+    const FLAG: WorkspaceFeatureFlag;
+}
+
+/// Gates routes on [`WorkspaceFeatureFlag::ModuleInstall`].
+#[derive(Clone, Copy, Debug)]
+pub struct ModuleInstallFeature;
+
+impl GatedWorkspaceFeature for ModuleInstallFeature {
+    const FLAG: WorkspaceFeatureFlag = WorkspaceFeatureFlag::ModuleInstall;
+}
+
+///
+/// Ensures the feature is enabled for the target workspace, responding as if the route didn't
+/// exist (404) if it isn't.
+///
+/// - Authorizes the user to the workspace (via WorkspaceAuthorization)
+///
+#[derive(Clone, Copy, Debug)]
+pub struct WorkspaceFeatureEnabled<F>(PhantomData<F>);
+
+#[async_trait]
+impl<F: GatedWorkspaceFeature> FromRequestParts<AppState> for WorkspaceFeatureEnabled<F> {
+    type Rejection = ErrorResponse;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let WorkspaceAuthorization {
+            ctx_without_snapshot,
+            workspace_id,
+            ..
+        } = parts.extract_with_state(state).await?;
+
+        let workspace = Workspace::get_by_pk(&ctx_without_snapshot, workspace_id)
+            .await
+            .map_err(internal_error)?;
+        if !workspace.feature_flag_enabled(F::FLAG) {
+            return Err(not_found_error("not found"));
+        }
+
+        Ok(Self(PhantomData))
+    }
+}
+
 /// The target workspace id from the path or header.
 ///
 /// *Not* validated in any way (for example, not checked against the token's workspace ID--
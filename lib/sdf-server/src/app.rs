@@ -6,7 +6,10 @@ use dal::ServicesContext;
 use edda_client::EddaClient;
 use frigg::FriggStore;
 use nats_multiplexer_client::MultiplexerClient;
-use sdf_core::nats_multiplexer::EddaUpdatesMultiplexerClient;
+use sdf_core::{
+    long_tasks::LongTasks,
+    nats_multiplexer::EddaUpdatesMultiplexerClient,
+};
 use si_data_spicedb::SpiceDbClient;
 use si_jwt_public_key::JwtPublicSigningKeyChain;
 use si_posthog::PosthogClient;
@@ -20,6 +23,7 @@ use crate::{
     ApplicationRuntimeMode,
     WorkspacePermissions,
     WorkspacePermissionsMode,
+    middleware::RateLimits,
     routes::routes,
 };
 
@@ -45,6 +49,8 @@ impl AxumApp {
         frigg: FriggStore,
         audit_database_context: AuditDatabaseContext,
         edda_client: EddaClient,
+        long_tasks: LongTasks,
+        rate_limits: RateLimits,
     ) -> Self {
         Self::inner_from_services(
             services_context,
@@ -63,6 +69,8 @@ impl AxumApp {
             frigg,
             audit_database_context,
             edda_client,
+            long_tasks,
+            rate_limits,
         )
     }
 
@@ -89,6 +97,7 @@ impl AxumApp {
         frigg: FriggStore,
         audit_database_context: AuditDatabaseContext,
         edda_client: EddaClient,
+        long_tasks: LongTasks,
     ) -> Self {
         Self::inner_from_services(
             services_context,
@@ -107,6 +116,8 @@ impl AxumApp {
             frigg,
             audit_database_context,
             edda_client,
+            long_tasks,
+            RateLimits::default(),
         )
     }
 
@@ -132,6 +143,8 @@ impl AxumApp {
         frigg: FriggStore,
         audit_database_context: AuditDatabaseContext,
         edda_client: EddaClient,
+        long_tasks: LongTasks,
+        rate_limits: RateLimits,
     ) -> Self {
         let state = AppState::new(
             services_context,
@@ -150,6 +163,7 @@ impl AxumApp {
             frigg,
             audit_database_context,
             edda_client,
+            long_tasks,
         );
 
         let path_filter = Box::new(|path: &str| match path {
@@ -157,7 +171,7 @@ impl AxumApp {
             _ => None,
         });
 
-        let app = routes(state).layer(
+        let app = routes(state, rate_limits).layer(
             TraceLayer::new_for_http()
                 .make_span_with(
                     telemetry_http::HttpMakeSpan::builder()
@@ -1,5 +1,8 @@
 use std::{
-    collections::HashSet,
+    collections::{
+        BTreeSet,
+        HashSet,
+    },
     env,
     net::{
         SocketAddr,
@@ -9,12 +12,17 @@ use std::{
         Path,
         PathBuf,
     },
+    time::Duration,
 };
 
 use audit_database::AuditDatabaseConfig;
 use buck2_resources::Buck2Resources;
 pub use dal::MigrationMode;
-use dal::feature_flags::FeatureFlag;
+use dal::{
+    feature_flags::FeatureFlag,
+    health::HealthDependency,
+    ws_event_log::WsEventLog,
+};
 use derive_builder::Builder;
 pub use sdf_core::workspace_permissions::{
     WorkspacePermissions,
@@ -54,9 +62,21 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use ulid::Ulid;
 
+use crate::middleware::{
+    DEFAULT_ACTION_BATCH_RATE_LIMIT_PER_MINUTE,
+    DEFAULT_ACTION_RUN_RATE_LIMIT_PER_MINUTE,
+    DEFAULT_MODULE_INSTALL_RATE_LIMIT_PER_MINUTE,
+    RateLimit,
+    RateLimits,
+};
+
 const DEFAULT_MODULE_INDEX_URL: &str = "https://module-index.systeminit.com";
 const DEFAULT_AUTH_API_URL: &str = "https://auth-api.systeminit.com";
 
+const DEFAULT_SHUTDOWN_GRACE_PERIOD_SECS: u64 = 60;
+const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration =
+    Duration::from_secs(DEFAULT_SHUTDOWN_GRACE_PERIOD_SECS);
+
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -141,6 +161,18 @@ pub struct Config {
 
     #[builder(default)]
     dev_mode: bool,
+
+    #[builder(default = "HealthDependency::default_critical()")]
+    readiness_critical_dependencies: BTreeSet<HealthDependency>,
+
+    #[builder(default = "default_shutdown_grace_period()")]
+    shutdown_grace_period: Duration,
+
+    #[builder(default = "WsEventLog::default_excluded_kinds()")]
+    ws_event_log_excluded_kinds: BTreeSet<String>,
+
+    #[builder(default)]
+    rate_limits: RateLimits,
 }
 
 impl StandardConfig for Config {
@@ -257,6 +289,27 @@ impl Config {
     pub fn dev_mode(&self) -> bool {
         self.dev_mode
     }
+
+    /// Gets the dependencies that must be healthy for the server to report itself as ready.
+    pub fn readiness_critical_dependencies(&self) -> &BTreeSet<HealthDependency> {
+        &self.readiness_critical_dependencies
+    }
+
+    /// Gets how long in-flight long tasks (workspace installs, module cache updates, etc.) get to
+    /// finish when shutting down before they are cancelled.
+    pub fn shutdown_grace_period(&self) -> Duration {
+        self.shutdown_grace_period
+    }
+
+    /// Gets the kinds of WsEvents which are never recorded in the ws event log.
+    pub fn ws_event_log_excluded_kinds(&self) -> &BTreeSet<String> {
+        &self.ws_event_log_excluded_kinds
+    }
+
+    /// Gets how often each user can call the rate limited routes.
+    pub fn rate_limits(&self) -> RateLimits {
+        self.rate_limits
+    }
 }
 
 impl ConfigBuilder {
@@ -309,6 +362,18 @@ pub struct ConfigFile {
     spicedb: SpiceDbConfig,
     #[serde(default)]
     audit: AuditDatabaseConfig,
+    #[serde(default = "default_readiness_critical_dependencies")]
+    readiness_critical_dependencies: Vec<HealthDependency>,
+    #[serde(default = "default_shutdown_grace_period_secs")]
+    shutdown_grace_period_secs: u64,
+    #[serde(default = "default_ws_event_log_excluded_kinds")]
+    ws_event_log_excluded_kinds: Vec<String>,
+    #[serde(default = "default_action_run_rate_limit_per_minute")]
+    action_run_rate_limit_per_minute: u32,
+    #[serde(default = "default_action_batch_rate_limit_per_minute")]
+    action_batch_rate_limit_per_minute: u32,
+    #[serde(default = "default_module_install_rate_limit_per_minute")]
+    module_install_rate_limit_per_minute: u32,
 }
 
 impl Default for ConfigFile {
@@ -333,6 +398,12 @@ impl Default for ConfigFile {
             spicedb: Default::default(),
             audit: Default::default(),
             dev_mode: false,
+            readiness_critical_dependencies: default_readiness_critical_dependencies(),
+            shutdown_grace_period_secs: default_shutdown_grace_period_secs(),
+            ws_event_log_excluded_kinds: default_ws_event_log_excluded_kinds(),
+            action_run_rate_limit_per_minute: default_action_run_rate_limit_per_minute(),
+            action_batch_rate_limit_per_minute: default_action_batch_rate_limit_per_minute(),
+            module_install_rate_limit_per_minute: default_module_install_rate_limit_per_minute(),
         }
     }
 }
@@ -368,6 +439,17 @@ impl TryFrom<ConfigFile> for Config {
             spicedb: value.spicedb,
             audit: value.audit,
             dev_mode: value.dev_mode,
+            readiness_critical_dependencies: value
+                .readiness_critical_dependencies
+                .into_iter()
+                .collect(),
+            shutdown_grace_period: Duration::from_secs(value.shutdown_grace_period_secs),
+            ws_event_log_excluded_kinds: value.ws_event_log_excluded_kinds.into_iter().collect(),
+            rate_limits: RateLimits {
+                action_run: RateLimit::per_minute(value.action_run_rate_limit_per_minute),
+                action_batch: RateLimit::per_minute(value.action_batch_rate_limit_per_minute),
+                module_install: RateLimit::per_minute(value.module_install_rate_limit_per_minute),
+            },
         })
     }
 }
@@ -425,10 +507,38 @@ fn default_auth_api_url() -> String {
     DEFAULT_AUTH_API_URL.into()
 }
 
+fn default_readiness_critical_dependencies() -> Vec<HealthDependency> {
+    HealthDependency::default_critical().into_iter().collect()
+}
+
 fn default_layer_db_config() -> LayerDbConfig {
     LayerDbConfig::default()
 }
 
+fn default_shutdown_grace_period() -> Duration {
+    DEFAULT_SHUTDOWN_GRACE_PERIOD
+}
+
+fn default_shutdown_grace_period_secs() -> u64 {
+    DEFAULT_SHUTDOWN_GRACE_PERIOD_SECS
+}
+
+fn default_ws_event_log_excluded_kinds() -> Vec<String> {
+    WsEventLog::default_excluded_kinds().into_iter().collect()
+}
+
+fn default_action_run_rate_limit_per_minute() -> u32 {
+    DEFAULT_ACTION_RUN_RATE_LIMIT_PER_MINUTE
+}
+
+fn default_action_batch_rate_limit_per_minute() -> u32 {
+    DEFAULT_ACTION_BATCH_RATE_LIMIT_PER_MINUTE
+}
+
+fn default_module_install_rate_limit_per_minute() -> u32 {
+    DEFAULT_MODULE_INSTALL_RATE_LIMIT_PER_MINUTE
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
@@ -130,7 +130,9 @@ pub(crate) async fn services_context_from_config(
         layer_db,
         feature_flags_service,
         compute_executor,
-    );
+    )
+    .with_critical_health_dependencies(config.readiness_critical_dependencies().iter().copied())
+    .with_ws_event_log_excluded_kinds(config.ws_event_log_excluded_kinds().iter().cloned());
 
     Ok((services_context, layer_db_graceful_shutdown))
 }
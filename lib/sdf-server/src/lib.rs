@@ -67,7 +67,10 @@ pub use self::{
     },
     garbage_collection::SnapshotGarbageCollector,
     migrations::Migrator,
-    nats_multiplexer::CRDT_MULTIPLEXER_SUBJECT,
+    nats_multiplexer::{
+        CRDT_MULTIPLEXER_SUBJECT,
+        WS_MULTIPLEXER_SUBJECT,
+    },
     server::{
         Server,
         ServerMetadata,
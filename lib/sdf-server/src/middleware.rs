@@ -1,6 +1,18 @@
+mod rate_limit;
 mod workspace_permission;
 
-pub use self::workspace_permission::{
-    WorkspacePermission,
-    WorkspacePermissionLayer,
+pub use self::{
+    rate_limit::{
+        DEFAULT_ACTION_BATCH_RATE_LIMIT_PER_MINUTE,
+        DEFAULT_ACTION_RUN_RATE_LIMIT_PER_MINUTE,
+        DEFAULT_MODULE_INSTALL_RATE_LIMIT_PER_MINUTE,
+        RateLimit,
+        RateLimitLayer,
+        RateLimited,
+        RateLimits,
+    },
+    workspace_permission::{
+        WorkspacePermission,
+        WorkspacePermissionLayer,
+    },
 };
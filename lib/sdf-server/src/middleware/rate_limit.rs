@@ -0,0 +1,291 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    task::{
+        Context,
+        Poll,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use axum::{
+    RequestPartsExt as _,
+    body::Body,
+    extract::MatchedPath,
+    http::{
+        Request,
+        StatusCode,
+        header,
+    },
+    response::{
+        IntoResponse,
+        Response,
+    },
+};
+use dal::UserPk;
+use futures::future::BoxFuture;
+use sdf_core::api_error::ApiError;
+use tower::{
+    Layer,
+    Service,
+};
+
+use crate::{
+    AppState,
+    extract::request::HistoryActor,
+};
+
+/// How many requests a user may make to a route in a window. Requests are replenished steadily
+/// over the window rather than all at once at its end, so bursts up to the limit are allowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    requests: u32,
+    window: Duration,
+}
+
+impl RateLimit {
+    pub const fn new(requests: u32, window: Duration) -> Self {
+        Self { requests, window }
+    }
+
+    pub const fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    /// The time it takes for a single request to be replenished.
+    fn refill_interval(&self) -> Duration {
+        self.window / self.requests.max(1)
+    }
+}
+
+/// How often a user can enqueue or retry individual actions, by default.
+pub const DEFAULT_ACTION_RUN_RATE_LIMIT_PER_MINUTE: u32 = 60;
+/// How often a user can run a prototype for every matching component, by default.
+pub const DEFAULT_ACTION_BATCH_RATE_LIMIT_PER_MINUTE: u32 = 10;
+/// How often a user can install modules, by default.
+pub const DEFAULT_MODULE_INSTALL_RATE_LIMIT_PER_MINUTE: u32 = 10;
+
+/// The limit of each group of rate limited routes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Enqueueing or retrying individual actions.
+    pub action_run: RateLimit,
+    /// Running a prototype for every matching component, as each one enqueues many actions.
+    pub action_batch: RateLimit,
+    /// Installing modules, from the cache or from a file.
+    pub module_install: RateLimit,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            action_run: RateLimit::per_minute(DEFAULT_ACTION_RUN_RATE_LIMIT_PER_MINUTE),
+            action_batch: RateLimit::per_minute(DEFAULT_ACTION_BATCH_RATE_LIMIT_PER_MINUTE),
+            module_install: RateLimit::per_minute(DEFAULT_MODULE_INSTALL_RATE_LIMIT_PER_MINUTE),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.requests as f64,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        let refilled = elapsed.as_secs_f64() / limit.refill_interval().as_secs_f64();
+        self.tokens = (self.tokens + refilled).min(limit.requests as f64);
+        self.updated_at = now;
+    }
+
+    /// Takes a token for a request, or returns how long until one is available.
+    fn take(&mut self, limit: RateLimit, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(limit.refill_interval().mul_f64(1.0 - self.tokens))
+        }
+    }
+
+    fn is_full(&self, limit: RateLimit) -> bool {
+        self.tokens >= limit.requests as f64
+    }
+}
+
+#[derive(Debug)]
+struct Buckets {
+    by_user_and_route: HashMap<(UserPk, String), TokenBucket>,
+    swept_at: Instant,
+}
+
+/// Limits how often each user can call the routes it is applied to, answering with a 429 and a
+/// `Retry-After` header once they go over. Every route is limited separately. Requests made by
+/// the system rather than a user are never limited.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    state: AppState,
+    limit: RateLimit,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RateLimitLayer {
+    pub fn new(state: AppState, limit: RateLimit) -> Self {
+        Self {
+            state,
+            limit,
+            buckets: Arc::new(Mutex::new(Buckets {
+                by_user_and_route: HashMap::new(),
+                swept_at: Instant::now(),
+            })),
+        }
+    }
+
+    fn check(&self, user_pk: UserPk, route: String, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|err| err.into_inner());
+
+        // Forget about users who have been idle long enough for their buckets to fill back up,
+        // as they would be treated the same as a user we have never seen.
+        if now.saturating_duration_since(buckets.swept_at) >= self.limit.window {
+            let limit = self.limit;
+            buckets.by_user_and_route.retain(|_, bucket| {
+                bucket.refill(limit, now);
+                !bucket.is_full(limit)
+            });
+            buckets.swept_at = now;
+        }
+
+        buckets
+            .by_user_and_route
+            .entry((user_pk, route))
+            .or_insert_with(|| TokenBucket::full(self.limit, now))
+            .take(self.limit, now)
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimited<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimited {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimited<S> {
+    inner: S,
+    layer: RateLimitLayer,
+}
+
+impl<S> Service<Request<Body>> for RateLimited<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut me = self.clone();
+
+        Box::pin(async move {
+            let (mut parts, body) = req.into_parts();
+
+            let user_pk = match parts.extract_with_state(&me.layer.state).await {
+                Ok(HistoryActor(si_db::HistoryActor::User(user_pk))) => Some(user_pk),
+                Ok(HistoryActor(si_db::HistoryActor::SystemInit)) => None,
+                Err(err) => return Ok(err.into_response()),
+            };
+
+            if let Some(user_pk) = user_pk {
+                let route = match parts.extensions.get::<MatchedPath>() {
+                    Some(matched_path) => matched_path.as_str().to_owned(),
+                    None => parts.uri.path().to_owned(),
+                };
+                if let Err(retry_after) = me.layer.check(user_pk, route, Instant::now()) {
+                    return Ok((
+                        [(
+                            header::RETRY_AFTER,
+                            retry_after.as_secs_f64().ceil().to_string(),
+                        )],
+                        ApiError::new(
+                            StatusCode::TOO_MANY_REQUESTS,
+                            "too many requests, please retry later",
+                        ),
+                    )
+                        .into_response());
+                }
+            }
+
+            let req = Request::from_parts(parts, body);
+
+            let response = me.inner.call(req).await?;
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_bursts_up_to_the_limit() {
+        let limit = RateLimit::per_minute(10);
+        let now = Instant::now();
+        let mut bucket = TokenBucket::full(limit, now);
+
+        for _ in 0..10 {
+            assert_eq!(Ok(()), bucket.take(limit, now));
+        }
+        assert_eq!(Err(Duration::from_secs(6)), bucket.take(limit, now));
+    }
+
+    #[test]
+    fn bucket_refills_over_the_window() {
+        let limit = RateLimit::per_minute(10);
+        let now = Instant::now();
+        let mut bucket = TokenBucket::full(limit, now);
+        for _ in 0..10 {
+            assert_eq!(Ok(()), bucket.take(limit, now));
+        }
+
+        // Halfway to the next request
+        let later = now + Duration::from_secs(3);
+        assert_eq!(Err(Duration::from_secs(3)), bucket.take(limit, later));
+
+        let later = now + Duration::from_secs(6);
+        assert_eq!(Ok(()), bucket.take(limit, later));
+        assert!(bucket.take(limit, later).is_err());
+
+        // Never refills past the limit, however long the wait
+        let much_later = now + Duration::from_secs(60 * 60);
+        bucket.refill(limit, much_later);
+        assert!(bucket.is_full(limit));
+        assert_eq!(10.0, bucket.tokens);
+    }
+}
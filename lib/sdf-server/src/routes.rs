@@ -17,14 +17,17 @@ use axum::{
     },
     routing::get,
 };
+use dal::health::HealthReport;
 use hyper::{
     Method,
     header,
 };
+use sdf_extract::request::ValidatedToken;
 use serde_json::{
     Value,
     json,
 };
+use telemetry::prelude::*;
 use tower_http::{
     compression::CompressionLayer,
     cors::{
@@ -33,9 +36,12 @@ use tower_http::{
     },
 };
 
-use crate::app_state::{
-    AppState,
-    ApplicationRuntimeMode,
+use crate::{
+    app_state::{
+        AppState,
+        ApplicationRuntimeMode,
+    },
+    middleware::RateLimits,
 };
 
 const MAINTENANCE_MODE_MESSAGE: &str = concat!(
@@ -60,10 +66,13 @@ async fn app_state_middeware<B>(
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn routes(state: AppState) -> Router {
+pub fn routes(state: AppState, rate_limits: RateLimits) -> Router {
     Router::new()
         .nest("/api", v1_routes())
-        .nest("/api/v2", crate::service::v2::routes(state.clone()))
+        .nest(
+            "/api/v2",
+            crate::service::v2::routes(state.clone(), rate_limits),
+        )
         .nest("/api/whoami", crate::service::whoami::routes())
         .layer(CompressionLayer::new())
         // allows us to be permissive about cors from our owned subdomains
@@ -100,7 +109,10 @@ pub fn routes(state: AppState) -> Router {
         // it is last in the list so that it still services even if we are in maintenance mode
         .nest(
             "/api/",
-            Router::new().route("/", get(system_status_route).layer(CorsLayer::permissive())),
+            Router::new()
+                .route("/", get(system_status_route).layer(CorsLayer::permissive()))
+                .route("/health", get(health_route))
+                .route("/readiness", get(readiness_route)),
         )
         // Load dev routes if we are in dev mode (decided by "opt-level" at the moment).
         .nest("/api/dev", dev_routes())
@@ -122,6 +134,52 @@ async fn system_status_route() -> Json<Value> {
     Json(json!({ "ok": true }))
 }
 
+/// Reports the health of every dependency, whether or not it is critical. The errors of unhealthy
+/// dependencies are logged, and only sent to authenticated callers.
+async fn health_route(
+    State(state): State<AppState>,
+    validated_token: Option<ValidatedToken>,
+) -> Json<HealthReport> {
+    let report = health_report(&state).await;
+    Json(match validated_token {
+        Some(_) => report,
+        None => report.without_errors(),
+    })
+}
+
+/// Reports whether the dependencies needed to serve users are usable, so that broken
+/// configuration (e.g. an unreachable database) is noticed at deploy time. Which dependencies
+/// must be healthy is configured with `readiness_critical_dependencies`. As probes are
+/// unauthenticated, the errors of unhealthy dependencies are only logged.
+async fn readiness_route(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let report = health_report(&state).await.without_errors();
+    let status_code = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status_code,
+        Json(json!({ "ok": report.ready, "dependencies": report.dependencies })),
+    )
+}
+
+async fn health_report(state: &AppState) -> HealthReport {
+    let report = state.services_context().health_report().await;
+    for dependency in &report.dependencies {
+        if let Some(error) = &dependency.error {
+            warn!(
+                si.error.message = error,
+                dependency = %dependency.dependency,
+                critical = dependency.critical,
+                "dependency is unhealthy",
+            );
+        }
+    }
+    report
+}
+
 #[cfg(debug_assertions)]
 pub fn dev_routes() -> Router<AppState> {
     crate::service::dev::routes()
@@ -4,6 +4,7 @@ use std::{
     net::SocketAddr,
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 
 use audit_database::AuditDatabaseContext;
@@ -21,7 +22,10 @@ use frigg::{
 use hyper::server::accept::Accept;
 use nats_multiplexer::Multiplexer;
 use nats_multiplexer_client::MultiplexerClient;
-use sdf_core::nats_multiplexer::EddaUpdatesMultiplexerClient;
+use sdf_core::{
+    long_tasks::LongTasks,
+    nats_multiplexer::EddaUpdatesMultiplexerClient,
+};
 use si_data_nats::jetstream;
 use si_data_spicedb::SpiceDbClient;
 use si_jwt_public_key::JwtPublicSigningKeyChain;
@@ -51,6 +55,7 @@ use crate::{
     WorkspacePermissions,
     WorkspacePermissionsMode,
     init,
+    middleware::RateLimits,
     nats_multiplexer::{
         CRDT_MULTIPLEXER_SUBJECT,
         WS_MULTIPLEXER_SUBJECT,
@@ -196,6 +201,8 @@ impl Server {
             frigg,
             audit_database_context,
             edda_client,
+            config.shutdown_grace_period(),
+            config.rate_limits(),
         )
         .await
     }
@@ -220,7 +227,10 @@ impl Server {
         frigg: FriggStore,
         audit_database_context: AuditDatabaseContext,
         edda_client: EddaClient,
+        shutdown_grace_period: Duration,
+        rate_limits: RateLimits,
     ) -> ServerResult<Self> {
+        let long_tasks = LongTasks::new();
         let app = AxumApp::from_services(
             services_context.clone(),
             jwt_public_signing_key_chain,
@@ -237,6 +247,8 @@ impl Server {
             frigg,
             audit_database_context.clone(),
             edda_client,
+            long_tasks.clone(),
+            rate_limits,
         )
         .into_inner();
 
@@ -248,7 +260,12 @@ impl Server {
                 info!(%socket, "http service bound to tcp socket");
 
                 (
-                    Box::new(InnerServer { inner, token }),
+                    Box::new(InnerServer {
+                        inner,
+                        token,
+                        long_tasks,
+                        shutdown_grace_period,
+                    }),
                     ServerSocket::SocketAddr(socket),
                 )
             }
@@ -260,7 +277,12 @@ impl Server {
                 info!(socket = %socket.display(), "http service bound to unix domain socket");
 
                 (
-                    Box::new(InnerServer { inner, token }),
+                    Box::new(InnerServer {
+                        inner,
+                        token,
+                        long_tasks,
+                        shutdown_grace_period,
+                    }),
                     ServerSocket::DomainSocket(socket),
                 )
             }
@@ -308,6 +330,8 @@ pub enum ServerSocket {
 struct InnerServer<I> {
     inner: axum::Server<I, IntoMakeService<Router>>,
     token: CancellationToken,
+    long_tasks: LongTasks,
+    shutdown_grace_period: Duration,
 }
 
 #[async_trait]
@@ -319,13 +343,21 @@ where
 {
     async fn try_run(self) -> ServerResult<()> {
         let token = self.token;
+        let long_tasks = self.long_tasks;
+        let shutdown_grace_period = self.shutdown_grace_period;
+
+        // Drain the long tasks alongside the graceful shutdown of the http server, since the tasks
+        // outlive the requests that spawned them.
+        let drain_long_tasks = async {
+            token.cancelled().await;
+            long_tasks.drain(shutdown_grace_period).await;
+        };
+        let serve = self.inner.with_graceful_shutdown(async {
+            token.cancelled().await;
+        });
 
-        self.inner
-            .with_graceful_shutdown(async {
-                token.cancelled().await;
-            })
-            .await
-            .map_err(ServerError::Axum)
+        let (served, ()) = tokio::join!(serve, drain_long_tasks);
+        served.map_err(ServerError::Axum)
     }
 }
 
@@ -17,6 +17,7 @@ use crate::{
             WorkspaceAuthorization,
         },
     },
+    middleware::RateLimits,
 };
 
 pub mod action;
@@ -25,6 +26,7 @@ pub mod approval_requirement_definition;
 pub mod audit_log;
 pub mod change_set;
 pub mod component;
+pub mod events;
 pub mod func;
 pub mod index;
 pub mod integrations;
@@ -34,13 +36,16 @@ pub mod variant;
 pub mod view;
 pub mod workspace;
 
-pub fn routes(state: AppState) -> Router<AppState> {
+pub fn routes(state: AppState, rate_limits: RateLimits) -> Router<AppState> {
     Router::new()
         .nest("/admin", admin::v2_routes(state.clone()))
-        .nest("/workspaces/:workspace_id", workspace_routes(state))
+        .nest(
+            "/workspaces/:workspace_id",
+            workspace_routes(state, rate_limits),
+        )
 }
 
-fn workspace_routes(state: AppState) -> Router<AppState> {
+fn workspace_routes(state: AppState, rate_limits: RateLimits) -> Router<AppState> {
     Router::new()
         .nest("/", workspace::v2_routes())
         .nest("/change-sets", change_set::change_sets_routes())
@@ -49,12 +54,13 @@ fn workspace_routes(state: AppState) -> Router<AppState> {
             change_set::change_set_routes(state.clone())
                 .nest("/audit-logs", audit_log::v2_routes())
                 .nest("/components", component::v2_routes())
+                .nest("/events", events::v2_routes())
                 .nest("/funcs", func::v2_routes())
-                .nest("/modules", module::v2_routes())
+                .nest("/modules", module::v2_routes(state.clone(), rate_limits))
                 .nest("/schema-variants", variant::v2_routes())
                 .nest("/management", management::v2_routes())
                 .nest("/views", view::v2_routes())
-                .nest("/action", action::v2_routes())
+                .nest("/action", action::v2_routes(state.clone(), rate_limits))
                 .nest(
                     "/approval-requirement-definitions",
                     approval_requirement_definition::v2_routes(),
@@ -1,8 +1,14 @@
 use axum::{
     Json,
     Router,
-    extract::Path,
-    http::StatusCode,
+    extract::{
+        Path,
+        Query,
+    },
+    http::{
+        StatusCode,
+        header,
+    },
     response::{
         IntoResponse,
         Response,
@@ -31,11 +37,23 @@ use dal::{
     WorkspaceSnapshotError,
     action::{
         Action,
+        batch::{
+            ActionBatch,
+            ActionBatchError,
+            ActionBatchFilter,
+            ActionBatchId,
+            ActionBatchStatus,
+        },
         prototype::{
             ActionKind,
             ActionPrototype,
             ActionPrototypeError,
         },
+        report::{
+            ActionRunReport,
+            ActionRunReportError,
+            ActionRunReportFormat,
+        },
     },
     slow_rt::SlowRuntimeError,
 };
@@ -66,6 +84,10 @@ use thiserror::Error;
 use crate::{
     app_state::AppState,
     extract::HandlerContext,
+    middleware::{
+        RateLimitLayer,
+        RateLimits,
+    },
     service::v2::AccessBuilder,
 };
 
@@ -76,10 +98,14 @@ pub enum ActionRequestError {
     Action(#[from] dal::action::ActionError),
     #[error("action already enqueued: {0}")]
     ActionAlreadyEnqueued(ActionPrototypeId),
+    #[error("action batch error: {0}")]
+    ActionBatch(#[from] ActionBatchError),
     #[error("action history is missing a field - this is a bug!: {0}")]
     ActionHistoryFieldMissing(String),
     #[error("action prototype error: {0}")]
     ActionPrototype(#[from] ActionPrototypeError),
+    #[error("action run report error: {0}")]
+    ActionRunReport(#[from] ActionRunReportError),
     #[error("changeset error: {0}")]
     ChangeSet(#[from] ChangeSetError),
     #[error("component error: {0}")]
@@ -135,6 +161,18 @@ impl IntoResponse for ActionRequestError {
             | ActionRequestError::Action(dal::action::ActionError::ActionAlreadyEnqueued(_)) => {
                 (StatusCode::CONFLICT, self.to_string())
             }
+            ActionRequestError::Action(dal::action::ActionError::ChangeSet(ref err))
+            | ActionRequestError::ActionPrototype(ActionPrototypeError::ChangeSet(ref err))
+                if matches!(**err, ChangeSetError::ChangeSetNotActive { .. }) =>
+            {
+                (StatusCode::CONFLICT, self.to_string())
+            }
+            ActionRequestError::ChangeSet(ChangeSetError::ChangeSetNotActive { .. }) => {
+                (StatusCode::CONFLICT, self.to_string())
+            }
+            ActionRequestError::ActionBatch(ActionBatchError::ActionBatchNotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -142,15 +180,27 @@ impl IntoResponse for ActionRequestError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
-    Router::new()
+pub fn v2_routes(state: AppState, rate_limits: RateLimits) -> Router<AppState> {
+    let run_routes = Router::new()
         .route("/add", post(add))
+        .route("/:action_id/retry", put(retry))
+        .route_layer(RateLimitLayer::new(state.clone(), rate_limits.action_run));
+
+    let batch_routes = Router::new()
+        .route("/batch", post(run_batch))
+        .route("/batch/:batch_id/rerun_failed", post(rerun_failed_batch))
+        .route_layer(RateLimitLayer::new(state, rate_limits.action_batch));
+
+    Router::new()
+        .route("/batch/:batch_id", get(batch_status))
         .route("/refresh/:component_id", put(refresh))
+        .route("/report", get(report))
         .route("/:action_id/cancel", put(cancel))
         .route("/:action_id/put_on_hold", put(hold))
-        .route("/:action_id/retry", put(retry))
         .route("/:action_id/func_run_id", get(get_func_run_id))
         .route("/:action_id/queued_details", get(queued_details))
+        .merge(run_routes)
+        .merge(batch_routes)
 }
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -202,6 +252,79 @@ pub async fn add(
     Ok(ForceChangeSetResponse::new(force_change_set_id, ()))
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunBatchRequest {
+    pub prototype_id: ActionPrototypeId,
+    #[serde(default)]
+    pub filter: ActionBatchFilter,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunBatchResponse {
+    pub batch_id: ActionBatchId,
+    pub action_ids: Vec<ActionId>,
+}
+
+/// Enqueues the prototype for every component it applies to that matches the filter.
+pub async fn run_batch(
+    ChangeSetDalContext(ref mut ctx): ChangeSetDalContext,
+    Path((_workspace_pk, _change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Json(RunBatchRequest {
+        prototype_id,
+        filter,
+    }): Json<RunBatchRequest>,
+) -> ActionResult<ForceChangeSetResponse<RunBatchResponse>> {
+    let force_change_set_id = ChangeSet::force_new(ctx).await?;
+
+    let batch = ActionBatch::run_for_all_components(ctx, prototype_id, filter).await?;
+
+    ctx.commit().await?;
+    Ok(ForceChangeSetResponse::new(
+        force_change_set_id,
+        RunBatchResponse {
+            batch_id: batch.id(),
+            action_ids: batch.action_ids(),
+        },
+    ))
+}
+
+/// Re-runs the members of a batch that did not succeed, as a new batch.
+pub async fn rerun_failed_batch(
+    ChangeSetDalContext(ref mut ctx): ChangeSetDalContext,
+    Path((_workspace_pk, _change_set_id, batch_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        ActionBatchId,
+    )>,
+) -> ActionResult<ForceChangeSetResponse<RunBatchResponse>> {
+    let force_change_set_id = ChangeSet::force_new(ctx).await?;
+
+    let batch = ActionBatch::rerun_failed(ctx, batch_id).await?;
+
+    ctx.commit().await?;
+    Ok(ForceChangeSetResponse::new(
+        force_change_set_id,
+        RunBatchResponse {
+            batch_id: batch.id(),
+            action_ids: batch.action_ids(),
+        },
+    ))
+}
+
+pub async fn batch_status(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id, batch_id)): Path<(WorkspacePk, ChangeSetId, ActionBatchId)>,
+) -> ActionResult<Json<ActionBatchStatus>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    Ok(Json(ActionBatch::status(&ctx, batch_id).await?))
+}
+
 pub async fn refresh(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
@@ -396,3 +519,28 @@ pub async fn queued_details(
     };
     Ok(Json(QueuedDetails { code, args }))
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportParams {
+    #[serde(default)]
+    pub format: ActionRunReportFormat,
+}
+
+/// Exports the runs of the actions applied from the change set, as JSON or as JUnit XML (with
+/// `?format=junit`).
+pub async fn report(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(params): Query<ReportParams>,
+) -> ActionResult<Response> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let report = ActionRunReport::for_originating_change_set(&ctx, change_set_id).await?;
+    let body = report.render(params.format)?;
+
+    Ok(([(header::CONTENT_TYPE, params.format.content_type())], body).into_response())
+}
@@ -70,6 +70,8 @@ mod kill_execution;
 mod list_change_sets;
 mod search_workspaces;
 mod set_concurrency_limit;
+mod set_execution_budget;
+mod set_feature_flag;
 mod set_snapshot;
 mod update_module_cache;
 mod upload_cas_data;
@@ -212,11 +214,19 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
             "/update_module_cache",
             post(update_module_cache::update_module_cache),
         )
+        .route(
+            "/execution_budget",
+            put(set_execution_budget::set_execution_budget),
+        )
         .route("/workspaces", get(search_workspaces::search_workspaces))
         .route(
             "/workspaces/:workspace_id/set_concurrency_limit",
             post(set_concurrency_limit::set_concurrency_limit),
         )
+        .route(
+            "/workspaces/:workspace_id/set_feature_flag",
+            post(set_feature_flag::set_feature_flag),
+        )
         .route(
             "/workspaces/:workspace_id/change_sets",
             get(list_change_sets::list_change_sets),
@@ -0,0 +1,48 @@
+use axum::response::Json;
+use dal::func::execution_budget::DEFAULT_EXECUTION_BUDGET_PERMITS;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use telemetry::prelude::*;
+
+use crate::service::v2::admin::{
+    AdminAPIResult,
+    AdminUserContext,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetExecutionBudgetRequest {
+    /// The number of functions each workspace can have executing at once, or the default if
+    /// `None`.
+    pub permits: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetExecutionBudgetResponse {
+    pub permits: usize,
+}
+
+/// Changes the per-workspace function execution budget of this sdf instance. Other services keep
+/// their own budgets.
+#[instrument(
+    name = "admin.set_execution_budget",
+    level = "info",
+    skip_all,
+    fields(si.execution_budget.permits = Empty),
+)]
+pub async fn set_execution_budget(
+    AdminUserContext(ctx): AdminUserContext,
+    Json(request): Json<SetExecutionBudgetRequest>,
+) -> AdminAPIResult<Json<SetExecutionBudgetResponse>> {
+    let span = current_span_for_instrument_at!("info");
+
+    let budget = ctx.execution_budget();
+    budget.set_permits(request.permits.unwrap_or(DEFAULT_EXECUTION_BUDGET_PERMITS));
+    let permits = budget.permits();
+    span.record("si.execution_budget.permits", permits);
+
+    Ok(Json(SetExecutionBudgetResponse { permits }))
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{
+        Host,
+        OriginalUri,
+        Path,
+    },
+    response::Json,
+};
+use dal::{
+    Workspace,
+    WorkspaceFeatureFlag,
+    WorkspacePk,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_db::Tenancy;
+use telemetry::prelude::*;
+
+use crate::{
+    extract::PosthogClient,
+    service::v2::admin::{
+        AdminAPIResult,
+        AdminUserContext,
+    },
+    track_no_ctx_workspace,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFeatureFlagRequest {
+    pub flag: WorkspaceFeatureFlag,
+    /// Whether the feature is enabled, or `None` to go back to the flag's default.
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFeatureFlagResponse {
+    pub enabled: bool,
+    pub feature_flags: BTreeMap<String, bool>,
+}
+
+#[instrument(
+    name = "admin.set_feature_flag",
+    level = "info",
+    skip_all,
+    fields(
+        si.workspace.id = %workspace_id,
+        si.workspace.feature_flag = %request.flag,
+        si.workspace.feature_flag.enabled = Empty,
+    ),
+)]
+pub async fn set_feature_flag(
+    AdminUserContext(mut ctx): AdminUserContext,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path(workspace_id): Path<WorkspacePk>,
+    Json(request): Json<SetFeatureFlagRequest>,
+) -> AdminAPIResult<Json<SetFeatureFlagResponse>> {
+    ctx.update_tenancy(Tenancy::new(workspace_id));
+
+    let span = current_span_for_instrument_at!("info");
+
+    span.record(
+        "si.workspace.feature_flag.enabled",
+        request
+            .enabled
+            .map(|enabled| enabled.to_string())
+            .unwrap_or("default".to_string()),
+    );
+
+    let mut workspace = Workspace::get_by_pk(&ctx, workspace_id).await?;
+
+    workspace
+        .set_feature_flag(&ctx, request.flag, request.enabled)
+        .await?;
+
+    ctx.commit_no_rebase().await?;
+
+    track_no_ctx_workspace(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        workspace_id,
+        "admin.set_feature_flag",
+        serde_json::json!({
+            "flag": request.flag,
+            "enabled": request.enabled,
+        }),
+    );
+
+    Ok(Json(SetFeatureFlagResponse {
+        enabled: workspace.feature_flag_enabled(request.flag),
+        feature_flags: workspace.feature_flags().clone(),
+    }))
+}
@@ -8,11 +8,19 @@ use axum::{
 };
 use dal::{
     DalContext,
-    WsEvent,
     cached_module::CachedModule,
 };
-use sdf_core::async_route::handle_error;
-use sdf_extract::EddaClient;
+use sdf_core::{
+    async_route::{
+        handle_error,
+        handle_finish,
+    },
+    long_tasks::LongTaskKind,
+};
+use sdf_extract::{
+    EddaClient,
+    LongTaskSpawner,
+};
 use serde::{
     Deserialize,
     Serialize,
@@ -47,34 +55,32 @@ pub async fn update_module_cache(
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
     EddaClient(edda_client): EddaClient,
+    LongTaskSpawner(long_tasks): LongTaskSpawner,
 ) -> AdminAPIResult<Json<UpdateModuleCacheResponse>> {
     let task_id = Ulid::new();
 
     ctx.update_tenancy(Tenancy::new(workspace_id.into()));
-    tokio::task::spawn(async move {
-        if let Err(err) = update_cached_modules_inner(
-            &ctx,
-            &original_uri,
-            &host_name,
-            PosthogClient(posthog_client),
-            edda_client,
-        )
-        .await
-        {
-            return handle_error(&ctx, original_uri, task_id, err).await;
-        };
-
-        let event = match WsEvent::async_finish_workspace(&ctx, task_id).await {
-            Ok(event) => event,
-            Err(err) => {
-                return handle_error(&ctx, original_uri, task_id, err).await;
-            }
-        };
+    long_tasks.spawn_async_route(
+        ctx,
+        original_uri,
+        task_id,
+        LongTaskKind::ModuleCacheUpdate,
+        move |ctx, original_uri, statuses| async move {
+            if let Err(err) = update_cached_modules_inner(
+                &ctx,
+                &original_uri,
+                &host_name,
+                PosthogClient(posthog_client),
+                edda_client,
+            )
+            .await
+            {
+                return handle_error(&ctx, &statuses, original_uri, task_id, err).await;
+            };
 
-        if let Err(err) = event.publish_immediately(&ctx).await {
-            handle_error(&ctx, original_uri, task_id, err).await;
-        };
-    });
+            handle_finish(&ctx, &statuses, original_uri, task_id).await;
+        },
+    );
 
     Ok(Json(UpdateModuleCacheResponse { id: task_id }))
 }
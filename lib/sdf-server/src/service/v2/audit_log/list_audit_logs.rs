@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
-use audit_database::AuditLogRow;
+use audit_database::{
+    AuditLogCursor,
+    AuditLogFilter,
+    AuditLogRow,
+};
 use axum::{
     Json,
     extract::{
@@ -9,15 +13,21 @@ use axum::{
         State,
     },
 };
+use chrono::{
+    DateTime,
+    Utc,
+};
 use dal::{
     ChangeSet,
     DalContext,
     audit_logging,
 };
+use sdf_extract::PosthogEventTracker;
 use serde::{
     Deserialize,
     Serialize,
 };
+use serde_json::json;
 use si_db::User;
 use si_events::{
     ChangeSetId,
@@ -37,6 +47,40 @@ use crate::{
 pub struct ListAuditLogsRequest {
     size: Option<usize>,
     sort_ascending: Option<bool>,
+    /// Comma-separated list of audit log kinds to include.
+    kinds: Option<String>,
+    /// Only include audit logs written by this user.
+    user_id: Option<UserPk>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    /// Together with `after_pk`, the `nextCursor` of the previous page.
+    after_timestamp: Option<DateTime<Utc>>,
+    after_pk: Option<i64>,
+}
+
+impl ListAuditLogsRequest {
+    fn filter(&self) -> AuditLogFilter {
+        AuditLogFilter {
+            kinds: self
+                .kinds
+                .iter()
+                .flat_map(|kinds| kinds.split(','))
+                .map(str::trim)
+                .filter(|kind| !kind.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+            user_ids: self.user_id.into_iter().collect(),
+            since: self.since,
+            until: self.until,
+        }
+    }
+
+    fn after(&self) -> Option<AuditLogCursor> {
+        match (self.after_timestamp, self.after_pk) {
+            (Some(timestamp), Some(pk)) => Some(AuditLogCursor { timestamp, pk }),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -44,6 +88,7 @@ pub struct ListAuditLogsRequest {
 pub struct ListAuditLogsResponse {
     logs: Vec<frontend_types::AuditLog>,
     can_load_more: bool,
+    next_cursor: Option<AuditLogCursor>,
 }
 
 pub async fn list_audit_logs(
@@ -52,14 +97,18 @@ pub async fn list_audit_logs(
     Path((_workspace_pk, change_set_id)): Path<(dal::WorkspacePk, dal::ChangeSetId)>,
     Query(request): Query<ListAuditLogsRequest>,
     State(state): State<AppState>,
+    tracker: PosthogEventTracker,
 ) -> AuditLogResult<Json<ListAuditLogsResponse>> {
     let ctx = builder
         .build(access_builder.build(change_set_id.into()))
         .await?;
 
-    let (database_logs, can_load_more) = audit_logging::list(
+    let filter = request.filter();
+    let (database_logs, next_cursor) = audit_logging::list_filtered(
         &ctx,
         state.audit_database_context(),
+        &filter,
+        request.after(),
         request.size.unwrap_or(0),
         request.sort_ascending.unwrap_or(false),
     )
@@ -71,9 +120,20 @@ pub async fn list_audit_logs(
         logs.push(assembler.assemble(&ctx, database_log).await?);
     }
 
+    tracker.track(
+        &ctx,
+        "list_audit_logs",
+        json!({
+            "kinds": filter.kinds,
+            "filtered_by_user": !filter.user_ids.is_empty(),
+            "paged": request.after().is_some(),
+        }),
+    );
+
     Ok(Json(ListAuditLogsResponse {
         logs,
-        can_load_more,
+        can_load_more: next_cursor.is_some(),
+        next_cursor,
     }))
 }
 
@@ -110,6 +170,7 @@ pub async fn list_audit_logs_for_component(
     Ok(Json(ListAuditLogsResponse {
         logs,
         can_load_more,
+        next_cursor: None,
     }))
 }
 
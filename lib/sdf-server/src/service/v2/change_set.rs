@@ -55,10 +55,13 @@ mod list;
 mod rename;
 mod reopen;
 mod request_approval;
+mod summary;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] dal::action::prototype::ActionPrototypeError),
     #[error("attributes error: {0}")]
     Attributes(#[from] dal::attribute::attributes::AttributesError),
     #[error("cannot abandon head change set")]
@@ -71,6 +74,8 @@ pub enum Error {
     ChangeSetApproval(#[from] dal::change_set::approval::ChangeSetApprovalError),
     #[error("change set mvs error: {0}")]
     ChangeSetMvs(#[from] sdf_core::change_set_mvs::ChangeSetMvsError),
+    #[error("change set summary error: {0}")]
+    ChangeSetSummary(#[from] dal::change_set::summary::ChangeSetSummaryError),
     #[error("component error: {0}")]
     Component(#[from] dal::ComponentError),
     #[error("dal wrapper error: {0}")]
@@ -260,6 +265,7 @@ pub fn change_set_routes(state: AppState) -> Router<AppState> {
             "/request_approval",
             post(request_approval::request_approval),
         )
+        .route("/summary", get(summary::summary))
         .nest("/index", super::index::v2_change_set_routes())
 }
 
@@ -0,0 +1,46 @@
+use axum::{
+    Json,
+    extract::State,
+};
+use dal::{
+    action::prototype::{
+        ActionPrototype,
+        ActionPrototypeImpact,
+    },
+    change_set::summary::ChangeSetSummary,
+};
+use sdf_extract::change_set::ChangeSetDalContext;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::Result;
+use crate::app_state::AppState;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetSummaryResponse {
+    #[serde(flatten)]
+    pub summary: ChangeSetSummary,
+    pub pending_async_tasks: usize,
+    /// The action prototypes whose func code changes when the change set is applied.
+    pub impacted_action_prototypes: Vec<ActionPrototypeImpact>,
+}
+
+pub async fn summary(
+    ChangeSetDalContext(ref ctx): ChangeSetDalContext,
+    State(state): State<AppState>,
+) -> Result<Json<ChangeSetSummaryResponse>> {
+    let summary = ChangeSetSummary::assemble(ctx).await?;
+    let impacted_action_prototypes = ActionPrototype::preview_impact(ctx).await?;
+
+    Ok(Json(ChangeSetSummaryResponse {
+        summary,
+        pending_async_tasks: state
+            .long_tasks()
+            .task_statuses()
+            .pending_count(ctx.tenancy().workspace_pk_opt()),
+        impacted_action_prototypes,
+    }))
+}
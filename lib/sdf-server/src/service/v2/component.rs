@@ -32,9 +32,11 @@ pub mod attributes;
 pub mod autosubscribe;
 pub mod debug_component;
 pub mod delete_components;
+pub mod export_resources;
 pub mod get_json;
 pub mod manage;
 pub mod name;
+pub mod resource;
 pub mod restore_components;
 pub mod secrets;
 pub mod upgrade_components;
@@ -92,10 +94,12 @@ impl IntoResponse for Error {
             Error::SchemaVariantUpgradeSkipped | Error::UpgradeSkippedDueToActions => {
                 StatusCode::NOT_MODIFIED
             }
-            Error::AttributeValueNotFound(_, _) => StatusCode::NOT_FOUND,
+            Error::AttributeValueNotFound(_, _)
+            | Error::Component(dal::ComponentError::NotFound(_)) => StatusCode::NOT_FOUND,
             Error::Attributes(AttributesError::AttributeValue(
                 AttributeValueError::SubscriptionTypeMismatch { .. },
-            )) => StatusCode::BAD_REQUEST,
+            ))
+            | Error::JsonptrParseError(_) => StatusCode::BAD_REQUEST,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
@@ -109,6 +113,7 @@ pub fn v2_routes() -> Router<AppState> {
         .route("/upgrade", post(upgrade_components::upgrade_components))
         .route("/delete", delete(delete_components::delete_components))
         .route("/restore", put(restore_components::restore_components))
+        .route("/resources/export", get(export_resources::export_resources))
         .route(
             "/autosubscribe",
             post(autosubscribe::autosubscribe_component),
@@ -120,6 +125,7 @@ pub fn v2_routes() -> Router<AppState> {
                 .route("/json", get(get_json::get_json))
                 .nest("/attributes", attributes::v2_routes())
                 .nest("/name", name::v2_routes())
+                .nest("/resource", resource::v2_routes())
                 .nest("/secret", secrets::v2_routes())
                 .nest("/manage", manage::v2_routes()),
         )
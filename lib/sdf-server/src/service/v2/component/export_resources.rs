@@ -0,0 +1,120 @@
+use axum::{
+    BoxError,
+    body::StreamBody,
+    extract::Query,
+    http::header,
+    response::{
+        IntoResponse,
+        Response,
+    },
+};
+use dal::{
+    component::resource::ResourceInventoryEntry,
+    diagram::view::ViewId,
+};
+use futures::{
+    StreamExt,
+    TryStreamExt,
+    future,
+    stream,
+};
+use sdf_extract::{
+    PosthogEventTracker,
+    change_set::ChangeSetDalContext,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::Result;
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Csv,
+    #[default]
+    Json,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResourcesRequest {
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Only export the resources of components in this view.
+    pub view_id: Option<ViewId>,
+    /// Comma separated JSON pointers into the resource payloads, each exported as its own field.
+    pub fields: Option<String>,
+}
+
+/// Exports the resource inventory of the change set, as either CSV or JSON.
+pub async fn export_resources(
+    ChangeSetDalContext(ref ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Query(request): Query<ExportResourcesRequest>,
+) -> Result<Response> {
+    let fields: Vec<String> = request
+        .fields
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter(|field| !field.is_empty())
+        .map(ToOwned::to_owned)
+        .collect();
+    for field in &fields {
+        jsonptr::Pointer::parse(field)?;
+    }
+
+    // Components are only loaded as the body is written, so an error part way through ends the
+    // response early rather than changing its status.
+    let entries =
+        ResourceInventoryEntry::stream(ctx.clone(), request.view_id, fields.clone()).await?;
+
+    tracker.track(
+        ctx,
+        "export_resources",
+        json!({
+            "how": "/components/resources/export",
+            "format": format!("{:?}", request.format),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    Ok(match request.format {
+        ExportFormat::Csv => {
+            let header = ResourceInventoryEntry::csv_header(&fields);
+            let records = entries.map_ok(move |entry| entry.to_csv_record(&fields));
+            let body = stream::once(future::ok(header))
+                .chain(records)
+                .map_err(BoxError::from);
+            (
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        "attachment; filename=\"resources.csv\"",
+                    ),
+                ],
+                StreamBody::new(body),
+            )
+                .into_response()
+        }
+        ExportFormat::Json => {
+            // Written out as a JSON array, one entry at a time.
+            let elements = entries
+                .map_err(BoxError::from)
+                .enumerate()
+                .map(|(index, entry)| {
+                    let json = serde_json::to_string(&entry?)?;
+                    Ok::<_, BoxError>(if index == 0 { json } else { format!(",{json}") })
+                });
+            let body = stream::once(future::ok("[".to_owned()))
+                .chain(elements)
+                .chain(stream::once(future::ok("]".to_owned())));
+            (
+                [(header::CONTENT_TYPE, "application/json")],
+                StreamBody::new(body),
+            )
+                .into_response()
+        }
+    })
+}
@@ -0,0 +1,112 @@
+use axum::{
+    Json,
+    Router,
+    extract::{
+        OriginalUri,
+        Path,
+    },
+    routing::{
+        get,
+        post,
+    },
+};
+use dal::{
+    action::Action,
+    component::resource::ResourceView,
+};
+use sdf_core::{
+    async_route::{
+        handle_error,
+        handle_finish,
+    },
+    long_tasks::LongTaskKind,
+};
+use sdf_extract::{
+    LongTaskSpawner,
+    PosthogEventTracker,
+    change_set::ChangeSetDalContext,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::json;
+use ulid::Ulid;
+
+use super::{
+    ComponentIdFromPath,
+    Result,
+};
+use crate::app_state::AppState;
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_resource))
+        .route("/refresh", post(refresh_resource))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshResourceResponse {
+    pub id: Ulid,
+}
+
+async fn get_resource(
+    ChangeSetDalContext(ref mut ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path(ComponentIdFromPath { component_id }): Path<ComponentIdFromPath>,
+) -> Result<Json<ResourceView>> {
+    let resource = ResourceView::get_by_component_id(ctx, component_id).await?;
+
+    tracker.track(
+        ctx,
+        "component_get_resource",
+        json!({
+            "how": "/component/resource",
+            "component_id": component_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    Ok(Json(resource))
+}
+
+/// Enqueues a refresh action for the component in the background and immediately returns the id
+/// of the task, whose outcome is reported via the async finish/error WsEvents.
+async fn refresh_resource(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    OriginalUri(original_uri): OriginalUri,
+    LongTaskSpawner(long_tasks): LongTaskSpawner,
+    Path(ComponentIdFromPath { component_id }): Path<ComponentIdFromPath>,
+) -> Result<Json<RefreshResourceResponse>> {
+    let task_id = Ulid::new();
+
+    tracker.track(
+        &ctx,
+        "component_refresh_resource",
+        json!({
+            "how": "/component/resource/refresh",
+            "component_id": component_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    long_tasks.spawn_async_route(
+        ctx,
+        original_uri,
+        task_id,
+        LongTaskKind::ResourceRefresh,
+        move |ctx, original_uri, statuses| async move {
+            if let Err(err) =
+                Action::enqueue_refresh_in_correct_change_set_and_commit(&ctx, component_id).await
+            {
+                return handle_error(&ctx, &statuses, original_uri, task_id, err).await;
+            }
+
+            handle_finish(&ctx, &statuses, original_uri, task_id).await;
+        },
+    );
+
+    Ok(Json(RefreshResourceResponse { id: task_id }))
+}
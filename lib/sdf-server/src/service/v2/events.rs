@@ -0,0 +1,163 @@
+//! A server-sent events fallback for clients that are unable to hold a websocket open (e.g. those
+//! behind proxies that break websocket upgrades), and a replay of recent events for clients that
+//! missed some while reconnecting. The events come from the workspace's relay (see
+//! [`sdf_core::event_relays`]).
+
+use std::{
+    convert::Infallible,
+    time::Duration,
+};
+
+use axum::{
+    Json,
+    Router,
+    extract::{
+        Query,
+        State,
+    },
+    http::HeaderMap,
+    response::sse::{
+        Event,
+        KeepAlive,
+        Sse,
+    },
+    routing::get,
+};
+use futures::Stream;
+use sdf_core::{
+    event_relays::{
+        EventRelays,
+        RelayedEvent,
+    },
+    nats_multiplexer::NatsMultiplexerClients,
+};
+use sdf_extract::change_set::ChangeSetAuthorization;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use telemetry::prelude::*;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use crate::AppState;
+
+/// How often a comment is sent down an otherwise idle stream to keep proxies from closing it.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new()
+        .route("/stream", get(events_stream))
+        .route("/replay", get(replay_events))
+}
+
+fn to_sse_event(event: &RelayedEvent) -> Event {
+    Event::default()
+        .id(event.id().to_string())
+        .event(event.kind())
+        .data(event.data())
+}
+
+/// Streams the WsEvents for the caller's workspace and change set as server-sent events named
+/// after their [`WsPayload`](dal::WsPayload) variant. Clients reconnecting with a `Last-Event-ID`
+/// header are first sent any buffered events they missed.
+pub async fn events_stream(
+    ChangeSetAuthorization {
+        workspace_id,
+        change_set_id,
+        ..
+    }: ChangeSetAuthorization,
+    State(shutdown_token): State<CancellationToken>,
+    State(channel_multiplexer_clients): State<NatsMultiplexerClients>,
+    State(event_relays): State<EventRelays>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    let (replay, receiver) = event_relays
+        .relay_for(
+            workspace_id,
+            channel_multiplexer_clients.ws,
+            &shutdown_token,
+        )
+        .subscribe(change_set_id, last_event_id);
+
+    let stream = futures::stream::unfold(
+        (replay, receiver, shutdown_token),
+        move |(mut replay, mut receiver, shutdown_token)| async move {
+            if let Some(event) = replay.pop_front() {
+                return Some((Ok(to_sse_event(&event)), (replay, receiver, shutdown_token)));
+            }
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_token.cancelled() => return None,
+                    recv_result = receiver.recv() => match recv_result {
+                        Ok(event) if event.is_visible_in(change_set_id) => {
+                            return Some((
+                                Ok(to_sse_event(&event)),
+                                (replay, receiver, shutdown_token),
+                            ));
+                        }
+                        Ok(_) => continue,
+                        Err(RecvError::Lagged(skipped)) => {
+                            warn!(%workspace_id, skipped, "event stream lagged behind its relay");
+                        }
+                        Err(RecvError::Closed) => return None,
+                    },
+                }
+            }
+        },
+    );
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReplayEventsRequest {
+    since_seq: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayEventsResponse {
+    events: Vec<serde_json::Value>,
+    latest_seq: Option<u64>,
+}
+
+/// Returns the buffered WsEvents for the caller's workspace and change set with a sequence
+/// greater than `since_seq`, oldest first. Each event's `sequence` is the one the relay gave it.
+///
+/// Events are only buffered while the workspace has a relay, which the first stream or replay
+/// starts, so a client should ask for a replay when it connects and then again from the last
+/// sequence it saw whenever it reconnects.
+pub async fn replay_events(
+    ChangeSetAuthorization {
+        workspace_id,
+        change_set_id,
+        ..
+    }: ChangeSetAuthorization,
+    State(shutdown_token): State<CancellationToken>,
+    State(channel_multiplexer_clients): State<NatsMultiplexerClients>,
+    State(event_relays): State<EventRelays>,
+    Query(request): Query<ReplayEventsRequest>,
+) -> Json<ReplayEventsResponse> {
+    let replayed = event_relays
+        .relay_for(
+            workspace_id,
+            channel_multiplexer_clients.ws,
+            &shutdown_token,
+        )
+        .replay(change_set_id, request.since_seq);
+
+    Json(ReplayEventsResponse {
+        latest_seq: replayed.iter().map(|event| event.id()).max(),
+        events: replayed
+            .iter()
+            .filter_map(|event| serde_json::from_str(event.data()).ok())
+            .collect(),
+    })
+}
@@ -44,12 +44,16 @@ pub mod create_unlocked_copy;
 pub mod delete_func;
 pub mod execute_func;
 pub mod get_code;
+pub mod get_func;
+pub mod get_func_conflicts;
 pub mod get_func_run;
 pub mod get_func_run_logs;
 pub mod get_func_run_logs_av;
 pub mod get_func_runs_paginated;
+pub mod get_queued_func_runs;
 pub mod list_funcs;
 pub mod save_code;
+pub mod search_func_run_logs;
 pub mod test_execute;
 pub mod update_func;
 
@@ -70,6 +74,8 @@ pub enum FuncAPIError {
     Component(#[from] ComponentError),
     #[error("cannot create non-transformation attribute function(")]
     CreatingAttributeFuncWithoutBinding,
+    #[error("execution budget error: {0}")]
+    ExecutionBudget(#[from] dal::func::execution_budget::ExecutionBudgetError),
     #[error("func error: {0}")]
     Func(#[from] FuncError),
     #[error("func already unlocked: {0}")]
@@ -222,12 +228,25 @@ pub fn v2_routes() -> Router<AppState> {
             "/runs/paginated",
             get(get_func_runs_paginated::get_func_runs_paginated),
         )
+        .route(
+            "/runs/queued",
+            get(get_queued_func_runs::get_queued_func_runs),
+        )
+        .route(
+            "/runs/logs/search",
+            get(search_func_run_logs::search_func_run_logs),
+        )
         .route(
             "/runs/latest_av/:attribute_value_id/logs",
             get(get_func_run_logs_av::get_func_run_logs_av),
         )
         .route("/", post(create_func::create_func))
+        .route("/:func_id", get(get_func::get_func))
         .route("/:func_id", put(update_func::update_func)) // only save the func's metadata
+        .route(
+            "/:func_id/conflicts",
+            get(get_func_conflicts::get_func_conflicts),
+        )
         .route("/:func_id/code", put(save_code::save_code)) // only saves func code
         .route("/:func_id/test_execute", post(test_execute::test_execute))
         .route("/:func_id/execute", post(execute_func::execute_func))
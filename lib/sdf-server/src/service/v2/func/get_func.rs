@@ -0,0 +1,69 @@
+use axum::{
+    Json,
+    extract::Path,
+};
+use dal::{
+    ChangeSetId,
+    Func,
+    FuncId,
+    WorkspacePk,
+    func::FuncMetadataView,
+};
+use sdf_extract::{
+    PosthogEventTracker,
+    change_set::ChangeSetDalContext,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::json;
+use si_frontend_types as frontend_types;
+
+use super::{
+    FuncAPIError,
+    FuncAPIResult,
+};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFuncResponse {
+    #[serde(flatten)]
+    pub summary: frontend_types::FuncSummary,
+    pub code: String,
+    pub metadata: FuncMetadataView,
+    pub hidden: bool,
+    pub builtin: bool,
+}
+
+pub async fn get_func(
+    ChangeSetDalContext(ref mut ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path((_workspace_pk, _change_set_id, func_id)): Path<(WorkspacePk, ChangeSetId, FuncId)>,
+) -> FuncAPIResult<Json<GetFuncResponse>> {
+    let func = Func::get_by_id_opt(ctx, func_id)
+        .await?
+        .ok_or(FuncAPIError::FuncNotFound(func_id))?;
+
+    let code = func.code_plaintext()?.unwrap_or_default();
+    let summary = func.into_frontend_type(ctx).await?;
+
+    tracker.track(
+        ctx,
+        "get_func",
+        json!({
+            "how": "/func/get_func",
+            "func_id": func_id,
+            "func_name": func.name.clone(),
+            "func_kind": func.kind,
+        }),
+    );
+
+    Ok(Json(GetFuncResponse {
+        summary,
+        code,
+        metadata: func.metadata_view(),
+        hidden: func.hidden,
+        builtin: func.builtin,
+    }))
+}
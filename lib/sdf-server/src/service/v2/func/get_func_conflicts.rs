@@ -0,0 +1,30 @@
+use axum::{
+    Json,
+    extract::Path,
+};
+use dal::{
+    ChangeSetId,
+    Func,
+    FuncId,
+    WorkspacePk,
+    func::conflict::FuncConflict,
+};
+use sdf_extract::change_set::ChangeSetDalContext;
+
+use super::{
+    FuncAPIError,
+    FuncAPIResult,
+};
+
+/// Tells whether applying the change set would overwrite edits made to the func on HEAD, so that
+/// the author can be warned before applying.
+pub async fn get_func_conflicts(
+    ChangeSetDalContext(ref mut ctx): ChangeSetDalContext,
+    Path((_workspace_pk, _change_set_id, func_id)): Path<(WorkspacePk, ChangeSetId, FuncId)>,
+) -> FuncAPIResult<Json<FuncConflict>> {
+    if Func::get_by_id_opt(ctx, func_id).await?.is_none() {
+        return Err(FuncAPIError::FuncNotFound(func_id));
+    }
+
+    Ok(Json(Func::detect_conflicts(ctx, func_id).await?))
+}
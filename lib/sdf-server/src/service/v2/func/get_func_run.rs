@@ -36,6 +36,7 @@ use si_events::{
     FuncRunLogId,
     FuncRunState,
     OutputLine,
+    OutputLineLevel,
 };
 
 use crate::{
@@ -95,6 +96,16 @@ impl From<FuncRunLog> for FuncRunLogView {
     }
 }
 
+impl FuncRunLogView {
+    /// Drops the lines less severe than the given level. Lines whose level isn't recognized are
+    /// kept, since there is no telling how severe they are.
+    pub fn retain_at_least(&mut self, min_level: OutputLineLevel) {
+        self.logs.retain(|line| {
+            OutputLineLevel::from_level(&line.level).is_none_or(|level| level >= min_level)
+        });
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct FuncRunView {
@@ -2,10 +2,17 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::Path,
+    extract::{
+        Path,
+        Query,
+    },
 };
 use dal::WorkspacePk;
-use si_events::FuncRunId;
+use serde::Deserialize;
+use si_events::{
+    FuncRunId,
+    OutputLineLevel,
+};
 
 use super::get_func_run::FuncRunLogView;
 use crate::{
@@ -16,6 +23,13 @@ use crate::{
     },
 };
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFuncRunLogsRequest {
+    /// Only return lines at least this severe (e.g. `warn` for warnings and errors).
+    pub min_level: Option<OutputLineLevel>,
+}
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetFuncRunLogsResponse {
@@ -26,7 +40,7 @@ pub struct GetFuncRunLogsResponse {
 ///
 /// This endpoint returns only the logs for a function run without fetching
 /// the entire function run details, which makes it more efficient for
-/// monitoring log updates.
+/// monitoring log updates. Pass `minLevel` to only get lines at least that severe.
 pub async fn get_func_run_logs(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
@@ -35,19 +49,92 @@ pub async fn get_func_run_logs(
         dal::ChangeSetId,
         FuncRunId,
     )>,
+    Query(request): Query<GetFuncRunLogsRequest>,
 ) -> FuncAPIResult<Json<GetFuncRunLogsResponse>> {
     let ctx = builder
         .build(access_builder.build(change_set_id.into()))
         .await?;
 
     // Fetch only the logs for this function run
-    let logs = ctx
+    let mut logs: Option<FuncRunLogView> = ctx
         .layer_db()
         .func_run_log()
         .get_for_func_run_id(func_run_id)
         .await?
         .map(Arc::unwrap_or_clone)
         .map(|v| v.into());
+    if let (Some(logs), Some(min_level)) = (logs.as_mut(), request.min_level) {
+        logs.retain_at_least(min_level);
+    }
 
     Ok(Json(GetFuncRunLogsResponse { logs }))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Uri;
+    use si_events::{
+        ChangeSetId,
+        FuncRunLog,
+        OutputLine,
+        Tenancy,
+    };
+
+    use super::*;
+
+    fn levels_at_least(uri: &'static str, levels: &[&str]) -> Vec<String> {
+        let Query(request) = Query::<GetFuncRunLogsRequest>::try_from_uri(&Uri::from_static(uri))
+            .expect("could not parse query");
+
+        let mut func_run_log = FuncRunLog::new(
+            FuncRunId::new(),
+            Tenancy::new(WorkspacePk::new(), ChangeSetId::new()),
+        );
+        for level in levels {
+            func_run_log.push_log(OutputLine {
+                stream: "output".to_string(),
+                execution_id: "execution".to_string(),
+                level: level.to_string(),
+                group: None,
+                message: format!("a {level} line"),
+                timestamp: 0,
+            });
+        }
+
+        let mut logs = FuncRunLogView::from(func_run_log);
+        if let Some(min_level) = request.min_level {
+            logs.retain_at_least(min_level);
+        }
+
+        serde_json::to_value(logs).expect("could not serialize logs")["logs"]
+            .as_array()
+            .expect("logs should be an array")
+            .iter()
+            .map(|line| line["level"].as_str().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn min_level_drops_less_severe_lines() {
+        let levels = ["debug", "log", "warn", "ERROR", "custom"];
+
+        // Unrecognized levels are kept, since there is no telling how severe they are
+        assert_eq!(
+            vec!["warn", "ERROR", "custom"],
+            levels_at_least("/logs?minLevel=warn", &levels),
+        );
+        assert_eq!(
+            vec!["log", "warn", "ERROR", "custom"],
+            levels_at_least("/logs?minLevel=info", &levels),
+        );
+        assert_eq!(levels.to_vec(), levels_at_least("/logs", &levels));
+    }
+
+    #[test]
+    fn min_level_must_be_a_known_level() {
+        assert!(
+            Query::<GetFuncRunLogsRequest>::try_from_uri(&Uri::from_static("/logs?minLevel=loud"))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use axum::{
+    Json,
+    extract::Path,
+};
+use chrono::Utc;
+use dal::{
+    ChangeSetId,
+    WorkspacePk,
+    func::{
+        execution_budget::QueuedExecution,
+        runner::FuncRunner,
+    },
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    extract::HandlerContext,
+    service::v2::{
+        AccessBuilder,
+        func::FuncAPIResult,
+    },
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedFuncRun {
+    #[serde(flatten)]
+    pub execution: QueuedExecution,
+    /// How long the func run has been waiting so far.
+    pub wait_ms: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetQueuedFuncRunsResponse {
+    pub queued: Vec<QueuedFuncRun>,
+}
+
+/// Lists the func runs of the workspace that are waiting for its execution budget on any server,
+/// oldest first
+pub async fn get_queued_func_runs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> FuncAPIResult<Json<GetQueuedFuncRunsResponse>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let now = Utc::now();
+    let queued = FuncRunner::queued_executions(&ctx)
+        .await?
+        .into_iter()
+        .map(|execution| QueuedFuncRun {
+            wait_ms: (now - execution.queued_at).num_milliseconds().max(0),
+            execution,
+        })
+        .collect();
+
+    Ok(Json(GetQueuedFuncRunsResponse { queued }))
+}
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use axum::{
     Json,
-    extract::OriginalUri,
+    extract::{
+        OriginalUri,
+        Query,
+    },
 };
 use dal::{
     DalContext,
@@ -13,24 +16,62 @@ use dal::{
     func::binding::FuncBinding,
 };
 use sdf_extract::change_set::ChangeSetDalContext;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use si_frontend_types as frontend_types;
 use telemetry::prelude::*;
 
 use super::FuncAPIResult;
 use crate::extract::PosthogClient;
 
+/// Optional filters for [`list_funcs`]. With no parameters set, every visible func is returned.
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFuncsRequest {
+    pub kind: Option<frontend_types::FuncKind>,
+    /// Case-insensitive match against the func's name and display name.
+    pub search: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFuncsResponse {
+    pub items: Vec<frontend_types::FuncSummary>,
+    /// The number of funcs matching the filters, before `limit` and `offset` are applied.
+    pub total: usize,
+}
+
 pub async fn list_funcs(
     ChangeSetDalContext(ref mut ctx): ChangeSetDalContext,
     PosthogClient(_posthog_client): PosthogClient,
     OriginalUri(_original_uri): OriginalUri,
-) -> FuncAPIResult<Json<Vec<frontend_types::FuncSummary>>> {
+    Query(request): Query<ListFuncsRequest>,
+) -> FuncAPIResult<Json<ListFuncsResponse>> {
+    let search = request.search.as_deref().map(str::to_lowercase);
     let mut funcs = Vec::new();
 
     for func in Func::list_all(ctx).await? {
+        if let Some(search) = &search {
+            let name_matches = func.name.to_lowercase().contains(search);
+            let display_name_matches = func
+                .display_name
+                .as_deref()
+                .is_some_and(|display_name| display_name.to_lowercase().contains(search));
+            if !name_matches && !display_name_matches {
+                continue;
+            }
+        }
+
         match treat_single_function(ctx, &func).await {
             Ok(None) => {}
             Ok(Some(f)) => {
-                funcs.push(f);
+                if request.kind.is_none_or(|kind| kind == f.kind) {
+                    funcs.push(f);
+                }
             }
             Err(err) => {
                 error!(
@@ -40,7 +81,23 @@ pub async fn list_funcs(
             }
         }
     }
-    Ok(Json(funcs))
+
+    let total = funcs.len();
+
+    // Only impose an ordering when paging so that pages are stable between requests.
+    if request.limit.is_some() || request.offset.is_some() {
+        funcs.sort_by(|a, b| a.name.cmp(&b.name).then(a.func_id.cmp(&b.func_id)));
+        funcs = funcs
+            .into_iter()
+            .skip(request.offset.unwrap_or(0))
+            .take(request.limit.unwrap_or(usize::MAX))
+            .collect();
+    }
+
+    Ok(Json(ListFuncsResponse {
+        items: funcs,
+        total,
+    }))
 }
 
 async fn treat_single_function(
@@ -13,7 +13,10 @@ use dal::{
     FuncId,
     WorkspacePk,
     WsEvent,
-    func::authoring::FuncAuthoringClient,
+    func::authoring::{
+        FuncAuthoringClient,
+        FuncCodeWarning,
+    },
 };
 use serde::{
     Deserialize,
@@ -42,6 +45,14 @@ pub struct SaveCodeRequest {
     pub code: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveCodeResponse {
+    pub saved: bool,
+    /// Problems found with the code, which did not prevent it from being saved.
+    pub warnings: Vec<FuncCodeWarning>,
+}
+
 pub async fn save_code(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
@@ -50,13 +61,13 @@ pub async fn save_code(
     Host(host_name): Host,
     Path((_workspace_pk, change_set_id, func_id)): Path<(WorkspacePk, ChangeSetId, FuncId)>,
     Json(request): Json<SaveCodeRequest>,
-) -> FuncAPIResult<ForceChangeSetResponse<()>> {
+) -> FuncAPIResult<ForceChangeSetResponse<SaveCodeResponse>> {
     let mut ctx = builder
         .build(access_builder.build(change_set_id.into()))
         .await?;
     let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
 
-    FuncAuthoringClient::save_code(&ctx, func_id, request.code).await?;
+    let warnings = FuncAuthoringClient::save_code_and_validate(&ctx, func_id, request.code).await?;
     let func_code = get_code_response(&ctx, func_id).await?;
     let func = Func::get_by_id(&ctx, func_id).await?;
     WsEvent::func_code_saved(&ctx, func_code, false)
@@ -75,10 +86,17 @@ pub async fn save_code(
             "func_id": func_id,
             "func_name": func.name.clone(),
             "func_kind": func.kind.clone(),
+            "warning_count": warnings.len(),
         }),
     );
 
     ctx.commit().await?;
 
-    Ok(ForceChangeSetResponse::empty(force_change_set_id))
+    Ok(ForceChangeSetResponse::new(
+        force_change_set_id,
+        SaveCodeResponse {
+            saved: true,
+            warnings,
+        },
+    ))
 }
@@ -0,0 +1,86 @@
+use axum::{
+    Json,
+    extract::{
+        Path,
+        Query,
+    },
+};
+use chrono::{
+    DateTime,
+    Utc,
+};
+use dal::WorkspacePk;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_layer_cache::db::func_run_log::FuncRunLogMatch;
+
+use crate::{
+    extract::HandlerContext,
+    service::v2::{
+        AccessBuilder,
+        func::FuncAPIResult,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFuncRunLogsParams {
+    query: String,
+    since: DateTime<Utc>,
+    context_lines: Option<u32>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFuncRunLogsResponse {
+    matches: Vec<FuncRunLogMatch>,
+    next_offset: Option<u32>,
+}
+
+/// Search the logs of every function run in the workspace since a point in time
+///
+/// This endpoint supports offset-based pagination:
+/// - `limit` parameter controls how many matching lines to return per page (default: 50, max: 100)
+/// - `offset` parameter should be the `nextOffset` returned with the previous page
+/// - `contextLines` parameter controls how many lines around each match are returned (default: 2, max: 10)
+///
+/// Results are ordered by creation time of the function run (newest first).
+pub async fn search_func_run_logs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, dal::ChangeSetId)>,
+    Query(params): Query<SearchFuncRunLogsParams>,
+) -> FuncAPIResult<Json<SearchFuncRunLogsResponse>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let limit = params.limit.unwrap_or(50).min(100);
+    let offset = params.offset.unwrap_or_default();
+    let context_lines = params.context_lines.unwrap_or(2).min(10);
+
+    let matches = ctx
+        .layer_db()
+        .func_run_log()
+        .search(
+            workspace_pk,
+            &params.query,
+            params.since,
+            context_lines as i64,
+            limit as i64,
+            offset as i64,
+        )
+        .await?;
+
+    // There may be more matches if we filled the page
+    let next_offset = (matches.len() == limit as usize).then_some(offset + limit);
+
+    Ok(Json(SearchFuncRunLogsResponse {
+        matches,
+        next_offset,
+    }))
+}
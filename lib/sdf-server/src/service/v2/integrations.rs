@@ -13,6 +13,7 @@ use dal::{
     UserPk,
     WorkspacePk,
     workspace_integrations::WorkspaceIntegration,
+    workspace_webhook::WorkspaceWebhookError,
 };
 use hyper::StatusCode;
 use sdf_core::api_error::ApiError;
@@ -26,6 +27,7 @@ use crate::AppState;
 
 pub mod get_integrations;
 pub mod update_integration;
+pub mod webhooks;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -42,15 +44,29 @@ pub enum IntegrationsError {
     Transactions(#[from] dal::TransactionsError),
     #[error("user unable to approve integration: {0}")]
     UserUnableToApproveIntegration(UserPk),
+    #[error("user unable to manage webhooks: {0}")]
+    UserUnableToManageWebhooks(UserPk),
     #[error("workspace integration error: {0}")]
     WorkspaceIntegrations(#[from] dal::workspace_integrations::WorkspaceIntegrationsError),
+    #[error("workspace webhook error: {0}")]
+    WorkspaceWebhook(#[from] dal::workspace_webhook::WorkspaceWebhookError),
 }
 
 pub type IntegrationsResult<T> = Result<T, IntegrationsError>;
 
 impl IntoResponse for IntegrationsError {
     fn into_response(self) -> Response {
-        let (status_code, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let status_code = match &self {
+            Self::UserUnableToManageWebhooks(_) => StatusCode::FORBIDDEN,
+            Self::WorkspaceWebhook(
+                WorkspaceWebhookError::InsecureUrl(_) | WorkspaceWebhookError::InvalidUrl(..),
+            ) => StatusCode::BAD_REQUEST,
+            Self::WorkspaceWebhook(WorkspaceWebhookError::WebhookNotFound(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let error_message = self.to_string();
 
         ApiError::new(status_code, error_message).into_response()
     }
@@ -60,6 +76,7 @@ pub fn v2_routes() -> Router<AppState> {
     Router::new()
         .route("/", post(update_integration::update_integration))
         .route("/", get(get_integrations::get_integration))
+        .nest("/webhooks", webhooks::v2_routes())
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -0,0 +1,203 @@
+use axum::{
+    Json,
+    Router,
+    extract::{
+        Path,
+        State,
+    },
+    routing::{
+        get,
+        put,
+    },
+};
+use dal::{
+    DalContext,
+    UserPk,
+    WorkspacePk,
+    workspace_webhook::{
+        WorkspaceWebhook,
+        WorkspaceWebhookDelivery,
+        WorkspaceWebhookEventKind,
+        WorkspaceWebhookId,
+    },
+};
+use permissions::{
+    Permission,
+    PermissionBuilder,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use si_db::HistoryActor;
+
+use super::{
+    IntegrationsError,
+    IntegrationsResult,
+};
+use crate::{
+    AppState,
+    extract::HandlerContext,
+    service::v2::AccessBuilder,
+};
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_webhooks).post(create_webhook))
+        .route("/:webhook_id", put(update_webhook).delete(delete_webhook))
+        .route("/:webhook_id/deliveries", get(list_deliveries))
+}
+
+/// A [`WorkspaceWebhook`], without its secret.
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookResponse {
+    pub id: WorkspaceWebhookId,
+    pub url: String,
+    pub event_kinds: Vec<WorkspaceWebhookEventKind>,
+    pub enabled: bool,
+}
+
+impl From<WorkspaceWebhook> for WebhookResponse {
+    fn from(webhook: WorkspaceWebhook) -> Self {
+        Self {
+            id: webhook.id(),
+            url: webhook.url().to_owned(),
+            event_kinds: webhook.event_kinds().to_vec(),
+            enabled: webhook.enabled(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    url: String,
+    secret: String,
+    event_kinds: Vec<WorkspaceWebhookEventKind>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookRequest {
+    url: Option<String>,
+    secret: Option<String>,
+    event_kinds: Option<Vec<WorkspaceWebhookEventKind>>,
+    enabled: Option<bool>,
+}
+
+pub async fn list_webhooks(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> IntegrationsResult<Json<Vec<WebhookResponse>>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let webhooks = WorkspaceWebhook::list(&ctx)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(webhooks))
+}
+
+pub async fn create_webhook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    State(mut state): State<AppState>,
+    Path(workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> IntegrationsResult<Json<WebhookResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+    ensure_can_manage_webhooks(&ctx, &mut state, workspace_pk).await?;
+
+    let webhook =
+        WorkspaceWebhook::new(&ctx, request.url, request.secret, request.event_kinds).await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(webhook.into()))
+}
+
+pub async fn update_webhook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    State(mut state): State<AppState>,
+    Path((workspace_pk, webhook_id)): Path<(WorkspacePk, WorkspaceWebhookId)>,
+    Json(request): Json<UpdateWebhookRequest>,
+) -> IntegrationsResult<Json<WebhookResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+    ensure_can_manage_webhooks(&ctx, &mut state, workspace_pk).await?;
+
+    let mut webhook = WorkspaceWebhook::get_by_id(&ctx, webhook_id).await?;
+    webhook
+        .update(
+            &ctx,
+            request.url,
+            request.secret,
+            request.event_kinds,
+            request.enabled,
+        )
+        .await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(webhook.into()))
+}
+
+pub async fn delete_webhook(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    State(mut state): State<AppState>,
+    Path((workspace_pk, webhook_id)): Path<(WorkspacePk, WorkspaceWebhookId)>,
+) -> IntegrationsResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+    ensure_can_manage_webhooks(&ctx, &mut state, workspace_pk).await?;
+
+    WorkspaceWebhook::delete(&ctx, webhook_id).await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(())
+}
+
+pub async fn list_deliveries(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, webhook_id)): Path<(WorkspacePk, WorkspaceWebhookId)>,
+) -> IntegrationsResult<Json<Vec<WorkspaceWebhookDelivery>>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let deliveries = WorkspaceWebhookDelivery::list_for_webhook(&ctx, webhook_id).await?;
+
+    Ok(Json(deliveries))
+}
+
+/// Webhooks carry a secret and reach outside of SI, so only users that can approve in the
+/// workspace may manage them, as with the other integrations.
+async fn ensure_can_manage_webhooks(
+    ctx: &DalContext,
+    state: &mut AppState,
+    workspace_pk: WorkspacePk,
+) -> IntegrationsResult<()> {
+    let spicedb_client = state
+        .spicedb_client()
+        .ok_or(IntegrationsError::SpiceDbClientNotFound)?;
+
+    let user_pk: UserPk = match ctx.history_actor() {
+        HistoryActor::User(user_id) => *user_id,
+        _ => return Err(IntegrationsError::InvalidUser),
+    };
+    let has_permission = PermissionBuilder::new()
+        .workspace_object(workspace_pk)
+        .permission(Permission::Approve)
+        .user_subject(user_pk)
+        .has_permission(spicedb_client)
+        .await?;
+    if !has_permission {
+        return Err(IntegrationsError::UserUnableToManageWebhooks(user_pk));
+    }
+
+    Ok(())
+}
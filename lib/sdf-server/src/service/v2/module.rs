@@ -5,6 +5,7 @@ use axum::{
         multipart::MultipartError,
     },
     http::StatusCode,
+    middleware,
     response::{
         IntoResponse,
         Response,
@@ -27,10 +28,22 @@ use si_pkg::SiPkgError;
 use telemetry::prelude::*;
 use thiserror::Error;
 
-use crate::AppState;
+use crate::{
+    AppState,
+    extract::workspace::{
+        ModuleInstallFeature,
+        WorkspaceFeatureEnabled,
+    },
+    middleware::{
+        RateLimitLayer,
+        RateLimits,
+    },
+};
 
 mod builtins;
+mod cached;
 mod contribute;
+mod install_cached;
 mod install_from_file;
 mod list;
 mod module_by_hash;
@@ -45,10 +58,14 @@ pub type ModuleAPIResult<T> = Result<T, ModulesAPIError>;
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ModulesAPIError {
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] dal::action::prototype::ActionPrototypeError),
     #[error("axum http error: {0}")]
     AxumHttp(#[from] axum::http::Error),
     #[error("cached module error: {0:?}")]
     CachedModule(#[from] CachedModuleError),
+    #[error("no cached module found for schema: {0}")]
+    CachedModuleNotFound(dal::SchemaId),
     #[error("changeset error: {0:?}")]
     Changeset(#[from] ChangeSetError),
     #[error("module not contributed: {0:?}")]
@@ -70,6 +87,8 @@ pub enum ModulesAPIError {
     #[error("pkg file error: {0}")]
     PkgFileError(&'static str),
     #[error("schema error: {0}")]
+    Schema(#[from] dal::SchemaError),
+    #[error("schema variant error: {0}")]
     SchemaVariant(#[from] dal::SchemaVariantError),
     #[error("changeset error: {0:?}")]
     Serde(#[from] serde_json::Error),
@@ -85,6 +104,20 @@ pub enum ModulesAPIError {
     WsEvent(#[from] WsEventError),
 }
 
+impl ModulesAPIError {
+    /// A stable, machine-readable code for the error, sent alongside its message in responses.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ModuleIndexNotConfigured => "module_index_not_configured",
+            Self::SchemaVariant(dal::SchemaVariantError::NotFound(_))
+            | Self::Schema(dal::SchemaError::UninstalledSchemaNotFound(_))
+            | Self::ModuleHashNotFound(_)
+            | Self::CachedModuleNotFound(_) => "not_found",
+            _ => "internal_error",
+        }
+    }
+}
+
 impl IntoResponse for ModulesAPIError {
     fn into_response(self) -> Response {
         let status_code = match &self {
@@ -95,28 +128,50 @@ impl IntoResponse for ModulesAPIError {
                 error!(%schema_variant_id, "schema variant not found");
                 StatusCode::NOT_FOUND
             }
+            Self::Schema(dal::SchemaError::UninstalledSchemaNotFound(_)) => StatusCode::NOT_FOUND,
             Self::Module(dal::module::ModuleError::EmptyMetadata(_, _)) => StatusCode::BAD_REQUEST,
             Self::ContributionFailure(_) => StatusCode::BAD_REQUEST,
-            Self::ModuleHashNotFound(_) => StatusCode::NOT_FOUND,
+            Self::ModuleHashNotFound(_) | Self::CachedModuleNotFound(_) => StatusCode::NOT_FOUND,
+            Self::ModuleIndexNotConfigured => StatusCode::SERVICE_UNAVAILABLE,
             _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
         };
 
-        ApiError::new(status_code, self).into_response()
+        ApiError::new(status_code, &self)
+            .with_code(self.code())
+            .into_response()
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState, rate_limits: RateLimits) -> Router<AppState> {
+    // Installing can be turned off per workspace.
+    let install_routes = Router::new()
+        .route(
+            "/cached/:schema_id/install",
+            post(install_cached::install_cached),
+        )
+        .route(
+            "/install_from_file",
+            post(install_from_file::install_module_from_file),
+        )
+        .route_layer(RateLimitLayer::new(
+            state.clone(),
+            rate_limits.module_install,
+        ))
+        .route_layer(middleware::from_extractor_with_state::<
+            WorkspaceFeatureEnabled<ModuleInstallFeature>,
+            AppState,
+        >(state));
+
     Router::new()
         .route("/contribute", post(contribute::contribute))
         .route("/sync", get(sync::sync))
         .route("/", get(list::list))
+        .route("/cached", get(cached::list_cached))
+        .route("/cached/:schema_id", get(cached::get_cached))
         .route("/:module_id/builtins/reject", post(builtins::reject))
         .route("/:module_id/builtins/promote", post(builtins::promote))
         .route("/module_by_hash", get(module_by_hash::module_by_hash))
         .route("/module_by_id", get(module_by_id::remote_module_by_id))
-        .route(
-            "/install_from_file",
-            post(install_from_file::install_module_from_file),
-        )
+        .merge(install_routes)
         .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
 }
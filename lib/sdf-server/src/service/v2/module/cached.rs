@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+
+use axum::{
+    Json,
+    extract::{
+        Path,
+        Query,
+    },
+};
+use chrono::{
+    DateTime,
+    Utc,
+};
+use dal::{
+    ChangeSetId,
+    ComponentType,
+    SchemaId,
+    SchemaVariant,
+    WorkspacePk,
+    cached_module::{
+        CachedModule,
+        PackageSummary,
+    },
+};
+use sdf_extract::{
+    PosthogEventTracker,
+    change_set::ChangeSetDalContext,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::json;
+use si_frontend_types::UninstalledVariant;
+
+use super::{
+    ModuleAPIResult,
+    ModulesAPIError,
+};
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCachedModulesRequest {
+    /// Case-insensitive exact match on the module's category.
+    pub category: Option<String>,
+    /// Case-insensitive match against the schema name and display name.
+    pub search: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedModuleContentsSummary {
+    pub schema_id: SchemaId,
+    pub schema_name: String,
+    pub display_name: Option<String>,
+    pub category: Option<String>,
+    pub link: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub component_type: ComponentType,
+    pub latest_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub package_summary: Option<PackageSummary>,
+    pub installed: bool,
+}
+
+/// Lists the latest cached module for every schema that is not yet installed in the change set.
+pub async fn list_cached(
+    ChangeSetDalContext(ref ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Query(request): Query<ListCachedModulesRequest>,
+) -> ModuleAPIResult<Json<Vec<UninstalledVariant>>> {
+    let installed = SchemaVariant::list_user_facing(ctx).await?;
+
+    let mut installed_schema_ids = HashSet::new();
+    let mut installed_cat_and_name = HashSet::new();
+    for installed_variant in &installed {
+        installed_schema_ids.insert(installed_variant.schema_id);
+        installed_cat_and_name.insert((
+            installed_variant.category.as_str(),
+            installed_variant.schema_name.as_str(),
+        ));
+    }
+
+    let category_filter = request.category.as_deref().map(str::to_lowercase);
+    let search = request.search.as_deref().map(str::to_lowercase);
+
+    // The listing queries never select package data, so this stays cheap however large the
+    // cached modules are.
+    let mut uninstalled = vec![];
+    for module in CachedModule::latest_modules(ctx).await? {
+        let category = module.category.as_deref().unwrap_or("");
+        if installed_schema_ids.contains(&module.schema_id)
+            || installed_cat_and_name.contains(&(category, module.schema_name.as_str()))
+        {
+            continue;
+        }
+
+        if category_filter
+            .as_ref()
+            .is_some_and(|category_filter| category.to_lowercase() != *category_filter)
+        {
+            continue;
+        }
+
+        if let Some(search) = &search {
+            let name_matches = module.schema_name.to_lowercase().contains(search);
+            let display_name_matches = module
+                .display_name
+                .as_deref()
+                .is_some_and(|display_name| display_name.to_lowercase().contains(search));
+            if !name_matches && !display_name_matches {
+                continue;
+            }
+        }
+
+        uninstalled.push(UninstalledVariant::from(module));
+    }
+
+    tracker.track(
+        ctx,
+        "list_cached_modules",
+        json!({
+            "how": "/modules/cached",
+            "category": request.category,
+            "search": request.search,
+            "count": uninstalled.len(),
+        }),
+    );
+
+    Ok(Json(uninstalled))
+}
+
+/// Returns the contents summary of the latest cached module for a schema.
+pub async fn get_cached(
+    ChangeSetDalContext(ref ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path((_workspace_pk, _change_set_id, schema_id)): Path<(WorkspacePk, ChangeSetId, SchemaId)>,
+) -> ModuleAPIResult<Json<CachedModuleContentsSummary>> {
+    // "list_for_schema_id" is ordered newest first and, unlike "find_latest_for_schema_id", does
+    // not pull the package data.
+    let module = CachedModule::list_for_schema_id(ctx, schema_id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(ModulesAPIError::CachedModuleNotFound(schema_id))?;
+
+    let installed = SchemaVariant::list_user_facing(ctx)
+        .await?
+        .iter()
+        .any(|variant| variant.schema_id == schema_id);
+
+    tracker.track(
+        ctx,
+        "get_cached_module",
+        json!({
+            "how": "/modules/cached/:schema_id",
+            "schema_id": schema_id,
+            "schema_name": module.schema_name.clone(),
+        }),
+    );
+
+    Ok(Json(CachedModuleContentsSummary {
+        schema_id: module.schema_id,
+        schema_name: module.schema_name,
+        display_name: module.display_name,
+        category: module.category,
+        link: module.link,
+        color: module.color,
+        description: module.description,
+        component_type: module.component_type,
+        latest_hash: module.latest_hash,
+        created_at: module.created_at,
+        package_summary: module.package_summary,
+        installed,
+    }))
+}
@@ -0,0 +1,162 @@
+use axum::extract::{
+    Path,
+    Query,
+};
+use dal::{
+    ActionPrototypeId,
+    ChangeSet,
+    ChangeSetId,
+    DalContext,
+    Func,
+    FuncId,
+    Schema,
+    SchemaId,
+    SchemaVariant,
+    SchemaVariantId,
+    WorkspacePk,
+    WsEvent,
+    action::prototype::{
+        ActionKind,
+        ActionPrototype,
+    },
+};
+use sdf_extract::{
+    PosthogEventTracker,
+    change_set::ChangeSetDalContext,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::json;
+use si_frontend_types::SchemaVariant as FrontendVariant;
+
+use super::ModuleAPIResult;
+use crate::service::force_change_set_response::ForceChangeSetResponse;
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallCachedModuleParams {
+    /// Import a copy of every func in the module, even those identical to a func already in the
+    /// change set.
+    #[serde(default)]
+    pub force_copy: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallCachedModuleResponse {
+    pub schema_variant: FrontendVariant,
+    /// The action prototypes bundled with the module, bound to the installed variant.
+    pub action_prototypes: Vec<InstalledActionPrototype>,
+    /// Set when the schema was already installed, in which case nothing was imported.
+    pub already_installed: bool,
+    /// The funcs already in the change set which were reused instead of imported again.
+    pub reused_func_ids: Vec<FuncId>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledActionPrototype {
+    pub id: ActionPrototypeId,
+    pub kind: ActionKind,
+    pub name: String,
+    pub func_id: FuncId,
+}
+
+/// Installs the latest cached module for a schema into the change set. Installing a schema that
+/// is already installed is a no-op which returns the existing default variant.
+pub async fn install_cached(
+    ChangeSetDalContext(ref mut ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path((_workspace_pk, _change_set_id, schema_id)): Path<(WorkspacePk, ChangeSetId, SchemaId)>,
+    Query(params): Query<InstallCachedModuleParams>,
+) -> ModuleAPIResult<ForceChangeSetResponse<InstallCachedModuleResponse>> {
+    if Schema::exists_locally(ctx, schema_id).await? {
+        let schema_variant_id = Schema::default_variant_id(ctx, schema_id).await?;
+        let schema_variant = SchemaVariant::get_by_id(ctx, schema_variant_id)
+            .await?
+            .into_frontend_type(ctx, schema_id)
+            .await?;
+
+        return Ok(ForceChangeSetResponse::new(
+            None,
+            InstallCachedModuleResponse {
+                schema_variant,
+                action_prototypes: installed_action_prototypes(ctx, schema_variant_id).await?,
+                already_installed: true,
+                reused_func_ids: vec![],
+            },
+        ));
+    }
+
+    let force_change_set_id = ChangeSet::force_new(ctx).await?;
+
+    let install = Schema::install_from_cache(ctx, schema_id, params.force_copy).await?;
+    let schema_variant_id = install.schema_variant_id;
+    let installed = install.installed;
+    let schema_variant = SchemaVariant::get_by_id(ctx, schema_variant_id)
+        .await?
+        .into_frontend_type(ctx, schema_id)
+        .await?;
+
+    if installed {
+        WsEvent::module_imported(ctx, vec![schema_variant.clone()])
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        for func_id in schema_variant.func_ids.iter() {
+            let func = Func::get_by_id(ctx, *func_id).await?;
+            let front_end_func = func.into_frontend_type(ctx).await?;
+            WsEvent::func_updated(ctx, front_end_func, None)
+                .await?
+                .publish_on_commit(ctx)
+                .await?;
+        }
+    }
+
+    tracker.track(
+        ctx,
+        "install_cached_module",
+        json!({
+            "how": "/modules/cached/:schema_id/install",
+            "schema_id": schema_id,
+            "schema_name": schema_variant.schema_name.clone(),
+            "schema_variant_id": schema_variant_id,
+            "force_copy": params.force_copy,
+            "reused_func_count": install.reused_func_ids.len(),
+        }),
+    );
+
+    let action_prototypes = installed_action_prototypes(ctx, schema_variant_id).await?;
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::new(
+        force_change_set_id,
+        InstallCachedModuleResponse {
+            schema_variant,
+            action_prototypes,
+            already_installed: !installed,
+            reused_func_ids: install.reused_func_ids,
+        },
+    ))
+}
+
+async fn installed_action_prototypes(
+    ctx: &DalContext,
+    schema_variant_id: SchemaVariantId,
+) -> ModuleAPIResult<Vec<InstalledActionPrototype>> {
+    let mut action_prototypes = Vec::new();
+    for prototype in ActionPrototype::list_for_schema_and_variant_id(ctx, schema_variant_id).await?
+    {
+        action_prototypes.push(InstalledActionPrototype {
+            id: prototype.id(),
+            kind: prototype.kind,
+            func_id: ActionPrototype::func_id(ctx, prototype.id()).await?,
+            name: prototype.name,
+        });
+    }
+
+    Ok(action_prototypes)
+}
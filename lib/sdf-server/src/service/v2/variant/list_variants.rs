@@ -14,6 +14,7 @@ use dal::{
     WorkspacePk,
     cached_module::CachedModule,
 };
+use sdf_extract::EddaClient;
 use si_frontend_types::{
     ListVariantsResponse,
     UninstalledVariant,
@@ -37,6 +38,7 @@ pub async fn list_variants(
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
+    EddaClient(edda_client): EddaClient,
     Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
 ) -> Result<Json<ListVariantsResponse>, SchemaVariantsAPIError> {
     let ctx = builder
@@ -55,29 +57,22 @@ pub async fn list_variants(
         ));
     }
 
-    let cached_modules: Vec<CachedModule> = CachedModule::latest_modules(&ctx).await?;
+    // A brand new deployment may not have filled its module cache yet, in which case this kicks
+    // off filling it and lets the caller know to wait for it.
+    let latest = CachedModule::latest_modules_or_warm_up(&ctx, edda_client).await?;
 
     let mut uninstalled = vec![];
     // We want to hide uninstalled modules that would create duplicate assets in
     // the AssetPanel in old workspace. We do this just by name + category
     // matching. (We also hide if the schema is installed)
-    for module in cached_modules {
+    for module in latest.modules {
         let category = module.category.as_deref().unwrap_or("");
 
         let schema_name = module.schema_name.as_str();
         if !installed_schema_ids.contains(&module.schema_id)
             && !installed_cat_and_name.contains(&(category, schema_name))
         {
-            uninstalled.push(UninstalledVariant {
-                schema_id: module.schema_id,
-                schema_name: module.schema_name,
-                display_name: module.display_name,
-                category: module.category,
-                link: module.link,
-                color: module.color,
-                description: module.description,
-                component_type: module.component_type.into(),
-            });
+            uninstalled.push(UninstalledVariant::from(module));
         }
     }
 
@@ -87,11 +82,12 @@ pub async fn list_variants(
         &original_uri,
         &host_name,
         "list_variants",
-        serde_json::json!({}),
+        serde_json::json!({ "warming": latest.warming }),
     );
 
     Ok(Json(ListVariantsResponse {
         installed,
         uninstalled,
+        warming: latest.warming,
     }))
 }
@@ -24,6 +24,8 @@ use tokio::task::JoinError;
 use super::AccessBuilder;
 use crate::app_state::AppState;
 
+mod backups;
+mod get_async_task_status;
 mod get_deployment_index;
 mod install_workspace;
 mod list_workspace_users;
@@ -34,6 +36,8 @@ mod mjolnir;
 pub enum WorkspaceAPIError {
     #[error("semaphore acquire error: {0}")]
     Acquire(#[from] tokio::sync::AcquireError),
+    #[error("async task not found: {0}")]
+    AsyncTaskNotFound(ulid::Ulid),
     #[error("deserializing mv index data error: {0}")]
     DeserializingMvIndexData(#[source] serde_json::Error),
     #[error("edda client error: {0}")]
@@ -68,13 +72,49 @@ pub enum WorkspaceAPIError {
 
 pub type WorkspaceAPIResult<T> = Result<T, WorkspaceAPIError>;
 
+impl WorkspaceAPIError {
+    /// A stable, machine-readable name for the kind of error. Unlike the error message, this never
+    /// contains urls, tokens or other request data, so it is safe to send to analytics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Acquire(_) => "acquire",
+            Self::AsyncTaskNotFound(_) => "async_task_not_found",
+            Self::DeserializingMvIndexData(_) => "deserializing_mv_index_data",
+            Self::EddaClient(_) => "edda_client",
+            Self::Frigg(_) => "frigg",
+            Self::IndexNotFound => "index_not_found",
+            Self::LatestItemNotFound(_, _, _) => "latest_item_not_found",
+            Self::ModuleIndexClient(_) => "module_index_client",
+            Self::ModuleIndexUrlNotSet => "module_index_url_not_set",
+            Self::RootTenancyExportAttempt => "root_tenancy_export_attempt",
+            Self::RootTenancyInstallAttempt => "root_tenancy_install_attempt",
+            Self::SiDb(_) => "si_db",
+            Self::TokioJoin(_) => "tokio_join",
+            Self::Transactions(_) => "transactions",
+            Self::Url(_) => "url",
+            Self::WatchIndexTimeout(_) => "watch_index_timeout",
+            Self::Workspace(dal::WorkspaceError::IncompatibleExportVersion { .. }) => {
+                "incompatible_export_version"
+            }
+            Self::Workspace(_) => "workspace",
+        }
+    }
+}
+
 impl IntoResponse for WorkspaceAPIError {
     fn into_response(self) -> Response {
         let (status_code, error_message) = match self {
-            WorkspaceAPIError::LatestItemNotFound(_, _, _)
+            WorkspaceAPIError::AsyncTaskNotFound(_)
+            | WorkspaceAPIError::LatestItemNotFound(_, _, _)
             | Self::Workspace(dal::WorkspaceError::WorkspaceNotFound(_)) => {
                 (StatusCode::NOT_FOUND, self.to_string())
             }
+            Self::Workspace(dal::WorkspaceError::IncompatibleExportVersion { .. }) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, self.to_string())
+            }
+            WorkspaceAPIError::ModuleIndexUrlNotSet => {
+                (StatusCode::SERVICE_UNAVAILABLE, self.to_string())
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -85,6 +125,12 @@ impl IntoResponse for WorkspaceAPIError {
 pub fn v2_routes() -> Router<AppState> {
     Router::new()
         .route("/install", post(install_workspace::install_workspace))
+        .route(
+            "/async/:task_id",
+            get(get_async_task_status::get_async_task_status),
+        )
+        .route("/backups", get(backups::list_backups))
+        .route("/backups/:backup_id/restore", post(backups::restore_backup))
         .route("/users", get(list_workspace_users::list_workspace_users))
         .route(
             "/deployment_index",
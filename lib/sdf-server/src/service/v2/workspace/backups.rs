@@ -0,0 +1,129 @@
+use axum::{
+    Json,
+    extract::{
+        Host,
+        OriginalUri,
+        Path,
+        Query,
+    },
+};
+use chrono::{
+    DateTime,
+    Utc,
+};
+use dal::{
+    Workspace,
+    WorkspacePk,
+};
+use module_index_client::{
+    ExtraMetadata,
+    ModuleIndexClient,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use ulid::Ulid;
+
+use super::{
+    WorkspaceAPIError,
+    WorkspaceAPIResult,
+    install_workspace::{
+        InstallWorkspaceRequest,
+        InstallWorkspaceResponse,
+        install_workspace,
+    },
+};
+use crate::{
+    extract::{
+        HandlerContext,
+        LongTaskSpawner,
+        PosthogClient,
+        request::RawAccessToken,
+    },
+    service::v2::AccessBuilder,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceBackup {
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<String>,
+}
+
+/// Lists the backups of the current workspace that the caller has uploaded to the module index,
+/// newest first.
+pub async fn list_backups(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> WorkspaceAPIResult<Json<Vec<WorkspaceBackup>>> {
+    let ctx = builder.build_head(request_ctx).await?;
+
+    let workspace = {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk_opt()
+            .ok_or(WorkspaceAPIError::RootTenancyExportAttempt)?;
+        Workspace::get_by_pk(&ctx, workspace_pk).await?
+    };
+
+    let module_index_url = ctx
+        .module_index_url()
+        .ok_or(WorkspaceAPIError::ModuleIndexUrlNotSet)?;
+    let module_index_client =
+        ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token)?;
+
+    // Backups are uploaded under the name of the workspace they were exported from
+    let mut backups: Vec<WorkspaceBackup> = module_index_client
+        .list_workspace_backups()
+        .await?
+        .modules
+        .into_iter()
+        .filter(|module| &module.name == workspace.name())
+        .map(|module| WorkspaceBackup {
+            version: serde_json::from_value::<ExtraMetadata>(module.metadata)
+                .ok()
+                .map(|metadata| metadata.version),
+            id: module.id,
+            name: module.name,
+            created_at: module.created_at,
+            created_by: module.owner_display_name,
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(Json(backups))
+}
+
+/// Restores a backup listed by [`list_backups`] into the current workspace. This is the same
+/// async task as installing a workspace.
+#[allow(clippy::too_many_arguments)]
+pub async fn restore_backup(
+    handler_context: HandlerContext,
+    access_builder: AccessBuilder,
+    raw_access_token: RawAccessToken,
+    posthog_client: PosthogClient,
+    original_uri: OriginalUri,
+    host: Host,
+    long_tasks: LongTaskSpawner,
+    Path((_workspace_pk, backup_id)): Path<(WorkspacePk, Ulid)>,
+    query: Query<InstallWorkspaceRequest>,
+) -> WorkspaceAPIResult<Json<InstallWorkspaceResponse>> {
+    install_workspace(
+        handler_context,
+        access_builder,
+        raw_access_token,
+        posthog_client,
+        original_uri,
+        host,
+        long_tasks,
+        Path(backup_id.into()),
+        query,
+    )
+    .await
+}
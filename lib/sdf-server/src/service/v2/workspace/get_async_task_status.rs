@@ -0,0 +1,36 @@
+use axum::{
+    Json,
+    extract::{
+        Path,
+        State,
+    },
+};
+use dal::WorkspacePk;
+use sdf_core::async_route::{
+    AsyncTaskStatus,
+    TaskId,
+};
+
+use super::{
+    WorkspaceAPIError,
+    WorkspaceAPIResult,
+};
+use crate::{
+    app_state::AppState,
+    service::v2::AccessBuilder,
+};
+
+/// Reports the outcome of an "async" route's task for clients that missed its WsEvent.
+pub async fn get_async_task_status(
+    AccessBuilder(request_ctx): AccessBuilder,
+    State(state): State<AppState>,
+    Path((_workspace_pk, task_id)): Path<(WorkspacePk, TaskId)>,
+) -> WorkspaceAPIResult<Json<AsyncTaskStatus>> {
+    let status = state
+        .long_tasks()
+        .task_statuses()
+        .get(request_ctx.tenancy().workspace_pk_opt(), task_id)
+        .ok_or(WorkspaceAPIError::AsyncTaskNotFound(task_id))?;
+
+    Ok(Json(status))
+}
@@ -4,6 +4,7 @@ use axum::{
         Host,
         OriginalUri,
         Path,
+        Query,
     },
     http::Uri,
 };
@@ -12,15 +13,26 @@ use dal::{
     Workspace,
     WorkspacePk,
     WsEvent,
+    workspace::WorkspaceImportReport,
 };
 use module_index_client::ModuleIndexClient;
-use sdf_core::async_route::handle_error;
+use sdf_core::{
+    async_route::{
+        handle_error,
+        handle_error_and_track,
+        handle_finish,
+    },
+    long_tasks::LongTaskKind,
+};
 use serde::{
     Deserialize,
     Serialize,
 };
 use si_events::audit_log::AuditLogKind;
-use si_pkg::WorkspaceExportContentV0;
+use si_pkg::{
+    WorkspaceExport,
+    WorkspaceExportContentV0,
+};
 use telemetry::prelude::info;
 use ulid::Ulid;
 
@@ -31,6 +43,7 @@ use super::{
 use crate::{
     extract::{
         HandlerContext,
+        LongTaskSpawner,
         PosthogClient,
         request::RawAccessToken,
     },
@@ -38,12 +51,21 @@ use crate::{
     track,
 };
 
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallWorkspaceRequest {
+    /// Validate the backup and report what an install would do, without installing it.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct InstallWorkspaceResponse {
     pub id: Ulid,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn install_workspace(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
@@ -51,9 +73,11 @@ pub async fn install_workspace(
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
+    LongTaskSpawner(long_tasks): LongTaskSpawner,
     Path(req_workspace_pk): Path<WorkspacePk>,
+    Query(request): Query<InstallWorkspaceRequest>,
 ) -> WorkspaceAPIResult<Json<InstallWorkspaceResponse>> {
-    let mut ctx = builder.build_head(request_ctx).await?;
+    let ctx = builder.build_head(request_ctx).await?;
 
     let current_workspace = {
         let workspace_pk = ctx
@@ -63,61 +87,113 @@ pub async fn install_workspace(
         Workspace::get_by_pk(&ctx, workspace_pk).await?
     };
 
+    // Only the cheap checks happen up front. The export can be large, so it is downloaded by the
+    // long task, which reports a failed download or an incompatible export via its status.
+    let module_index_url = ctx
+        .module_index_url()
+        .ok_or(WorkspaceAPIError::ModuleIndexUrlNotSet)?;
+    let module_index_client =
+        ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token)?;
+
     let id = Ulid::new();
+    let tracking_posthog_client = posthog_client.clone();
 
-    tokio::task::spawn(async move {
-        match install_workspace_inner(
-            &mut ctx,
-            req_workspace_pk,
-            current_workspace,
-            &original_uri,
-            &host_name,
-            PosthogClient(posthog_client),
-            raw_access_token,
-        )
-        .await
-        {
-            Err(err) => {
-                handle_error(&ctx, original_uri, id, err).await;
-            }
-            _ => match WsEvent::async_finish_workspace(&ctx, id).await {
-                Ok(event) => {
-                    if let Err(err) = event.publish_immediately(&ctx).await {
-                        handle_error(&ctx, original_uri, id, err).await;
-                    }
+    long_tasks.spawn_async_route(
+        ctx,
+        original_uri,
+        id,
+        LongTaskKind::WorkspaceInstall,
+        move |mut ctx, original_uri, statuses| async move {
+            let event_name = if request.dry_run {
+                "validate_workspace_import"
+            } else {
+                "import_workspace"
+            };
+            let result = async {
+                let workspace_data =
+                    download_workspace(&module_index_client, req_workspace_pk).await?;
+                Workspace::check_export_format_version(&workspace_data)?;
+
+                if request.dry_run {
+                    validate_workspace_inner(
+                        &mut ctx,
+                        workspace_data,
+                        &current_workspace,
+                        &original_uri,
+                        &host_name,
+                        PosthogClient(posthog_client),
+                    )
+                    .await
+                    .map(Some)
+                } else {
+                    install_workspace_inner(
+                        &mut ctx,
+                        workspace_data,
+                        current_workspace,
+                        &original_uri,
+                        &host_name,
+                        PosthogClient(posthog_client),
+                    )
+                    .await
+                    .map(|_| None)
                 }
+            }
+            .await;
+
+            match result {
                 Err(err) => {
-                    handle_error(&ctx, original_uri, id, err).await;
+                    let uri = original_uri.clone();
+                    let error_kind = err.kind();
+                    handle_error_and_track(
+                        &ctx,
+                        &statuses,
+                        original_uri,
+                        id,
+                        err,
+                        error_kind,
+                        |error_kind| {
+                            track(
+                                &tracking_posthog_client,
+                                &ctx,
+                                &uri,
+                                &host_name,
+                                event_name,
+                                serde_json::json!({
+                                    "outcome": "failure",
+                                    "error_kind": error_kind,
+                                    "backup_workspace_id": req_workspace_pk,
+                                }),
+                            )
+                        },
+                    )
+                    .await;
                 }
-            },
-        }
-    });
+                Ok(None) => handle_finish(&ctx, &statuses, original_uri, id).await,
+                Ok(Some(report)) => {
+                    match WsEvent::workspace_import_validated(&ctx, id, report).await {
+                        Ok(event) => match event.publish_immediately(&ctx).await {
+                            Ok(()) => handle_finish(&ctx, &statuses, original_uri, id).await,
+                            Err(err) => handle_error(&ctx, &statuses, original_uri, id, err).await,
+                        },
+                        Err(err) => handle_error(&ctx, &statuses, original_uri, id, err).await,
+                    }
+                }
+            }
+        },
+    );
 
     Ok(Json(InstallWorkspaceResponse { id }))
 }
 
 async fn install_workspace_inner(
     ctx: &mut DalContext,
-    workspace_pk: WorkspacePk,
+    workspace_data: WorkspaceExport,
     mut current_workspace: Workspace,
     original_uri: &Uri,
     host_name: &String,
     PosthogClient(posthog_client): PosthogClient,
-    raw_access_token: String,
 ) -> WorkspaceAPIResult<()> {
     info!("Importing workspace backup");
-    let workspace_data = {
-        let module_index_url = match ctx.module_index_url() {
-            Some(url) => url,
-            None => return Err(WorkspaceAPIError::ModuleIndexUrlNotSet),
-        };
-        let module_index_client =
-            ModuleIndexClient::new(module_index_url.try_into()?, &raw_access_token)?;
-        module_index_client
-            .download_workspace(workspace_pk.into())
-            .await?
-    };
-
     current_workspace
         .import(ctx, workspace_data.clone())
         .await?;
@@ -126,6 +202,7 @@ async fn install_workspace_inner(
         change_sets: _,
         content_store_values: _,
         metadata,
+        manifest: _,
     } = workspace_data.into_latest();
     let workspace_id = *current_workspace.pk();
 
@@ -148,6 +225,7 @@ async fn install_workspace_inner(
             host_name,
             "import_workspace",
             serde_json::json!({
+                "outcome": "success",
                 "pkg_name": current_workspace.name().to_owned(),
                 "pkg_version": metadata.version.clone(),
                 "pkg_created_by_email": metadata.created_by,
@@ -160,3 +238,54 @@ async fn install_workspace_inner(
 
     Ok(())
 }
+
+async fn validate_workspace_inner(
+    ctx: &mut DalContext,
+    workspace_data: WorkspaceExport,
+    current_workspace: &Workspace,
+    original_uri: &Uri,
+    host_name: &String,
+    PosthogClient(posthog_client): PosthogClient,
+) -> WorkspaceAPIResult<WorkspaceImportReport> {
+    info!("Validating workspace backup");
+    let report = Workspace::validate_import(ctx, workspace_data).await?;
+
+    ctx.write_audit_log(
+        AuditLogKind::ValidateWorkspaceImport {
+            id: *current_workspace.pk(),
+            name: current_workspace.name().clone(),
+            version: report.version.clone(),
+            importable: report.is_importable(),
+        },
+        current_workspace.name().to_string(),
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        ctx,
+        original_uri,
+        host_name,
+        "validate_workspace_import",
+        serde_json::json!({
+            "outcome": "success",
+            "pkg_name": current_workspace.name().to_owned(),
+            "pkg_version": report.version.clone(),
+            "importable": report.is_importable(),
+            "change_set_count": report.change_sets.len(),
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(report)
+}
+
+async fn download_workspace(
+    module_index_client: &ModuleIndexClient,
+    workspace_pk: WorkspacePk,
+) -> WorkspaceAPIResult<WorkspaceExport> {
+    Ok(module_index_client
+        .download_workspace(workspace_pk.into())
+        .await?)
+}
@@ -0,0 +1,199 @@
+use std::time::Duration;
+
+use axum::{
+    Router,
+    http::{
+        Request,
+        StatusCode,
+        header,
+    },
+};
+use dal::{
+    DalContext,
+    WsEvent,
+};
+use dal_test::{
+    AuthToken,
+    Result,
+    eyre,
+    sdf_test,
+};
+use hyper::Body;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::Value;
+use tower::ServiceExt;
+use ulid::Ulid;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn replay(
+    ctx: &DalContext,
+    router: &Router,
+    auth_token: &AuthToken,
+    since_seq: u64,
+) -> Result<Vec<Value>> {
+    let workspace_pk = ctx
+        .tenancy()
+        .workspace_pk_opt()
+        .ok_or_else(|| eyre!("no workspace pk set on context"))?;
+    let request = Request::builder()
+        .uri(format!(
+            "/api/v2/workspaces/{workspace_pk}/change-sets/{}/events/replay?since_seq={since_seq}",
+            ctx.change_set_id()
+        ))
+        .header(header::AUTHORIZATION, format!("Bearer {}", auth_token.0))
+        .body(Body::empty())?;
+    let response = router.clone().oneshot(request).await?;
+    assert_eq!(
+        StatusCode::OK,    // expected
+        response.status(), // actual
+    );
+
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let mut response: Value = serde_json::from_slice(&body)?;
+    match response["events"].take() {
+        Value::Array(events) => Ok(events),
+        other => Err(eyre!("unexpected events in replay response: {other}")),
+    }
+}
+
+/// Replays until at least `count` events newer than `since_seq` have been buffered.
+async fn replay_at_least(
+    ctx: &DalContext,
+    router: &Router,
+    auth_token: &AuthToken,
+    since_seq: u64,
+    count: usize,
+) -> Result<Vec<Value>> {
+    let deadline = tokio::time::Instant::now() + TIMEOUT;
+    loop {
+        let events = replay(ctx, router, auth_token, since_seq).await?;
+        if events.len() >= count {
+            return Ok(events);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(eyre!(
+                "expected at least {count} replayed events within {TIMEOUT:?}, found {}",
+                events.len()
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+async fn publish(ctx: &DalContext) -> Result<Ulid> {
+    let id = Ulid::new();
+    WsEvent::async_finish(ctx, id)
+        .await?
+        .publish_immediately(ctx)
+        .await?;
+    Ok(id)
+}
+
+/// Publishes an event as a publisher whose clock is behind would, with a sequence lower than any
+/// handed out by this process.
+async fn publish_with_stale_sequence(ctx: &DalContext) -> Result<Ulid> {
+    let id = Ulid::new();
+    let mut event = serde_json::to_value(WsEvent::async_finish(ctx, id).await?)?;
+    event["sequence"] = Value::from(1);
+    let event: WsEvent = serde_json::from_value(event)?;
+    event.publish_immediately(ctx).await?;
+    Ok(id)
+}
+
+/// Returns the ids of the events published by [`publish`], ignoring any others.
+fn ids(events: &[Value]) -> Vec<Ulid> {
+    events
+        .iter()
+        .filter(|event| event["payload"]["kind"] == "AsyncFinish")
+        .filter_map(|event| event["payload"]["data"]["id"].as_str())
+        .filter_map(|id| id.parse().ok())
+        .collect()
+}
+
+/// Returns the sequence the relay gave to the event published by [`publish`] with the given id.
+fn sequence_of(events: &[Value], id: Ulid) -> Option<u64> {
+    events
+        .iter()
+        .find(|event| event["payload"]["data"]["id"].as_str() == Some(id.to_string().as_str()))
+        .and_then(|event| event["sequence"].as_u64())
+}
+
+/// Starts relaying the workspace's events and returns the sequence of the first one buffered.
+///
+/// The first replay starts the relay, which only begins buffering once its subscription is in
+/// place, so keep publishing until an event makes it into the buffer.
+async fn warm_up(ctx: &DalContext, router: &Router, auth_token: &AuthToken) -> Result<u64> {
+    replay(ctx, router, auth_token, 0).await?;
+    let deadline = tokio::time::Instant::now() + TIMEOUT;
+    loop {
+        let id = publish(ctx).await?;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if let Some(seq) = sequence_of(&replay(ctx, router, auth_token, 0).await?, id) {
+            return Ok(seq);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(eyre!("relay did not start buffering within {TIMEOUT:?}"));
+        }
+    }
+}
+
+#[sdf_test]
+async fn replay_events_since_sequence(
+    ctx: &mut DalContext,
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    let warm_up_seq = warm_up(ctx, &router, &auth_token).await?;
+
+    let mut published = Vec::new();
+    for _ in 0..5 {
+        published.push(publish(ctx).await?);
+    }
+
+    // Everything since the warm up event, then only what came after the second event.
+    let events = replay_at_least(ctx, &router, &auth_token, warm_up_seq, 5).await?;
+    assert_eq!(
+        published,    // expected
+        ids(&events), // actual
+    );
+    let sequences: Vec<u64> = published
+        .iter()
+        .filter_map(|id| sequence_of(&events, *id))
+        .collect();
+    assert!(sequences.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let events = replay(ctx, &router, &auth_token, sequences[1]).await?;
+    assert_eq!(
+        published[2..].to_vec(), // expected
+        ids(&events),            // actual
+    );
+
+    Ok(())
+}
+
+#[sdf_test]
+async fn replay_orders_events_from_out_of_order_publishers(
+    ctx: &mut DalContext,
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    let warm_up_seq = warm_up(ctx, &router, &auth_token).await?;
+
+    // The second event was published after the first, but by a publisher whose own sequence is
+    // lower. The relay orders them as they arrived.
+    let first = publish(ctx).await?;
+    let events = replay_at_least(ctx, &router, &auth_token, warm_up_seq, 1).await?;
+    let first_seq = sequence_of(&events, first).ok_or_else(|| eyre!("first event missing"))?;
+
+    let second = publish_with_stale_sequence(ctx).await?;
+    let events = replay_at_least(ctx, &router, &auth_token, first_seq, 1).await?;
+    assert_eq!(
+        vec![second], // expected
+        ids(&events), // actual
+    );
+    let second_seq = sequence_of(&events, second).ok_or_else(|| eyre!("second event missing"))?;
+    assert!(first_seq < second_seq);
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use axum::{
+    Router,
+    http::{
+        Request,
+        StatusCode,
+        header,
+    },
+};
+use dal::{
+    DalContext,
+    Func,
+    FuncId,
+    func::intrinsics::IntrinsicFunc,
+};
+use dal_test::{
+    AuthToken,
+    Result,
+    sdf_test,
+};
+use hyper::Body;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::Value;
+use si_frontend_types::{
+    FuncKind,
+    FuncSummary,
+};
+use tower::ServiceExt;
+
+async fn get(
+    ctx: &DalContext,
+    router: &Router,
+    auth_token: &AuthToken,
+    path: &str,
+) -> Result<(StatusCode, Value)> {
+    let request = Request::builder()
+        .uri(format!(
+            "/api/v2/workspaces/{}/change-sets/{}/funcs{path}",
+            ctx.workspace_pk()?,
+            ctx.change_set_id(),
+        ))
+        .header(header::AUTHORIZATION, format!("Bearer {}", auth_token.0))
+        .body(Body::empty())?;
+    let response = router.clone().oneshot(request).await?;
+
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    Ok((status, serde_json::from_slice(&body)?))
+}
+
+#[sdf_test]
+async fn list_funcs_filters_by_kind_and_search(
+    ctx: &DalContext,
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    let (status, body) = get(ctx, &router, &auth_token, "?kind=Intrinsic&search=IDENT").await?;
+    assert_eq!(
+        StatusCode::OK, // expected
+        status,         // actual
+    );
+    let funcs: Vec<FuncSummary> = serde_json::from_value(body["items"].clone())?;
+    assert_eq!(
+        Some(funcs.len() as u64), // expected
+        body["total"].as_u64(),   // actual
+    );
+    assert!(
+        funcs
+            .iter()
+            .any(|func| func.name == IntrinsicFunc::Identity.name())
+    );
+    for func in &funcs {
+        assert_eq!(
+            FuncKind::Intrinsic, // expected
+            func.kind,           // actual
+        );
+        assert!(func.name.to_lowercase().contains("ident"));
+    }
+
+    let (status, body) = get(ctx, &router, &auth_token, "?search=no-such-func").await?;
+    let no_funcs = serde_json::json!({ "items": [], "total": 0 });
+    assert_eq!(
+        (StatusCode::OK, no_funcs), // expected
+        (status, body),             // actual
+    );
+
+    let (_, body) = get(
+        ctx,
+        &router,
+        &auth_token,
+        "?kind=Intrinsic&limit=2&offset=1",
+    )
+    .await?;
+    let page: Vec<FuncSummary> = serde_json::from_value(body["items"].clone())?;
+    let page_total = body["total"].clone();
+    let (_, body) = get(ctx, &router, &auth_token, "?kind=Intrinsic&limit=3").await?;
+    let first_page: Vec<FuncSummary> = serde_json::from_value(body["items"].clone())?;
+    assert_eq!(
+        first_page[1..], // expected
+        page[..],        // actual
+    );
+    // The total counts every matching func, not just the page.
+    let (_, body) = get(ctx, &router, &auth_token, "?kind=Intrinsic").await?;
+    assert_eq!(
+        body["total"], // expected
+        page_total,    // actual
+    );
+
+    Ok(())
+}
+
+#[sdf_test]
+async fn get_func_returns_the_func_or_not_found(
+    ctx: &DalContext,
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    let func_id = Func::find_intrinsic(ctx, IntrinsicFunc::Identity).await?;
+    let (status, body) = get(ctx, &router, &auth_token, &format!("/{func_id}")).await?;
+    assert_eq!(
+        StatusCode::OK, // expected
+        status,         // actual
+    );
+    assert_eq!(
+        Value::from(IntrinsicFunc::Identity.name()), // expected
+        body["name"],                                // actual
+    );
+
+    let (status, _) = get(ctx, &router, &auth_token, &format!("/{}", FuncId::new())).await?;
+    assert_eq!(
+        StatusCode::NOT_FOUND, // expected
+        status,                // actual
+    );
+
+    Ok(())
+}
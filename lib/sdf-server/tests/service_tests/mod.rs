@@ -1,2 +1,9 @@
 mod change_set_apply;
 mod change_set_approval;
+mod events;
+mod funcs;
+mod modules;
+mod readiness;
+mod shutdown;
+mod whoami;
+mod workspace;
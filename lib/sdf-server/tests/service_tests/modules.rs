@@ -0,0 +1,534 @@
+use axum::{
+    Router,
+    http::{
+        Method,
+        Request,
+        StatusCode,
+        header,
+    },
+    response::{
+        IntoResponse,
+        Response,
+    },
+};
+use dal::{
+    DalContext,
+    Schema,
+    SchemaId,
+    SchemaVariant,
+    Workspace,
+    WorkspaceFeatureFlag,
+    WorkspacePk,
+};
+use dal_test::{
+    AuthToken,
+    Result,
+    module_index_stub::ModuleIndexStub,
+    pkg_fixture::PkgFixture,
+    sdf_test,
+};
+use hyper::Body;
+use pretty_assertions_sorted::assert_eq;
+use sdf_v1_routes_module::{
+    ModuleError,
+    index_status::{
+        ModuleIndexStatus,
+        module_index_status,
+    },
+};
+use serde_json::{
+    Value,
+    json,
+};
+use si_frontend_types::UninstalledVariant;
+use tower::ServiceExt;
+use ulid::Ulid;
+
+async fn set_module_install(ctx: &mut DalContext, enabled: bool) -> Result<()> {
+    let mut workspace = Workspace::get_by_pk(ctx, ctx.workspace_pk()?).await?;
+    workspace
+        .set_feature_flag(ctx, WorkspaceFeatureFlag::ModuleInstall, Some(enabled))
+        .await?;
+    ctx.commit_no_rebase().await?;
+    Ok(())
+}
+
+fn install_cached_schema_request(
+    ctx: &DalContext,
+    auth_token: &AuthToken,
+    schema_id: SchemaId,
+) -> Result<Request<Body>> {
+    Ok(Request::builder()
+        .method(Method::POST)
+        .uri(format!(
+            "/api/v2/workspaces/{}/change-sets/{}/modules/cached/{schema_id}/install",
+            ctx.workspace_pk()?,
+            ctx.change_set_id(),
+        ))
+        .header(header::AUTHORIZATION, format!("Bearer {}", auth_token.0))
+        .body(Body::empty())?)
+}
+
+async fn install_cached_request(
+    ctx: &DalContext,
+    auth_token: &AuthToken,
+    schema_name: &str,
+) -> Result<Request<Body>> {
+    let schema = Schema::get_by_name(ctx, schema_name).await?;
+    install_cached_schema_request(ctx, auth_token, schema.id())
+}
+
+async fn install_cached_schema(
+    ctx: &DalContext,
+    router: &Router,
+    auth_token: &AuthToken,
+    schema_id: SchemaId,
+) -> Result<(StatusCode, Value)> {
+    let request = install_cached_schema_request(ctx, auth_token, schema_id)?;
+    let response = router.clone().oneshot(request).await?;
+
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    Ok((status, serde_json::from_slice(&body)?))
+}
+
+async fn install_cached(
+    ctx: &DalContext,
+    router: &Router,
+    auth_token: &AuthToken,
+    schema_name: &str,
+) -> Result<(StatusCode, Value)> {
+    let schema = Schema::get_by_name(ctx, schema_name).await?;
+    install_cached_schema(ctx, router, auth_token, schema.id()).await
+}
+
+#[sdf_test]
+async fn module_install_is_gated_by_workspace_feature_flag(
+    ctx: &mut DalContext,
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    // The flag is on by default.
+    let (status, body) = install_cached(ctx, &router, &auth_token, "starfield").await?;
+    assert_eq!(
+        StatusCode::OK, // expected
+        status,         // actual
+    );
+    assert_eq!(
+        Value::Bool(true),        // expected
+        body["alreadyInstalled"]  // actual
+    );
+
+    // With the flag off, the route doesn't exist for the workspace.
+    set_module_install(ctx, false).await?;
+    let (status, _) = install_cached(ctx, &router, &auth_token, "starfield").await?;
+    assert_eq!(
+        StatusCode::NOT_FOUND, // expected
+        status,                // actual
+    );
+
+    // Turning it back on restores it.
+    set_module_install(ctx, true).await?;
+    let (status, _) = install_cached(ctx, &router, &auth_token, "starfield").await?;
+    assert_eq!(
+        StatusCode::OK, // expected
+        status,         // actual
+    );
+
+    Ok(())
+}
+
+#[sdf_test]
+async fn module_install_is_rate_limited(
+    ctx: &mut DalContext,
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    // Installing an installed schema is a no-op, so this only exercises the limit of 10/min.
+    for _ in 0..10 {
+        let (status, _) = install_cached(ctx, &router, &auth_token, "starfield").await?;
+        assert_eq!(
+            StatusCode::OK, // expected
+            status,         // actual
+        );
+    }
+
+    let request = install_cached_request(ctx, &auth_token, "starfield").await?;
+    let response = router.clone().oneshot(request).await?;
+    assert_eq!(
+        StatusCode::TOO_MANY_REQUESTS, // expected
+        response.status(),             // actual
+    );
+    let retry_after: u64 = response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .expect("no retry-after header")
+        .to_str()?
+        .parse()?;
+    assert!((1..=6).contains(&retry_after));
+
+    Ok(())
+}
+
+async fn error_parts(response: Response) -> Result<(StatusCode, Value)> {
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let body: Value = serde_json::from_slice(&body)?;
+    Ok((status, body["error"]["code"].clone()))
+}
+
+#[tokio::test]
+async fn module_errors_carry_status_and_code() -> Result<()> {
+    let (status, code) =
+        error_parts(ModuleError::ExportingImportingWithRootTenancy.into_response()).await?;
+    assert_eq!(
+        (StatusCode::FORBIDDEN, Value::from("root_tenancy")), // expected
+        (status, code),                                       // actual
+    );
+
+    let (status, code) =
+        error_parts(ModuleError::WorkspaceNotFound(WorkspacePk::new()).into_response()).await?;
+    assert_eq!(
+        (StatusCode::NOT_FOUND, Value::from("workspace_not_found")), // expected
+        (status, code),                                              // actual
+    );
+
+    let (status, code) = error_parts(ModuleError::ModuleIndexNotConfigured.into_response()).await?;
+    assert_eq!(
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Value::from("module_index_not_configured")
+        ), // expected
+        (status, code), // actual
+    );
+
+    Ok(())
+}
+
+#[sdf_test]
+async fn index_status_reports_the_configured_module_index(
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    let request = Request::builder()
+        .uri("/api/module/index_status")
+        .header(header::AUTHORIZATION, format!("Bearer {}", auth_token.0))
+        .body(Body::empty())?;
+    let response = router.oneshot(request).await?;
+    assert_eq!(
+        StatusCode::OK,    // expected
+        response.status(), // actual
+    );
+
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let status: ModuleIndexStatus = serde_json::from_slice(&body)?;
+    assert!(status.configured);
+    assert_eq!(
+        status.reachable,            // expected
+        status.latency_ms.is_some(), // actual
+    );
+    assert_eq!(
+        status.reachable,       // expected
+        status.error.is_none(), // actual
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn module_index_status_when_reachable() -> Result<()> {
+    let stub = ModuleIndexStub::start()?;
+
+    let status = module_index_status(Some(stub.url()), "token").await;
+    assert!(status.configured);
+    assert!(status.reachable);
+    assert!(status.latency_ms.is_some());
+    assert_eq!(
+        None,         // expected
+        status.error, // actual
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn module_index_status_when_unreachable() -> Result<()> {
+    let stub = ModuleIndexStub::start()?;
+    stub.fail_next_requests(1);
+
+    let status = module_index_status(Some(stub.url()), "token").await;
+    assert!(status.configured);
+    assert!(!status.reachable);
+    assert_eq!(
+        None,              // expected
+        status.latency_ms, // actual
+    );
+    assert!(status.error.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn module_index_status_when_not_configured() -> Result<()> {
+    assert_eq!(
+        ModuleIndexStatus {
+            configured: false,
+            reachable: false,
+            latency_ms: None,
+            error: Some(dal::health::MODULE_INDEX_URL_NOT_SET.to_string()),
+        }, // expected
+        module_index_status(None, "token").await, // actual
+    );
+
+    Ok(())
+}
+
+async fn insert_cached_module(
+    ctx: &DalContext,
+    schema_id: SchemaId,
+    schema_name: &str,
+    display_name: &str,
+    category: &str,
+) -> Result<()> {
+    insert_cached_module_with_package(
+        ctx,
+        schema_id,
+        schema_name,
+        display_name,
+        category,
+        &Ulid::new().to_string(),
+        None,
+    )
+    .await
+}
+
+async fn insert_cached_module_with_package(
+    ctx: &DalContext,
+    schema_id: SchemaId,
+    schema_name: &str,
+    display_name: &str,
+    category: &str,
+    latest_hash: &str,
+    package_data: Option<&[u8]>,
+) -> Result<()> {
+    ctx.txns()
+        .await?
+        .pg()
+        .execute(
+            "INSERT INTO cached_modules (
+                schema_id,
+                schema_name,
+                display_name,
+                category,
+                component_type,
+                latest_hash,
+                created_at,
+                package_data
+            ) VALUES ($1, $2, $3, $4, 'component', $5, now(), $6)",
+            &[
+                &schema_id,
+                &schema_name,
+                &display_name,
+                &category,
+                &latest_hash,
+                &package_data,
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+async fn get_cached(
+    ctx: &DalContext,
+    router: &Router,
+    auth_token: &AuthToken,
+    path: &str,
+) -> Result<(StatusCode, Value)> {
+    let request = Request::builder()
+        .uri(format!(
+            "/api/v2/workspaces/{}/change-sets/{}/modules/cached{path}",
+            ctx.workspace_pk()?,
+            ctx.change_set_id(),
+        ))
+        .header(header::AUTHORIZATION, format!("Bearer {}", auth_token.0))
+        .body(Body::empty())?;
+    let response = router.clone().oneshot(request).await?;
+
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    Ok((status, serde_json::from_slice(&body)?))
+}
+
+fn schema_names(body: Value) -> Result<Vec<String>> {
+    let variants: Vec<UninstalledVariant> = serde_json::from_value(body)?;
+    let mut names: Vec<_> = variants
+        .into_iter()
+        .map(|variant| variant.schema_name)
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+#[sdf_test]
+async fn cached_modules_are_listed_and_summarized(
+    ctx: &DalContext,
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    // The module cache is shared by every workspace, so the seeded rows are made unique to this
+    // test and looked up by that.
+    let unique = Ulid::new().to_string().to_lowercase();
+    let alpha_schema_id = SchemaId::new();
+    let installed_schema_id = Schema::get_by_name(ctx, "starfield").await?.id();
+    insert_cached_module(
+        ctx,
+        alpha_schema_id,
+        &format!("{unique}-alpha"),
+        "Alpha",
+        &format!("Test {unique}"),
+    )
+    .await?;
+    insert_cached_module(
+        ctx,
+        SchemaId::new(),
+        &format!("{unique}-beta"),
+        &format!("Beta {unique}"),
+        &format!("Other {unique}"),
+    )
+    .await?;
+    insert_cached_module(
+        ctx,
+        installed_schema_id,
+        &format!("{unique}-installed"),
+        "Installed",
+        &format!("Test {unique}"),
+    )
+    .await?;
+    ctx.commit_no_rebase().await?;
+
+    // Schemas installed in the change set are left out.
+    let (status, body) =
+        get_cached(ctx, &router, &auth_token, &format!("?search={unique}")).await?;
+    assert_eq!(
+        StatusCode::OK, // expected
+        status,         // actual
+    );
+    assert_eq!(
+        vec![format!("{unique}-alpha"), format!("{unique}-beta")], // expected
+        schema_names(body)?,                                       // actual
+    );
+
+    // The category must match, ignoring case.
+    let (_, body) = get_cached(
+        ctx,
+        &router,
+        &auth_token,
+        &format!("?search={unique}&category=TEST%20{unique}"),
+    )
+    .await?;
+    assert_eq!(
+        vec![format!("{unique}-alpha")], // expected
+        schema_names(body)?,             // actual
+    );
+
+    // The search matches display names too.
+    let (_, body) = get_cached(
+        ctx,
+        &router,
+        &auth_token,
+        &format!("?search=BETA%20{unique}"),
+    )
+    .await?;
+    assert_eq!(
+        vec![format!("{unique}-beta")], // expected
+        schema_names(body)?,            // actual
+    );
+
+    let (status, body) =
+        get_cached(ctx, &router, &auth_token, &format!("/{alpha_schema_id}")).await?;
+    assert_eq!(
+        StatusCode::OK, // expected
+        status,         // actual
+    );
+    assert_eq!(
+        (
+            json!(format!("{unique}-alpha")),
+            json!("Alpha"),
+            json!(false)
+        ), // expected
+        (
+            body["schemaName"].clone(),
+            body["displayName"].clone(),
+            body["installed"].clone()
+        ), // actual
+    );
+
+    let (_, body) = get_cached(
+        ctx,
+        &router,
+        &auth_token,
+        &format!("/{installed_schema_id}"),
+    )
+    .await?;
+    assert_eq!(
+        json!(true),       // expected
+        body["installed"], // actual
+    );
+
+    let (status, body) =
+        get_cached(ctx, &router, &auth_token, &format!("/{}", SchemaId::new())).await?;
+    assert_eq!(
+        (StatusCode::NOT_FOUND, json!("not_found")), // expected
+        (status, body["error"]["code"].clone()),     // actual
+    );
+
+    Ok(())
+}
+
+#[sdf_test]
+async fn installing_a_cached_module_again_is_a_no_op(
+    ctx: &mut DalContext,
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    let schema_name = format!("reinstalled-{}", Ulid::new().to_string().to_lowercase());
+    let fixture = PkgFixture::builder(&schema_name).build()?;
+    let schema_id = SchemaId::new();
+    insert_cached_module_with_package(
+        ctx,
+        schema_id,
+        &schema_name,
+        &schema_name,
+        "test exclusive",
+        &fixture.hash()?,
+        Some(&fixture.bytes),
+    )
+    .await?;
+    ctx.commit_no_rebase().await?;
+
+    let (status, installed) = install_cached_schema(ctx, &router, &auth_token, schema_id).await?;
+    assert_eq!(
+        (StatusCode::OK, json!(false)),                  // expected
+        (status, installed["alreadyInstalled"].clone()), // actual
+    );
+    let schema_variant_id = installed["schemaVariant"]["schemaVariantId"].clone();
+
+    let (status, reinstalled) = install_cached_schema(ctx, &router, &auth_token, schema_id).await?;
+    assert_eq!(
+        (StatusCode::OK, json!(true)),                     // expected
+        (status, reinstalled["alreadyInstalled"].clone()), // actual
+    );
+    assert_eq!(
+        schema_variant_id,                                       // expected
+        reinstalled["schemaVariant"]["schemaVariantId"].clone(), // actual
+    );
+
+    // Nothing was imported the second time.
+    ctx.update_snapshot_to_visibility().await?;
+    assert_eq!(
+        1,                                                           // expected
+        SchemaVariant::list_for_schema(ctx, schema_id).await?.len(), // actual
+    );
+
+    Ok(())
+}
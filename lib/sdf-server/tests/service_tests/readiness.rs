@@ -0,0 +1,111 @@
+use axum::{
+    Router,
+    http::{
+        Request,
+        StatusCode,
+        header,
+    },
+};
+use dal::health::{
+    DependencyHealth,
+    HealthDependency,
+    HealthReport,
+};
+use dal_test::{
+    AuthToken,
+    Result,
+    sdf_test,
+};
+use hyper::Body;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::Value;
+use tower::ServiceExt;
+
+async fn readiness(router: &Router) -> Result<(StatusCode, Value)> {
+    let request = Request::builder()
+        .uri("/api/readiness")
+        .body(Body::empty())?;
+    let response = router.clone().oneshot(request).await?;
+
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    Ok((status, serde_json::from_slice(&body)?))
+}
+
+#[sdf_test]
+async fn readiness_reports_critical_dependencies(router: Router) -> Result<()> {
+    let (status, body) = readiness(&router).await?;
+
+    // Whether or not the module index is reachable from the test environment, it isn't critical,
+    // so it never makes sdf unready.
+    assert_eq!(
+        StatusCode::OK, // expected
+        status,         // actual
+    );
+    assert_eq!(
+        Value::Bool(true), // expected
+        body["ok"],        // actual
+    );
+
+    let dependencies: Vec<DependencyHealth> = serde_json::from_value(body["dependencies"].clone())?;
+    assert_eq!(
+        vec![
+            (HealthDependency::ModuleIndex, false),
+            (HealthDependency::Nats, true),
+            (HealthDependency::Pg, true),
+            (HealthDependency::Veritech, true),
+        ], // expected
+        dependencies
+            .iter()
+            .map(|dependency| (dependency.dependency, dependency.critical))
+            .collect::<Vec<_>>(), // actual
+    );
+    for dependency in dependencies.iter().filter(|dependency| dependency.critical) {
+        assert!(dependency.healthy, "{dependency:?}");
+    }
+
+    Ok(())
+}
+
+async fn health(router: &Router, auth_token: Option<&AuthToken>) -> Result<HealthReport> {
+    let mut request = Request::builder().uri("/api/health");
+    if let Some(auth_token) = auth_token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", auth_token.0));
+    }
+    let response = router.clone().oneshot(request.body(Body::empty())?).await?;
+    assert_eq!(
+        StatusCode::OK,    // expected
+        response.status(), // actual
+    );
+
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+#[sdf_test]
+async fn health_only_reports_errors_to_authenticated_callers(
+    router: Router,
+    auth_token: AuthToken,
+) -> Result<()> {
+    let anonymous = health(&router, None).await?;
+    assert_eq!(4, anonymous.dependencies.len());
+    for dependency in &anonymous.dependencies {
+        assert_eq!(
+            None,             // expected
+            dependency.error, // actual
+        );
+    }
+
+    // Whether the module index is reachable from the test environment varies, but any
+    // dependency that is unhealthy for an authenticated caller says why
+    let authenticated = health(&router, Some(&auth_token)).await?;
+    assert_eq!(4, authenticated.dependencies.len());
+    for dependency in &authenticated.dependencies {
+        assert_eq!(
+            !dependency.healthy,        // expected
+            dependency.error.is_some(), // actual
+        );
+    }
+
+    Ok(())
+}
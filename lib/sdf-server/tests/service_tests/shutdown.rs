@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use dal::DalContext;
+use dal_test::{
+    Result,
+    sdf_test,
+};
+use hyper::Uri;
+use pretty_assertions_sorted::assert_eq;
+use sdf_core::{
+    async_route::{
+        AsyncTaskStatus,
+        handle_finish,
+    },
+    long_tasks::{
+        LongTaskKind,
+        LongTasks,
+    },
+};
+use ulid::Ulid;
+
+const GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+#[sdf_test]
+async fn drain_cancels_tasks_that_outlive_the_grace_period(ctx: &DalContext) -> Result<()> {
+    let long_tasks = LongTasks::new();
+    let uri = Uri::from_static("/api/test/long_task");
+
+    let quick_task_id = Ulid::new();
+    long_tasks.spawn_async_route(
+        ctx.clone(),
+        uri.clone(),
+        quick_task_id,
+        LongTaskKind::ModuleCacheUpdate,
+        move |ctx, uri, statuses| async move {
+            tokio::time::sleep(GRACE_PERIOD / 5).await;
+            handle_finish(&ctx, &statuses, uri, quick_task_id).await;
+        },
+    );
+
+    let slow_task_id = Ulid::new();
+    long_tasks.spawn_async_route(
+        ctx.clone(),
+        uri,
+        slow_task_id,
+        LongTaskKind::WorkspaceInstall,
+        move |ctx, uri, statuses| async move {
+            tokio::time::sleep(Duration::from_secs(60 * 10)).await;
+            handle_finish(&ctx, &statuses, uri, slow_task_id).await;
+        },
+    );
+
+    assert!(!long_tasks.is_draining());
+    assert_eq!(
+        2,                            // expected
+        long_tasks.in_flight().len(), // actual
+    );
+
+    let outcome = long_tasks.drain(GRACE_PERIOD).await;
+
+    assert!(long_tasks.is_draining());
+    assert_eq!(
+        vec![(slow_task_id, LongTaskKind::WorkspaceInstall)], // expected
+        outcome.cancelled,                                    // actual
+    );
+    assert_eq!(
+        Vec::<(Ulid, LongTaskKind)>::new(), // expected
+        long_tasks.in_flight(),             // actual
+    );
+
+    let workspace_pk = ctx.tenancy().workspace_pk_opt();
+    let statuses = long_tasks.task_statuses();
+    assert_eq!(
+        Some(AsyncTaskStatus::Finished),           // expected
+        statuses.get(workspace_pk, quick_task_id), // actual
+    );
+    assert_eq!(
+        Some(AsyncTaskStatus::Cancelled),         // expected
+        statuses.get(workspace_pk, slow_task_id), // actual
+    );
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use axum::{
+    Router,
+    http::{
+        Request,
+        StatusCode,
+        header,
+    },
+};
+use dal_test::{
+    Result,
+    WorkspaceSignup,
+    helpers::{
+        AuthTokenExpiry,
+        create_auth_token_with,
+    },
+    sdf_test,
+};
+use hyper::Body;
+use pretty_assertions_sorted::assert_eq;
+use si_jwt_public_key::SiJwtClaims;
+use tower::ServiceExt;
+
+async fn whoami_status(router: &Router, auth_token: &str) -> Result<StatusCode> {
+    let request = Request::builder()
+        .uri("/api/whoami")
+        .header(header::AUTHORIZATION, format!("Bearer {auth_token}"))
+        .body(Body::empty())?;
+    let response = router.clone().oneshot(request).await?;
+    Ok(response.status())
+}
+
+#[sdf_test]
+async fn whoami_rejects_expired_token(router: Router, nw: &WorkspaceSignup) -> Result<()> {
+    let claims = SiJwtClaims::for_web(nw.user.pk(), *nw.workspace.pk());
+
+    let valid_token = create_auth_token_with(claims.clone(), AuthTokenExpiry::default()).await?;
+    assert_eq!(
+        StatusCode::OK,                              // expected
+        whoami_status(&router, &valid_token).await?, // actual
+    );
+
+    let expired_token = create_auth_token_with(
+        claims,
+        AuthTokenExpiry::ExpiredFor(Duration::from_secs(60 * 60)),
+    )
+    .await?;
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,                      // expected
+        whoami_status(&router, &expired_token).await?, // actual
+    );
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use axum::{
+    Router,
+    http::{
+        Method,
+        Request,
+        StatusCode,
+        header,
+    },
+};
+use dal::{
+    DalContext,
+    WorkspacePk,
+};
+use dal_test::{
+    AuthToken,
+    Result,
+    sdf_test,
+};
+use hyper::{
+    Body,
+    Uri,
+};
+use pretty_assertions_sorted::assert_eq;
+use sdf_core::{
+    async_route::{
+        self,
+        AsyncTaskStatus,
+        AsyncTaskStatuses,
+    },
+    long_tasks::LongTasks,
+};
+use sdf_server::service::v2::workspace::WorkspaceAPIError;
+use serde_json::{
+    Value,
+    json,
+};
+use tower::ServiceExt;
+use ulid::Ulid;
+
+const URI: &str = "/api/v2/workspaces/install";
+
+async fn get_async_task_status(
+    ctx: &DalContext,
+    router: &Router,
+    auth_token: &AuthToken,
+    task_id: Ulid,
+) -> Result<(StatusCode, Value)> {
+    let request = Request::builder()
+        .uri(format!(
+            "/api/v2/workspaces/{}/async/{task_id}",
+            ctx.workspace_pk()?
+        ))
+        .header(header::AUTHORIZATION, format!("Bearer {}", auth_token.0))
+        .body(Body::empty())?;
+    let response = router.clone().oneshot(request).await?;
+
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    Ok((status, serde_json::from_slice(&body)?))
+}
+
+#[sdf_test]
+async fn handle_error_and_track_sends_only_the_error_kind(ctx: &DalContext) -> Result<()> {
+    let statuses = AsyncTaskStatuses::default();
+    let task_id = Ulid::new();
+    statuses.register(ctx, task_id);
+
+    let err = WorkspaceAPIError::LatestItemNotFound(
+        WorkspacePk::new(),
+        "secret-kind".to_string(),
+        "https://token@example.com".to_string(),
+    );
+    let error_message = err.to_string();
+    let error_kind = err.kind();
+
+    let mut tracked = None;
+    async_route::handle_error_and_track(
+        ctx,
+        &statuses,
+        Uri::from_static(URI),
+        task_id,
+        err,
+        error_kind,
+        |error_kind| tracked = Some(error_kind.to_owned()),
+    )
+    .await;
+
+    assert_eq!(
+        Some("latest_item_not_found".to_string()), // expected
+        tracked,                                   // actual
+    );
+    assert_eq!(
+        Some(AsyncTaskStatus::Errored {
+            error: error_message
+        }), // expected
+        statuses.get(ctx.tenancy().workspace_pk_opt(), task_id), // actual
+    );
+
+    Ok(())
+}
+
+#[sdf_test]
+async fn get_async_task_status_reports_each_outcome(
+    ctx: &DalContext,
+    router: Router,
+    auth_token: AuthToken,
+    long_tasks: LongTasks,
+) -> Result<()> {
+    let statuses = long_tasks.task_statuses();
+
+    let pending_task_id = Ulid::new();
+    statuses.register(ctx, pending_task_id);
+    assert_eq!(
+        (StatusCode::OK, json!({ "status": "pending" })), // expected
+        get_async_task_status(ctx, &router, &auth_token, pending_task_id).await?, // actual
+    );
+
+    let finished_task_id = Ulid::new();
+    statuses.register(ctx, finished_task_id);
+    async_route::handle_finish(ctx, statuses, Uri::from_static(URI), finished_task_id).await;
+    assert_eq!(
+        (StatusCode::OK, json!({ "status": "finished" })), // expected
+        get_async_task_status(ctx, &router, &auth_token, finished_task_id).await?, // actual
+    );
+
+    let errored_task_id = Ulid::new();
+    statuses.register(ctx, errored_task_id);
+    async_route::handle_error(
+        ctx,
+        statuses,
+        Uri::from_static(URI),
+        errored_task_id,
+        WorkspaceAPIError::ModuleIndexUrlNotSet,
+    )
+    .await;
+    assert_eq!(
+        (
+            StatusCode::OK,
+            json!({ "status": "errored", "error": "module index url not set" })
+        ), // expected
+        get_async_task_status(ctx, &router, &auth_token, errored_task_id).await?, // actual
+    );
+
+    // Tasks of other apps aren't visible
+    let other_task_id = Ulid::new();
+    AsyncTaskStatuses::default().register(ctx, other_task_id);
+    let (status, _) = get_async_task_status(ctx, &router, &auth_token, other_task_id).await?;
+    assert_eq!(
+        StatusCode::NOT_FOUND, // expected
+        status,                // actual
+    );
+
+    Ok(())
+}
+
+#[sdf_test]
+async fn restore_backup_reports_its_outcome_through_the_task(
+    ctx: &DalContext,
+    router: Router,
+    auth_token: AuthToken,
+    long_tasks: LongTasks,
+) -> Result<()> {
+    // No backup has this id, so the restore fails, but only once the task downloads it.
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!(
+            "/api/v2/workspaces/{}/backups/{}/restore",
+            ctx.workspace_pk()?,
+            Ulid::new(),
+        ))
+        .header(header::AUTHORIZATION, format!("Bearer {}", auth_token.0))
+        .body(Body::empty())?;
+    let response = router.clone().oneshot(request).await?;
+    assert_eq!(
+        StatusCode::OK,    // expected
+        response.status(), // actual
+    );
+    let body: Value = serde_json::from_slice(&hyper::body::to_bytes(response.into_body()).await?)?;
+    let task_id: Ulid = serde_json::from_value(body["id"].clone())?;
+
+    let workspace_pk = ctx.tenancy().workspace_pk_opt();
+    let status = tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            match long_tasks.task_statuses().get(workspace_pk, task_id) {
+                Some(AsyncTaskStatus::Pending) | None => {
+                    tokio::time::sleep(Duration::from_millis(50)).await
+                }
+                Some(status) => break status,
+            }
+        }
+    })
+    .await?;
+    assert!(matches!(status, AsyncTaskStatus::Errored { .. }));
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::time::Instant;
+
+use axum::{
+    Json,
+    extract::State,
+};
+use dal::health::check_module_index;
+use sdf_core::app_state::AppState;
+use sdf_extract::{
+    request::RawAccessToken,
+    v1::AccessBuilder,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleIndexStatus {
+    pub configured: bool,
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+pub async fn index_status(
+    AccessBuilder(_request_ctx): AccessBuilder,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    State(state): State<AppState>,
+) -> Json<ModuleIndexStatus> {
+    Json(
+        module_index_status(
+            state.services_context().module_index_url(),
+            &raw_access_token,
+        )
+        .await,
+    )
+}
+
+/// Reports whether the module index at the url responds to a ping with the caller's token.
+pub async fn module_index_status(
+    module_index_url: Option<&str>,
+    auth_token: &str,
+) -> ModuleIndexStatus {
+    let started_at = Instant::now();
+    let result = check_module_index(module_index_url, Some(auth_token)).await;
+
+    ModuleIndexStatus {
+        configured: module_index_url.is_some(),
+        reachable: result.is_ok(),
+        latency_ms: result
+            .is_ok()
+            .then(|| started_at.elapsed().as_millis() as u64),
+        error: result.err(),
+    }
+}
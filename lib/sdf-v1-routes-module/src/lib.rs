@@ -10,7 +10,10 @@ use axum::{
         IntoResponse,
         Response,
     },
-    routing::post,
+    routing::{
+        get,
+        post,
+    },
 };
 use convert_case::{
     Case,
@@ -48,6 +51,7 @@ use ulid::Ulid;
 
 pub mod approval_process;
 pub mod import_workspace_vote;
+pub mod index_status;
 pub mod install_module;
 pub mod upgrade_modules;
 
@@ -145,18 +149,39 @@ pub enum ModuleError {
 
 pub type ModuleResult<T> = Result<T, ModuleError>;
 
+impl ModuleError {
+    /// A stable, machine-readable code for the error, sent alongside its message in responses and
+    /// async error WsEvents.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ModuleError::ExportingImportingWithRootTenancy => "root_tenancy",
+            ModuleError::ModuleIndexNotConfigured => "module_index_not_configured",
+            ModuleError::WorkspaceNotFound(_) => "workspace_not_found",
+            ModuleError::ModuleHashNotFound(_)
+            | ModuleError::PackageNotFound(_)
+            | ModuleError::SchemaNotFoundForVariant(_)
+            | ModuleError::SchemaVariantNotFound(_) => "not_found",
+            _ => "internal_error",
+        }
+    }
+}
+
 impl IntoResponse for ModuleError {
     fn into_response(self) -> Response {
-        let (status_code, error_message) = match self {
+        let status_code = match self {
+            ModuleError::ExportingImportingWithRootTenancy => StatusCode::FORBIDDEN,
+            ModuleError::ModuleIndexNotConfigured => StatusCode::SERVICE_UNAVAILABLE,
             ModuleError::ModuleHashNotFound(_)
             | ModuleError::PackageNotFound(_)
             | ModuleError::SchemaNotFoundForVariant(_)
             | ModuleError::SchemaVariantNotFound(_)
-            | ModuleError::WorkspaceNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
-            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+            | ModuleError::WorkspaceNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
-        ApiError::new(status_code, error_message).into_response()
+        ApiError::new(status_code, &self)
+            .with_code(self.code())
+            .into_response()
     }
 }
 
@@ -251,6 +276,7 @@ pub async fn pkg_open(builder: &DalContextBuilder, file_name: &str) -> ModuleRes
 pub fn routes() -> Router<sdf_core::app_state::AppState> {
     Router::new()
         .route("/install_module", post(install_module::install_module)) // USED IN CUSTOMIZE SCREEN
+        .route("/index_status", get(index_status::index_status))
         .route("/upgrade_modules", post(upgrade_modules::upgrade_modules)) // USED IN CUSTOMIZE SCREEN
         .route(
             "/begin_approval_process", // USED IN CUSTOMIZE SCREEN
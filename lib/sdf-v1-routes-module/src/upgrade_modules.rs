@@ -21,12 +21,17 @@ use dal::{
     },
 };
 use sdf_core::{
-    async_route::handle_error,
+    async_route::{
+        handle_error_with_code,
+        handle_finish,
+    },
     force_change_set_response::ForceChangeSetResponse,
+    long_tasks::LongTaskKind,
     tracking::track,
 };
 use sdf_extract::{
     HandlerContext,
+    LongTaskSpawner,
     PosthogClient,
     v1::AccessBuilder,
 };
@@ -57,6 +62,7 @@ pub async fn upgrade_modules(
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
+    LongTaskSpawner(long_tasks): LongTaskSpawner,
     Json(request): Json<UpgradeModulesRequest>,
 ) -> ModuleResult<ForceChangeSetResponse<Ulid>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
@@ -65,30 +71,36 @@ pub async fn upgrade_modules(
 
     let task_id = Ulid::new();
 
-    tokio::task::spawn(async move {
-        if let Err(err) = upgrade_modules_inner(
-            &ctx,
-            &original_uri,
-            &host_name,
-            PosthogClient(posthog_client),
-            request.schema_ids,
-        )
-        .await
-        {
-            return handle_error(&ctx, original_uri, task_id, err).await;
-        };
-
-        let event = match WsEvent::async_finish_workspace(&ctx, task_id).await {
-            Ok(event) => event,
-            Err(err) => {
-                return handle_error(&ctx, original_uri, task_id, err).await;
-            }
-        };
-
-        if let Err(err) = event.publish_immediately(&ctx).await {
-            handle_error(&ctx, original_uri, task_id, err).await;
-        };
-    });
+    long_tasks.spawn_async_route(
+        ctx,
+        original_uri,
+        task_id,
+        LongTaskKind::ModuleUpgrade,
+        move |ctx, original_uri, statuses| async move {
+            if let Err(err) = upgrade_modules_inner(
+                &ctx,
+                &original_uri,
+                &host_name,
+                PosthogClient(posthog_client),
+                request.schema_ids,
+            )
+            .await
+            {
+                let code = err.code();
+                return handle_error_with_code(
+                    &ctx,
+                    &statuses,
+                    original_uri,
+                    task_id,
+                    err,
+                    Some(code),
+                )
+                .await;
+            };
+
+            handle_finish(&ctx, &statuses, original_uri, task_id).await;
+        },
+    );
 
     Ok(ForceChangeSetResponse::new(force_change_set_id, task_id))
 }
@@ -1426,10 +1426,17 @@ impl ConnectionMetadata {
     }
 }
 
+#[derive(Clone, Debug)]
+struct PendingPublish {
+    subject: Subject,
+    coalesce_key: Option<String>,
+    object: serde_json::Value,
+}
+
 #[derive(Clone, Debug)]
 pub struct NatsTxn {
     client: Client,
-    pending_publish: Arc<Mutex<Vec<(Subject, serde_json::Value)>>>,
+    pending_publish: Arc<Mutex<Vec<PendingPublish>>>,
     metadata: Arc<ConnectionMetadata>,
     tx_span: Span,
 }
@@ -1467,7 +1474,53 @@ impl NatsTxn {
         let json: serde_json::Value = serde_json::to_value(object)
             .map_err(|err| span.record_err(self.tx_span.record_err(Error::Serialize(err))))?;
         let mut pending_publish = self.pending_publish.lock().await;
-        pending_publish.push((subject, json));
+        pending_publish.push(PendingPublish {
+            subject,
+            coalesce_key: None,
+            object: json,
+        });
+
+        Ok(())
+    }
+
+    /// Like [`Self::publish`], but replaces any message still pending for the same subject and
+    /// coalesce key, so that only the last one is published on commit.
+    #[instrument(
+        name = "nats_txn.publish_coalesced",
+        skip_all,
+        level = "debug",
+        fields(
+            messaging.destination.name = Empty,
+            otel.kind = SpanKind::Internal.as_str(),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+        )
+    )]
+    pub async fn publish_coalesced<T>(
+        &self,
+        subject: impl ToSubject,
+        coalesce_key: impl Into<String>,
+        object: &T,
+    ) -> Result<()>
+    where
+        T: Serialize + Debug,
+    {
+        let span = current_span_for_instrument_at!("debug");
+        span.follows_from(&self.tx_span);
+
+        let subject = subject.to_subject();
+        span.record("messaging.destination.name", subject.as_str());
+        let json: serde_json::Value = serde_json::to_value(object)
+            .map_err(|err| span.record_err(self.tx_span.record_err(Error::Serialize(err))))?;
+        let coalesce_key = Some(coalesce_key.into());
+        let mut pending_publish = self.pending_publish.lock().await;
+        pending_publish
+            .retain(|pending| pending.subject != subject || pending.coalesce_key != coalesce_key);
+        pending_publish.push(PendingPublish {
+            subject,
+            coalesce_key,
+            object: json,
+        });
 
         Ok(())
     }
@@ -1518,15 +1571,9 @@ impl NatsTxn {
         let span = current_span_for_instrument_at!("debug");
         span.follows_from(&self.tx_span);
 
-        let mut pending_publish = self.pending_publish.lock_owned().await;
-        for (subject, object) in pending_publish.drain(0..) {
-            let msg = serde_json::to_vec(&object)
-                .map_err(|err| span.record_err(self.tx_span.record_err(Error::Serialize(err))))?;
-            self.client
-                .publish(subject, msg.into())
-                .await
-                .map_err(|err| span.record_err(self.tx_span.record_err(err)))?;
-        }
+        self.publish_pending()
+            .await
+            .map_err(|err| span.record_err(self.tx_span.record_err(err)))?;
 
         self.tx_span.record_ok();
         self.tx_span.record("messaging.x.transaction", "commit");
@@ -1548,6 +1595,44 @@ impl NatsTxn {
         Ok(())
     }
 
+    /// Publishes every message pending so far without committing the transaction, for
+    /// long-lived transactions whose messages should not wait for the commit. Messages published
+    /// afterwards are pending until the commit (or the next flush), as usual.
+    #[instrument(
+        name = "nats_txn.flush_pending",
+        skip_all,
+        level = "debug",
+        fields(
+            otel.kind = SpanKind::Internal.as_str(),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+        )
+    )]
+    pub async fn flush_pending(&self) -> Result<()> {
+        let span = current_span_for_instrument_at!("debug");
+        span.follows_from(&self.tx_span);
+
+        self.publish_pending()
+            .await
+            .map_err(|err| span.record_err(err))?;
+
+        span.record_ok();
+        Ok(())
+    }
+
+    async fn publish_pending(&self) -> Result<()> {
+        let mut pending_publish = self.pending_publish.lock().await;
+        for PendingPublish {
+            subject, object, ..
+        } in pending_publish.drain(0..)
+        {
+            let msg = serde_json::to_vec(&object).map_err(Error::Serialize)?;
+            self.client.publish(subject, msg.into()).await?;
+        }
+
+        Ok(())
+    }
+
     #[instrument(
         name = "nats_txn.rollback_into_conn",
         skip_all,
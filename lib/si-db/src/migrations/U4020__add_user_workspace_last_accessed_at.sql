@@ -0,0 +1 @@
+ALTER TABLE user_belongs_to_workspaces ADD COLUMN last_accessed_at timestamp with time zone;
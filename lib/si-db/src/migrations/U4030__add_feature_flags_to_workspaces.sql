@@ -0,0 +1,2 @@
+ALTER TABLE workspaces
+    ADD COLUMN feature_flags jsonb NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,2 @@
+ALTER TABLE debug_func_job_states
+    ADD COLUMN secret_names text[] NOT NULL DEFAULT '{}';
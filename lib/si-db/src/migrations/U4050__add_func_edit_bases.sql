@@ -0,0 +1,9 @@
+CREATE TABLE func_edit_bases
+(
+    workspace_id            ident not null,
+    change_set_id           ident not null,
+    func_id                 ident not null,
+    base_content_hash       text not null,
+    created_at              timestamp with time zone not null default now(),
+    PRIMARY KEY (workspace_id, change_set_id, func_id)
+);
@@ -0,0 +1,26 @@
+CREATE TABLE workspace_webhooks
+(
+    id                      ident primary key default ident_create_v1(),
+    workspace_pk            ident not null,
+    url                     text not null,
+    secret                  text not null,
+    event_kinds             text[] not null default '{}',
+    enabled                 boolean not null default true,
+    created_at              timestamp with time zone not null default now(),
+    updated_at              timestamp with time zone not null default now()
+);
+
+CREATE INDEX idx_workspace_webhooks_workspace_pk ON workspace_webhooks (workspace_pk);
+
+CREATE TABLE workspace_webhook_deliveries
+(
+    id                      ident primary key default ident_create_v1(),
+    webhook_id              ident not null,
+    event_kind              text not null,
+    attempt                 integer not null,
+    status_code             integer,
+    error                   text,
+    created_at              timestamp with time zone not null default now()
+);
+
+CREATE INDEX idx_workspace_webhook_deliveries_webhook_id ON workspace_webhook_deliveries (webhook_id, created_at);
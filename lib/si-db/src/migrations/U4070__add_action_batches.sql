@@ -0,0 +1,11 @@
+CREATE TABLE action_batches
+(
+    id                      ident primary key default ident_create_v1(),
+    workspace_pk            ident not null,
+    change_set_id           ident not null,
+    action_prototype_id     ident not null,
+    members                 jsonb not null default '[]',
+    created_at              timestamp with time zone not null default now()
+);
+
+CREATE INDEX idx_action_batches_workspace_pk ON action_batches (workspace_pk);
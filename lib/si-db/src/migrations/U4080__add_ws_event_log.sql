@@ -0,0 +1,14 @@
+CREATE TABLE ws_event_log
+(
+    id                      ident primary key default ident_create_v1(),
+    workspace_pk            ident not null,
+    change_set_id           ident,
+    sequence                bigint not null,
+    kind                    text not null,
+    actor                   jsonb,
+    payload                 jsonb not null,
+    created_at              timestamp with time zone not null default now()
+);
+
+CREATE INDEX idx_ws_event_log_workspace_pk_sequence ON ws_event_log (workspace_pk, sequence);
+CREATE INDEX idx_ws_event_log_created_at ON ws_event_log (created_at);
@@ -0,0 +1 @@
+ALTER TABLE action_batches ADD COLUMN retries_batch_id ident;
@@ -0,0 +1,4 @@
+ALTER TABLE ws_event_log ADD COLUMN position bigserial;
+
+DROP INDEX IF EXISTS idx_ws_event_log_workspace_pk_sequence;
+CREATE INDEX idx_ws_event_log_workspace_pk_position ON ws_event_log (workspace_pk, position);
@@ -0,0 +1,11 @@
+CREATE TABLE queued_executions
+(
+    func_run_id             ident primary key,
+    workspace_pk            ident not null,
+    function_name           text not null,
+    action_id               ident,
+    component_id            ident,
+    queued_at               timestamp with time zone not null
+);
+
+CREATE INDEX idx_queued_executions_workspace_pk ON queued_executions (workspace_pk, queued_at);
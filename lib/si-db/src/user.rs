@@ -155,6 +155,24 @@ impl User {
         Ok(())
     }
 
+    /// Stamps the user's membership of the workspace with the current time, for "recently used"
+    /// ordering and finding dormant workspaces.
+    pub async fn record_workspace_access(
+        ctx: &impl SiDbContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+    ) -> Result<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "UPDATE user_belongs_to_workspaces SET last_accessed_at = CLOCK_TIMESTAMP() WHERE user_pk = $1 AND workspace_pk = $2",
+                &[&user_pk, &workspace_pk],
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn list_members_for_workspace(
         ctx: &impl SiDbContext,
         workspace_pk: String,
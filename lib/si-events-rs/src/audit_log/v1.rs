@@ -29,6 +29,7 @@ use crate::{
     InputSocketId,
     OutputSocketId,
     PropId,
+    ResourceStatus,
     SchemaId,
     SchemaVariantId,
     SecretId,
@@ -300,6 +301,13 @@ pub enum AuditLogKindV1 {
         func_display_name: Option<String>,
         func_name: String,
     },
+    RefreshResource {
+        component_id: ComponentId,
+        component_name: String,
+        resource_id: Option<String>,
+        status: Option<ResourceStatus>,
+        func_run_id: FuncRunId,
+    },
     RegenerateSchemaVariant {
         schema_variant_id: SchemaVariantId,
     },
@@ -353,6 +361,7 @@ pub enum AuditLogKindV1 {
         func_display_name: Option<String>,
         func_name: String,
         run_status: bool,
+        component_id: Option<ComponentId>,
     },
     SetAttribute {
         component_id: ComponentId,
@@ -508,6 +517,12 @@ pub enum AuditLogKindV1 {
         old_schema_variant_id: SchemaVariantId,
         old_schema_variant_name: String,
     },
+    ValidateWorkspaceImport {
+        id: WorkspacePk,
+        name: String,
+        version: String,
+        importable: bool,
+    },
     WithdrawRequestForChangeSetApply {
         from_status: ChangeSetStatus,
     },
@@ -803,6 +818,14 @@ pub enum AuditLogMetadataV1 {
         func_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    RefreshResource {
+        component_id: ComponentId,
+        component_name: String,
+        resource_id: Option<String>,
+        status: Option<ResourceStatus>,
+        func_run_id: FuncRunId,
+    },
+    #[serde(rename_all = "camelCase")]
     RegenerateSchemaVariant { schema_variant_id: SchemaVariantId },
     #[serde(rename_all = "camelCase")]
     RejectChangeSetApply { from_status: ChangeSetStatus },
@@ -857,6 +880,7 @@ pub enum AuditLogMetadataV1 {
         func_display_name: Option<String>,
         func_name: String,
         run_status: bool,
+        component_id: Option<ComponentId>,
     },
     #[serde(rename_all = "camelCase")]
     SetAttribute {
@@ -1025,6 +1049,13 @@ pub enum AuditLogMetadataV1 {
         old_schema_variant_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    ValidateWorkspaceImport {
+        id: WorkspacePk,
+        name: String,
+        version: String,
+        importable: bool,
+    },
+    #[serde(rename_all = "camelCase")]
     WithdrawRequestForChangeSetApply { from_status: ChangeSetStatus },
     #[serde(rename_all = "camelCase")]
     WorkspaceIntegration {
@@ -1091,6 +1122,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::OrphanComponent => ("Orphaned", Some("Component")),
             MetadataDiscrim::PurgeOpenChangeSets => ("Purged Open", Some("Change Sets")),
             MetadataDiscrim::PutActionOnHold => ("Paused", Some("Action")),
+            MetadataDiscrim::RefreshResource => ("Refreshed", Some("Resource")),
             MetadataDiscrim::RegenerateSchemaVariant => ("Regenerated", Some("Schema Variant")),
             MetadataDiscrim::RejectChangeSetApply => {
                 ("Rejected Request to Apply", Some("Change Set"))
@@ -1131,6 +1163,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::UpdateSchemaVariant => ("Updated", Some("Schema Variant")),
             MetadataDiscrim::UpdateView => ("Updated", Some("View")),
             MetadataDiscrim::UpgradeComponent => ("Upgraded", Some("Component")),
+            MetadataDiscrim::ValidateWorkspaceImport => ("Validated Import", Some("Workspace")),
             MetadataDiscrim::WithdrawRequestForChangeSetApply => {
                 ("Withdrew Request to Apply", Some("Change Set"))
             }
@@ -1547,6 +1580,19 @@ impl From<Kind> for Metadata {
                 func_display_name,
                 func_name,
             },
+            Kind::RefreshResource {
+                component_id,
+                component_name,
+                resource_id,
+                status,
+                func_run_id,
+            } => Self::RefreshResource {
+                component_id,
+                component_name,
+                resource_id,
+                status,
+                func_run_id,
+            },
             Kind::RegenerateSchemaVariant { schema_variant_id } => {
                 Self::RegenerateSchemaVariant { schema_variant_id }
             }
@@ -1627,6 +1673,7 @@ impl From<Kind> for Metadata {
                 func_display_name,
                 func_name,
                 run_status,
+                component_id,
             } => Self::RunAction {
                 prototype_id,
                 action_kind,
@@ -1634,6 +1681,7 @@ impl From<Kind> for Metadata {
                 func_display_name,
                 func_name,
                 run_status,
+                component_id,
             },
             Kind::SetAttribute {
                 component_id,
@@ -1911,6 +1959,17 @@ impl From<Kind> for Metadata {
                 old_schema_variant_id,
                 old_schema_variant_name,
             },
+            Kind::ValidateWorkspaceImport {
+                id,
+                name,
+                version,
+                importable,
+            } => Self::ValidateWorkspaceImport {
+                id,
+                name,
+                version,
+                importable,
+            },
             Kind::WithdrawRequestForChangeSetApply { from_status } => {
                 Self::WithdrawRequestForChangeSetApply { from_status }
             }
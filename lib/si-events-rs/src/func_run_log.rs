@@ -24,6 +24,32 @@ pub struct OutputLine {
     pub timestamp: u64,
 }
 
+/// The severity of an [`OutputLine`], ordered from least to most severe.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputLineLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl OutputLineLevel {
+    /// Maps a free-form [`OutputLine`] level (e.g. `"warn"`, or `"log"` for plain console output)
+    /// to a severity.
+    pub fn from_level(level: &str) -> Option<Self> {
+        match level.to_ascii_lowercase().as_str() {
+            "trace" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "info" | "log" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FuncRunLog {
     id: FuncRunLogId,
@@ -85,3 +111,39 @@ impl FuncRunLog {
         self.finalized = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_level() {
+        for (level, expected) in [
+            ("trace", Some(OutputLineLevel::Trace)),
+            ("debug", Some(OutputLineLevel::Debug)),
+            ("info", Some(OutputLineLevel::Info)),
+            ("log", Some(OutputLineLevel::Info)),
+            ("warn", Some(OutputLineLevel::Warn)),
+            ("warning", Some(OutputLineLevel::Warn)),
+            ("error", Some(OutputLineLevel::Error)),
+            ("ERROR", Some(OutputLineLevel::Error)),
+            ("Warn", Some(OutputLineLevel::Warn)),
+            ("fatal", None),
+            ("", None),
+        ] {
+            assert_eq!(
+                expected,
+                OutputLineLevel::from_level(level),
+                "level: {level:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn ordered_by_severity() {
+        assert!(OutputLineLevel::Trace < OutputLineLevel::Debug);
+        assert!(OutputLineLevel::Debug < OutputLineLevel::Info);
+        assert!(OutputLineLevel::Info < OutputLineLevel::Warn);
+        assert!(OutputLineLevel::Warn < OutputLineLevel::Error);
+    }
+}
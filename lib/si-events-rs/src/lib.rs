@@ -91,6 +91,7 @@ pub use crate::{
         FuncRunLog,
         FuncRunLogId,
         OutputLine,
+        OutputLineLevel,
     },
     resource_metadata::{
         ResourceMetadata,
@@ -23,6 +23,10 @@ use strum::{
 pub struct ListVariantsResponse {
     pub installed: Vec<SchemaVariant>,
     pub uninstalled: Vec<UninstalledVariant>,
+    /// Set when the module cache was empty and is being filled. The uninstalled variants will be
+    /// available once the `ModulesUpdated` event is published.
+    #[serde(default)]
+    pub warming: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, Serialize, PartialEq)]
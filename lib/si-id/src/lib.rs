@@ -33,7 +33,6 @@ pub mod ulid;
 pub use ::ulid as ulid_upstream;
 
 // Please keep these alphabetically sorted!
-id!(ActionPrototypeId);
 id!(ActivityId);
 id!(ApprovalRequirementDefinitionId);
 id!(AttributePrototypeArgumentId);
@@ -66,7 +65,9 @@ id!(ViewId);
 id!(WorkspaceSnapshotNodeId);
 
 // Please keep these alphabetically sorted!
+id_with_pg_types!(ActionBatchId);
 id_with_pg_types!(ActionId);
+id_with_pg_types!(ActionPrototypeId);
 id_with_pg_types!(CachedModuleId);
 id_with_pg_types!(ChangeSetId);
 id_with_pg_types!(ChangeSetApprovalId);
@@ -78,6 +79,7 @@ id_with_pg_types!(ManagementFuncJobStateId);
 id_with_pg_types!(ManagementPrototypeId);
 id_with_pg_types!(UserPk);
 id_with_pg_types!(WorkspaceIntegrationId);
+id_with_pg_types!(WorkspaceWebhookId);
 
 // Please keep these alphabetically sorted!
 id_with_pg_and_sea_orm_types!(ModuleIndexModuleId);
@@ -5,6 +5,7 @@ use std::{
 
 use si_events::{
     ActionId,
+    ActionPrototypeId,
     Actor,
     AttributeValueId,
     ChangeSetId,
@@ -43,7 +44,9 @@ pub struct FuncRunDb {
     ready_many_for_workspace_id_query: String,
     get_last_qualification_for_attribute_value_id: String,
     list_action_history: String,
+    list_action_history_for_originating_change_set: String,
     get_last_action_by_action_id: String,
+    list_latest_action_runs_for_component: String,
     list_management_history: String,
     get_last_management_by_func_and_component_id: String,
     paginated_workspace_query_with_cursor: String,
@@ -71,6 +74,12 @@ impl FuncRunDb {
                    WHERE function_kind = 'Action' AND workspace_id = $1
                    ORDER BY updated_at DESC",
             ),
+            list_action_history_for_originating_change_set: format!(
+                "SELECT value FROM {DBNAME}
+                   WHERE function_kind = 'Action' AND workspace_id = $1
+                     AND action_originating_change_set_id = $2
+                   ORDER BY created_at, key",
+            ),
             get_last_action_by_action_id: format!(
                 "
                 SELECT value FROM {DBNAME}
@@ -78,6 +87,16 @@ impl FuncRunDb {
                   ORDER BY updated_at DESC
                   LIMIT 1",
             ),
+            list_latest_action_runs_for_component: format!(
+                r#"
+                SELECT DISTINCT ON (json_value->>'prototype_id') value FROM {DBNAME}
+                WHERE function_kind = 'Action'
+                  AND workspace_id = $1
+                  AND component_id = $2
+                  AND json_value->>'prototype_id' = ANY($3::text[])
+                ORDER BY json_value->>'prototype_id', created_at DESC
+            "#
+            ),
             list_management_history: format!(
                 r#"
                 SELECT value FROM {DBNAME}
@@ -166,6 +185,29 @@ impl FuncRunDb {
         Ok(result)
     }
 
+    /// Lists the action runs that originated from the change set, wherever they ran, oldest
+    /// first.
+    #[instrument(level = "debug", skip_all)]
+    pub async fn list_action_history_for_originating_change_set(
+        &self,
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+    ) -> LayerDbResult<Vec<FuncRun>> {
+        let rows = self
+            .cache
+            .pg()
+            .query(
+                &self.list_action_history_for_originating_change_set,
+                &[&workspace_id, &change_set_id.to_string()],
+            )
+            .await?
+            .unwrap_or_default();
+
+        rows.into_iter()
+            .map(|row| serialize::from_bytes(row.get("value")))
+            .collect()
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub async fn get_last_run_for_action_id_opt(
         &self,
@@ -200,6 +242,35 @@ impl FuncRunDb {
             .ok_or_else(|| LayerDbError::ActionIdNotFound(action_id))
     }
 
+    /// Lists the most recent run of each of the given action prototypes for the component, in a
+    /// single query. Prototypes that have never run for the component are left out.
+    pub async fn list_latest_action_runs_for_component(
+        &self,
+        workspace_pk: WorkspacePk,
+        component_id: ComponentId,
+        action_prototype_ids: &[ActionPrototypeId],
+    ) -> LayerDbResult<Vec<FuncRun>> {
+        let action_prototype_ids: Vec<String> = action_prototype_ids
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let maybe_rows = self
+            .cache
+            .pg()
+            .query(
+                &self.list_latest_action_runs_for_component,
+                &[&workspace_pk, &component_id, &action_prototype_ids],
+            )
+            .await?;
+
+        let mut func_runs = Vec::new();
+        for row in maybe_rows.unwrap_or_default() {
+            let postcard_bytes: Vec<u8> = row.get("value");
+            func_runs.push(serialize::from_bytes(&postcard_bytes[..])?);
+        }
+        Ok(func_runs)
+    }
+
     pub async fn list_management_history(
         &self,
         workspace_pk: WorkspacePk,
@@ -1,11 +1,21 @@
 use std::sync::Arc;
 
+use chrono::{
+    DateTime,
+    Utc,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use si_events::{
+    ActionId,
     Actor,
     FuncRunId,
     FuncRunLog,
     Tenancy,
     WebEvent,
+    WorkspacePk,
 };
 
 use super::serialize;
@@ -23,11 +33,28 @@ pub const DBNAME: &str = "func_run_logs";
 pub const CACHE_NAME: &str = DBNAME;
 pub const PARTITION_KEY: &str = "workspace_id";
 
+/// A log line matching a [`FuncRunLogDb::search`], with the lines logged around it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunLogMatch {
+    pub func_run_id: FuncRunId,
+    /// Missing when the func run itself has not been persisted.
+    pub function_name: Option<String>,
+    pub action_id: Option<ActionId>,
+    pub created_at: DateTime<Utc>,
+    /// The position of the matching line in the func run's logs, starting at 1.
+    pub line_number: i64,
+    pub line: String,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct FuncRunLogDb {
     pub cache: Arc<LayerCache<Arc<FuncRunLog>>>,
     persister_client: PersisterClient,
     get_for_func_run_id_query: String,
+    search_query: String,
 }
 
 impl FuncRunLogDb {
@@ -36,6 +63,27 @@ impl FuncRunLogDb {
             cache,
             persister_client,
             get_for_func_run_id_query: format!("SELECT value FROM {DBNAME} WHERE func_run_id = $1"),
+            search_query: format!(
+                r#"SELECT logs.func_run_id,
+                          logs.created_at,
+                          runs.json_value->>'function_name' AS function_name,
+                          runs.action_id,
+                          lines.line_number,
+                          lines.line,
+                          logs.messages[GREATEST(lines.line_number - $4::bigint, 1)::int
+                                        :(lines.line_number - 1)::int] AS context_before,
+                          logs.messages[(lines.line_number + 1)::int
+                                        :(lines.line_number + $4::bigint)::int] AS context_after
+                   FROM {DBNAME} logs
+                   CROSS JOIN LATERAL unnest(logs.messages) WITH ORDINALITY AS lines(line, line_number)
+                   LEFT JOIN func_runs runs ON runs.key = logs.func_run_id
+                   WHERE logs.workspace_id = $1
+                     AND logs.created_at >= $2
+                     AND lines.line ILIKE $3
+                   ORDER BY logs.created_at DESC, logs.func_run_id, lines.line_number
+                   LIMIT $5
+                   OFFSET $6"#
+            ),
         }
     }
 
@@ -88,6 +136,60 @@ impl FuncRunLogDb {
         }
     }
 
+    /// Finds the log lines containing `query` (case insensitively) in the workspace's func runs
+    /// created since `since`, newest runs first. Each match carries up to `context_lines` lines
+    /// from either side of it. The matching happens in the database, so that the logs themselves
+    /// never have to be loaded and decoded.
+    pub async fn search(
+        &self,
+        workspace_pk: WorkspacePk,
+        query: &str,
+        since: DateTime<Utc>,
+        context_lines: i64,
+        limit: i64,
+        offset: i64,
+    ) -> LayerDbResult<Vec<FuncRunLogMatch>> {
+        let pattern = format!(
+            "%{}%",
+            query
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let maybe_rows = self
+            .cache
+            .pg()
+            .query(
+                &self.search_query,
+                &[
+                    &workspace_pk.to_string(),
+                    &since,
+                    &pattern,
+                    &context_lines.max(0),
+                    &limit,
+                    &offset,
+                ],
+            )
+            .await?;
+
+        let mut matches = Vec::new();
+        for row in maybe_rows.unwrap_or_default() {
+            let func_run_id: String = row.get("func_run_id");
+            let action_id: Option<String> = row.get("action_id");
+            matches.push(FuncRunLogMatch {
+                func_run_id: func_run_id.parse()?,
+                function_name: row.get("function_name"),
+                action_id: action_id.map(|action_id| action_id.parse()).transpose()?,
+                created_at: row.get("created_at"),
+                line_number: row.get("line_number"),
+                line: row.get("line"),
+                context_before: row.get("context_before"),
+                context_after: row.get("context_after"),
+            });
+        }
+        Ok(matches)
+    }
+
     pub async fn insert_to_pg(&self, func_run_log: Arc<FuncRunLog>) -> LayerDbResult<()> {
         self.cache
             .pg()
@@ -101,7 +203,8 @@ impl FuncRunLogDb {
                     workspace_id,
                     change_set_id,
                     func_run_id,
-                    value
+                    value,
+                    messages
                 ) VALUES (
                     $1,
                     $2,
@@ -110,10 +213,12 @@ impl FuncRunLogDb {
                     $5,
                     $6,
                     $7,
-                    $8
+                    $8,
+                    $9
                 ) ON CONFLICT (key) DO UPDATE SET
                     updated_at = EXCLUDED.updated_at,
-                    value = EXCLUDED.value;"
+                    value = EXCLUDED.value,
+                    messages = EXCLUDED.messages;"
                 ),
                 &[
                     &func_run_log.id().to_string(),
@@ -124,6 +229,11 @@ impl FuncRunLogDb {
                     &func_run_log.tenancy().change_set_id.to_string(),
                     &func_run_log.func_run_id().to_string(),
                     &serialize::to_vec(&func_run_log)?.0,
+                    &func_run_log
+                        .logs()
+                        .iter()
+                        .map(|line| line.message.as_str())
+                        .collect::<Vec<_>>(),
                 ],
             )
             .await?;
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS func_runs_action_component_id_and_workspace_id ON func_runs (component_id, workspace_id, created_at DESC) WHERE function_kind = 'Action';
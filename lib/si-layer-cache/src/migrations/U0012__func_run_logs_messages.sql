@@ -0,0 +1,5 @@
+-- The log lines are otherwise only stored in the postcard encoded value, which cannot be searched.
+-- Logs written before this column existed are left empty and will not show up in searches.
+ALTER TABLE func_run_logs ADD COLUMN IF NOT EXISTS messages text[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS func_run_logs_by_workspace_id_and_created_at ON func_run_logs (workspace_id, created_at DESC);
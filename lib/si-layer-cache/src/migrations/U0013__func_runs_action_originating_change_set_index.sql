@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS func_runs_action_originating_change_set_id_and_workspace_id ON func_runs (action_originating_change_set_id, workspace_id, created_at) WHERE function_kind = 'Action';
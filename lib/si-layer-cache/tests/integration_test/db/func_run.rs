@@ -439,3 +439,79 @@ fn create_func_run(actor: Actor, tenancy: Tenancy, function_name: impl Into<Stri
         .build()
         .expect("could not build func run")
 }
+
+#[tokio::test]
+async fn list_action_history_for_originating_change_set() {
+    let token = CancellationToken::new();
+
+    let (ldb, _): (TestLayerDb, _) = LayerDb::from_services(
+        make_test_layerdb_config(),
+        setup_pg_db("func_run_list_action_history_for_originating_change_set").await,
+        setup_nats_client(Some(
+            "func_run_list_action_history_for_originating_change_set".to_string(),
+        ))
+        .await,
+        setup_compute_executor(),
+        token,
+    )
+    .await
+    .expect("cannot create layerdb");
+    ldb.pg_migrate().await.expect("migrate ldb");
+
+    let (tenancy, actor) = (
+        Tenancy::new(WorkspacePk::new(), ChangeSetId::new()),
+        Actor::User(UserPk::new()),
+    );
+    let originating_change_set_id = ChangeSetId::new();
+
+    let first_created_at = Utc::now();
+    let mut expected = Vec::new();
+    for (offset, (name, originating)) in [
+        ("dead money", Some(originating_change_set_id)),
+        ("honest hearts", Some(ChangeSetId::new())),
+        ("old world blues", Some(originating_change_set_id)),
+        ("lonesome road", None),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let created_at = first_created_at + chrono::Duration::seconds(offset as i64);
+        let func_run = FuncRunBuilder::default()
+            .actor(actor)
+            .tenancy(tenancy)
+            .component_id(None)
+            .attribute_value_id(None)
+            .action_originating_change_set_id(originating)
+            .backend_kind(FuncBackendKind::JsAction)
+            .backend_response_type(FuncBackendResponseType::Action)
+            .function_name(name.to_string())
+            .function_kind(FuncKind::Action)
+            .function_args_cas_address(ContentHash::default())
+            .function_code_cas_address(ContentHash::default())
+            .created_at(created_at)
+            .updated_at(created_at)
+            .build()
+            .expect("could not build func run");
+        if originating == Some(originating_change_set_id) {
+            expected.push(func_run.id());
+        }
+        ldb.func_run()
+            .write(Arc::new(func_run), None, tenancy, actor)
+            .await
+            .expect("failed to write to layerdb");
+    }
+
+    let func_runs = ldb
+        .func_run()
+        .list_action_history_for_originating_change_set(
+            tenancy.workspace_pk,
+            originating_change_set_id,
+        )
+        .await
+        .expect("error getting data from pg");
+
+    assert_eq!(
+        expected,
+        func_runs.iter().map(|v| v.id()).collect::<Vec<_>>()
+    );
+}
@@ -3,6 +3,7 @@ use std::{
     time::Duration,
 };
 
+use chrono::Utc;
 use si_events::{
     Actor,
     ChangeSetId,
@@ -244,3 +245,125 @@ async fn write_and_get_for_func_run_id() {
 
     assert_eq!(value.id(), read_value.id());
 }
+
+#[tokio::test]
+async fn search() {
+    let token = CancellationToken::new();
+
+    let (ldb, _): (TestLayerDb, _) = LayerDb::from_services(
+        make_test_layerdb_config(),
+        setup_pg_db("func_run_log_search").await,
+        setup_nats_client(Some("func_run_log_search".to_string())).await,
+        setup_compute_executor(),
+        token,
+    )
+    .await
+    .expect("cannot create layerdb");
+    ldb.pg_migrate().await.expect("migrate ldb");
+
+    let workspace_pk = WorkspacePk::new();
+    let (tenancy, actor) = (
+        Tenancy::new(workspace_pk, ChangeSetId::new()),
+        Actor::User(UserPk::new()),
+    );
+    let since = Utc::now();
+
+    let mut func_run_ids = Vec::new();
+    for messages in [
+        vec!["starting", "created instance i-0abc", "done"],
+        vec!["starting", "nothing to see here"],
+        vec!["Created INSTANCE i-0def", "done"],
+    ] {
+        let mut func_run_log = FuncRunLog::new(FuncRunId::new(), tenancy);
+        for (timestamp, message) in messages.into_iter().enumerate() {
+            func_run_log.push_log(OutputLine {
+                stream: "stdout".to_string(),
+                execution_id: "execution".to_string(),
+                level: "info".to_string(),
+                group: None,
+                message: message.to_string(),
+                timestamp: timestamp as u64,
+            });
+        }
+        func_run_ids.push(func_run_log.func_run_id());
+        ldb.func_run_log()
+            .write(Arc::new(func_run_log), None, tenancy, actor)
+            .await
+            .expect("failed to write to layerdb");
+    }
+
+    // Logs from another workspace should never match
+    let other_tenancy = Tenancy::new(WorkspacePk::new(), ChangeSetId::new());
+    let mut other_func_run_log = FuncRunLog::new(FuncRunId::new(), other_tenancy);
+    other_func_run_log.push_log(OutputLine {
+        stream: "stdout".to_string(),
+        execution_id: "execution".to_string(),
+        level: "info".to_string(),
+        group: None,
+        message: "created instance i-0ghi".to_string(),
+        timestamp: 0,
+    });
+    ldb.func_run_log()
+        .write(Arc::new(other_func_run_log), None, other_tenancy, actor)
+        .await
+        .expect("failed to write to layerdb");
+
+    let matches = ldb
+        .func_run_log()
+        .search(workspace_pk, "created instance", since, 1, 10, 0)
+        .await
+        .expect("could not search logs");
+    assert_eq!(
+        vec![
+            (
+                func_run_ids[2],
+                1,
+                "Created INSTANCE i-0def".to_string(),
+                vec![],
+                vec!["done".to_string()]
+            ),
+            (
+                func_run_ids[0],
+                2,
+                "created instance i-0abc".to_string(),
+                vec!["starting".to_string()],
+                vec!["done".to_string()]
+            ),
+        ], // expected
+        matches
+            .into_iter()
+            .map(|m| (
+                m.func_run_id,
+                m.line_number,
+                m.line,
+                m.context_before,
+                m.context_after
+            ))
+            .collect::<Vec<_>>(), // actual
+    );
+
+    // Wildcards in the query are matched literally
+    assert!(
+        ldb.func_run_log()
+            .search(workspace_pk, "%", since, 0, 10, 0)
+            .await
+            .expect("could not search logs")
+            .is_empty()
+    );
+
+    // Pages pick up where the previous one left off
+    let second_page = ldb
+        .func_run_log()
+        .search(workspace_pk, "i-0", since, 0, 1, 1)
+        .await
+        .expect("could not search logs");
+    assert_eq!(1, second_page.len());
+    assert_eq!(func_run_ids[0], second_page[0].func_run_id);
+    assert!(
+        ldb.func_run_log()
+            .search(workspace_pk, "i-0", Utc::now(), 0, 10, 0)
+            .await
+            .expect("could not search logs")
+            .is_empty()
+    );
+}
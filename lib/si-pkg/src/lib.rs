@@ -7,8 +7,11 @@ pub use pkg::*;
 pub use spec::*;
 pub use workspace::{
     WorkspaceExport,
+    WorkspaceExportArtifactKindV0,
+    WorkspaceExportArtifactV0,
     WorkspaceExportChangeSetV0,
     WorkspaceExportContentV0,
+    WorkspaceExportManifestV0,
     WorkspaceExportMetadataV0,
 };
 
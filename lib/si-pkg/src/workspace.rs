@@ -9,6 +9,7 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use strum::Display;
 use ulid::Ulid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +35,10 @@ pub struct WorkspaceExportContentV0 {
     pub change_sets: HashMap<Ulid, Vec<WorkspaceExportChangeSetV0>>,
     pub content_store_values: Vec<u8>,
     pub metadata: WorkspaceExportMetadataV0,
+    /// Lists every artifact in the export with its size and hash, so an import can tell whether
+    /// anything is missing or corrupted. Exports made before manifests existed don't have one.
+    #[serde(default)]
+    pub manifest: Option<WorkspaceExportManifestV0>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,3 +61,45 @@ pub struct WorkspaceExportMetadataV0 {
     pub workspace_pk: Ulid,
     pub workspace_name: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceExportManifestV0 {
+    /// The version of the export format, which importers check before importing anything.
+    /// Exports without a manifest, or with one that predates this field, are format version 0.
+    #[serde(default)]
+    pub format_version: u32,
+    /// The version of SI that produced the export.
+    pub si_version: String,
+    /// The snapshot version of the exported workspace.
+    pub snapshot_version: String,
+    pub artifacts: Vec<WorkspaceExportArtifactV0>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceExportArtifactV0 {
+    pub kind: WorkspaceExportArtifactKindV0,
+    /// The change set the artifact belongs to, if any.
+    pub id: Option<Ulid>,
+    pub size: u64,
+    /// The hex encoded blake3 hash of the artifact.
+    pub hash: String,
+}
+
+impl WorkspaceExportArtifactV0 {
+    /// A human readable name for the artifact, for error messages.
+    pub fn name(&self) -> String {
+        match self.id {
+            Some(id) => format!("{} {id}", self.kind),
+            None => self.kind.to_string(),
+        }
+    }
+}
+
+#[remain::sorted]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display)]
+pub enum WorkspaceExportArtifactKindV0 {
+    #[strum(serialize = "change set snapshot")]
+    ChangeSetSnapshot,
+    #[strum(serialize = "content store values")]
+    ContentStoreValues,
+}
@@ -78,8 +78,9 @@ pub(crate) fn expand_test(item: ItemFn, _args: Args, fn_setup: impl FnSetup) ->
 
             ::dal_test::COLOR_EYRE_INIT.call_once(|| {
                 #color_eyre_init
-                #tracing_init
             });
+            // Held until the end of the test so that its spans are flushed even if it fails
+            let _tracing_guard = #tracing_init;
             let start = ::std::time::Instant::now();
             let thread_builder = ::std::thread::Builder::new().stack_size(#thread_stack_size);
             let thread_join_handle = thread_builder.spawn(|| {
@@ -156,7 +157,7 @@ fn expand_tracing_init() -> TokenStream {
     let span_events_env_var = SPAN_EVENTS_ENV_VAR;
     let log_env_var = LOG_ENV_VAR;
     quote! {
-        ::dal_test::expand_helpers::tracing_init(#span_events_env_var, #log_env_var);
+        ::dal_test::expand_helpers::tracing_init(#span_events_env_var, #log_env_var)
     }
 }
 
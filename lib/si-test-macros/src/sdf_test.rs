@@ -102,6 +102,11 @@ fn fn_setup<'a>(params: impl Iterator<Item = &'a FnArg>) -> SdfTestFnSetup {
                                 let var = var.as_ref();
                                 expander.push_arg(parse_quote! {#var});
                             }
+                            "LongTasks" => {
+                                let var = expander.setup_long_tasks();
+                                let var = var.as_ref();
+                                expander.push_arg(parse_quote! {#var.clone()});
+                            }
                             "Router" => {
                                 let var = expander.setup_router();
                                 let var = var.as_ref();
@@ -264,6 +269,7 @@ struct SdfTestFnSetupExpander {
     posthog_client: Option<Rc<Ident>>,
     ws_multiplexer_client: Option<Rc<Ident>>,
     crdt_multiplexer_client: Option<Rc<Ident>>,
+    long_tasks: Option<Rc<Ident>>,
     router: Option<Rc<Ident>>,
     auth_token: Option<Rc<Ident>>,
     auth_token_ref: Option<Rc<Ident>>,
@@ -301,6 +307,7 @@ impl SdfTestFnSetupExpander {
             posthog_client: None,
             ws_multiplexer_client: None,
             crdt_multiplexer_client: None,
+            long_tasks: None,
             router: None,
             auth_token: None,
             auth_token_ref: None,
@@ -357,11 +364,26 @@ impl SdfTestFnSetupExpander {
             return ident.clone();
         }
 
+        let test_context = self.setup_test_context();
+        let test_context = test_context.as_ref();
+        let cancellation_token = self.setup_cancellation_token();
+        let cancellation_token = cancellation_token.as_ref();
+        let task_tracker = self.setup_task_tracker();
+        let task_tracker = task_tracker.as_ref();
+
+        // A running multiplexer lets routes that relay WsEvents (e.g. the event stream and replay)
+        // be tested end to end.
         let var = Ident::new("ws_multiplexer_client", Span::call_site());
         self.code_extend(quote! {
             let #var = {
-                let (tx, _) = ::tokio::sync::mpsc::unbounded_channel();
-                let client = ::nats_multiplexer_client::MultiplexerClient::new(tx);
+                let (multiplexer, client) = ::nats_multiplexer::Multiplexer::new(
+                    #test_context.nats_conn(),
+                    ::sdf_server::WS_MULTIPLEXER_SUBJECT,
+                    #cancellation_token.clone(),
+                )
+                .await
+                .wrap_err("failed to create ws multiplexer")?;
+                #task_tracker.spawn(multiplexer.run());
                 client
             };
         });
@@ -388,6 +410,20 @@ impl SdfTestFnSetupExpander {
         self.crdt_multiplexer_client.as_ref().unwrap().clone()
     }
 
+    fn setup_long_tasks(&mut self) -> Rc<Ident> {
+        if let Some(ref ident) = self.long_tasks {
+            return ident.clone();
+        }
+
+        let var = Ident::new("long_tasks", Span::call_site());
+        self.code_extend(quote! {
+            let #var = ::sdf_core::long_tasks::LongTasks::new();
+        });
+        self.long_tasks = Some(Rc::new(var));
+
+        self.long_tasks.as_ref().unwrap().clone()
+    }
+
     fn setup_router(&mut self) -> Rc<Ident> {
         if let Some(ref ident) = self.router {
             return ident.clone();
@@ -409,6 +445,8 @@ impl SdfTestFnSetupExpander {
         let crdt_multiplexer_client = crdt_multiplexer_client.as_ref();
         let spicedb_client = self.setup_spicedb_client();
         let audit_database_context = self.setup_audit_database_context();
+        let long_tasks = self.setup_long_tasks();
+        let long_tasks = long_tasks.as_ref();
 
         let var = Ident::new("router", Span::call_site());
         self.code_extend(quote! {
@@ -435,6 +473,7 @@ impl SdfTestFnSetupExpander {
                     #cancellation_token.clone(),
                     #spicedb_client,
                     #audit_database_context,
+                    #long_tasks.clone(),
                 ).into_inner()
             };
         });
@@ -53,8 +53,10 @@ use tokio_util::sync::CancellationToken;
 use veritech_core::{
     FINAL_MESSAGE_HEADER_KEY,
     GetNatsSubjectFor,
+    NATS_WORK_QUEUE_CONSUMER_NAME,
     reply_mailbox_for_output,
     reply_mailbox_for_result,
+    veritech_work_queue_name,
 };
 pub use veritech_core::{
     VeritechValueEncryptError,
@@ -247,6 +249,31 @@ impl Client {
         .await
     }
 
+    /// Returns whether a veritech server is pulling from the work queue, i.e. whether requests
+    /// would get executed.
+    ///
+    /// The work queue consumer is durable, so it outlives the servers pulling through it. A
+    /// server is taken to be running only while it has pull requests waiting for messages or is
+    /// working through messages it has not yet acknowledged.
+    #[instrument(
+        name = "veritech_client.work_queue_is_being_pulled",
+        level = "debug",
+        skip_all
+    )]
+    pub async fn work_queue_is_being_pulled(&self) -> ClientResult<bool> {
+        let stream = self
+            .context
+            .get_stream(veritech_work_queue_name(self.nats_subject_prefix()))
+            .await
+            .map_err(|err| ClientError::Transport(Box::new(err)))?;
+        let info = stream
+            .consumer_info(NATS_WORK_QUEUE_CONSUMER_NAME)
+            .await
+            .map_err(|err| ClientError::Transport(Box::new(err)))?;
+
+        Ok(info.num_waiting > 0 || info.num_ack_pending > 0)
+    }
+
     async fn execute_jetstream_request<R>(
         &self,
         output_tx: mpsc::Sender<OutputStream>,
@@ -38,6 +38,9 @@ pub use crypto::{
 const NATS_WORK_QUEUE_STREAM_NAME: &str = "VERITECH_REQUESTS";
 const NATS_WORK_QUEUE_STREAM_SUBJECTS: &[&str] = &["veritech.requests.>"];
 
+/// The durable consumer that veritech servers pull the work queue through.
+pub const NATS_WORK_QUEUE_CONSUMER_NAME: &str = "veritech-server";
+
 const NATS_ACTION_RUN_DEFAULT_SUBJECT_SUFFIX: &str = "actionrun";
 const NATS_RESOLVER_FUNCTION_DEFAULT_SUBJECT_SUFFIX: &str = "resolverfunction";
 const NATS_SCHEMA_VARIANT_DEFINITION_DEFAULT_SUBJECT_SUFFIX: &str = "schemavariantdefinition";
@@ -68,7 +71,7 @@ pub async fn veritech_work_queue(
 
     let stream = context
         .get_or_create_stream(async_nats::jetstream::stream::Config {
-            name: nats_std::jetstream::prefixed(prefix, NATS_WORK_QUEUE_STREAM_NAME),
+            name: veritech_work_queue_name(prefix),
             description: Some("Veritech work queue of requests".to_owned()),
             retention: async_nats::jetstream::stream::RetentionPolicy::WorkQueue,
             discard: async_nats::jetstream::stream::DiscardPolicy::New,
@@ -81,6 +84,11 @@ pub async fn veritech_work_queue(
     Ok(stream)
 }
 
+/// Returns the name of the veritech work queue stream, e.g. to look it up without creating it.
+pub fn veritech_work_queue_name(prefix: Option<&str>) -> String {
+    nats_std::jetstream::prefixed(prefix, NATS_WORK_QUEUE_STREAM_NAME)
+}
+
 pub fn reply_mailbox_for_output(reply_mailbox: &str) -> String {
     format!("{reply_mailbox}.output")
 }
@@ -63,6 +63,7 @@ use tokio_util::sync::CancellationToken;
 use veritech_core::{
     ExecutionId,
     GetNatsSubjectFor,
+    NATS_WORK_QUEUE_CONSUMER_NAME,
     incoming_subject,
     veritech_work_queue,
 };
@@ -80,7 +81,6 @@ use crate::{
     heartbeat::HeartbeatApp,
 };
 
-const CONSUMER_NAME: &str = "veritech-server";
 const CONSUMER_MAX_DELIVERY: i64 = 5;
 
 /// Server metadata, used with telemetry.
@@ -388,7 +388,7 @@ impl Server {
         subject_prefix: Option<&str>,
     ) -> async_nats::jetstream::consumer::pull::Config {
         async_nats::jetstream::consumer::pull::Config {
-            durable_name: Some(CONSUMER_NAME.to_owned()),
+            durable_name: Some(NATS_WORK_QUEUE_CONSUMER_NAME.to_owned()),
             filter_subject: incoming_subject(subject_prefix).to_string(),
             max_deliver: CONSUMER_MAX_DELIVERY,
             ..Default::default()